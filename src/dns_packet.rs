@@ -1,32 +1,45 @@
+mod dns_class;
 mod dns_domain;
+mod dns_encoding;
 mod dns_header;
+mod dns_idna;
+mod dns_name;
 mod dns_packet_buf;
 mod dns_packet_err;
 mod dns_qtype;
 mod dns_question;
 mod dns_record;
+pub use dns_class::*;
 pub use dns_domain::*;
+pub(crate) use dns_encoding::*;
 pub use dns_header::*;
+pub use dns_idna::*;
+pub use dns_name::*;
 pub use dns_packet_buf::*;
 pub use dns_packet_err::*;
 pub use dns_qtype::*;
 pub use dns_question::*;
 pub use dns_record::*;
+use std::fmt;
 
 pub const PACKET_SIZE: usize = 512;
+pub const MAX_PACKET_SIZE: usize = 65535;
 pub const HEADER_SIZE: usize = 12;
+const ADDITIONAL_COUNT_POS: usize = 10;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct DNSPacket {
     header: Header,
     questions: Vec<Question>,
     answers: Vec<Record>,
     authorities: Vec<Record>,
     additional_records: Vec<Record>,
+    edns: Option<Edns>,
 }
 
 impl DNSPacket {
     // NOTE: Constructor
+    #[allow(clippy::manual_unwrap_or_default)]
     pub fn new(
         header: Header,
         questions: Option<Vec<Question>>,
@@ -60,9 +73,71 @@ impl DNSPacket {
             answers,
             authorities,
             additional_records,
+            edns: None,
         }
     }
 
+    // NOTE: Accessors
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn questions(&self) -> &[Question] {
+        &self.questions
+    }
+
+    pub fn answers(&self) -> &[Record] {
+        &self.answers
+    }
+
+    pub fn authorities(&self) -> &[Record] {
+        &self.authorities
+    }
+
+    /// Additional records, not including the OPT pseudo-record. See edns().
+    pub fn additional_records(&self) -> &[Record] {
+        &self.additional_records
+    }
+
+    pub fn edns(&self) -> Option<&Edns> {
+        self.edns.as_ref()
+    }
+
+    pub fn edns_mut(&mut self) -> Option<&mut Edns> {
+        self.edns.as_mut()
+    }
+
+    /// Set the EDNS information of the packet. When written, it is appended to the additional
+    /// section as an OPT pseudo-record.
+    pub fn set_edns(&mut self, edns: Option<Edns>) {
+        self.edns = edns;
+    }
+
+    /// Full response code of the packet, combining the 4 bits in the header with the upper 8 bits
+    /// carried by EDNS if present.
+    pub fn response_code(&self) -> Result<ResponseCode, DNSError> {
+        let low_bits = self.header.response_code.to_num() & 0x000F;
+        let high_bits = self
+            .edns
+            .as_ref()
+            .map_or(0, |edns| edns.extended_rcode as u16);
+        ResponseCode::from_num(high_bits << 4 | low_bits)
+    }
+
+    /// Set the full response code of the packet, splitting it between the header and EDNS. Codes
+    /// which don't fit in the header's 4 bits require EDNS to be present.
+    pub fn set_response_code(&mut self, response_code: ResponseCode) -> Result<(), DNSError> {
+        let code = response_code.to_num();
+        match self.edns.as_mut() {
+            Some(edns) => edns.extended_rcode = (code >> 4) as u8,
+            None if code > 0x000F => return Err(DNSError::ExtendedRcodeWithoutEdns(code)),
+            None => (),
+        }
+        self.header.response_code = ResponseCode::from_num(code & 0x000F)?;
+        Ok(())
+    }
+
     // NOTE: Buffer parsing functions
 
     /// Parse and return DNS header from buffer. Move pointer's position to the byte after the
@@ -103,16 +178,44 @@ impl DNSPacket {
         let questions = Self::parse_questions(buffer, header.question_count)?;
         let answers = Self::parse_records(buffer, header.answer_count)?;
         let authorities = Self::parse_records(buffer, header.authority_count)?;
-        let additional_records = Self::parse_records(buffer, header.additional_count)?;
+        let (additional_records, edns) =
+            Self::extract_edns(Self::parse_records(buffer, header.additional_count)?)?;
+
+        // The header additional count does not include the OPT pseudo-record, which is modelled
+        // separately
+        let mut header = header;
+        if edns.is_some() {
+            header.additional_count -= 1;
+        }
+
         Ok(Self {
             header,
             questions,
             answers,
             authorities,
             additional_records,
+            edns,
         })
     }
 
+    /// Split the OPT pseudo-record out of the additional records, if present.
+    fn extract_edns(records: Vec<Record>) -> Result<(Vec<Record>, Option<Edns>), DNSError> {
+        let (opt_records, additional_records): (Vec<Record>, Vec<Record>) = records
+            .into_iter()
+            .partition(|record| matches!(record.data(), RecordData::OPT(_)));
+
+        if opt_records.len() > 1 {
+            return Err(DNSError::MultipleOPT);
+        }
+
+        let edns = opt_records
+            .into_iter()
+            .next()
+            .map(Edns::from_record)
+            .transpose()?;
+        Ok((additional_records, edns))
+    }
+
     // NOTE: Buffer writing functions
 
     /// Write DNS questions to packet buffer.
@@ -136,9 +239,17 @@ impl DNSPacket {
         Ok(())
     }
 
-    /// Write DNS packet.
+    /// Write DNS packet. The returned buffer only holds the bytes actually written.
     pub fn write_dns_packet(&self) -> Result<DNSPacketBuffer, DNSError> {
-        let mut buffer = DNSPacketBuffer::new(&[0; PACKET_SIZE]);
+        self.write_dns_packet_with_max_size(MAX_PACKET_SIZE)
+    }
+
+    /// Write DNS packet, failing with EndOfBuffer if it does not fit in max_size bytes.
+    pub fn write_dns_packet_with_max_size(
+        &self,
+        max_size: usize,
+    ) -> Result<DNSPacketBuffer, DNSError> {
+        let mut buffer = DNSPacketBuffer::with_max_size(&[], max_size);
 
         self.header.write_to_buffer(&mut buffer)?;
         Self::write_questions(&self.questions, &mut buffer)?;
@@ -146,10 +257,85 @@ impl DNSPacket {
         Self::write_records(&self.authorities, &mut buffer)?;
         Self::write_records(&self.additional_records, &mut buffer)?;
 
+        if let Some(edns) = &self.edns {
+            edns.write_to_buffer(&mut buffer)?;
+            let additional_count = self
+                .header
+                .additional_count
+                .checked_add(1)
+                .ok_or(DNSError::SectionCountOverflow)?;
+            buffer.set_u16(ADDITIONAL_COUNT_POS, additional_count)?;
+        }
+
         Ok(buffer)
     }
 }
 
+/// Packet in the format of dig's output: header, OPT pseudosection and non-empty sections.
+impl fmt::Display for DNSPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self.response_code() {
+            Ok(response_code) => response_code.to_string(),
+            Err(_) => {
+                let extended = self.edns.as_ref().map_or(0, |edns| edns.extended_rcode);
+                ((extended as u16) << 4 | self.header.response_code.to_num()).to_string()
+            }
+        };
+        writeln!(
+            f,
+            ";; ->>HEADER<<- opcode: {}, status: {}, id: {}",
+            self.header.opcode, status, self.header.id
+        )?;
+
+        let flags: Vec<&str> = [
+            (self.header.query_response, "qr"),
+            (self.header.authoritative_answer, "aa"),
+            (self.header.truncated_message, "tc"),
+            (self.header.recursion_desired, "rd"),
+            (self.header.recursion_available, "ra"),
+            (self.header.authentic_data, "ad"),
+            (self.header.checking_disabled, "cd"),
+        ]
+        .into_iter()
+        .filter_map(|(set, flag)| set.then_some(flag))
+        .collect();
+        // The OPT pseudo-record is counted in the additional section, as on the wire
+        write!(
+            f,
+            ";; flags: {}; QUERY: {}, ANSWER: {}, AUTHORITY: {}, ADDITIONAL: {}",
+            flags.join(" "),
+            self.questions.len(),
+            self.answers.len(),
+            self.authorities.len(),
+            self.additional_records.len() + self.edns.is_some() as usize
+        )?;
+
+        if let Some(edns) = &self.edns {
+            write!(f, "\n\n;; OPT PSEUDOSECTION:\n{}", edns)?;
+        }
+        if !self.questions.is_empty() {
+            write!(f, "\n\n;; QUESTION SECTION:")?;
+            for question in self.questions.iter() {
+                write!(f, "\n{}", question)?;
+            }
+        }
+        for (name, records) in [
+            ("ANSWER", &self.answers),
+            ("AUTHORITY", &self.authorities),
+            ("ADDITIONAL", &self.additional_records),
+        ] {
+            if !records.is_empty() {
+                write!(f, "\n\n;; {} SECTION:", name)?;
+                for record in records.iter() {
+                    write!(f, "\n{}", record)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,12 +356,14 @@ mod tests {
         let expected_header = Header {
             id: 0x862a,
             query_response: false,
-            opcode: 0,
+            opcode: Opcode::Query,
             authoritative_answer: false,
             truncated_message: false,
             recursion_desired: true,
             recursion_available: false,
-            reserved: 2,
+            reserved: false,
+            authentic_data: true,
+            checking_disabled: false,
             response_code: ResponseCode::NoError,
             question_count: 2,
             answer_count: 0,
@@ -185,14 +373,14 @@ mod tests {
 
         let expected_questions = vec![
             Question {
-                domain: Domain("google.com".to_string()),
+                domain: "google.com".parse().unwrap(),
                 record_type: QueryType::A,
-                class: 0x01,
+                class: Class::IN,
             },
             Question {
-                domain: Domain("yahoo.com".to_string()),
+                domain: "yahoo.com".parse().unwrap(),
                 record_type: QueryType::A,
-                class: 0x00,
+                class: Class::Unknown(0),
             },
         ];
 
@@ -206,6 +394,7 @@ mod tests {
             answers: expected_answers,
             authorities: expected_authorities,
             additional_records: expected_additional_records,
+            edns: None,
         };
 
         assert_eq!(parsed_dns_packet.header, expected_packet.header);
@@ -228,12 +417,14 @@ mod tests {
         let expected_header = Header {
             id: 0x862a,
             query_response: true,
-            opcode: 0,
+            opcode: Opcode::Query,
             authoritative_answer: false,
             truncated_message: false,
             recursion_desired: true,
             recursion_available: true,
-            reserved: 0,
+            reserved: false,
+            authentic_data: false,
+            checking_disabled: false,
             response_code: ResponseCode::NoError,
             question_count: 1,
             answer_count: 1,
@@ -242,14 +433,14 @@ mod tests {
         };
 
         let expected_questions = vec![Question {
-            domain: Domain("google.com".to_string()),
+            domain: "google.com".parse().unwrap(),
             record_type: QueryType::A,
-            class: 0x01,
+            class: Class::IN,
         }];
 
         let expected_answers = vec![Record::new(
-            Domain("google.com".to_string()),
-            1,
+            "google.com".parse().unwrap(),
+            Class::IN,
             293,
             RecordData::A(A {
                 addr: Ipv4Addr::new(216, 58, 211, 142),
@@ -265,6 +456,7 @@ mod tests {
             answers: expected_answers,
             authorities: expected_authorities,
             additional_records: expected_additional_records,
+            edns: None,
         };
 
         assert_eq!(parsed_dns_packet.header, expected_packet.header);
@@ -288,12 +480,14 @@ mod tests {
         let expected_header = Header {
             id: 0x862a,
             query_response: true,
-            opcode: 0,
+            opcode: Opcode::Query,
             authoritative_answer: false,
             truncated_message: false,
             recursion_desired: true,
             recursion_available: true,
-            reserved: 0,
+            reserved: false,
+            authentic_data: false,
+            checking_disabled: false,
             response_code: ResponseCode::NoError,
             question_count: 1,
             answer_count: 2,
@@ -302,21 +496,24 @@ mod tests {
         };
 
         let expected_questions = vec![Question {
-            domain: Domain("google.com".to_string()),
+            domain: "google.com".parse().unwrap(),
             record_type: QueryType::A,
-            class: 0x01,
+            class: Class::IN,
         }];
 
         let expected_answers = vec![
             Record::new(
-                Domain("google.com".to_string()),
-                1,
+                "google.com".parse().unwrap(),
+                Class::IN,
                 293,
-                RecordData::Unknown(Unknown {}),
+                RecordData::Unknown(Unknown {
+                    code: 255,
+                    data: vec![0xd8, 0x3a, 0xd3, 0x8e],
+                }),
             ),
             Record::new(
-                Domain("google.com".to_string()),
-                1,
+                "google.com".parse().unwrap(),
+                Class::IN,
                 293,
                 RecordData::A(A {
                     addr: Ipv4Addr::new(216, 58, 211, 142),
@@ -333,6 +530,7 @@ mod tests {
             answers: expected_answers,
             authorities: expected_authorities,
             additional_records: expected_additional_records,
+            edns: None,
         };
 
         assert_eq!(parsed_dns_packet.header, expected_packet.header);
@@ -345,12 +543,14 @@ mod tests {
         let original_header = Header {
             id: 0x862a,
             query_response: false,
-            opcode: 0,
+            opcode: Opcode::Query,
             authoritative_answer: false,
             truncated_message: false,
             recursion_desired: true,
             recursion_available: false,
-            reserved: 2,
+            reserved: false,
+            authentic_data: true,
+            checking_disabled: false,
             response_code: ResponseCode::NoError,
             question_count: 5,
             answer_count: 2,
@@ -360,44 +560,44 @@ mod tests {
 
         let original_questions = vec![
             Question {
-                domain: Domain("google.com".to_string()),
+                domain: "google.com".parse().unwrap(),
                 record_type: QueryType::A,
-                class: 0x01,
+                class: Class::IN,
             },
             Question {
-                domain: Domain("yahoo.com".to_string()),
+                domain: "yahoo.com".parse().unwrap(),
                 record_type: QueryType::A,
-                class: 0x00,
+                class: Class::Unknown(0),
             },
             Question {
-                domain: Domain("dev.google.com".to_string()),
+                domain: "dev.google.com".parse().unwrap(),
                 record_type: QueryType::A,
-                class: 0x01,
+                class: Class::IN,
             },
             Question {
-                domain: Domain("api.dev.google.com".to_string()),
+                domain: "api.dev.google.com".parse().unwrap(),
                 record_type: QueryType::A,
-                class: 0x00,
+                class: Class::Unknown(0),
             },
             Question {
-                domain: Domain("dev.yahoo.com".to_string()),
+                domain: "dev.yahoo.com".parse().unwrap(),
                 record_type: QueryType::A,
-                class: 0x01,
+                class: Class::IN,
             },
         ];
 
         let original_answers = vec![
             Record::new(
-                Domain("api.dev.google.com".to_string()),
-                1,
+                "api.dev.google.com".parse().unwrap(),
+                Class::IN,
                 342,
                 RecordData::A(A {
                     addr: Ipv4Addr::new(16, 28, 21, 42),
                 }),
             ),
             Record::new(
-                Domain("yahoo.com".to_string()),
-                1,
+                "yahoo.com".parse().unwrap(),
+                Class::IN,
                 272,
                 RecordData::A(A {
                     addr: Ipv4Addr::new(216, 58, 211, 142),
@@ -407,16 +607,16 @@ mod tests {
 
         let original_authorities = vec![
             Record::new(
-                Domain("api.dev.yahoo.com".to_string()),
-                1,
+                "api.dev.yahoo.com".parse().unwrap(),
+                Class::IN,
                 278,
                 RecordData::A(A {
                     addr: Ipv4Addr::new(116, 253, 244, 2),
                 }),
             ),
             Record::new(
-                Domain("yahoo.com".to_string()),
-                1,
+                "yahoo.com".parse().unwrap(),
+                Class::IN,
                 22,
                 RecordData::A(A {
                     addr: Ipv4Addr::new(255, 244, 233, 222),
@@ -426,16 +626,16 @@ mod tests {
 
         let original_additional_records = vec![
             Record::new(
-                Domain("google.com".to_string()),
-                1,
+                "google.com".parse().unwrap(),
+                Class::IN,
                 93,
                 RecordData::A(A {
                     addr: Ipv4Addr::new(216, 58, 211, 142),
                 }),
             ),
             Record::new(
-                Domain("api.google.com".to_string()),
-                1,
+                "api.google.com".parse().unwrap(),
+                Class::IN,
                 93,
                 RecordData::A(A {
                     addr: Ipv4Addr::new(216, 58, 211, 142),
@@ -460,4 +660,242 @@ mod tests {
 
         assert_eq!(original_packet, parsed_packet);
     }
+
+    #[test]
+    fn test_write_read_large_packet() {
+        let answers: Vec<Record> = (0..40)
+            .map(|i| {
+                Record::new(
+                    format!("host{}.example.com", i).parse().unwrap(),
+                    Class::IN,
+                    300,
+                    RecordData::A(A {
+                        addr: Ipv4Addr::new(10, 0, 0, i),
+                    }),
+                )
+            })
+            .collect();
+
+        let original_packet = DNSPacket::new(
+            Header {
+                id: 0x1234,
+                query_response: true,
+                opcode: Opcode::Query,
+                authoritative_answer: false,
+                truncated_message: false,
+                recursion_desired: true,
+                recursion_available: true,
+                reserved: false,
+                authentic_data: false,
+                checking_disabled: false,
+                response_code: ResponseCode::NoError,
+                question_count: 0,
+                answer_count: 40,
+                authority_count: 0,
+                additional_count: 0,
+            },
+            None,
+            Some(answers),
+            None,
+            None,
+        );
+
+        let mut dns_packet_buffer = original_packet.write_dns_packet().unwrap();
+        assert!(dns_packet_buffer.len() > PACKET_SIZE);
+        assert_eq!(
+            original_packet.write_dns_packet_with_max_size(PACKET_SIZE),
+            Err(DNSError::EndOfBuffer)
+        );
+
+        // Read
+        dns_packet_buffer.seek(0);
+
+        let parsed_packet = DNSPacket::parse_dns_packet(&mut dns_packet_buffer).unwrap();
+
+        assert_eq!(original_packet, parsed_packet);
+    }
+
+    #[test]
+    fn test_read_answer_packet_edns() {
+        let dns_packet_data = [
+            0x86, 0x2a, 0x81, 0x80, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x06, 0x67,
+            0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x00, 0x01, 0x00, 0x01,
+            0x00, 0x00, 0x29, 0x10, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x06, 0x00, 0x0B, 0x00,
+            0x02, 0x01, 0x2C,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        let parsed_dns_packet = DNSPacket::parse_dns_packet(&mut dns_packet_buffer).unwrap();
+
+        let expected_edns = Edns {
+            udp_payload_size: 4096,
+            extended_rcode: 1,
+            version: 0,
+            dnssec_ok: true,
+            options: vec![EdnsOption::TcpKeepalive(Some(300))],
+        };
+
+        assert_eq!(parsed_dns_packet.header().additional_count, 0);
+        assert!(parsed_dns_packet.additional_records().is_empty());
+        assert_eq!(parsed_dns_packet.edns(), Some(&expected_edns));
+        assert_eq!(parsed_dns_packet.response_code(), Ok(ResponseCode::BadVers));
+
+        // Unassigned extended response code
+        let mut data = dns_packet_data;
+        data[3] = 0x81;
+        data[33] = 0xF0;
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&data);
+        let parsed_dns_packet = DNSPacket::parse_dns_packet(&mut dns_packet_buffer).unwrap();
+        assert_eq!(
+            parsed_dns_packet.response_code(),
+            Ok(ResponseCode::Unknown(3841))
+        );
+
+        // OPT record not owned by the root
+        let mut data = dns_packet_data.to_vec();
+        data.splice(28..29, [0x01, b'a', 0x00]);
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&data);
+        assert_eq!(
+            DNSPacket::parse_dns_packet(&mut dns_packet_buffer),
+            Err(DNSError::InvalidOPT("owner a. is not the root".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_write_read_edns() {
+        let mut original_packet = DNSPacket::new(
+            Header {
+                id: 0x862a,
+                query_response: true,
+                opcode: Opcode::Query,
+                authoritative_answer: false,
+                truncated_message: false,
+                recursion_desired: true,
+                recursion_available: true,
+                reserved: false,
+                authentic_data: false,
+                checking_disabled: false,
+                response_code: ResponseCode::NoError,
+                question_count: 1,
+                answer_count: 0,
+                authority_count: 0,
+                additional_count: 1,
+            },
+            Some(vec![Question {
+                domain: "google.com".parse().unwrap(),
+                record_type: QueryType::A,
+                class: Class::IN,
+            }]),
+            None,
+            None,
+            Some(vec![Record::new(
+                "ns1.google.com".parse().unwrap(),
+                Class::IN,
+                93,
+                RecordData::A(A {
+                    addr: Ipv4Addr::new(216, 239, 32, 10),
+                }),
+            )]),
+        );
+
+        assert_eq!(
+            original_packet.set_response_code(ResponseCode::BadVers),
+            Err(DNSError::ExtendedRcodeWithoutEdns(16))
+        );
+
+        original_packet.set_edns(Some(Edns {
+            dnssec_ok: true,
+            options: vec![EdnsOption::Nsid(Vec::new())],
+            ..Edns::default()
+        }));
+        original_packet
+            .set_response_code(ResponseCode::BadVers)
+            .unwrap();
+
+        let mut dns_packet_buffer = original_packet.write_dns_packet().unwrap();
+
+        // OPT record is counted in the additional section on the wire
+        assert_eq!(dns_packet_buffer.get_data()[10..12], [0x00, 0x02]);
+
+        // Read
+        dns_packet_buffer.seek(0);
+
+        let parsed_packet = DNSPacket::parse_dns_packet(&mut dns_packet_buffer).unwrap();
+
+        assert_eq!(original_packet, parsed_packet);
+        assert_eq!(parsed_packet.response_code(), Ok(ResponseCode::BadVers));
+
+        // No room is left in the additional count for the OPT record
+        original_packet.header.additional_count = u16::MAX;
+        assert_eq!(
+            original_packet.write_dns_packet(),
+            Err(DNSError::SectionCountOverflow)
+        );
+    }
+
+    #[test]
+    fn test_display_packet() {
+        let mut packet = DNSPacket::new(
+            Header {
+                id: 0x862a,
+                query_response: true,
+                opcode: Opcode::Query,
+                authoritative_answer: false,
+                truncated_message: false,
+                recursion_desired: true,
+                recursion_available: true,
+                reserved: false,
+                authentic_data: false,
+                checking_disabled: false,
+                response_code: ResponseCode::NoError,
+                question_count: 1,
+                answer_count: 2,
+                authority_count: 0,
+                additional_count: 0,
+            },
+            Some(vec![Question {
+                domain: "example.com".parse().unwrap(),
+                record_type: QueryType::MX,
+                class: Class::IN,
+            }]),
+            Some(vec![
+                Record::new(
+                    "example.com".parse().unwrap(),
+                    Class::IN,
+                    300,
+                    RecordData::MX(MX {
+                        preference: 10,
+                        exchange: "mail.example.com".parse().unwrap(),
+                    }),
+                ),
+                Record::new(
+                    "example.com".parse().unwrap(),
+                    Class::IN,
+                    300,
+                    RecordData::TXT(TXT {
+                        txt_data: vec![b"v=spf1 -all".to_vec()],
+                    }),
+                ),
+            ]),
+            None,
+            None,
+        );
+        packet.set_edns(Some(Edns::default()));
+
+        assert_eq!(
+            packet.to_string(),
+            ";; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 34346\n\
+             ;; flags: qr rd ra; QUERY: 1, ANSWER: 2, AUTHORITY: 0, ADDITIONAL: 1\n\
+             \n\
+             ;; OPT PSEUDOSECTION:\n\
+             ; EDNS: version: 0, flags:; udp: 1232\n\
+             \n\
+             ;; QUESTION SECTION:\n\
+             ;example.com.\t\tIN\tMX\n\
+             \n\
+             ;; ANSWER SECTION:\n\
+             example.com.\t300\tIN\tMX\t10 mail.example.com.\n\
+             example.com.\t300\tIN\tTXT\t\"v=spf1 -all\""
+        );
+    }
 }
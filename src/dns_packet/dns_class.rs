@@ -0,0 +1,124 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Class {
+    IN,           // Internet
+    CS,           // CSNET, obsolete
+    CH,           // Chaos
+    HS,           // Hesiod
+    NONE,         // No class, only meaningful in UPDATE messages
+    ANY,          // Any class, only meaningful in questions and UPDATE messages
+    Unknown(u16), // Unknown class
+}
+
+impl Class {
+    /// Class of a code. Codes of the known classes never become Unknown.
+    pub fn from_num(code_num: u16) -> Class {
+        match code_num {
+            1 => Class::IN,
+            2 => Class::CS,
+            3 => Class::CH,
+            4 => Class::HS,
+            254 => Class::NONE,
+            255 => Class::ANY,
+            code => Class::Unknown(code),
+        }
+    }
+
+    pub fn to_num(&self) -> u16 {
+        match self {
+            Class::IN => 1,
+            Class::CS => 2,
+            Class::CH => 3,
+            Class::HS => 4,
+            Class::NONE => 254,
+            Class::ANY => 255,
+            Class::Unknown(code) => *code,
+        }
+    }
+
+    /// Parse a class from its presentation format mnemonic, or from the CLASSnnn generic notation
+    /// (RFC 3597 section 5). Case insensitive.
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Class> {
+        let mnemonic = mnemonic.to_ascii_uppercase();
+        if let Some(code) = mnemonic.strip_prefix("CLASS") {
+            return code.parse::<u16>().ok().map(Class::from_num);
+        }
+
+        let class = match mnemonic.as_str() {
+            "IN" => Class::IN,
+            "CS" => Class::CS,
+            "CH" => Class::CH,
+            "HS" => Class::HS,
+            "NONE" => Class::NONE,
+            "ANY" => Class::ANY,
+            _ => return None,
+        };
+        Some(class)
+    }
+
+    /// Whether the class is a meta class (RFC 6895 section 3.2), which no data is stored in. ANY
+    /// is used by questions and, like NONE, by UPDATE messages to delete records or state
+    /// prerequisites (RFC 2136 section 2.4 and 2.5).
+    pub fn is_meta(&self) -> bool {
+        matches!(self, Class::NONE | Class::ANY)
+    }
+
+    /// Whether records of a class answer a question of this class: ANY matches every class, and
+    /// NONE doesn't match any.
+    pub fn matches(&self, class: Class) -> bool {
+        match self {
+            Class::ANY => true,
+            Class::NONE => false,
+            question_class => *question_class == class,
+        }
+    }
+}
+
+/// Class mnemonic, or CLASSnnn for classes without one (RFC 3597 section 5).
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Class::Unknown(code) => write!(f, "CLASS{}", code),
+            class => write!(f, "{:?}", class),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_class_codes() {
+        for code in [0, 1, 2, 3, 4, 5, 254, 255, 65535] {
+            assert_eq!(Class::from_num(code).to_num(), code);
+        }
+        assert_eq!(Class::from_num(3), Class::CH);
+        assert_eq!(Class::from_num(42), Class::Unknown(42));
+    }
+
+    #[test]
+    fn test_mnemonic() {
+        assert_eq!(Class::from_mnemonic("in"), Some(Class::IN));
+        assert_eq!(Class::from_mnemonic("ANY"), Some(Class::ANY));
+        assert_eq!(Class::from_mnemonic("CLASS1"), Some(Class::IN));
+        assert_eq!(Class::from_mnemonic("class42"), Some(Class::Unknown(42)));
+        assert_eq!(Class::from_mnemonic("CLASS65536"), None);
+        assert_eq!(Class::from_mnemonic("A"), None);
+
+        assert_eq!(Class::CH.to_string(), "CH");
+        assert_eq!(Class::NONE.to_string(), "NONE");
+        assert_eq!(Class::Unknown(42).to_string(), "CLASS42");
+    }
+
+    #[test]
+    fn test_semantics() {
+        assert!(Class::ANY.matches(Class::CH));
+        assert!(Class::IN.matches(Class::IN));
+        assert!(!Class::IN.matches(Class::CH));
+        assert!(!Class::NONE.matches(Class::NONE));
+        assert!(Class::NONE.is_meta() && Class::ANY.is_meta());
+        assert!(!Class::IN.is_meta() && !Class::Unknown(42).is_meta());
+    }
+}
@@ -1,135 +1,92 @@
 use super::DNSError;
-use super::DNSPacketBuffer;
+use super::Name;
+use super::{idna_to_ascii, idna_to_unicode};
 
-#[derive(Debug)]
-pub struct Domain(pub String);
+/// Domain name, stored as its wire format labels (see Name).
+///
+/// This used to be a `Domain(String)` tuple struct. Names are now built by parsing their
+/// presentation format, with `Domain::new`, `str::parse` or `TryFrom<String>`.
+pub type Domain = Name;
 
-impl PartialEq for Domain {
-    fn eq(&self, other: &Self) -> bool {
-        let (Domain(self_str), Domain(other_str)) = (self, other);
+impl Domain {
+    /// Create a domain from its presentation format, such as "www.example.com". This replaces the
+    /// former `Domain(String)` constructor, and fails for names which are not valid.
+    pub fn new(name: &str) -> Result<Domain, DNSError> {
+        name.parse()
+    }
+
+    /// Create a domain from a possibly internationalized name, converting its labels to their
+    /// ASCII form (see idna_to_ascii). "Bücher.example" becomes "xn--bcher-kva.example".
+    pub fn from_unicode(name: &str) -> Result<Domain, DNSError> {
+        idna_to_ascii(name)?.parse()
+    }
 
-        self_str.split(&['.', '@']).collect::<Vec<_>>()
-            == other_str.split(&['.', '@']).collect::<Vec<_>>()
+    /// Domain with its Unicode labels converted to their ASCII form (see idna_to_ascii), which is
+    /// how they are written on the wire. Labels which are ASCII or not UTF-8 are kept.
+    pub fn to_ascii(&self) -> Result<Domain, DNSError> {
+        let labels = self
+            .labels()
+            .map(|label| match std::str::from_utf8(label) {
+                Ok(unicode) if !unicode.is_ascii() => {
+                    let ascii = idna_to_ascii(unicode)?;
+                    if ascii.contains('.') {
+                        return Err(DNSError::InvalidIDNA(unicode.to_string()));
+                    }
+                    Ok(ascii.into_bytes())
+                }
+                _ => Ok(label.to_vec()),
+            })
+            .collect::<Result<Vec<_>, DNSError>>()?;
+        Domain::from_labels(labels)
+    }
+
+    /// Name of the domain for display, with its "xn--" labels decoded to Unicode and the other ones
+    /// kept (see idna_to_unicode). Fails for labels which are not UTF-8 or contain dots.
+    pub fn to_unicode(&self) -> Result<String, DNSError> {
+        let labels = self
+            .labels()
+            .map(|label| {
+                let label = std::str::from_utf8(label).map_err(|_| DNSError::NonUTF8)?;
+                if label.contains('.') {
+                    return Err(DNSError::UnrepresentableLabel(label.to_string()));
+                }
+                Ok(label)
+            })
+            .collect::<Result<Vec<_>, DNSError>>()?;
+        idna_to_unicode(&labels.join("."))
     }
 }
 
-impl Domain {
-    /// Parse DNS domain name composed by labels starting from the current buffer pointer's position. Move pointer's
-    /// position to the byte after the last label.
-    pub(crate) fn parse_domain(buffer: &mut DNSPacketBuffer, jump: u8) -> Result<Domain, DNSError> {
-        const MAX_JUMPS: u8 = 5;
-        if jump == MAX_JUMPS {
-            return Err(DNSError::MaxJumps);
-        }
-
-        let mut labels_buf = Vec::<String>::new();
-
-        // Parse each label until a 0 label_size byte is encountered or until a label jump found
-        loop {
-            let jump_or_len_byte = buffer.get_u8()?;
-
-            // If two MSBs are 1, mask with 0xC000 and jump to that position to reuse a previous label,
-            // then jump back
-            if 0b1100_0000 & jump_or_len_byte == 0b1100_0000 {
-                let next_pos = buffer.get_pos() + 2;
-                let jump_pos = buffer.read_u16()? ^ 0b1100_0000_0000_0000;
-                buffer.seek(jump_pos as usize);
-                let Domain(reused_labels) = Domain::parse_domain(buffer, jump + 1)?;
-                labels_buf.push(reused_labels);
-                buffer.seek(next_pos);
-                break;
-            }
-
-            // If byte didn't indicate jump, then it indicates the label size
-            let label_size = buffer.read_u8()?;
-
-            // 0 size byte, finish parsing labels
-            if label_size == 0 {
-                break;
-            }
-
-            let mut label_buf = Vec::<u8>::new();
-
-            // [b'g', b'o', b'o', b'g', b'l', b'e']
-            for _ in 0..label_size {
-                label_buf.push(buffer.read_u8()?);
-            }
-
-            // [b'g', b'o', b'o', b'g', b'l', b'e'] -> "google"
-            let label =
-                (String::from_utf8(label_buf).map_err(|_| DNSError::NonUTF8)?).to_lowercase();
-
-            // ["google"].push("com")
-            labels_buf.push(label);
-        }
-
-        // [google", "com"] -> "google.com"
-        let label_sequence = labels_buf.join(".");
-
-        Ok(Domain(label_sequence))
-    }
-
-    pub(crate) fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
-        const MAX_LABEL_SIZE: usize = 63;
-        const MAX_DOMAIN_SIZE: usize = 253;
-
-        let Domain(domain_string) = self;
-
-        if domain_string.len() > MAX_DOMAIN_SIZE {
-            return Err(DNSError::DomainNameTooLarge(
-                domain_string.clone(),
-                domain_string.len(),
-            ));
-        }
-
-        let labels_vec: Vec<&str> = domain_string.split(&['.', '@']).collect();
-
-        let mut jumped = false;
-
-        for (i, label) in labels_vec.iter().enumerate() {
-            let sequence_section = labels_vec[i..].join(".");
-            // Check if section of label sequence is cached. If it is, use it for DNS compression.
-            if let Some(cached_pos) = buffer.sequence_check_cached(&sequence_section) {
-                buffer.write_u16(cached_pos | 0xC000)?;
-                jumped = true;
-                break;
-            }
-
-            if label.len() > MAX_LABEL_SIZE {
-                return Err(DNSError::LabelTooLarge(label.to_string(), label.len()));
-            }
-
-            // If label sequence is not cached, cache it and write it to buffer.
-            buffer.cache_sequence(&sequence_section, buffer.get_pos() as u16);
-            buffer.write_u8(label.len() as u8)?;
-            for b in label.as_bytes() {
-                buffer.write_u8(*b)?;
-            }
-        }
-
-        if !jumped {
-            buffer.write_u8(0x00)?;
-        }
-        Ok(())
+impl TryFrom<String> for Domain {
+    type Error = DNSError;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Domain::new(&name)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::DNSPacketBuffer;
     use super::*;
 
+    fn domain(name: &str) -> Domain {
+        name.parse().unwrap()
+    }
+
     #[test]
     fn test_parse_domain() {
         let domain_data = [
-            0x06, 0x67, 0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+            0x06, 0x47, 0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
         ];
 
         let mut dns_packet_buffer = DNSPacketBuffer::new(&domain_data);
 
-        let parsed_domain = Domain::parse_domain(&mut dns_packet_buffer, 0).unwrap();
+        let parsed_domain = Domain::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
-        let expected_domain = Domain("google.com".to_string());
-        assert_eq!(parsed_domain, expected_domain);
+        // Case is kept, but ignored by comparisons
+        assert_eq!(parsed_domain, domain("google.com"));
+        assert_eq!(parsed_domain.to_string(), "Google.com.");
     }
 
     #[test]
@@ -142,11 +99,11 @@ mod tests {
 
         let mut dns_packet_buffer = DNSPacketBuffer::new(&domain_data);
 
-        let parsed_domain1 = Domain::parse_domain(&mut dns_packet_buffer, 0);
+        let parsed_domain1 = Domain::parse_from_buffer(&mut dns_packet_buffer);
         dns_packet_buffer.step(4); // Skip rest of the record information. Jump to next domain
-        let parsed_domain2 = Domain::parse_domain(&mut dns_packet_buffer, 0);
+        let parsed_domain2 = Domain::parse_from_buffer(&mut dns_packet_buffer);
 
-        let expected_domain1 = Ok(Domain("google.com".to_string()));
+        let expected_domain1 = Ok(domain("google.com"));
         let expected_domain2 = Err(DNSError::MaxJumps);
 
         assert_eq!(parsed_domain1, expected_domain1);
@@ -155,7 +112,7 @@ mod tests {
 
     #[test]
     fn test_write_to_buffer() {
-        let domain = Domain("api.youtube.com".to_string());
+        let domain = domain("api.youtube.com");
         let mut buffer = DNSPacketBuffer::new(&[]);
         domain.write_to_buffer(&mut buffer).unwrap();
 
@@ -172,8 +129,8 @@ mod tests {
 
     #[test]
     fn test_write_compression() {
-        let domain0 = Domain("api.youtube.com".to_string());
-        let domain1 = Domain("dev.youtube.com".to_string());
+        let domain0 = domain("api.youtube.com");
+        let domain1 = domain("dev.youtube.com");
         let mut buffer = DNSPacketBuffer::new(&[]);
         domain0.write_to_buffer(&mut buffer).unwrap();
         domain1.write_to_buffer(&mut buffer).unwrap();
@@ -189,13 +146,43 @@ mod tests {
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
 
+    #[test]
+    fn test_write_compression_ignores_case() {
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        domain("Example.com").write_to_buffer(&mut buffer).unwrap();
+        domain("www.example.COM")
+            .write_to_buffer(&mut buffer)
+            .unwrap();
+
+        let expected_domain_bytes = [
+            0x07, 0x45, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x03,
+            0x77, 0x77, 0x77, 0xC0, 0x00,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_domain_bytes)
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            Domain::new("www.example.com"),
+            Ok(domain("www.example.com"))
+        );
+        assert_eq!(
+            Domain::try_from("www.example.com".to_string()),
+            Ok(domain("www.example.com"))
+        );
+        assert_eq!(
+            Domain::new("a..com"),
+            Err(DNSError::InvalidEncoding("a..com".to_string()))
+        );
+    }
+
     #[test]
     fn test_label_too_large() {
         let large_label =
             "apigodanfpandsadkjsabdjkasdjasjdnapfnapifamnfpkamnfpkanfpanspfasfpsanfpa".to_string();
-        let domain = Domain(large_label.clone());
-        let mut buffer = DNSPacketBuffer::new(&[]);
-        let res = domain.write_to_buffer(&mut buffer);
+        let res = large_label.parse::<Domain>();
 
         let expected = Err(DNSError::LabelTooLarge(
             large_label.clone(),
@@ -207,16 +194,136 @@ mod tests {
 
     #[test]
     fn test_domain_too_large() {
-        let super_long_domain = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb.ccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccc.dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd".to_string();
-        let domain = Domain(super_long_domain.clone());
-        let mut buffer = DNSPacketBuffer::new(&[]);
-        let res = domain.write_to_buffer(&mut buffer);
+        let super_long_domain = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb.ccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccc.dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd.e".to_string();
+        let res = super_long_domain.parse::<Domain>();
 
         let expected = Err(DNSError::DomainNameTooLarge(
-            super_long_domain.clone(),
-            super_long_domain.len(),
+            format!("{}.", super_long_domain),
+            super_long_domain.len() + 2,
         ));
 
         assert_eq!(res, expected)
     }
+
+    #[test]
+    fn test_write_root() {
+        let domain = Domain::root();
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        domain.write_to_buffer(&mut buffer).unwrap();
+        domain.write_to_buffer(&mut buffer).unwrap();
+
+        assert_eq!(buffer.get_data(), [0x00, 0x00])
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(domain("google.com").to_string(), "google.com.");
+        assert_eq!(Domain::root().to_string(), ".");
+        assert_eq!(
+            Domain::from_labels(["a b;c", "\u{e9}", "com"])
+                .unwrap()
+                .to_string(),
+            "a\\ b\\;c.\\195\\169.com."
+        );
+    }
+
+    #[test]
+    fn test_labels_with_dots_and_at() {
+        // Labels may hold dots and @, which used to be taken as label separators
+        let mailbox = domain("john\\.doe.example.com");
+        let at = domain("a@b.example.com");
+        assert_eq!(mailbox.label_count(), 3);
+        assert_ne!(mailbox, domain("john.doe.example.com"));
+        assert_ne!(at, domain("a.b.example.com"));
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        mailbox.write_to_buffer(&mut buffer).unwrap();
+        assert_eq!(buffer.get_data()[..9], *b"\x08john.doe");
+
+        buffer.seek(0);
+        assert_eq!(Domain::parse_from_buffer(&mut buffer), Ok(mailbox));
+    }
+
+    #[test]
+    fn test_unicode() {
+        let bucher = Domain::from_unicode("Bücher.example").unwrap();
+        assert_eq!(bucher, domain("xn--bcher-kva.example"));
+        assert_eq!(bucher.to_unicode(), Ok("bücher.example".to_string()));
+        assert_eq!(Domain::root().to_unicode(), Ok("".to_string()));
+        assert_eq!(
+            domain("_smimecert._tcp.XN--BCHER-KVA.example").to_unicode(),
+            Ok("_smimecert._tcp.bücher.example".to_string())
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        bucher.write_to_buffer(&mut buffer).unwrap();
+        assert_eq!(buffer.get_data()[..14], *b"\x0dxn--bcher-kva");
+    }
+
+    #[test]
+    fn test_write_unicode() {
+        // Unicode labels are written in their ASCII form
+        let bucher = domain("Bücher.example");
+        assert_eq!(bucher.to_ascii(), Ok(domain("xn--bcher-kva.example")));
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        bucher.write_to_buffer(&mut buffer).unwrap();
+        domain("www.bücher.example")
+            .write_to_buffer(&mut buffer)
+            .unwrap();
+        assert_eq!(
+            buffer.get_data(),
+            b"\x0dxn--bcher-kva\x07example\x00\x03www\xC0\x00"
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        assert_eq!(
+            domain("a\u{200C}b.example").write_to_buffer(&mut buffer),
+            Err(DNSError::InvalidIDNA("a\u{200C}b".to_string()))
+        );
+        assert_eq!(
+            domain("a\u{3002}b.example").to_ascii(),
+            Err(DNSError::InvalidIDNA("a\u{3002}b".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_write_past_pointer_range() {
+        // Names written past offset 0x3FFF cannot be the target of a 14 bit compression pointer
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.write_bytes(&[0; 0x4000]).unwrap();
+        domain("example.com").write_to_buffer(&mut buffer).unwrap();
+        domain("www.example.com")
+            .write_to_buffer(&mut buffer)
+            .unwrap();
+
+        assert_eq!(
+            buffer.get_data()[0x4000..0x400D],
+            *b"\x07example\x03com\x00"
+        );
+        assert_eq!(
+            buffer.get_data()[0x400D..],
+            *b"\x03www\x07example\x03com\x00"
+        );
+
+        buffer.seek(0x400D);
+        assert_eq!(
+            Domain::parse_from_buffer(&mut buffer),
+            Ok(domain("www.example.com"))
+        );
+    }
+
+    #[test]
+    fn test_write_uncompressed() {
+        let domain = domain("youtube.com");
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        domain.write_to_buffer(&mut buffer).unwrap();
+        domain.write_uncompressed_to_buffer(&mut buffer).unwrap();
+
+        let expected_domain_bytes = [
+            0x07, 0x79, 0x6f, 0x75, 0x74, 0x75, 0x62, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x07,
+            0x79, 0x6f, 0x75, 0x74, 0x75, 0x62, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_domain_bytes)
+    }
 }
@@ -0,0 +1,308 @@
+use super::DNSError;
+
+const BASE32HEX_ALPHABET: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+
+/// Encode bytes as unpadded lowercase base32 with extended hex alphabet (RFC 4648 section 7), as
+/// used by NSEC3 hashed owner names.
+pub(crate) fn base32hex_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity((data.len() * 8).div_ceil(5));
+    let mut acc: u16 = 0;
+    let mut bits = 0;
+
+    for b in data {
+        acc = (acc << 8) | *b as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32HEX_ALPHABET[((acc >> bits) & 0x1F) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32HEX_ALPHABET[((acc << (5 - bits)) & 0x1F) as usize] as char);
+    }
+
+    encoded
+}
+
+/// Decode unpadded base32 with extended hex alphabet, case insensitive.
+pub(crate) fn base32hex_decode(encoded: &str) -> Result<Vec<u8>, DNSError> {
+    let mut decoded = Vec::<u8>::with_capacity(encoded.len() * 5 / 8);
+    let mut acc: u16 = 0;
+    let mut bits = 0;
+
+    for c in encoded.bytes() {
+        let val = match c.to_ascii_lowercase() {
+            c @ b'0'..=b'9' => c - b'0',
+            c @ b'a'..=b'v' => c - b'a' + 10,
+            _ => return Err(DNSError::InvalidEncoding(encoded.to_string())),
+        };
+        acc = (acc << 5) | val as u16;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((acc >> bits) as u8);
+        }
+    }
+
+    // Leftover bits must be padding zeros
+    if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+        return Err(DNSError::InvalidEncoding(encoded.to_string()));
+    }
+
+    Ok(decoded)
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes as padded base64 (RFC 4648 section 4), as used by the presentation format of keys
+/// and signatures.
+pub(crate) fn base64_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let acc = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[((acc >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Decode base64, with or without padding.
+pub(crate) fn base64_decode(encoded: &str) -> Result<Vec<u8>, DNSError> {
+    let invalid = || DNSError::InvalidEncoding(encoded.to_string());
+    let data = encoded.trim_end_matches('=');
+    if encoded.len() - data.len() > 2 {
+        return Err(invalid());
+    }
+
+    let mut decoded = Vec::<u8>::with_capacity(data.len() * 3 / 4);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+
+    for c in data.bytes() {
+        let val = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return Err(invalid()),
+        };
+        acc = (acc << 6) | val as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((acc >> bits) as u8);
+        }
+    }
+
+    // Leftover bits must be padding zeros
+    if bits >= 6 || acc & ((1 << bits) - 1) != 0 {
+        return Err(invalid());
+    }
+
+    Ok(decoded)
+}
+
+/// Encode bytes as uppercase hexadecimal.
+pub(crate) fn hex_encode(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// Decode hexadecimal, case insensitive.
+pub(crate) fn hex_decode(encoded: &str) -> Result<Vec<u8>, DNSError> {
+    let invalid = || DNSError::InvalidEncoding(encoded.to_string());
+    if !encoded.len().is_multiple_of(2) || !encoded.is_ascii() {
+        return Err(invalid());
+    }
+
+    (0..encoded.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&encoded[i..i + 2], 16).map_err(|_| invalid()))
+        .collect()
+}
+
+/// Escape a byte as required in the presentation format (RFC 1035 section 5.1): non-printable
+/// bytes become \\DDD and the given special characters are preceded by a backslash.
+pub(crate) fn escape_byte(b: u8, special: &[u8]) -> String {
+    match b {
+        b if special.contains(&b) || b == b'\\' => format!("\\{}", b as char),
+        0x20..=0x7E => (b as char).to_string(),
+        b => format!("\\{:03}", b),
+    }
+}
+
+/// Format a <character-string> as a quoted string (RFC 1035 section 5.1).
+pub(crate) fn quote_character_string(data: &[u8]) -> String {
+    let escaped: String = data.iter().map(|b| escape_byte(*b, b"\"")).collect();
+    format!("\"{}\"", escaped)
+}
+
+/// Days since the UNIX epoch of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month as i64 + 9) % 12; // March is 0
+    let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Date in the proleptic Gregorian calendar of a day since the UNIX epoch.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153; // March is 0
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+/// Parse an RRSIG timestamp, either as YYYYMMDDHHmmSS in UTC or as seconds since the UNIX epoch
+/// (RFC 4034 section 3.2).
+pub(crate) fn parse_rrsig_time(text: &str) -> Result<u32, DNSError> {
+    let invalid = || DNSError::InvalidEncoding(text.to_string());
+    if !text.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    if text.len() != 14 {
+        return text.parse::<u32>().map_err(|_| invalid());
+    }
+
+    let field = |range: std::ops::Range<usize>| text[range].parse::<u32>().unwrap_or(0);
+    let (year, month, day) = (field(0..4) as i64, field(4..6), field(6..8));
+    let (hour, minute, second) = (field(8..10), field(10..12), field(12..14));
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return Err(invalid());
+    }
+    if second > 60 {
+        return Err(invalid());
+    }
+
+    let secs = days_from_civil(year, month, day) * 86400
+        + hour as i64 * 3600
+        + minute as i64 * 60
+        + second as i64;
+    // Timestamps wrap around every 2^32 seconds
+    Ok(secs.rem_euclid(1 << 32) as u32)
+}
+
+/// Format an RRSIG timestamp as YYYYMMDDHHmmSS in UTC.
+pub(crate) fn format_rrsig_time(timestamp: u32) -> String {
+    let secs = timestamp as i64;
+    let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+    let secs_of_day = secs.rem_euclid(86400);
+    format!(
+        "{:04}{:02}{:02}{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base32hex() {
+        // RFC 4648 section 10 test vectors, unpadded and lowercased
+        let vectors = [
+            ("", ""),
+            ("f", "co"),
+            ("fo", "cpng"),
+            ("foo", "cpnmu"),
+            ("foob", "cpnmuog"),
+            ("fooba", "cpnmuoj1"),
+            ("foobar", "cpnmuoj1e8"),
+        ];
+
+        for (data, encoded) in vectors {
+            assert_eq!(base32hex_encode(data.as_bytes()), encoded);
+            assert_eq!(base32hex_decode(encoded).unwrap(), data.as_bytes());
+        }
+        assert_eq!(base32hex_decode("CPNMUOJ1E8").unwrap(), b"foobar");
+        assert_eq!(
+            base32hex_decode("cpnmuoj1e9"),
+            Err(DNSError::InvalidEncoding("cpnmuoj1e9".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_base64() {
+        // RFC 4648 section 10 test vectors
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (data, encoded) in vectors {
+            assert_eq!(base64_encode(data.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded).unwrap(), data.as_bytes());
+        }
+        assert_eq!(base64_decode("Zm9vYg").unwrap(), b"foob");
+        assert_eq!(
+            base64_decode("Zm9v!"),
+            Err(DNSError::InvalidEncoding("Zm9v!".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(hex_encode(&[0x00, 0xAB, 0x3F]), "00AB3F");
+        assert_eq!(hex_decode("00ab3F").unwrap(), vec![0x00, 0xAB, 0x3F]);
+        assert_eq!(
+            hex_decode("0AB"),
+            Err(DNSError::InvalidEncoding("0AB".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rrsig_time() {
+        // RFC 4034 section 3.3 example
+        assert_eq!(parse_rrsig_time("20030322173103").unwrap(), 1048354263);
+        assert_eq!(format_rrsig_time(1048354263), "20030322173103");
+        assert_eq!(parse_rrsig_time("1048354263").unwrap(), 1048354263);
+        assert_eq!(format_rrsig_time(0), "19700101000000");
+        // Dates after 2106 wrap around
+        assert_eq!(parse_rrsig_time("21060207062816").unwrap(), 0);
+        assert!(parse_rrsig_time("20031322173103").is_err());
+    }
+
+    #[test]
+    fn test_quote_character_string() {
+        assert_eq!(quote_character_string(b"v=spf1 -all"), "\"v=spf1 -all\"");
+        assert_eq!(
+            quote_character_string("a\"b\\c\n\u{e9}".as_bytes()),
+            "\"a\\\"b\\\\c\\010\\195\\169\""
+        );
+    }
+}
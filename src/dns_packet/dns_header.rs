@@ -2,8 +2,9 @@ use super::DNSError;
 use super::DNSPacketBuffer;
 #[cfg(test)]
 use super::{HEADER_SIZE, PACKET_SIZE};
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum ResponseCode {
     NoError,
     FormErr,
@@ -13,12 +14,26 @@ pub enum ResponseCode {
     Refused,
     YXDomain,
     XRRset,
+    NXRRset,
     NoAuth,
     NotZone,
+    DSOTypeNI,
+    // The following codes require EDNS, the upper 8 bits are carried in the OPT record
+    BadVers,
+    // The following codes are only carried by TSIG and TKEY records
+    BadSig,
+    BadKey,
+    BadTime,
+    BadMode,
+    BadName,
+    BadAlg,
+    BadTrunc,
+    BadCookie,
+    Unknown(u16), // Unassigned or private use code, up to 12 bits with EDNS
 }
 
 impl ResponseCode {
-    fn to_num(&self) -> u8 {
+    pub fn to_num(&self) -> u16 {
         match self {
             Self::NoError => 0,
             Self::FormErr => 1,
@@ -28,11 +43,27 @@ impl ResponseCode {
             Self::Refused => 5,
             Self::YXDomain => 6,
             Self::XRRset => 7,
-            Self::NoAuth => 8,
-            Self::NotZone => 9,
+            Self::NXRRset => 8,
+            Self::NoAuth => 9,
+            Self::NotZone => 10,
+            Self::DSOTypeNI => 11,
+            Self::BadVers => 16,
+            Self::BadSig => 16,
+            Self::BadKey => 17,
+            Self::BadTime => 18,
+            Self::BadMode => 19,
+            Self::BadName => 20,
+            Self::BadAlg => 21,
+            Self::BadTrunc => 22,
+            Self::BadCookie => 23,
+            Self::Unknown(code) => *code,
         }
     }
-    fn from_num(code_num: u8) -> Result<ResponseCode, DNSError> {
+
+    /// Response code of a number. Code 16 is BADVERS, as BADSIG shares it but only appears in TSIG
+    /// records. Only codes above 4095, which don't fit in the 12 bits carried with EDNS, are
+    /// rejected.
+    pub fn from_num(code_num: u16) -> Result<ResponseCode, DNSError> {
         match code_num {
             0 => Ok(Self::NoError),
             1 => Ok(Self::FormErr),
@@ -42,23 +73,126 @@ impl ResponseCode {
             5 => Ok(Self::Refused),
             6 => Ok(Self::YXDomain),
             7 => Ok(Self::XRRset),
-            8 => Ok(Self::NoAuth),
-            9 => Ok(Self::NotZone),
+            8 => Ok(Self::NXRRset),
+            9 => Ok(Self::NoAuth),
+            10 => Ok(Self::NotZone),
+            11 => Ok(Self::DSOTypeNI),
+            16 => Ok(Self::BadVers),
+            17 => Ok(Self::BadKey),
+            18 => Ok(Self::BadTime),
+            19 => Ok(Self::BadMode),
+            20 => Ok(Self::BadName),
+            21 => Ok(Self::BadAlg),
+            22 => Ok(Self::BadTrunc),
+            23 => Ok(Self::BadCookie),
+            code if code <= 0x0FFF => Ok(Self::Unknown(code)),
             _ => Err(DNSError::UnknownResponseCode(code_num)),
         }
     }
+
+    /// Response code of the 4 bit header field, which never fails.
+    pub fn from_header_num(code_num: u8) -> ResponseCode {
+        Self::from_num((code_num & 0x0F) as u16).unwrap_or(Self::Unknown((code_num & 0x0F) as u16))
+    }
+}
+
+/// Response code mnemonic as used by dig, e.g. NXDOMAIN, or the code number if it has none.
+impl fmt::Display for ResponseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = match self {
+            Self::NoError => "NOERROR",
+            Self::FormErr => "FORMERR",
+            Self::ServFail => "SERVFAIL",
+            Self::NXDomain => "NXDOMAIN",
+            Self::NoTimp => "NOTIMP",
+            Self::Refused => "REFUSED",
+            Self::YXDomain => "YXDOMAIN",
+            Self::XRRset => "YXRRSET",
+            Self::NXRRset => "NXRRSET",
+            Self::NoAuth => "NOTAUTH",
+            Self::NotZone => "NOTZONE",
+            Self::DSOTypeNI => "DSOTYPENI",
+            Self::BadVers => "BADVERS",
+            Self::BadSig => "BADSIG",
+            Self::BadKey => "BADKEY",
+            Self::BadTime => "BADTIME",
+            Self::BadMode => "BADMODE",
+            Self::BadName => "BADNAME",
+            Self::BadAlg => "BADALG",
+            Self::BadTrunc => "BADTRUNC",
+            Self::BadCookie => "BADCOOKIE",
+            Self::Unknown(code) => return write!(f, "{}", code),
+        };
+        write!(f, "{}", mnemonic)
+    }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Opcode {
+    Query,       // Standard query
+    IQuery,      // Inverse query, obsolete
+    Status,      // Server status request
+    Notify,      // Zone change notification
+    Update,      // Dynamic update
+    DSO,         // DNS Stateful Operations
+    Unknown(u8), // Unassigned opcode
+}
+
+impl Opcode {
+    /// Opcode of the 4 bit header field. Only the lower 4 bits of the code are used.
+    pub fn from_num(code_num: u8) -> Opcode {
+        match code_num & 0x0F {
+            0 => Self::Query,
+            1 => Self::IQuery,
+            2 => Self::Status,
+            4 => Self::Notify,
+            5 => Self::Update,
+            6 => Self::DSO,
+            code => Self::Unknown(code),
+        }
+    }
+
+    pub fn to_num(&self) -> u8 {
+        match self {
+            Self::Query => 0,
+            Self::IQuery => 1,
+            Self::Status => 2,
+            Self::Notify => 4,
+            Self::Update => 5,
+            Self::DSO => 6,
+            Self::Unknown(code) => *code & 0x0F,
+        }
+    }
+}
+
+/// Opcode mnemonic as used by dig, or the opcode number if it has none.
+impl fmt::Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = match self {
+            Self::Query => "QUERY",
+            Self::IQuery => "IQUERY",
+            Self::Status => "STATUS",
+            Self::Notify => "NOTIFY",
+            Self::Update => "UPDATE",
+            Self::DSO => "DSO",
+            Self::Unknown(code) => return write!(f, "{}", code),
+        };
+        write!(f, "{}", mnemonic)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Header {
     pub id: u16,                     // 2 bytes
     pub query_response: bool,        // 1 bit
-    pub opcode: u8,                  // 4 bits
+    pub opcode: Opcode,              // 4 bits
     pub authoritative_answer: bool,  // 1 bit
     pub truncated_message: bool,     // 1 bit
     pub recursion_desired: bool,     // 1 bit
     pub recursion_available: bool,   // 1 bit
-    pub reserved: u8,                // 3 bits
+    pub reserved: bool,              // 1 bit, Z
+    pub authentic_data: bool,        // 1 bit, AD
+    pub checking_disabled: bool,     // 1 bit, CD
     pub response_code: ResponseCode, // 4 bits
     pub question_count: u16,         // 2 bytes
     pub answer_count: u16,           // 2 bytes
@@ -76,15 +210,17 @@ impl Header {
 
         let mut next_byte = buffer.read_u8()?;
         let query_response = next_byte & 0b1000_0000 != 0;
-        let opcode = (next_byte & 0b0111_1000) >> 3;
+        let opcode = Opcode::from_num((next_byte & 0b0111_1000) >> 3);
         let authoritative_answer = next_byte & 0b0000_0100 != 0;
         let truncated_message = next_byte & 0b0000_0010 != 0;
         let recursion_desired = next_byte & 0b0000_0001 != 0;
 
         next_byte = buffer.read_u8()?;
         let recursion_available = next_byte & 0b1000_0000 != 0;
-        let reserved = (next_byte & 0b0111_0000) >> 4;
-        let response_code = ResponseCode::from_num(next_byte & 0b0000_1111)?;
+        let reserved = next_byte & 0b0100_0000 != 0;
+        let authentic_data = next_byte & 0b0010_0000 != 0;
+        let checking_disabled = next_byte & 0b0001_0000 != 0;
+        let response_code = ResponseCode::from_header_num(next_byte & 0b0000_1111);
 
         let question_count = buffer.read_u16()?;
         let answer_count = buffer.read_u16()?;
@@ -100,6 +236,8 @@ impl Header {
             recursion_desired,
             recursion_available,
             reserved,
+            authentic_data,
+            checking_disabled,
             response_code,
             question_count,
             answer_count,
@@ -118,7 +256,7 @@ impl Header {
 
         // NOTE: Third byte
         let third_byte = (self.query_response as u8) << 7
-            | self.opcode << 3
+            | self.opcode.to_num() << 3
             | (self.authoritative_answer as u8) << 2
             | (self.truncated_message as u8) << 1
             | (self.recursion_desired as u8);
@@ -127,8 +265,10 @@ impl Header {
 
         // NOTE: Fourth byte
         let fourth_byte = (self.recursion_available as u8) << 7
-            | self.reserved << 4
-            | self.response_code.to_num();
+            | (self.reserved as u8) << 6
+            | (self.authentic_data as u8) << 5
+            | (self.checking_disabled as u8) << 4
+            | (self.response_code.to_num() & 0x000F) as u8;
 
         buffer.write_u8(fourth_byte)?;
 
@@ -158,13 +298,15 @@ mod tests {
         let expected_header = Header {
             id: 0x5544,
             query_response: false,
-            opcode: 15,
+            opcode: Opcode::Unknown(15),
             authoritative_answer: true,
             truncated_message: true,
             recursion_desired: false,
             recursion_available: true,
-            reserved: 7,
-            response_code: ResponseCode::NotZone,
+            reserved: true,
+            authentic_data: true,
+            checking_disabled: true,
+            response_code: ResponseCode::NoAuth,
             question_count: 0xABCD,
             answer_count: 0xEF12,
             authority_count: 0x3456,
@@ -175,17 +317,33 @@ mod tests {
     }
 
     #[test]
-    fn test_wrong_rcode() {
+    fn test_unknown_rcode() {
         let dns_packet_data = [
             0x55, 0x44, 0x7E, 0xFF, 0xAB, 0xCD, 0xEF, 0x12, 0x34, 0x56, 0x78, 0x91,
         ];
 
         let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
-        let parsed_dns_header = Header::parse_from_buffer(&mut dns_packet_buffer);
-
-        let expected = Err(DNSError::UnknownResponseCode(0xF));
-
-        assert_eq!(parsed_dns_header, expected);
+        let parsed_dns_header = Header::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+        assert_eq!(parsed_dns_header.response_code, ResponseCode::Unknown(15));
+
+        let mut buffer = DNSPacketBuffer::new(&[0; HEADER_SIZE]);
+        parsed_dns_header.write_to_buffer(&mut buffer).unwrap();
+        assert_eq!(buffer.get_data(), dns_packet_data);
+
+        assert_eq!(ResponseCode::from_num(10), Ok(ResponseCode::NotZone));
+        assert_eq!(ResponseCode::from_num(23), Ok(ResponseCode::BadCookie));
+        assert_eq!(ResponseCode::from_num(16), Ok(ResponseCode::BadVers));
+        assert_eq!(ResponseCode::from_num(200), Ok(ResponseCode::Unknown(200)));
+        assert_eq!(
+            ResponseCode::from_num(3841),
+            Ok(ResponseCode::Unknown(3841))
+        );
+        assert_eq!(
+            ResponseCode::from_num(4096),
+            Err(DNSError::UnknownResponseCode(4096))
+        );
+        assert_eq!(ResponseCode::BadSig.to_num(), 16);
+        assert_eq!(ResponseCode::Unknown(200).to_string(), "200");
     }
 
     #[test]
@@ -193,13 +351,15 @@ mod tests {
         let header = Header {
             id: 0x5544,
             query_response: false,
-            opcode: 15,
+            opcode: Opcode::Unknown(15),
             authoritative_answer: false,
             truncated_message: true,
             recursion_desired: false,
             recursion_available: true,
-            reserved: 7,
-            response_code: ResponseCode::NotZone,
+            reserved: true,
+            authentic_data: true,
+            checking_disabled: true,
+            response_code: ResponseCode::NoAuth,
             question_count: 0xABCD,
             answer_count: 0xEF12,
             authority_count: 0x3456,
@@ -223,4 +383,49 @@ mod tests {
 
         assert_eq!(buffer, expected_buffer)
     }
+
+    #[test]
+    fn test_opcode() {
+        for code in 0..16 {
+            assert_eq!(Opcode::from_num(code).to_num(), code);
+        }
+        assert_eq!(Opcode::from_num(5), Opcode::Update);
+        assert_eq!(Opcode::from_num(3), Opcode::Unknown(3));
+        assert_eq!(Opcode::Notify.to_string(), "NOTIFY");
+        assert_eq!(Opcode::Unknown(3).to_string(), "3");
+    }
+
+    #[test]
+    fn test_dnssec_flags() {
+        let header = Header {
+            id: 0x1234,
+            query_response: true,
+            opcode: Opcode::Notify,
+            authoritative_answer: false,
+            truncated_message: false,
+            recursion_desired: false,
+            recursion_available: false,
+            reserved: false,
+            authentic_data: true,
+            checking_disabled: false,
+            response_code: ResponseCode::NoError,
+            question_count: 0,
+            answer_count: 0,
+            authority_count: 0,
+            additional_count: 0,
+        };
+
+        for (checking_disabled, fourth_byte) in [(false, 0x20), (true, 0x30)] {
+            let header = Header {
+                checking_disabled,
+                ..header.clone()
+            };
+            let mut buffer = DNSPacketBuffer::new(&[0; HEADER_SIZE]);
+            header.write_to_buffer(&mut buffer).unwrap();
+            assert_eq!(buffer.get_data()[2..4], [0xA0, fourth_byte]);
+
+            buffer.seek(0);
+            assert_eq!(Header::parse_from_buffer(&mut buffer).unwrap(), header);
+        }
+    }
 }
@@ -0,0 +1,169 @@
+use super::DNSError;
+use idna::uts46::{AsciiDenyList, DnsLength, Hyphens, Uts46};
+
+const ACE_PREFIX: &str = "xn--";
+const MAX_LABEL_SIZE: usize = 63;
+const MAX_DOMAIN_SIZE: usize = 253;
+
+/// Convert a domain name to its ASCII form (UTS #46 section 4.2, nontransitional processing):
+/// labels are mapped, normalized to NFC and validated, including the CONTEXTJ rules for the zero
+/// width joiners and the Bidi rules, and the ones which are not ASCII are encoded with Punycode
+/// behind the "xn--" prefix. Labels already in that form are checked to decode to valid labels.
+///
+/// The STD3 rules are not applied, so that labels such as "_sip" or "_dmarc" stay valid. Hyphens
+/// are only disallowed at the start and end of labels.
+pub fn idna_to_ascii(name: &str) -> Result<String, DNSError> {
+    let ascii = Uts46::new()
+        .to_ascii(
+            name.as_bytes(),
+            AsciiDenyList::EMPTY,
+            Hyphens::CheckFirstLast,
+            DnsLength::Ignore,
+        )
+        .map_err(|_| DNSError::InvalidIDNA(name.to_string()))?;
+    let ascii = ascii.strip_suffix('.').unwrap_or(&ascii);
+    if ascii.is_empty() {
+        return Ok(String::new());
+    }
+
+    for label in ascii.split('.') {
+        if label.is_empty() {
+            return Err(DNSError::EmptyLabel(name.to_string()));
+        }
+        if label.len() > MAX_LABEL_SIZE {
+            return Err(DNSError::LabelTooLarge(label.to_string(), label.len()));
+        }
+    }
+    if ascii.len() > MAX_DOMAIN_SIZE {
+        return Err(DNSError::DomainNameTooLarge(ascii.to_string(), ascii.len()));
+    }
+    Ok(ascii.to_string())
+}
+
+/// Convert a domain name to its Unicode form for display (UTS #46 section 4.3): labels with the
+/// "xn--" prefix are decoded and validated, and the other ones are kept unchanged.
+pub fn idna_to_unicode(name: &str) -> Result<String, DNSError> {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    if name.is_empty() {
+        return Ok(String::new());
+    }
+
+    let labels = name
+        .split('.')
+        .map(|label| {
+            let is_ace = label
+                .get(..ACE_PREFIX.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(ACE_PREFIX));
+            if !is_ace {
+                return Ok(label.to_string());
+            }
+            let (unicode, result) = Uts46::new().to_unicode(
+                label.as_bytes(),
+                AsciiDenyList::EMPTY,
+                Hyphens::CheckFirstLast,
+            );
+            result.map_err(|_| DNSError::InvalidIDNA(label.to_string()))?;
+            Ok(unicode.into_owned())
+        })
+        .collect::<Result<Vec<String>, DNSError>>()?;
+    Ok(labels.join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ascii() {
+        assert_eq!(
+            idna_to_ascii("Bücher.Example."),
+            Ok("xn--bcher-kva.example".to_string())
+        );
+        assert_eq!(
+            idna_to_ascii("日本語。ＪＰ"),
+            Ok("xn--wgv71a119e.jp".to_string())
+        );
+        assert_eq!(
+            idna_to_ascii("ex\u{00AD}ample.com"),
+            Ok("example.com".to_string())
+        );
+        // Nontransitional processing keeps the sharp s
+        assert_eq!(idna_to_ascii("faß.de"), Ok("xn--fa-hia.de".to_string()));
+        assert_eq!(
+            idna_to_ascii("XN--BCHER-KVA.com"),
+            Ok("xn--bcher-kva.com".to_string())
+        );
+        // Names are normalized to NFC
+        assert_eq!(
+            idna_to_ascii("cafe\u{0301}.fr"),
+            Ok("xn--caf-dma.fr".to_string())
+        );
+        // Zero width non-joiner after a virama (RFC 5892 appendix A.1)
+        assert_eq!(
+            idna_to_ascii("\u{0915}\u{094D}\u{200C}\u{0937}.in"),
+            Ok("xn--11b2ezcs70k.in".to_string())
+        );
+        assert_eq!(
+            idna_to_ascii("_sip._tcp.Example.com"),
+            Ok("_sip._tcp.example.com".to_string())
+        );
+        assert_eq!(idna_to_ascii("."), Ok(String::new()));
+
+        assert_eq!(
+            idna_to_ascii("a..com"),
+            Err(DNSError::EmptyLabel("a..com".to_string()))
+        );
+        assert_eq!(
+            idna_to_ascii("a\u{200C}b.com"),
+            Err(DNSError::InvalidIDNA("a\u{200C}b.com".to_string()))
+        );
+        assert_eq!(
+            idna_to_ascii("-bücher.com"),
+            Err(DNSError::InvalidIDNA("-bücher.com".to_string()))
+        );
+        assert_eq!(
+            idna_to_ascii("\u{0301}a.com"),
+            Err(DNSError::InvalidIDNA("\u{0301}a.com".to_string()))
+        );
+        assert_eq!(
+            idna_to_ascii("xn--99999999999.com"),
+            Err(DNSError::InvalidIDNA("xn--99999999999.com".to_string()))
+        );
+        let long_label = "ü".repeat(60);
+        assert!(matches!(
+            idna_to_ascii(&long_label),
+            Err(DNSError::LabelTooLarge(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_to_unicode() {
+        assert_eq!(
+            idna_to_unicode("xn--bcher-kva.example"),
+            Ok("bücher.example".to_string())
+        );
+        assert_eq!(
+            idna_to_unicode("XN--WGV71A119E.jp."),
+            Ok("日本語.jp".to_string())
+        );
+        // ASCII labels are kept as they are
+        assert_eq!(
+            idna_to_unicode("_sip._tcp.Example.com"),
+            Ok("_sip._tcp.Example.com".to_string())
+        );
+        assert_eq!(
+            idna_to_unicode("_dmarc.xn--bcher-kva.example"),
+            Ok("_dmarc.bücher.example".to_string())
+        );
+
+        // ACE labels must decode to valid, mapped labels in their canonical encoding
+        assert_eq!(
+            idna_to_unicode("xn--wca.example"),
+            Err(DNSError::InvalidIDNA("xn--wca".to_string()))
+        );
+        assert_eq!(
+            idna_to_unicode("xn--abc-.example"),
+            Err(DNSError::InvalidIDNA("xn--abc-".to_string()))
+        );
+    }
+}
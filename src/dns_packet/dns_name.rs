@@ -0,0 +1,522 @@
+use super::escape_byte;
+use super::DNSError;
+use super::DNSPacketBuffer;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+pub const MAX_LABEL_SIZE: usize = 63;
+/// Longest name in wire format, including the length bytes and the root label.
+pub const MAX_NAME_SIZE: usize = 255;
+
+/// Absolute domain name stored as its wire format labels, from the leftmost one. The root label
+/// is not included, so the root name has no labels.
+///
+/// Labels may hold any byte, dots included, and keep their case. Names are compared and hashed
+/// case-insensitively, and ordered in canonical DNS name order (RFC 4034 section 6.1).
+#[derive(Clone, Default)]
+pub struct Name {
+    labels: Vec<Vec<u8>>,
+}
+
+impl Name {
+    pub fn root() -> Self {
+        Name::default()
+    }
+
+    /// Create a name from its labels, from the leftmost one. Labels must not be empty nor longer
+    /// than 63 bytes, and the whole name must fit in 255 bytes.
+    pub fn from_labels<I, L>(labels: I) -> Result<Self, DNSError>
+    where
+        I: IntoIterator<Item = L>,
+        L: AsRef<[u8]>,
+    {
+        let name = Name {
+            labels: labels
+                .into_iter()
+                .map(|label| label.as_ref().to_vec())
+                .collect(),
+        };
+        for label in name.labels.iter() {
+            if label.is_empty() {
+                return Err(DNSError::InvalidEncoding(name.to_string()));
+            }
+            if label.len() > MAX_LABEL_SIZE {
+                return Err(DNSError::LabelTooLarge(
+                    String::from_utf8_lossy(label).to_string(),
+                    label.len(),
+                ));
+            }
+        }
+        if name.wire_len() > MAX_NAME_SIZE {
+            return Err(DNSError::DomainNameTooLarge(
+                name.to_string(),
+                name.wire_len(),
+            ));
+        }
+        Ok(name)
+    }
+
+    /// Labels of the name, from the leftmost one.
+    pub fn labels(&self) -> impl DoubleEndedIterator<Item = &[u8]> + ExactSizeIterator {
+        self.labels.iter().map(Vec::as_slice)
+    }
+
+    /// Amount of labels of the name, not counting the root label.
+    pub fn label_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Whether the leftmost label is the wildcard label "*" (RFC 4592).
+    pub fn is_wildcard(&self) -> bool {
+        self.labels.first().is_some_and(|label| label == b"*")
+    }
+
+    /// Length of the name in uncompressed wire format.
+    pub fn wire_len(&self) -> usize {
+        self.labels
+            .iter()
+            .map(|label| label.len() + 1)
+            .sum::<usize>()
+            + 1
+    }
+
+    /// Name without its leftmost label, or None for the root.
+    pub fn parent(&self) -> Option<Name> {
+        let (_, labels) = self.labels.split_first()?;
+        Some(Name {
+            labels: labels.to_vec(),
+        })
+    }
+
+    /// Whether the name is equal to or below another one.
+    pub fn is_subdomain_of(&self, ancestor: &Name) -> bool {
+        self.labels.len() >= ancestor.labels.len()
+            && self
+                .labels
+                .iter()
+                .rev()
+                .zip(ancestor.labels.iter().rev())
+                .all(|(label, ancestor_label)| label.eq_ignore_ascii_case(ancestor_label))
+    }
+
+    /// Name made of the labels of this name followed by the labels of the suffix, failing if it is
+    /// longer than 255 bytes.
+    pub fn append(&self, suffix: &Name) -> Result<Name, DNSError> {
+        Name::from_labels(self.labels.iter().chain(suffix.labels.iter()))
+    }
+
+    /// Name with the ancestor at its end replaced by the target, as done by DNAME substitution
+    /// (RFC 6672 section 2.2). Fails if the name is not below the ancestor, or if the result is
+    /// longer than 255 bytes.
+    pub fn replace_ancestor(&self, ancestor: &Name, target: &Name) -> Result<Name, DNSError> {
+        if !self.is_subdomain_of(ancestor) {
+            return Err(DNSError::OutOfZone(self.to_string()));
+        }
+        let prefix = &self.labels[..self.labels.len() - ancestor.labels.len()];
+        Name::from_labels(prefix.iter().chain(target.labels.iter()))
+    }
+
+    /// Name with its labels lowercased, as in canonical form (RFC 4034 section 6.2).
+    pub fn to_lowercase(&self) -> Name {
+        Name {
+            labels: self
+                .labels
+                .iter()
+                .map(|label| label.to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Parse a name starting from the current buffer pointer's position, following compression
+    /// pointers. Move pointer's position to the byte after the name.
+    pub(crate) fn parse_from_buffer(buffer: &mut DNSPacketBuffer) -> Result<Name, DNSError> {
+        const MAX_JUMPS: u8 = 5;
+        let mut labels = Vec::<Vec<u8>>::new();
+        let mut return_pos: Option<usize> = None;
+        let mut jumps = 0;
+
+        loop {
+            let len_byte = buffer.read_u8()?;
+            match len_byte & 0b1100_0000 {
+                // Compression pointer, the rest of the name is at the given position
+                0b1100_0000 => {
+                    if jumps == MAX_JUMPS {
+                        return Err(DNSError::MaxJumps);
+                    }
+                    jumps += 1;
+                    let jump_pos =
+                        ((len_byte & 0b0011_1111) as usize) << 8 | buffer.read_u8()? as usize;
+                    return_pos.get_or_insert(buffer.get_pos());
+                    buffer.seek(jump_pos);
+                }
+                0b0000_0000 if len_byte == 0 => break,
+                0b0000_0000 => labels.push(buffer.read_bytes(len_byte as usize)?),
+                // Extended label types (RFC 6891 section 5) are not supported
+                _ => {
+                    return Err(DNSError::InvalidEncoding(format!(
+                        "label type {:#04x}",
+                        len_byte
+                    )))
+                }
+            }
+        }
+
+        if let Some(pos) = return_pos {
+            buffer.seek(pos);
+        }
+        Name::from_labels(labels)
+    }
+
+    /// Write name to buffer, using DNS compression when a suffix of it was already written. Names
+    /// are never compressed in canonical buffers, and lowercased when the buffer asks for it.
+    pub(crate) fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        self.write_labels(buffer, true)
+    }
+
+    /// Write name to buffer without DNS compression, as required for fields such as the RRSIG
+    /// signer name or the NSEC next domain name.
+    pub(crate) fn write_uncompressed_to_buffer(
+        &self,
+        buffer: &mut DNSPacketBuffer,
+    ) -> Result<(), DNSError> {
+        self.write_labels(buffer, false)
+    }
+
+    fn write_labels(&self, buffer: &mut DNSPacketBuffer, compress: bool) -> Result<(), DNSError> {
+        const MAX_POINTER_POS: usize = 0x3FFF;
+        let canonical = buffer.is_canonical();
+        // Unicode labels go on the wire in their ASCII form (RFC 5891 section 4.4)
+        if self
+            .labels
+            .iter()
+            .any(|label| !label.is_ascii() && std::str::from_utf8(label).is_ok())
+        {
+            return self.to_ascii()?.write_labels(buffer, compress);
+        }
+
+        for (i, label) in self.labels.iter().enumerate() {
+            // Names are case-insensitive, so a suffix written in any case can be pointed to
+            let sequence_section = escaped_labels(&self.labels[i..]).to_ascii_lowercase();
+            if let Some(cached_pos) = buffer
+                .sequence_check_cached(&sequence_section)
+                .filter(|_| compress && !canonical)
+            {
+                return buffer.write_u16(cached_pos | 0xC000);
+            }
+
+            if buffer.get_pos() <= MAX_POINTER_POS {
+                buffer.cache_sequence(&sequence_section, buffer.get_pos() as u16);
+            }
+            buffer.write_u8(label.len() as u8)?;
+            if buffer.lowercases_names() {
+                buffer.write_bytes(&label.to_ascii_lowercase())?;
+            } else {
+                buffer.write_bytes(label)?;
+            }
+        }
+        buffer.write_u8(0x00)
+    }
+}
+
+/// Labels in presentation format joined by dots, without the trailing dot.
+fn escaped_labels(labels: &[Vec<u8>]) -> String {
+    labels
+        .iter()
+        .map(|label| {
+            label
+                .iter()
+                .map(|b| escape_byte(*b, b".\"();@$ "))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+impl PartialEq for Name {
+    fn eq(&self, other: &Self) -> bool {
+        self.labels.len() == other.labels.len()
+            && self
+                .labels
+                .iter()
+                .zip(other.labels.iter())
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    }
+}
+
+impl Eq for Name {}
+
+impl Hash for Name {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.labels.len());
+        for label in self.labels.iter() {
+            state.write_usize(label.len());
+            for b in label.iter() {
+                state.write_u8(b.to_ascii_lowercase());
+            }
+        }
+    }
+}
+
+/// Canonical DNS name order (RFC 4034 section 6.1): labels are compared from the rightmost one as
+/// lowercase octet sequences, and a name sorts before the names below it.
+impl Ord for Name {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lowercase = |label: &Vec<u8>| label.to_ascii_lowercase();
+        self.labels
+            .iter()
+            .rev()
+            .map(lowercase)
+            .cmp(other.labels.iter().rev().map(lowercase))
+    }
+}
+
+impl PartialOrd for Name {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Name").field(&self.to_string()).finish()
+    }
+}
+
+/// Absolute domain name in presentation format, e.g. "example.com." or "." for the root.
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.labels.is_empty() {
+            return write!(f, ".");
+        }
+        write!(f, "{}.", escaped_labels(&self.labels))
+    }
+}
+
+/// Parse a name in presentation format (RFC 1035 section 5.1), where \X is X and \DDD is the byte
+/// of decimal value DDD. Names are absolute whether they end with a dot or not.
+impl FromStr for Name {
+    type Err = DNSError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DNSError::InvalidEncoding(s.to_string());
+        if s.is_empty() || s == "." {
+            return Ok(Name::root());
+        }
+
+        let mut labels = Vec::<Vec<u8>>::new();
+        let mut label = Vec::<u8>::new();
+        let mut bytes = s.bytes();
+        while let Some(b) = bytes.next() {
+            match b {
+                b'.' => labels.push(std::mem::take(&mut label)),
+                b'\\' => match bytes.next() {
+                    Some(d) if d.is_ascii_digit() => {
+                        let digits = [Some(d), bytes.next(), bytes.next()];
+                        let value = digits.iter().try_fold(0u16, |value, digit| match digit {
+                            Some(digit) if digit.is_ascii_digit() => {
+                                Some(value * 10 + (digit - b'0') as u16)
+                            }
+                            _ => None,
+                        });
+                        let byte = value.and_then(|value| u8::try_from(value).ok());
+                        label.push(byte.ok_or_else(invalid)?);
+                    }
+                    Some(escaped) => label.push(escaped),
+                    None => return Err(invalid()),
+                },
+                b => label.push(b),
+            }
+        }
+        // The last label is only empty when ended by the trailing dot
+        if !label.is_empty() {
+            labels.push(label);
+        }
+        if labels.iter().any(Vec::is_empty) {
+            return Err(invalid());
+        }
+        Name::from_labels(labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn name(s: &str) -> Name {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_display() {
+        let parsed = name("a\\.b.Example.com.");
+        assert_eq!(parsed.label_count(), 3);
+        assert_eq!(
+            parsed.labels().collect::<Vec<_>>(),
+            [&b"a.b"[..], b"Example", b"com"]
+        );
+        assert_eq!(parsed.to_string(), "a\\.b.Example.com.");
+        assert_eq!(name("example.com"), name("example.com."));
+        assert_eq!(
+            name("\\001\\255x.com").labels().next(),
+            Some(&[1, 255, b'x'][..])
+        );
+        assert_eq!(name("\\001\\255x.com").to_string(), "\\001\\255x.com.");
+        assert_eq!(name("trailing\\..").to_string(), "trailing\\..");
+        assert!(name(".").is_root());
+        assert_eq!(Name::root().to_string(), ".");
+        assert_eq!(Name::root().wire_len(), 1);
+
+        for invalid in ["a..com", ".com", "a\\", "a\\25", "a\\256.com"] {
+            assert_eq!(
+                invalid.parse::<Name>(),
+                Err(DNSError::InvalidEncoding(invalid.to_string())),
+                "{}",
+                invalid
+            );
+        }
+        let long_label = "a".repeat(64);
+        assert_eq!(
+            long_label.parse::<Name>(),
+            Err(DNSError::LabelTooLarge(long_label.clone(), 64))
+        );
+        let long_name = ["a".repeat(63).as_str(); 4].join(".");
+        assert!(matches!(
+            long_name.parse::<Name>(),
+            Err(DNSError::DomainNameTooLarge(_, 257))
+        ));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let mixed = name("WWW.Example.COM");
+        assert_eq!(mixed, name("www.example.com"));
+        assert_ne!(mixed, name("www.example.org"));
+        assert_ne!(mixed, name("www.example.com.org"));
+        assert_eq!(mixed.to_string(), "WWW.Example.COM.");
+        assert_eq!(mixed.to_lowercase().to_string(), "www.example.com.");
+
+        let names: HashSet<Name> = [mixed, name("www.example.com"), name("a.b")].into();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&name("Www.example.Com")));
+    }
+
+    #[test]
+    fn test_hierarchy() {
+        let www = name("www.Example.com");
+        assert_eq!(www.parent(), Some(name("example.com")));
+        assert_eq!(name("com").parent(), Some(Name::root()));
+        assert_eq!(Name::root().parent(), None);
+
+        assert!(www.is_subdomain_of(&name("EXAMPLE.com")));
+        assert!(www.is_subdomain_of(&www));
+        assert!(www.is_subdomain_of(&Name::root()));
+        assert!(!www.is_subdomain_of(&name("ample.com")));
+        assert!(!name("example.com").is_subdomain_of(&www));
+
+        let wildcard = name("*").append(&name("example.com")).unwrap();
+        assert_eq!(wildcard, name("*.example.com"));
+        assert!(wildcard.is_wildcard());
+        assert!(!name("a.*.example.com").is_wildcard());
+        assert!(!name("\\*a.example.com").is_wildcard());
+        assert_eq!(Name::root().append(&www), Ok(www.clone()));
+
+        assert_eq!(
+            www.replace_ancestor(&name("example.COM"), &name("example.net")),
+            Ok(name("www.example.net"))
+        );
+        assert_eq!(
+            www.replace_ancestor(&name("com"), &Name::root()),
+            Ok(name("www.Example"))
+        );
+        assert_eq!(
+            www.replace_ancestor(&name("org"), &name("example.net")),
+            Err(DNSError::OutOfZone("www.Example.com.".to_string()))
+        );
+
+        let long = Name::from_labels(vec!["a".repeat(63); 3]).unwrap();
+        assert!(matches!(
+            long.append(&long),
+            Err(DNSError::DomainNameTooLarge(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_canonical_order() {
+        // Example of RFC 4034 section 6.1
+        let ordered = [
+            "example",
+            "a.example",
+            "yljkjljk.a.example",
+            "Z.a.example",
+            "zABC.a.EXAMPLE",
+            "z.example",
+            "\\001.z.example",
+            "*.z.example",
+            "\\200.z.example",
+        ];
+        let mut names: Vec<Name> = ordered.iter().rev().map(|s| name(s)).collect();
+        names.sort();
+        assert_eq!(names, ordered.map(name));
+        assert!(Name::root() < name("example"));
+    }
+
+    #[test]
+    fn test_write_parse() {
+        let names = [
+            name("WWW.Example.com"),
+            name("mail.example.com"),
+            name("dot\\.ted.example.com"),
+        ];
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        for name in names.iter() {
+            name.write_to_buffer(&mut buffer).unwrap();
+        }
+        assert_eq!(
+            buffer.get_data()[..22],
+            [
+                0x03, b'W', b'W', b'W', 0x07, b'E', b'x', b'a', b'm', b'p', b'l', b'e', 0x03, b'c',
+                b'o', b'm', 0x00, 0x04, b'm', b'a', b'i', b'l'
+            ]
+        );
+        // Suffixes are reused whatever their case, so both names point to Example.com
+        assert_eq!(buffer.get_data()[22..24], [0xC0, 4]);
+        assert_eq!(
+            buffer.get_data()[24..],
+            [0x07, b'd', b'o', b't', b'.', b't', b'e', b'd', 0xC0, 4]
+        );
+
+        buffer.seek(0);
+        for expected in names.iter() {
+            let parsed = Name::parse_from_buffer(&mut buffer).unwrap();
+            assert_eq!(&parsed, expected);
+        }
+        assert_eq!(buffer.get_pos(), buffer.len());
+        buffer.seek(17);
+        assert_eq!(
+            Name::parse_from_buffer(&mut buffer).unwrap().to_string(),
+            "mail.Example.com."
+        );
+
+        let mut canonical = DNSPacketBuffer::new_canonical();
+        names[0].write_to_buffer(&mut canonical).unwrap();
+        names[0].write_to_buffer(&mut canonical).unwrap();
+        assert_eq!(canonical.len(), 34);
+        assert_eq!(canonical.get_data()[1..4], *b"www");
+    }
+
+    #[test]
+    fn test_parse_pointer_loop() {
+        let mut buffer = DNSPacketBuffer::new(&[0x01, b'a', 0xC0, 0x00]);
+        assert_eq!(
+            Name::parse_from_buffer(&mut buffer),
+            Err(DNSError::MaxJumps)
+        );
+    }
+}
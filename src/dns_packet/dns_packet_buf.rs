@@ -1,28 +1,80 @@
 use std::collections::BTreeMap;
 
 use super::DNSError;
-use super::PACKET_SIZE;
+use super::MAX_PACKET_SIZE;
 
 #[derive(Debug, PartialEq)]
 pub struct DNSPacketBuffer {
-    data: [u8; PACKET_SIZE],
+    data: Vec<u8>,
     pos: usize,
+    max_size: usize,
+    canonical: bool,
+    lowercase_names: bool,
     labels_lookup: BTreeMap<String, u16>,
 }
 
 impl DNSPacketBuffer {
-    /// Initializes DNS packet buffer with the given data and its position pointer set to 0.
+    /// Initializes DNS packet buffer with the given data and its position pointer set to 0. The
+    /// buffer can grow up to MAX_PACKET_SIZE bytes when written to.
     pub fn new(data: &[u8]) -> Self {
-        let mut buf_data: [u8; PACKET_SIZE] = [0; PACKET_SIZE];
-        buf_data[0..data.len()].clone_from_slice(data);
+        Self::with_max_size(data, MAX_PACKET_SIZE)
+    }
 
+    /// Initializes DNS packet buffer with the given data and its position pointer set to 0. The
+    /// buffer can grow up to max_size bytes when written to.
+    pub fn with_max_size(data: &[u8], max_size: usize) -> Self {
         DNSPacketBuffer {
-            data: buf_data,
+            data: data.to_vec(),
             pos: 0,
+            max_size,
+            canonical: false,
+            lowercase_names: false,
             labels_lookup: BTreeMap::<String, u16>::new(),
         }
     }
 
+    /// Initializes an empty DNS packet buffer which writes records in canonical form (RFC 4034
+    /// section 6.2): domain names are never compressed, and lowercased until set_lowercase_names
+    /// says otherwise.
+    pub(crate) fn new_canonical() -> Self {
+        DNSPacketBuffer {
+            canonical: true,
+            lowercase_names: true,
+            ..Self::new(&[])
+        }
+    }
+
+    /// Check whether the buffer writes records in canonical form.
+    pub(crate) fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Choose whether the domain names written next are lowercased, which canonical form decides
+    /// per record type for the names inside RDATA (see QueryType::lowercases_rdata_names).
+    pub(crate) fn set_lowercase_names(&mut self, lowercase: bool) {
+        self.lowercase_names = lowercase;
+    }
+
+    /// Check whether the domain names written are lowercased.
+    pub(crate) fn lowercases_names(&self) -> bool {
+        self.lowercase_names
+    }
+
+    /// Get the maximum amount of bytes the buffer can hold.
+    pub fn get_max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Get the amount of bytes currently held by the buffer.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Check whether the buffer holds no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
     /// Get current buffer pointer position
     pub(crate) fn get_pos(&self) -> usize {
         self.pos
@@ -40,14 +92,32 @@ impl DNSPacketBuffer {
 
     // NOTE: Reading methods
 
-    /// Return reference to data array.
+    /// Return reference to the bytes held by the buffer.
     pub fn get_data(&self) -> &[u8] {
         &self.data
     }
 
+    /// Return reference to len bytes starting at a given position. Don't move position pointer.
+    pub(crate) fn get_range(&self, start: usize, len: usize) -> Result<&[u8], DNSError> {
+        let end = start.checked_add(len).ok_or(DNSError::EndOfBuffer)?;
+        if end > self.data.len() {
+            return Err(DNSError::EndOfBuffer);
+        }
+
+        Ok(&self.data[start..end])
+    }
+
+    /// Read len bytes starting at current position and advance position pointer.
+    pub(crate) fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>, DNSError> {
+        let res = self.get_range(self.pos, len)?.to_vec();
+        self.pos += len;
+
+        Ok(res)
+    }
+
     /// Read byte at current position. Don't move position pointer.
     pub(crate) fn get_u8(&self) -> Result<u8, DNSError> {
-        if self.pos >= PACKET_SIZE {
+        if self.pos >= self.data.len() {
             return Err(DNSError::EndOfBuffer);
         }
         let res = self.data[self.pos];
@@ -57,7 +127,7 @@ impl DNSPacketBuffer {
 
     /// Read byte at current position and advance position pointer.
     pub(crate) fn read_u8(&mut self) -> Result<u8, DNSError> {
-        if self.pos >= PACKET_SIZE {
+        if self.pos >= self.data.len() {
             return Err(DNSError::EndOfBuffer);
         }
         let res = self.data[self.pos];
@@ -86,21 +156,34 @@ impl DNSPacketBuffer {
 
     // NOTE: Writing methods
 
-    /// Write byte at current position and advance position pointer.
+    /// Write byte at current position and advance position pointer. Grow the buffer if the
+    /// position is past its current end, zero-filling any gap.
     pub(crate) fn write_u8(&mut self, val: u8) -> Result<(), DNSError> {
-        if self.pos >= PACKET_SIZE {
+        if self.pos >= self.max_size {
             return Err(DNSError::EndOfBuffer);
         }
 
+        if self.pos >= self.data.len() {
+            self.data.resize(self.pos + 1, 0);
+        }
         self.data[self.pos] = val;
         self.pos += 1;
 
         Ok(())
     }
 
+    /// Write a sequence of bytes at current position and advance position pointer.
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), DNSError> {
+        for b in bytes {
+            self.write_u8(*b)?;
+        }
+
+        Ok(())
+    }
+
     /// Write two bytes at current position and advance position pointer.
     pub(crate) fn write_u16(&mut self, val: u16) -> Result<(), DNSError> {
-        if self.pos >= PACKET_SIZE {
+        if self.pos + 2 > self.max_size {
             return Err(DNSError::EndOfBuffer);
         }
 
@@ -115,7 +198,7 @@ impl DNSPacketBuffer {
 
     /// Write four bytes at current position and advance position pointer.
     pub(crate) fn write_u32(&mut self, val: u32) -> Result<(), DNSError> {
-        if self.pos >= PACKET_SIZE {
+        if self.pos + 4 > self.max_size {
             return Err(DNSError::EndOfBuffer);
         }
 
@@ -153,3 +236,37 @@ impl DNSPacketBuffer {
         self.labels_lookup.get(label).copied()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_grows_buffer() {
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(600);
+        buffer.write_u16(0xABCD).unwrap();
+
+        assert_eq!(buffer.len(), 602);
+        assert_eq!(buffer.get_data()[599..], [0x00, 0xAB, 0xCD]);
+    }
+
+    #[test]
+    fn test_write_past_max_size() {
+        let mut buffer = DNSPacketBuffer::with_max_size(&[], 4);
+        buffer.write_u16(0xABCD).unwrap();
+        buffer.write_u8(0xEF).unwrap();
+
+        assert_eq!(buffer.write_u16(0x1234), Err(DNSError::EndOfBuffer));
+        assert_eq!(buffer.get_data(), [0xAB, 0xCD, 0xEF]);
+    }
+
+    #[test]
+    fn test_read_past_data() {
+        let mut buffer = DNSPacketBuffer::new(&[0x01, 0x02, 0x03]);
+
+        assert_eq!(buffer.read_u16(), Ok(0x0102));
+        assert_eq!(buffer.read_u16(), Err(DNSError::EndOfBuffer));
+        assert_eq!(buffer.get_range(1, 3), Err(DNSError::EndOfBuffer));
+    }
+}
@@ -1,19 +1,57 @@
-use super::QueryType;
+use super::{QueryType, ResponseCode};
 use std::error::Error;
 use std::fmt;
+use std::io;
 
 #[derive(Debug, PartialEq)]
 pub enum DNSError {
     EndOfBuffer,
     BadPointerPosition,
-    UnknownResponseCode(u8),
+    UnknownResponseCode(u16),
     NonUTF8,
     MaxJumps,
     LabelTooLarge(String, usize),
     DomainNameTooLarge(String, usize),
-    UnimplementedRecordType(QueryType),
-    UnknownRecordWrite,
     ReservedOrUnassigned(QueryType),
+    MalformedEdnsOption(u16),
+    MultipleOPT,
+    InvalidOPT(String),
+    SectionCountOverflow,
+    ExtendedRcodeWithoutEdns(u16),
+    MalformedTypeBitmap,
+    InvalidTXT(String),
+    InvalidEncoding(String),
+    UnsupportedAlgorithm(u8),
+    UnsupportedDigestType(u8),
+    MalformedPublicKey,
+    InvalidSignature,
+    SignatureExpired(u32),
+    SignatureNotYetValid(u32),
+    RRSIGMismatch,
+    DigestMismatch,
+    NoValidSignature,
+    MissingDenialProof,
+    NSEC3IterationsExceeded(u16),
+    MalformedPrivateKey,
+    MissingSOA(String),
+    OutOfZone(String),
+    ZoneSyntax(usize, usize, String),
+    Io(String),
+    Timeout,
+    NoNameservers,
+    ErrorResponse(ResponseCode),
+    MaxQueries(usize),
+    MaxDepth(usize),
+    MaxAliases(usize),
+    UnreachableZone(String),
+    UnrepresentableLabel(String),
+    InvalidIDNA(String),
+    EmptyLabel(String),
+    InvalidSvcParams(String),
+    InvalidCAA(String),
+    MalformedCertificate,
+    InvalidNAPTR(String),
+    InvalidLOC(String),
 }
 
 impl fmt::Display for DNSError {
@@ -37,16 +75,94 @@ impl fmt::Display for DNSError {
                 "label {} of size {} exceeds maximum label length",
                 domain, size
             ),
-            Self::UnimplementedRecordType(rtype) => {
-                format!("record of type {:?} has not been implemented", rtype)
-            }
-            Self::UnknownRecordWrite => "cannot write record of unknown type".to_string(),
             Self::ReservedOrUnassigned(qtype) => {
                 format!("tried to write record of type {:?} containing fields which are currently reserved or unassigned", qtype)
             }
+            Self::MalformedEdnsOption(code) => {
+                format!("EDNS option with code {} is malformed", code)
+            }
+            Self::MultipleOPT => "packet contains more than one OPT record".to_string(),
+            Self::InvalidOPT(msg) => format!("invalid OPT record: {}", msg),
+            Self::SectionCountOverflow => "section holds more than 65535 records".to_string(),
+            Self::ExtendedRcodeWithoutEdns(code) => format!(
+                "response code {} requires EDNS but the packet has no OPT record",
+                code
+            ),
+            Self::MalformedTypeBitmap => "type bit maps field is malformed".to_string(),
+            Self::InvalidTXT(msg) => format!("invalid TXT record: {}", msg),
+            Self::InvalidEncoding(data) => format!("{} is not validly encoded", data),
+            Self::UnsupportedAlgorithm(alg) => {
+                format!("DNSSEC algorithm {} is not supported", alg)
+            }
+            Self::UnsupportedDigestType(digest_type) => {
+                format!("DS digest type {} is not supported", digest_type)
+            }
+            Self::MalformedPublicKey => "DNSKEY public key is malformed".to_string(),
+            Self::InvalidSignature => "RRSIG signature does not verify".to_string(),
+            Self::SignatureExpired(expiration) => {
+                format!("RRSIG signature expired at {}", expiration)
+            }
+            Self::SignatureNotYetValid(inception) => {
+                format!("RRSIG signature is not valid before {}", inception)
+            }
+            Self::RRSIGMismatch => {
+                "RRSIG does not match the RRset or the DNSKEY it is checked against".to_string()
+            }
+            Self::DigestMismatch => "DS digest does not match the DNSKEY".to_string(),
+            Self::NoValidSignature => "no valid signature found for the RRset".to_string(),
+            Self::MissingDenialProof => {
+                "no authenticated proof that the delegation is unsigned".to_string()
+            }
+            Self::NSEC3IterationsExceeded(iterations) => {
+                format!("NSEC3 iteration count {} is too high", iterations)
+            }
+            Self::MalformedPrivateKey => "private key is malformed or unsupported".to_string(),
+            Self::MissingSOA(zone) => format!("zone {} has no SOA record at its apex", zone),
+            Self::OutOfZone(name) => format!("record {} is outside of the zone", name),
+            Self::ZoneSyntax(line, column, msg) => {
+                format!(
+                    "zone file syntax error at line {}, column {}: {}",
+                    line, column, msg
+                )
+            }
+            Self::Io(msg) => format!("I/O error: {}", msg),
+            Self::Timeout => "timed out waiting for a response".to_string(),
+            Self::NoNameservers => "no nameserver configured".to_string(),
+            Self::ErrorResponse(code) => format!("nameserver responded with {}", code),
+            Self::MaxQueries(max) => format!("resolution needs more than {} queries", max),
+            Self::MaxDepth(max) => format!(
+                "resolution of nameserver addresses nested more than {} levels deep",
+                max
+            ),
+            Self::MaxAliases(max) => format!("chain of more than {} aliases", max),
+            Self::UnreachableZone(zone) => {
+                format!("no nameserver of zone {} could be reached", zone)
+            }
+            Self::UnrepresentableLabel(label) => {
+                format!("label {} can't be represented in a Unicode name", label)
+            }
+            Self::InvalidIDNA(name) => {
+                format!("{} is not a valid internationalized domain name", name)
+            }
+            Self::EmptyLabel(name) => format!("domain name {} has an empty label", name),
+            Self::InvalidSvcParams(msg) => format!("invalid SvcParams: {}", msg),
+            Self::InvalidCAA(msg) => format!("invalid CAA property: {}", msg),
+            Self::MalformedCertificate => "certificate is not valid DER encoded X.509".to_string(),
+            Self::InvalidNAPTR(msg) => format!("invalid NAPTR record: {}", msg),
+            Self::InvalidLOC(msg) => format!("invalid LOC record: {}", msg),
         };
         write!(f, "{}", err_msg)
     }
 }
 
 impl Error for DNSError {}
+
+/// I/O errors of sockets, with timeouts reported as Timeout.
+impl From<io::Error> for DNSError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Self::Timeout,
+            _ => Self::Io(err.to_string()),
+        }
+    }
+}
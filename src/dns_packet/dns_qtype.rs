@@ -1,3 +1,5 @@
+use std::fmt;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum QueryType {
     A,            // Address record
@@ -31,6 +33,7 @@ pub enum QueryType {
     NSEC3,        // Next Secure record version 3
     NSEC3PARAM,   // NSEC3 parameters
     OPENPGPKEY,   // OpenPGP public key record
+    OPT,          // EDNS(0) option pseudo-record
     PTR,          // PTR Resource Record [de]
     RRSIG,        // DNSSEC signature
     RP,           // Responsible Person
@@ -84,6 +87,7 @@ impl QueryType {
             50 => QueryType::NSEC3,
             51 => QueryType::NSEC3PARAM,
             61 => QueryType::OPENPGPKEY,
+            41 => QueryType::OPT,
             12 => QueryType::PTR,
             46 => QueryType::RRSIG,
             17 => QueryType::RP,
@@ -137,6 +141,7 @@ impl QueryType {
             QueryType::NSEC3 => 50,
             QueryType::NSEC3PARAM => 51,
             QueryType::OPENPGPKEY => 61,
+            QueryType::OPT => 41,
             QueryType::PTR => 12,
             QueryType::RRSIG => 46,
             QueryType::RP => 17,
@@ -156,4 +161,99 @@ impl QueryType {
             QueryType::Unknown(code) => *code,
         }
     }
+
+    /// Whether the domain names in the RDATA of the type are lowercased in canonical form: the
+    /// types listed in RFC 4034 section 6.2, but NSEC (RFC 6840 section 5.1). Types defined later
+    /// keep the case of their names (RFC 3597 section 7).
+    pub fn lowercases_rdata_names(&self) -> bool {
+        matches!(
+            self,
+            QueryType::NS
+                | QueryType::CNAME
+                | QueryType::SOA
+                | QueryType::PTR
+                | QueryType::HINFO
+                | QueryType::MX
+                | QueryType::RP
+                | QueryType::AFSDB
+                | QueryType::SIG
+                | QueryType::NAPTR
+                | QueryType::KX
+                | QueryType::SRV
+                | QueryType::DNAME
+                | QueryType::RRSIG // MD, MF, MB, MG, MR, MINFO, RT, PX, NXT and A6, which have no variant
+        ) || matches!(self.to_num(), 3 | 4 | 7..=9 | 14 | 21 | 26 | 30 | 38)
+    }
+
+    /// Parse a type from its presentation format mnemonic, or from the TYPEnnn generic notation
+    /// (RFC 3597 section 5). Case insensitive.
+    pub fn from_mnemonic(mnemonic: &str) -> Option<QueryType> {
+        let mnemonic = mnemonic.to_ascii_uppercase();
+        if let Some(code) = mnemonic.strip_prefix("TYPE") {
+            return code.parse::<u16>().ok().map(QueryType::from_num);
+        }
+
+        let qtype = match mnemonic.as_str() {
+            "A" => QueryType::A,
+            "AAAA" => QueryType::AAAA,
+            "AFSDB" => QueryType::AFSDB,
+            "APL" => QueryType::APL,
+            "CAA" => QueryType::CAA,
+            "CDNSKEY" => QueryType::CDNSKEY,
+            "CDS" => QueryType::CDS,
+            "CERT" => QueryType::CERT,
+            "CNAME" => QueryType::CNAME,
+            "CSYNC" => QueryType::CSYNC,
+            "DHCID" => QueryType::DHCID,
+            "DLV" => QueryType::DLV,
+            "DNAME" => QueryType::DNAME,
+            "DNSKEY" => QueryType::DNSKEY,
+            "DS" => QueryType::DS,
+            "EUI48" => QueryType::EUI48,
+            "EUI64" => QueryType::EUI64,
+            "HINFO" => QueryType::HINFO,
+            "HIP" => QueryType::HIP,
+            "HTTPS" => QueryType::HTTPS,
+            "IPSECKEY" => QueryType::IPSECKEY,
+            "KEY" => QueryType::KEY,
+            "KX" => QueryType::KX,
+            "LOC" => QueryType::LOC,
+            "MX" => QueryType::MX,
+            "NAPTR" => QueryType::NAPTR,
+            "NS" => QueryType::NS,
+            "NSEC" => QueryType::NSEC,
+            "NSEC3" => QueryType::NSEC3,
+            "NSEC3PARAM" => QueryType::NSEC3PARAM,
+            "OPENPGPKEY" => QueryType::OPENPGPKEY,
+            "OPT" => QueryType::OPT,
+            "PTR" => QueryType::PTR,
+            "RRSIG" => QueryType::RRSIG,
+            "RP" => QueryType::RP,
+            "SIG" => QueryType::SIG,
+            "SMIMEA" => QueryType::SMIMEA,
+            "SOA" => QueryType::SOA,
+            "SRV" => QueryType::SRV,
+            "SSHFP" => QueryType::SSHFP,
+            "SVCB" => QueryType::SVCB,
+            "TA" => QueryType::TA,
+            "TKEY" => QueryType::TKEY,
+            "TLSA" => QueryType::TLSA,
+            "TSIG" => QueryType::TSIG,
+            "TXT" => QueryType::TXT,
+            "URI" => QueryType::URI,
+            "ZONEMD" => QueryType::ZONEMD,
+            _ => return None,
+        };
+        Some(qtype)
+    }
+}
+
+/// Type mnemonic, or TYPEnnn for types without one (RFC 3597 section 5).
+impl fmt::Display for QueryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryType::Unknown(code) => write!(f, "TYPE{}", code),
+            qtype => write!(f, "{:?}", qtype),
+        }
+    }
 }
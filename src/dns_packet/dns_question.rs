@@ -1,14 +1,16 @@
+use super::Class;
 use super::DNSError;
 use super::DNSPacketBuffer;
 use super::Domain;
 use super::QueryType;
 use super::HEADER_SIZE;
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Question {
     pub domain: Domain,         // Variable length
     pub record_type: QueryType, // 2 bytes
-    pub class: u16,             // 2 bytes
+    pub class: Class,           // 2 bytes
 }
 
 impl Question {
@@ -17,9 +19,9 @@ impl Question {
             return Err(DNSError::BadPointerPosition);
         }
 
-        let label_sequence = Domain::parse_domain(buffer, 0)?;
+        let label_sequence = Domain::parse_from_buffer(buffer)?;
         let record_type = QueryType::from_num(buffer.read_u16()?);
-        let class = buffer.read_u16()?;
+        let class = Class::from_num(buffer.read_u16()?);
         Ok(Question {
             domain: label_sequence,
             record_type,
@@ -34,16 +36,28 @@ impl Question {
 
         self.domain.write_to_buffer(buffer)?;
         buffer.write_u16(self.record_type.to_num())?;
-        buffer.write_u16(self.class)?;
+        buffer.write_u16(self.class.to_num())?;
         Ok(())
     }
 }
 
+/// Question in the format of dig's question section, e.g. ";example.com.\t\tIN\tA".
+impl fmt::Display for Question {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ";{}\t\t{}\t{}",
+            self.domain, self.class, self.record_type
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_read_question() {
         let dns_packet_data = [
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x67,
@@ -60,14 +74,14 @@ mod tests {
 
         let expected_questions = vec![
             Question {
-                domain: Domain("google.com".to_string()),
+                domain: "google.com".parse().unwrap(),
                 record_type: QueryType::A,
-                class: 0x01,
+                class: Class::IN,
             },
             Question {
-                domain: Domain("yahoo.com".to_string()),
+                domain: "yahoo.com".parse().unwrap(),
                 record_type: QueryType::A,
-                class: 0x00,
+                class: Class::Unknown(0),
             },
         ];
 
@@ -78,9 +92,9 @@ mod tests {
     #[test]
     fn test_write_question() {
         let question = Question {
-            domain: Domain("google.com".to_string()),
+            domain: "google.com".parse().unwrap(),
             record_type: QueryType::A,
-            class: 0x01,
+            class: Class::IN,
         };
 
         let mut buffer = DNSPacketBuffer::new(&[]);
@@ -99,4 +113,15 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_question() {
+        let question = Question {
+            domain: "google.com".parse().unwrap(),
+            record_type: QueryType::AAAA,
+            class: Class::IN,
+        };
+
+        assert_eq!(question.to_string(), ";google.com.\t\tIN\tAAAA");
+    }
 }
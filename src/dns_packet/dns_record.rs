@@ -1,35 +1,74 @@
 mod a_record;
 mod aaaa_record;
+mod caa_record;
+mod cdnskey_record;
+mod cds_record;
 mod cname_record;
 mod dname_record;
+mod dnskey_record;
+mod dnssec_algorithm;
+mod ds_record;
+mod loc_record;
 mod mx_record;
+mod naptr_record;
+mod naptr_regexp;
 mod ns_record;
+mod nsec3_record;
+mod nsec3param_record;
+mod nsec_record;
+mod opt_record;
 mod ptr_record;
+mod rrsig_record;
 mod soa_record;
 mod srv_record;
 mod sshfp_record;
+mod svcb_record;
+mod tlsa_record;
 mod txt_record;
+mod type_bitmap;
 mod unknown_record;
-#[cfg(test)]
-use super::PACKET_SIZE;
-use super::{DNSError, DNSPacketBuffer, Domain, QueryType, HEADER_SIZE};
+mod uri_record;
+use super::{
+    base32hex_encode, base64_encode, escape_byte, format_rrsig_time, hex_encode,
+    quote_character_string, Class, DNSError, DNSPacketBuffer, Domain, QueryType, HEADER_SIZE,
+};
+use std::fmt;
 
 use enum_dispatch::enum_dispatch;
 
 pub use a_record::A;
 pub use aaaa_record::AAAA;
+pub use caa_record::{caa_permits, CAAIodef, CAAIssuer, CAA, CAA_ISSUER_CRITICAL_FLAG};
+pub use cdnskey_record::CDNSKEY;
+pub use cds_record::CDS;
 pub use cname_record::CNAME;
 pub use dname_record::DNAME;
+pub use dnskey_record::{
+    DNSKEY, DNSKEY_REVOKE_FLAG, DNSKEY_SECURE_ENTRY_POINT_FLAG, DNSKEY_ZONE_KEY_FLAG,
+};
+pub use dnssec_algorithm::{DNSSECAlgorithm, DigestType, NSEC3HashAlgorithm};
+pub use ds_record::DS;
+pub(crate) use loc_record::encode_precision;
+pub use loc_record::{LOC, LOC_ALTITUDE_BASE, LOC_EQUATOR};
 pub use mx_record::MX;
+pub use naptr_record::{sort_naptr, NAPTR};
 pub use ns_record::NS;
+pub use nsec3_record::{NSEC3, NSEC3_OPT_OUT_FLAG};
+pub use nsec3param_record::NSEC3PARAM;
+pub use nsec_record::NSEC;
+pub use opt_record::{Edns, EdnsOption, DEFAULT_EDNS_PAYLOAD_SIZE, OPT};
 pub use ptr_record::PTR;
+pub use rrsig_record::RRSIG;
 pub use soa_record::SOA;
 pub use srv_record::SRV;
-pub use sshfp_record::SSHFP;
+pub use sshfp_record::{Algorithm, FingerprintType, SSHFP};
+pub use svcb_record::{SvcParam, HTTPS, SVCB};
+pub use tlsa_record::{smimea_owner, CertificateUsage, MatchingType, Selector, SMIMEA, TLSA};
 pub use txt_record::TXT;
 pub use unknown_record::Unknown;
+pub use uri_record::URI;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Record {
     preamble: RecordPreamble,
     data: RecordData,
@@ -45,20 +84,74 @@ impl PartialEq for Record {
 }
 
 #[enum_dispatch]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum RecordData {
     A,
     AAAA,
+    CAA,
+    CDNSKEY,
+    CDS,
     CNAME,
     DNAME,
+    DNSKEY,
+    DS,
+    HTTPS,
+    LOC,
     MX,
+    NAPTR,
     NS,
+    NSEC,
+    NSEC3,
+    NSEC3PARAM,
+    OPT,
     PTR,
+    RRSIG,
+    SMIMEA,
     SOA,
     SRV,
     SSHFP,
+    SVCB,
+    TLSA,
     TXT,
     Unknown,
+    URI,
+}
+
+/// RDATA in the presentation format of its record type.
+impl fmt::Display for RecordData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordData::A(data) => data.fmt(f),
+            RecordData::AAAA(data) => data.fmt(f),
+            RecordData::CAA(data) => data.fmt(f),
+            RecordData::CDNSKEY(data) => data.fmt(f),
+            RecordData::CDS(data) => data.fmt(f),
+            RecordData::CNAME(data) => data.fmt(f),
+            RecordData::DNAME(data) => data.fmt(f),
+            RecordData::DNSKEY(data) => data.fmt(f),
+            RecordData::DS(data) => data.fmt(f),
+            RecordData::HTTPS(data) => data.fmt(f),
+            RecordData::LOC(data) => data.fmt(f),
+            RecordData::MX(data) => data.fmt(f),
+            RecordData::NAPTR(data) => data.fmt(f),
+            RecordData::NS(data) => data.fmt(f),
+            RecordData::NSEC(data) => data.fmt(f),
+            RecordData::NSEC3(data) => data.fmt(f),
+            RecordData::NSEC3PARAM(data) => data.fmt(f),
+            RecordData::OPT(data) => data.fmt(f),
+            RecordData::PTR(data) => data.fmt(f),
+            RecordData::RRSIG(data) => data.fmt(f),
+            RecordData::SMIMEA(data) => data.fmt(f),
+            RecordData::SOA(data) => data.fmt(f),
+            RecordData::SRV(data) => data.fmt(f),
+            RecordData::SSHFP(data) => data.fmt(f),
+            RecordData::SVCB(data) => data.fmt(f),
+            RecordData::TLSA(data) => data.fmt(f),
+            RecordData::TXT(data) => data.fmt(f),
+            RecordData::Unknown(data) => data.fmt(f),
+            RecordData::URI(data) => data.fmt(f),
+        }
+    }
 }
 
 trait RecordDataRead {
@@ -76,11 +169,11 @@ pub trait RecordDataWrite: std::fmt::Debug {
     fn query_type(&self) -> QueryType;
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct RecordPreamble {
     domain: Domain,         // Variable length
     record_type: QueryType, // 2 bytes
-    class: u16,             // 2 bytes
+    class: Class,           // 2 bytes
     ttl: u32,               // 4 bytes
     len: u16,               // 2 bytes
 }
@@ -88,9 +181,9 @@ pub struct RecordPreamble {
 impl RecordPreamble {
     fn parse_from_buffer(buffer: &mut DNSPacketBuffer) -> Result<Self, DNSError> {
         Ok(RecordPreamble {
-            domain: Domain::parse_domain(buffer, 0)?,
+            domain: Domain::parse_from_buffer(buffer)?,
             record_type: QueryType::from_num(buffer.read_u16()?),
-            class: buffer.read_u16()?,
+            class: Class::from_num(buffer.read_u16()?),
             ttl: buffer.read_u32()?,
             len: buffer.read_u16()?,
         })
@@ -99,7 +192,7 @@ impl RecordPreamble {
     fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
         self.domain.write_to_buffer(buffer)?;
         buffer.write_u16(self.record_type.to_num())?; // filled by record data
-        buffer.write_u16(self.class)?;
+        buffer.write_u16(self.class.to_num())?;
         buffer.write_u32(self.ttl)?;
         buffer.write_u16(0)?; // filled by record data
 
@@ -107,8 +200,24 @@ impl RecordPreamble {
     }
 }
 
+/// Record in zone file presentation format, as printed by dig: <owner> <TTL> <class> <type>
+/// <RDATA>, separated by tabs.
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}",
+            self.preamble.domain,
+            self.preamble.ttl,
+            self.preamble.class,
+            self.record_type(),
+            self.data
+        )
+    }
+}
+
 impl Record {
-    pub fn new(domain: Domain, class: u16, ttl: u32, record_data: RecordData) -> Self {
+    pub fn new(domain: Domain, class: Class, ttl: u32, record_data: RecordData) -> Self {
         Self {
             preamble: RecordPreamble {
                 domain,
@@ -121,41 +230,134 @@ impl Record {
         }
     }
 
+    pub fn domain(&self) -> &Domain {
+        &self.preamble.domain
+    }
+
+    pub fn record_type(&self) -> QueryType {
+        self.data.query_type()
+    }
+
+    pub fn class(&self) -> Class {
+        self.preamble.class
+    }
+
+    pub fn ttl(&self) -> u32 {
+        self.preamble.ttl
+    }
+
+    pub fn data(&self) -> &RecordData {
+        &self.data
+    }
+
     pub(crate) fn parse_from_buffer(buffer: &mut DNSPacketBuffer) -> Result<Self, DNSError> {
         if buffer.get_pos() < HEADER_SIZE {
             return Err(DNSError::BadPointerPosition);
         }
 
         let preamble = RecordPreamble::parse_from_buffer(buffer)?;
-        let data = match preamble.record_type {
+        let data: Result<RecordData, DNSError> = match preamble.record_type {
             QueryType::A => Ok(RecordData::A(A::parse_from_buffer(buffer, &preamble)?)),
             QueryType::AAAA => Ok(RecordData::AAAA(AAAA::parse_from_buffer(
                 buffer, &preamble,
             )?)),
+            QueryType::CAA => Ok(RecordData::CAA(CAA::parse_from_buffer(buffer, &preamble)?)),
+            QueryType::CDNSKEY => Ok(RecordData::CDNSKEY(CDNSKEY::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
+            QueryType::CDS => Ok(RecordData::CDS(CDS::parse_from_buffer(buffer, &preamble)?)),
             QueryType::CNAME => Ok(RecordData::CNAME(CNAME::parse_from_buffer(
                 buffer, &preamble,
             )?)),
             QueryType::DNAME => Ok(RecordData::DNAME(DNAME::parse_from_buffer(
                 buffer, &preamble,
             )?)),
+            QueryType::DNSKEY => Ok(RecordData::DNSKEY(DNSKEY::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
+            QueryType::DS => Ok(RecordData::DS(DS::parse_from_buffer(buffer, &preamble)?)),
+            QueryType::HTTPS => Ok(RecordData::HTTPS(HTTPS::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
+            // The format of LOC versions other than 0 is unknown (RFC 1876 section 2)
+            QueryType::LOC if preamble.len > 0 && buffer.get_u8()? != 0 => Ok(RecordData::Unknown(
+                Unknown::parse_from_buffer(buffer, &preamble)?,
+            )),
+            QueryType::LOC => Ok(RecordData::LOC(LOC::parse_from_buffer(buffer, &preamble)?)),
             QueryType::MX => Ok(RecordData::MX(MX::parse_from_buffer(buffer, &preamble)?)),
+            QueryType::NAPTR => Ok(RecordData::NAPTR(NAPTR::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
             QueryType::NS => Ok(RecordData::NS(NS::parse_from_buffer(buffer, &preamble)?)),
+            QueryType::NSEC => Ok(RecordData::NSEC(NSEC::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
+            QueryType::NSEC3 => Ok(RecordData::NSEC3(NSEC3::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
+            QueryType::NSEC3PARAM => Ok(RecordData::NSEC3PARAM(NSEC3PARAM::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
+            QueryType::OPT => Ok(RecordData::OPT(OPT::parse_from_buffer(buffer, &preamble)?)),
             QueryType::PTR => Ok(RecordData::PTR(PTR::parse_from_buffer(buffer, &preamble)?)),
+            QueryType::RRSIG => Ok(RecordData::RRSIG(RRSIG::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
+            QueryType::SMIMEA => Ok(RecordData::SMIMEA(SMIMEA::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
             QueryType::SOA => Ok(RecordData::SOA(SOA::parse_from_buffer(buffer, &preamble)?)),
             QueryType::SRV => Ok(RecordData::SRV(SRV::parse_from_buffer(buffer, &preamble)?)),
             QueryType::SSHFP => Ok(RecordData::SSHFP(SSHFP::parse_from_buffer(
                 buffer, &preamble,
             )?)),
+            QueryType::SVCB => Ok(RecordData::SVCB(SVCB::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
+            QueryType::TLSA => Ok(RecordData::TLSA(TLSA::parse_from_buffer(
+                buffer, &preamble,
+            )?)),
             QueryType::TXT => Ok(RecordData::TXT(TXT::parse_from_buffer(buffer, &preamble)?)),
-            QueryType::Unknown(_) => Ok(RecordData::Unknown(Unknown::parse_from_buffer(
+            QueryType::URI => Ok(RecordData::URI(URI::parse_from_buffer(buffer, &preamble)?)),
+            // Types without a RecordData implementation are kept as raw RDATA (RFC 3597)
+            _ => Ok(RecordData::Unknown(Unknown::parse_from_buffer(
                 buffer, &preamble,
             )?)),
-            ref unimplemented_qtype => Err(DNSError::UnimplementedRecordType(
-                unimplemented_qtype.clone(),
-            )),
-        }?;
+        };
 
-        Ok(Self { preamble, data })
+        Ok(Self {
+            preamble,
+            data: data?,
+        })
+    }
+
+    /// Build a record from its RDATA in wire format, as given by the generic presentation format
+    /// (RFC 3597 section 5).
+    pub(crate) fn from_wire_rdata(
+        domain: Domain,
+        class: Class,
+        ttl: u32,
+        record_type: QueryType,
+        rdata: &[u8],
+    ) -> Result<Self, DNSError> {
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        buffer.write_u8(0x00)?; // root owner, replaced by the given domain
+        buffer.write_u16(record_type.to_num())?;
+        buffer.write_u16(class.to_num())?;
+        buffer.write_u32(ttl)?;
+        buffer.write_u16(rdata.len() as u16)?;
+        buffer.write_bytes(rdata)?;
+        let end_pos = buffer.get_pos();
+        buffer.seek(HEADER_SIZE);
+
+        let data = match Record::parse_from_buffer(&mut buffer) {
+            Ok(record) if buffer.get_pos() == end_pos => record.data,
+            Ok(_) => return Err(DNSError::EndOfBuffer),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Record::new(domain, class, ttl, data))
     }
 
     pub(crate) fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
@@ -166,4 +368,33 @@ impl Record {
         self.data.write_to_buffer(buffer)?;
         Ok(())
     }
+
+    /// Record in canonical wire format (RFC 4034 section 6.2), using the given owner name and TTL
+    /// instead of the record's own, as required when computing DNSSEC signatures.
+    pub(crate) fn to_canonical_wire(&self, owner: &Domain, ttl: u32) -> Result<Vec<u8>, DNSError> {
+        let mut buffer = DNSPacketBuffer::new_canonical();
+        buffer.seek(HEADER_SIZE);
+
+        owner.write_to_buffer(&mut buffer)?;
+        buffer.write_u16(self.data.query_type().to_num())?;
+        buffer.write_u16(self.preamble.class.to_num())?;
+        buffer.write_u32(ttl)?;
+        buffer.write_u16(0)?; // filled by record data
+        buffer.set_lowercase_names(self.data.query_type().lowercases_rdata_names());
+        self.data.write_to_buffer(&mut buffer)?;
+
+        Ok(buffer.get_data()[HEADER_SIZE..].to_vec())
+    }
+
+    /// Record data in canonical wire format, used to sort the records of an RRset in canonical
+    /// order (RFC 4034 section 6.3).
+    pub(crate) fn canonical_rdata(&self) -> Result<Vec<u8>, DNSError> {
+        let mut buffer = DNSPacketBuffer::new_canonical();
+        // Leave room for the length field filled by the record data
+        buffer.seek(HEADER_SIZE + 2);
+        buffer.set_lowercase_names(self.data.query_type().lowercases_rdata_names());
+        self.data.write_to_buffer(&mut buffer)?;
+
+        Ok(buffer.get_data()[HEADER_SIZE + 2..].to_vec())
+    }
 }
@@ -1,9 +1,10 @@
 use super::{
     DNSError, DNSPacketBuffer, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 use std::net::Ipv4Addr;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct A {
     pub addr: Ipv4Addr,
 }
@@ -36,9 +37,16 @@ impl RecordDataWrite for A {
     }
 }
 
+/// Presentation format: <address>
+impl fmt::Display for A {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.addr)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
     #[test]
@@ -55,8 +63,8 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("google.com".to_string()),
-            1,
+            "google.com".parse().unwrap(),
+            Class::IN,
             255,
             RecordData::A(A {
                 addr: Ipv4Addr::new(255, 0, 8, 15),
@@ -70,8 +78,8 @@ mod tests {
     #[test]
     fn test_write_a() {
         let a_record = Record::new(
-            Domain("youtube.com".to_string()),
-            1,
+            "youtube.com".parse().unwrap(),
+            Class::IN,
             171,
             RecordData::A(A {
                 addr: Ipv4Addr::new(255, 20, 28, 35),
@@ -94,4 +102,13 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_a() {
+        let a = A {
+            addr: Ipv4Addr::new(216, 58, 211, 142),
+        };
+
+        assert_eq!(a.to_string(), "216.58.211.142");
+    }
 }
@@ -1,9 +1,10 @@
 use super::{
     DNSError, DNSPacketBuffer, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 use std::net::Ipv6Addr;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct AAAA {
     pub addr: Ipv6Addr,
 }
@@ -45,9 +46,16 @@ impl RecordDataWrite for AAAA {
     }
 }
 
+/// Presentation format: <address>
+impl fmt::Display for AAAA {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.addr)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
     #[test]
@@ -65,8 +73,8 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("google.com".to_string()),
-            1,
+            "google.com".parse().unwrap(),
+            Class::IN,
             255,
             RecordData::AAAA(AAAA {
                 addr: Ipv6Addr::new(
@@ -82,8 +90,8 @@ mod tests {
     #[test]
     fn test_write_aaaa() {
         let aaaa_record = Record::new(
-            Domain("youtube.com".to_string()),
-            1,
+            "youtube.com".parse().unwrap(),
+            Class::IN,
             171,
             RecordData::AAAA(AAAA {
                 addr: Ipv6Addr::new(
@@ -109,4 +117,13 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_aaaa() {
+        let aaaa = AAAA {
+            addr: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1),
+        };
+
+        assert_eq!(aaaa.to_string(), "2001:db8::1");
+    }
 }
@@ -0,0 +1,364 @@
+use super::{
+    quote_character_string, DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead,
+    RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+
+/// Flag of a CAA property which CAs not understanding its tag must not ignore.
+pub const CAA_ISSUER_CRITICAL_FLAG: u8 = 0b1000_0000;
+
+const MAX_TAG_SIZE: usize = 15;
+
+/// Property tags defined by RFC 8659, understood by `caa_permits`.
+const KNOWN_TAGS: [&str; 3] = ["issue", "issuewild", "iodef"];
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CAA {
+    pub flags: u8,      // 1 byte
+    pub tag: String,    // Variable length, 1 to 15 ASCII letters and digits
+    pub value: Vec<u8>, // Variable length, up to the end of the RDATA
+}
+
+/// Value of an issue or issuewild property: the issuer allowed to issue certificates, or None if
+/// no issuer is, followed by the parameters for that issuer.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CAAIssuer {
+    pub issuer: Option<Domain>,
+    pub parameters: Vec<(String, String)>,
+}
+
+/// Value of an iodef property: where CAs report requests which violate the policy.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CAAIodef {
+    Email(String), // Address of a mailto: URL
+    Web(String),   // http: or https: URL
+}
+
+/// Check a CAA tag or issuer parameter tag: letters and digits, with hyphens inside for
+/// parameter tags only.
+fn is_valid_tag(tag: &str, hyphens: bool) -> bool {
+    let bytes = tag.as_bytes();
+    !bytes.is_empty()
+        && bytes
+            .iter()
+            .all(|b| b.is_ascii_alphanumeric() || hyphens && *b == b'-')
+        && bytes[0] != b'-'
+        && bytes[bytes.len() - 1] != b'-'
+}
+
+impl CAA {
+    pub fn issuer_critical(&self) -> bool {
+        self.flags & CAA_ISSUER_CRITICAL_FLAG != 0
+    }
+
+    /// Whether the tag is the given one, which is case insensitive.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tag.eq_ignore_ascii_case(tag)
+    }
+
+    pub(crate) fn validate_tag(&self) -> Result<(), DNSError> {
+        if self.tag.len() > MAX_TAG_SIZE || !is_valid_tag(&self.tag, false) {
+            return Err(DNSError::InvalidCAA(format!("invalid tag {}", self.tag)));
+        }
+        Ok(())
+    }
+
+    /// Parse the value of an issue or issuewild property (RFC 8659 section 4.2):
+    /// [issuer-domain-name] [; tag=value...].
+    pub fn issuer(&self) -> Result<CAAIssuer, DNSError> {
+        if !self.has_tag("issue") && !self.has_tag("issuewild") {
+            return Err(DNSError::InvalidCAA(format!(
+                "{} is not an issue property",
+                self.tag
+            )));
+        }
+        let invalid = || {
+            DNSError::InvalidCAA(format!(
+                "invalid issuer value {}",
+                String::from_utf8_lossy(&self.value)
+            ))
+        };
+
+        let value = std::str::from_utf8(&self.value).map_err(|_| invalid())?;
+        let (issuer, parameters) = match value.split_once(';') {
+            Some((issuer, parameters)) => (issuer, Some(parameters)),
+            None => (value, None),
+        };
+
+        let issuer = issuer.trim_matches([' ', '\t']);
+        let issuer = if issuer.is_empty() {
+            None
+        } else if issuer.split('.').all(|label| is_valid_tag(label, true)) {
+            Some(issuer.parse::<Domain>().map_err(|_| invalid())?)
+        } else {
+            return Err(invalid());
+        };
+
+        // Parameters may be absent after the separator
+        let parameters = parameters
+            .map(|parameters| parameters.trim_matches([' ', '\t']))
+            .filter(|parameters| !parameters.is_empty());
+        let mut issuer_parameters = Vec::<(String, String)>::new();
+        for parameter in parameters
+            .into_iter()
+            .flat_map(|parameters| parameters.split(';'))
+        {
+            let (tag, value) = parameter.split_once('=').ok_or_else(invalid)?;
+            let tag = tag.trim_matches([' ', '\t']);
+            let value = value.trim_matches([' ', '\t']);
+            if !is_valid_tag(tag, true) || !value.bytes().all(|b| (0x21..=0x7E).contains(&b)) {
+                return Err(invalid());
+            }
+            issuer_parameters.push((tag.to_string(), value.to_string()));
+        }
+
+        Ok(CAAIssuer {
+            issuer,
+            parameters: issuer_parameters,
+        })
+    }
+
+    /// Parse the value of an iodef property (RFC 8659 section 4.4), a mailto:, http: or https:
+    /// URL.
+    pub fn iodef(&self) -> Result<CAAIodef, DNSError> {
+        let invalid = || {
+            DNSError::InvalidCAA(format!(
+                "invalid iodef value {}",
+                String::from_utf8_lossy(&self.value)
+            ))
+        };
+        if !self.has_tag("iodef") {
+            return Err(DNSError::InvalidCAA(format!(
+                "{} is not an iodef property",
+                self.tag
+            )));
+        }
+
+        let url = std::str::from_utf8(&self.value).map_err(|_| invalid())?;
+        let (scheme, rest) = url.split_once(':').ok_or_else(invalid)?;
+        match scheme.to_ascii_lowercase().as_str() {
+            "mailto" if rest.contains('@') => Ok(CAAIodef::Email(rest.to_string())),
+            "http" | "https" if rest.starts_with("//") && rest.len() > 2 => {
+                Ok(CAAIodef::Web(url.to_string()))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Decide whether a CA may issue a certificate given the relevant CAA RRset of a name (RFC 8659
+/// section 4). Unknown critical properties forbid issuance. Wildcard certificates are governed by
+/// issuewild properties if there are any, else by issue properties like other certificates. The
+/// CA must then be the issuer of one of them. Malformed values authorize no issuer.
+pub fn caa_permits(relevant: &[CAA], issuer: &Domain, wildcard: bool) -> bool {
+    if relevant
+        .iter()
+        .any(|caa| caa.issuer_critical() && !KNOWN_TAGS.iter().any(|tag| caa.has_tag(tag)))
+    {
+        return false;
+    }
+
+    let issue_wild: Vec<&CAA> = relevant
+        .iter()
+        .filter(|caa| caa.has_tag("issuewild"))
+        .collect();
+    let properties: Vec<&CAA> = if wildcard && !issue_wild.is_empty() {
+        issue_wild
+    } else {
+        relevant.iter().filter(|caa| caa.has_tag("issue")).collect()
+    };
+    if properties.is_empty() {
+        return true;
+    }
+
+    properties.iter().any(|caa| {
+        caa.issuer()
+            .is_ok_and(|value| value.issuer.as_ref() == Some(issuer))
+    })
+}
+
+impl RecordDataRead for CAA {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let flags = buffer.read_u8()?;
+        let tag_len = buffer.read_u8()? as usize;
+        let tag = String::from_utf8(buffer.read_bytes(tag_len)?).map_err(|_| DNSError::NonUTF8)?;
+
+        // Minus the flags, tag length and tag fields
+        let value_len = (preamble.len as usize)
+            .checked_sub(2 + tag_len)
+            .ok_or(DNSError::BadPointerPosition)?;
+        let value = buffer.read_bytes(value_len)?;
+
+        let caa = CAA { flags, tag, value };
+        caa.validate_tag()?;
+        Ok(caa)
+    }
+}
+
+impl RecordDataWrite for CAA {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        self.validate_tag()?;
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u8(self.flags)?;
+        buffer.write_u8(self.tag.len() as u8)?;
+        buffer.write_bytes(self.tag.as_bytes())?;
+        buffer.write_bytes(&self.value)?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::CAA
+    }
+}
+
+/// Presentation format: <flags> <tag> "<value>"
+impl fmt::Display for CAA {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.flags,
+            self.tag,
+            quote_character_string(&self.value)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    fn caa(flags: u8, tag: &str, value: &str) -> CAA {
+        CAA {
+            flags,
+            tag: tag.to_string(),
+            value: value.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_read_write_caa() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x01, 0x01, 0x00,
+            0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x15, 0x80, 0x05, 0x69, 0x73, 0x73, 0x75, 0x65,
+            0x63, 0x61, 0x2E, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x2E, 0x6E, 0x65, 0x74,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::CAA(caa(128, "issue", "ca.example.net")),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 21);
+        assert_eq!(parsed_record, expected_record);
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        expected_record.write_to_buffer(&mut buffer).unwrap();
+        assert_eq!(buffer.get_data(), dns_packet_data);
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        let invalid_tag = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::CAA(caa(0, "is-sue", "ca.example.net")),
+        );
+        assert!(matches!(
+            invalid_tag.write_to_buffer(&mut buffer),
+            Err(DNSError::InvalidCAA(_))
+        ));
+    }
+
+    #[test]
+    fn test_display_caa() {
+        assert_eq!(
+            caa(0, "issue", "ca.example.net; account=230123").to_string(),
+            r#"0 issue "ca.example.net; account=230123""#
+        );
+    }
+
+    #[test]
+    fn test_caa_values() {
+        assert_eq!(
+            caa(0, "issue", "Ca.Example.net; account=230123; policy=ev").issuer(),
+            Ok(CAAIssuer {
+                issuer: Some("ca.example.net".parse().unwrap()),
+                parameters: vec![
+                    ("account".to_string(), "230123".to_string()),
+                    ("policy".to_string(), "ev".to_string()),
+                ],
+            })
+        );
+        assert_eq!(
+            caa(0, "issuewild", ";").issuer(),
+            Ok(CAAIssuer {
+                issuer: None,
+                parameters: Vec::new(),
+            })
+        );
+        assert!(caa(0, "issue", "ca..example.net").issuer().is_err());
+        assert!(caa(0, "issue", "ca.example.net; account").issuer().is_err());
+        assert!(caa(0, "iodef", "ca.example.net").issuer().is_err());
+
+        assert_eq!(
+            caa(0, "iodef", "mailto:security@example.com").iodef(),
+            Ok(CAAIodef::Email("security@example.com".to_string()))
+        );
+        assert_eq!(
+            caa(0, "iodef", "https://iodef.example.com/").iodef(),
+            Ok(CAAIodef::Web("https://iodef.example.com/".to_string()))
+        );
+        assert!(caa(0, "iodef", "ftp://example.com").iodef().is_err());
+    }
+
+    #[test]
+    fn test_caa_permits() {
+        let ca = "ca.example.net".parse().unwrap();
+        let other = "other.example.org".parse().unwrap();
+
+        assert!(caa_permits(&[], &ca, false));
+        assert!(caa_permits(
+            &[caa(0, "iodef", "mailto:a@example.com")],
+            &other,
+            false
+        ));
+
+        let relevant = [caa(0, "issue", "ca.example.net"), caa(0, "issuewild", ";")];
+        assert!(caa_permits(&relevant, &ca, false));
+        assert!(!caa_permits(&relevant, &other, false));
+        assert!(!caa_permits(&relevant, &ca, true));
+
+        let issue_only = [caa(0, "ISSUE", "ca.example.net; account=1")];
+        assert!(caa_permits(&issue_only, &ca, true));
+        assert!(!caa_permits(
+            &[caa(0, "issue", "ca.example.net!")],
+            &ca,
+            false
+        ));
+
+        let critical = [caa(0, "issue", "ca.example.net"), caa(128, "tbs", "x")];
+        assert!(!caa_permits(&critical, &ca, false));
+        let not_critical = [caa(0, "issue", "ca.example.net"), caa(0, "tbs", "x")];
+        assert!(caa_permits(&not_critical, &ca, false));
+    }
+}
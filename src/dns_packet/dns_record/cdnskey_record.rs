@@ -0,0 +1,108 @@
+use super::{
+    DNSError, DNSPacketBuffer, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble, DNSKEY,
+};
+use std::fmt;
+
+/// Child copy of a DNSKEY record, published by a child zone for its parent (RFC 7344). Its RDATA
+/// is identical to a DNSKEY record's.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CDNSKEY {
+    pub dnskey: DNSKEY,
+}
+
+impl RecordDataRead for CDNSKEY {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        Ok(CDNSKEY {
+            dnskey: DNSKEY::parse_from_buffer(buffer, preamble)?,
+        })
+    }
+}
+
+impl RecordDataWrite for CDNSKEY {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        self.dnskey.write_to_buffer(buffer)
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::CDNSKEY
+    }
+}
+
+/// Presentation format: same as DNSKEY
+impl fmt::Display for CDNSKEY {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.dnskey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, DNSSECAlgorithm, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    #[test]
+    fn test_read_cdnskey() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00, 0x3C, 0x00,
+            0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x08, 0x01, 0x01, 0x03, 0x0F, 0x97, 0x4D, 0x96,
+            0xA2,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::CDNSKEY(CDNSKEY {
+                dnskey: DNSKEY::new(257, DNSSECAlgorithm::ED25519, vec![0x97, 0x4D, 0x96, 0xA2]),
+            }),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 8);
+        assert_eq!(parsed_record.record_type(), QueryType::CDNSKEY);
+        assert_eq!(parsed_record, expected_record);
+    }
+
+    #[test]
+    fn test_write_cdnskey() {
+        let cdnskey_record = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::CDNSKEY(CDNSKEY {
+                dnskey: DNSKEY::new(257, DNSSECAlgorithm::ED25519, vec![0x97, 0x4D, 0x96, 0xA2]),
+            }),
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        cdnskey_record.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00, 0x3C, 0x00,
+            0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x08, 0x01, 0x01, 0x03, 0x0F, 0x97, 0x4D, 0x96,
+            0xA2,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_display_cdnskey() {
+        let cdnskey = CDNSKEY {
+            dnskey: DNSKEY::new(257, DNSSECAlgorithm::ED25519, vec![0x97, 0x4D, 0x96, 0xA2]),
+        };
+
+        assert_eq!(cdnskey.to_string(), "257 3 15 l02Wog==");
+    }
+}
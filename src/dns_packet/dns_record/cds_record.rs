@@ -0,0 +1,123 @@
+use super::{
+    DNSError, DNSPacketBuffer, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble, DS,
+};
+use std::fmt;
+
+/// Child copy of a DS record, published by a child zone for its parent (RFC 7344). Its RDATA is
+/// identical to a DS record's.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CDS {
+    pub ds: DS,
+}
+
+impl RecordDataRead for CDS {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        Ok(CDS {
+            ds: DS::parse_from_buffer(buffer, preamble)?,
+        })
+    }
+}
+
+impl RecordDataWrite for CDS {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        self.ds.write_to_buffer(buffer)
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::CDS
+    }
+}
+
+/// Presentation format: same as DS
+impl fmt::Display for CDS {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.ds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, DNSSECAlgorithm, DigestType, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    #[test]
+    fn test_read_cds() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00, 0x3B, 0x00,
+            0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x08, 0x0E, 0x1D, 0x0F, 0x02, 0x3A, 0xA5, 0xAB,
+            0x37,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::CDS(CDS {
+                ds: DS {
+                    key_tag: 3613,
+                    algorithm: DNSSECAlgorithm::ED25519,
+                    digest_type: DigestType::SHA256,
+                    digest: vec![0x3A, 0xA5, 0xAB, 0x37],
+                },
+            }),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 8);
+        assert_eq!(parsed_record.record_type(), QueryType::CDS);
+        assert_eq!(parsed_record, expected_record);
+    }
+
+    #[test]
+    fn test_write_cds() {
+        let cds_record = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::CDS(CDS {
+                ds: DS {
+                    key_tag: 3613,
+                    algorithm: DNSSECAlgorithm::ED25519,
+                    digest_type: DigestType::SHA256,
+                    digest: vec![0x3A, 0xA5, 0xAB, 0x37],
+                },
+            }),
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        cds_record.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00, 0x3B, 0x00,
+            0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x08, 0x0E, 0x1D, 0x0F, 0x02, 0x3A, 0xA5, 0xAB,
+            0x37,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_display_cds() {
+        let cds = CDS {
+            ds: DS {
+                key_tag: 3613,
+                algorithm: DNSSECAlgorithm::ED25519,
+                digest_type: DigestType::SHA256,
+                digest: vec![0x3A, 0xA5, 0xAB, 0x37],
+            },
+        };
+
+        assert_eq!(cds.to_string(), "3613 15 2 3AA5AB37");
+    }
+}
@@ -1,8 +1,9 @@
 use super::{
     DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct CNAME {
     pub cname: Domain,
 }
@@ -13,7 +14,7 @@ impl RecordDataRead for CNAME {
         _preamble: &RecordPreamble,
     ) -> Result<Self, DNSError> {
         Ok(CNAME {
-            cname: Domain::parse_domain(buffer, 0)?,
+            cname: Domain::parse_from_buffer(buffer)?,
         })
     }
 }
@@ -36,9 +37,16 @@ impl RecordDataWrite for CNAME {
     }
 }
 
+/// Presentation format: <cname>
+impl fmt::Display for CNAME {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.cname)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
     #[test]
@@ -56,11 +64,11 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             254,
             RecordData::CNAME(CNAME {
-                cname: Domain("foo.example.com".to_string()),
+                cname: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -71,11 +79,11 @@ mod tests {
     #[test]
     fn test_write_cname() {
         let cname_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             254,
             RecordData::CNAME(CNAME {
-                cname: Domain("foo.example.com".to_string()),
+                cname: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -96,4 +104,13 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_cname() {
+        let cname = CNAME {
+            cname: "www.example.com".parse().unwrap(),
+        };
+
+        assert_eq!(cname.to_string(), "www.example.com.");
+    }
 }
@@ -1,8 +1,9 @@
 use super::{
     DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct DNAME {
     pub dname: Domain,
 }
@@ -13,7 +14,7 @@ impl RecordDataRead for DNAME {
         _preamble: &RecordPreamble,
     ) -> Result<Self, DNSError> {
         Ok(DNAME {
-            dname: Domain::parse_domain(buffer, 0)?,
+            dname: Domain::parse_from_buffer(buffer)?,
         })
     }
 }
@@ -36,9 +37,16 @@ impl RecordDataWrite for DNAME {
     }
 }
 
+/// Presentation format: <dname>
+impl fmt::Display for DNAME {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.dname)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
     #[test]
@@ -56,11 +64,11 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             254,
             RecordData::DNAME(DNAME {
-                dname: Domain("foo.example.com".to_string()),
+                dname: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -71,11 +79,11 @@ mod tests {
     #[test]
     fn test_write_dname() {
         let dname_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             254,
             RecordData::DNAME(DNAME {
-                dname: Domain("foo.example.com".to_string()),
+                dname: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -96,4 +104,13 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_dname() {
+        let dname = DNAME {
+            dname: "www.example.com".parse().unwrap(),
+        };
+
+        assert_eq!(dname.to_string(), "www.example.com.");
+    }
 }
@@ -0,0 +1,242 @@
+use super::{
+    base64_encode, DNSError, DNSPacketBuffer, DNSSECAlgorithm, QueryType, RecordDataRead,
+    RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+
+pub const DNSKEY_ZONE_KEY_FLAG: u16 = 0b0000_0001_0000_0000;
+pub const DNSKEY_REVOKE_FLAG: u16 = 0b0000_0000_1000_0000;
+pub const DNSKEY_SECURE_ENTRY_POINT_FLAG: u16 = 0b0000_0000_0000_0001;
+const DNSKEY_PROTOCOL: u8 = 3;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DNSKEY {
+    pub flags: u16,                 // 2 bytes
+    pub protocol: u8,               // 1 byte, always 3
+    pub algorithm: DNSSECAlgorithm, // 1 byte
+    pub public_key: Vec<u8>,        // Variable length
+}
+
+impl DNSKEY {
+    pub fn new(flags: u16, algorithm: DNSSECAlgorithm, public_key: Vec<u8>) -> Self {
+        DNSKEY {
+            flags,
+            protocol: DNSKEY_PROTOCOL,
+            algorithm,
+            public_key,
+        }
+    }
+
+    pub fn is_zone_key(&self) -> bool {
+        self.flags & DNSKEY_ZONE_KEY_FLAG != 0
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.flags & DNSKEY_REVOKE_FLAG != 0
+    }
+
+    pub fn is_secure_entry_point(&self) -> bool {
+        self.flags & DNSKEY_SECURE_ENTRY_POINT_FLAG != 0
+    }
+
+    /// RDATA of the key in wire format, as used for key tag and DS digest calculation.
+    pub fn rdata(&self) -> Vec<u8> {
+        let mut rdata = Vec::<u8>::with_capacity(4 + self.public_key.len());
+        rdata.extend_from_slice(&self.flags.to_be_bytes());
+        rdata.push(self.protocol);
+        rdata.push(self.algorithm.to_num());
+        rdata.extend_from_slice(&self.public_key);
+        rdata
+    }
+
+    /// Key tag as calculated in RFC 4034 appendix B.
+    pub fn key_tag(&self) -> u16 {
+        if self.algorithm == DNSSECAlgorithm::RSAMD5 {
+            // Least significant 16 bits of the modulus
+            let len = self.public_key.len();
+            if len < 3 {
+                return 0;
+            }
+            return u16::from_be_bytes([self.public_key[len - 3], self.public_key[len - 2]]);
+        }
+
+        let mut acc: u32 = 0;
+        for (i, b) in self.rdata().iter().enumerate() {
+            if i & 1 == 0 {
+                acc += (*b as u32) << 8;
+            } else {
+                acc += *b as u32;
+            }
+        }
+        acc += (acc >> 16) & 0xFFFF;
+        (acc & 0xFFFF) as u16
+    }
+}
+
+impl RecordDataRead for DNSKEY {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let flags = buffer.read_u16()?;
+        let protocol = buffer.read_u8()?;
+        let algorithm = DNSSECAlgorithm::from_num(buffer.read_u8()?);
+        // Minus four bytes from the previous fields
+        let key_len = (preamble.len as usize).saturating_sub(4);
+        let public_key = buffer.read_bytes(key_len)?;
+
+        Ok(DNSKEY {
+            flags,
+            protocol,
+            algorithm,
+            public_key,
+        })
+    }
+}
+
+impl RecordDataWrite for DNSKEY {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u16(self.flags)?;
+        buffer.write_u8(self.protocol)?;
+        buffer.write_u8(self.algorithm.to_num())?;
+        buffer.write_bytes(&self.public_key)?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::DNSKEY
+    }
+}
+
+/// Presentation format: <flags> <protocol> <algorithm> <base64 public key>
+impl fmt::Display for DNSKEY {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.flags,
+            self.protocol,
+            self.algorithm.to_num(),
+            base64_encode(&self.public_key)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    #[test]
+    fn test_read_dnskey() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00, 0x30, 0x00,
+            0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x14, 0x01, 0x01, 0x03, 0x0D, 0x4A, 0x2B, 0x9F,
+            0x01, 0xC3, 0x77, 0x10, 0xEE, 0x52, 0x0D, 0xAB, 0x66, 0x38, 0x91, 0xF4, 0x05,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::DNSKEY(DNSKEY {
+                flags: 257,
+                protocol: 3,
+                algorithm: DNSSECAlgorithm::ECDSAP256SHA256,
+                public_key: vec![
+                    0x4A, 0x2B, 0x9F, 0x01, 0xC3, 0x77, 0x10, 0xEE, 0x52, 0x0D, 0xAB, 0x66, 0x38,
+                    0x91, 0xF4, 0x05,
+                ],
+            }),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 20);
+        assert_eq!(parsed_record, expected_record);
+    }
+
+    #[test]
+    fn test_write_dnskey() {
+        let dnskey_record = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::DNSKEY(DNSKEY::new(
+                257,
+                DNSSECAlgorithm::ECDSAP256SHA256,
+                vec![
+                    0x4A, 0x2B, 0x9F, 0x01, 0xC3, 0x77, 0x10, 0xEE, 0x52, 0x0D, 0xAB, 0x66, 0x38,
+                    0x91, 0xF4, 0x05,
+                ],
+            )),
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        dnskey_record.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00, 0x30, 0x00,
+            0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x14, 0x01, 0x01, 0x03, 0x0D, 0x4A, 0x2B, 0x9F,
+            0x01, 0xC3, 0x77, 0x10, 0xEE, 0x52, 0x0D, 0xAB, 0x66, 0x38, 0x91, 0xF4, 0x05,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_key_tag() {
+        // Root zone KSK-2017
+        let root_ksk = DNSKEY::new(
+            257,
+            DNSSECAlgorithm::RSASHA256,
+            vec![
+                0x03, 0x01, 0x00, 0x01, 0xAC, 0xFF, 0xB4, 0x09, 0xBC, 0xC9, 0x39, 0xF8, 0x31, 0xF7,
+                0xA1, 0xE5, 0xEC, 0x88, 0xF7, 0xA5, 0x92, 0x55, 0xEC, 0x53, 0x04, 0x0B, 0xE4, 0x32,
+                0x02, 0x73, 0x90, 0xA4, 0xCE, 0x89, 0x6D, 0x6F, 0x90, 0x86, 0xF3, 0xC5, 0xE1, 0x77,
+                0xFB, 0xFE, 0x11, 0x81, 0x63, 0xAA, 0xEC, 0x7A, 0xF1, 0x46, 0x2C, 0x47, 0x94, 0x59,
+                0x44, 0xC4, 0xE2, 0xC0, 0x26, 0xBE, 0x5E, 0x98, 0xBB, 0xCD, 0xED, 0x25, 0x97, 0x82,
+                0x72, 0xE1, 0xE3, 0xE0, 0x79, 0xC5, 0x09, 0x4D, 0x57, 0x3F, 0x0E, 0x83, 0xC9, 0x2F,
+                0x02, 0xB3, 0x2D, 0x35, 0x13, 0xB1, 0x55, 0x0B, 0x82, 0x69, 0x29, 0xC8, 0x0D, 0xD0,
+                0xF9, 0x2C, 0xAC, 0x96, 0x6D, 0x17, 0x76, 0x9F, 0xD5, 0x86, 0x7B, 0x64, 0x7C, 0x3F,
+                0x38, 0x02, 0x9A, 0xBD, 0xC4, 0x81, 0x52, 0xEB, 0x8F, 0x20, 0x71, 0x59, 0xEC, 0xC5,
+                0xD2, 0x32, 0xC7, 0xC1, 0x53, 0x7C, 0x79, 0xF4, 0xB7, 0xAC, 0x28, 0xFF, 0x11, 0x68,
+                0x2F, 0x21, 0x68, 0x1B, 0xF6, 0xD6, 0xAB, 0xA5, 0x55, 0x03, 0x2B, 0xF6, 0xF9, 0xF0,
+                0x36, 0xBE, 0xB2, 0xAA, 0xA5, 0xB3, 0x77, 0x8D, 0x6E, 0xEB, 0xFB, 0xA6, 0xBF, 0x9E,
+                0xA1, 0x91, 0xBE, 0x4A, 0xB0, 0xCA, 0xEA, 0x75, 0x9E, 0x2F, 0x77, 0x3A, 0x1F, 0x90,
+                0x29, 0xC7, 0x3E, 0xCB, 0x8D, 0x57, 0x35, 0xB9, 0x32, 0x1D, 0xB0, 0x85, 0xF1, 0xB8,
+                0xE2, 0xD8, 0x03, 0x8F, 0xE2, 0x94, 0x19, 0x92, 0x54, 0x8C, 0xEE, 0x0D, 0x67, 0xDD,
+                0x45, 0x47, 0xE1, 0x1D, 0xD6, 0x3A, 0xF9, 0xC9, 0xFC, 0x1C, 0x54, 0x66, 0xFB, 0x68,
+                0x4C, 0xF0, 0x09, 0xD7, 0x19, 0x7C, 0x2C, 0xF7, 0x9E, 0x79, 0x2A, 0xB5, 0x01, 0xE6,
+                0xA8, 0xA1, 0xCA, 0x51, 0x9A, 0xF2, 0xCB, 0x9B, 0x5F, 0x63, 0x67, 0xE9, 0x4C, 0x0D,
+                0x47, 0x50, 0x24, 0x51, 0x35, 0x7B, 0xE1, 0xB5,
+            ],
+        );
+
+        assert!(root_ksk.is_zone_key());
+        assert!(root_ksk.is_secure_entry_point());
+        assert!(!root_ksk.is_revoked());
+        assert_eq!(root_ksk.key_tag(), 20326);
+    }
+
+    #[test]
+    fn test_display_dnskey() {
+        let dnskey = DNSKEY::new(257, DNSSECAlgorithm::ED25519, vec![0x97, 0x4D, 0x96, 0xA2]);
+
+        assert_eq!(dnskey.to_string(), "257 3 15 l02Wog==");
+    }
+}
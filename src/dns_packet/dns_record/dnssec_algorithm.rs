@@ -0,0 +1,128 @@
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DNSSECAlgorithm {
+    Reserved,
+    RSAMD5,
+    DH,
+    DSA,
+    RSASHA1,
+    DSANSEC3SHA1,
+    RSASHA1NSEC3SHA1,
+    RSASHA256,
+    RSASHA512,
+    ECCGOST,
+    ECDSAP256SHA256,
+    ECDSAP384SHA384,
+    ED25519,
+    ED448,
+    Indirect,
+    PrivateDNS,
+    PrivateOID,
+    Unassigned(u8),
+}
+
+impl DNSSECAlgorithm {
+    pub fn from_num(code_num: u8) -> Self {
+        match code_num {
+            0 => Self::Reserved,
+            1 => Self::RSAMD5,
+            2 => Self::DH,
+            3 => Self::DSA,
+            5 => Self::RSASHA1,
+            6 => Self::DSANSEC3SHA1,
+            7 => Self::RSASHA1NSEC3SHA1,
+            8 => Self::RSASHA256,
+            10 => Self::RSASHA512,
+            12 => Self::ECCGOST,
+            13 => Self::ECDSAP256SHA256,
+            14 => Self::ECDSAP384SHA384,
+            15 => Self::ED25519,
+            16 => Self::ED448,
+            252 => Self::Indirect,
+            253 => Self::PrivateDNS,
+            254 => Self::PrivateOID,
+            _ => Self::Unassigned(code_num),
+        }
+    }
+
+    pub fn to_num(&self) -> u8 {
+        match self {
+            Self::Reserved => 0,
+            Self::RSAMD5 => 1,
+            Self::DH => 2,
+            Self::DSA => 3,
+            Self::RSASHA1 => 5,
+            Self::DSANSEC3SHA1 => 6,
+            Self::RSASHA1NSEC3SHA1 => 7,
+            Self::RSASHA256 => 8,
+            Self::RSASHA512 => 10,
+            Self::ECCGOST => 12,
+            Self::ECDSAP256SHA256 => 13,
+            Self::ECDSAP384SHA384 => 14,
+            Self::ED25519 => 15,
+            Self::ED448 => 16,
+            Self::Indirect => 252,
+            Self::PrivateDNS => 253,
+            Self::PrivateOID => 254,
+            Self::Unassigned(num) => *num,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DigestType {
+    Reserved,
+    SHA1,
+    SHA256,
+    GOST,
+    SHA384,
+    Unassigned(u8),
+}
+
+impl DigestType {
+    pub fn from_num(code_num: u8) -> Self {
+        match code_num {
+            0 => Self::Reserved,
+            1 => Self::SHA1,
+            2 => Self::SHA256,
+            3 => Self::GOST,
+            4 => Self::SHA384,
+            _ => Self::Unassigned(code_num),
+        }
+    }
+
+    pub fn to_num(&self) -> u8 {
+        match self {
+            Self::Reserved => 0,
+            Self::SHA1 => 1,
+            Self::SHA256 => 2,
+            Self::GOST => 3,
+            Self::SHA384 => 4,
+            Self::Unassigned(num) => *num,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NSEC3HashAlgorithm {
+    Reserved,
+    SHA1,
+    Unassigned(u8),
+}
+
+impl NSEC3HashAlgorithm {
+    pub fn from_num(code_num: u8) -> Self {
+        match code_num {
+            0 => Self::Reserved,
+            1 => Self::SHA1,
+            _ => Self::Unassigned(code_num),
+        }
+    }
+
+    pub fn to_num(&self) -> u8 {
+        match self {
+            Self::Reserved => 0,
+            Self::SHA1 => 1,
+            Self::Unassigned(num) => *num,
+        }
+    }
+}
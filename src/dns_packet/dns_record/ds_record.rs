@@ -0,0 +1,156 @@
+use super::{
+    hex_encode, DNSError, DNSPacketBuffer, DNSSECAlgorithm, DigestType, QueryType, RecordDataRead,
+    RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DS {
+    pub key_tag: u16,               // 2 bytes
+    pub algorithm: DNSSECAlgorithm, // 1 byte
+    pub digest_type: DigestType,    // 1 byte
+    pub digest: Vec<u8>,            // Variable length
+}
+
+impl RecordDataRead for DS {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let key_tag = buffer.read_u16()?;
+        let algorithm = DNSSECAlgorithm::from_num(buffer.read_u8()?);
+        let digest_type = DigestType::from_num(buffer.read_u8()?);
+        // Minus four bytes from the previous fields
+        let digest_len = (preamble.len as usize).saturating_sub(4);
+        let digest = buffer.read_bytes(digest_len)?;
+
+        Ok(DS {
+            key_tag,
+            algorithm,
+            digest_type,
+            digest,
+        })
+    }
+}
+
+impl RecordDataWrite for DS {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u16(self.key_tag)?;
+        buffer.write_u8(self.algorithm.to_num())?;
+        buffer.write_u8(self.digest_type.to_num())?;
+        buffer.write_bytes(&self.digest)?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::DS
+    }
+}
+
+/// Presentation format: <key tag> <algorithm> <digest type> <hexadecimal digest>
+impl fmt::Display for DS {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.key_tag,
+            self.algorithm.to_num(),
+            self.digest_type.to_num(),
+            hex_encode(&self.digest)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    #[test]
+    fn test_read_ds() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x2B, 0x00, 0x01, 0x00, 0x01, 0x51, 0x80, 0x00, 0x24, 0x4F, 0x66, 0x08, 0x02, 0xE0,
+            0x6D, 0x44, 0xB8, 0x0B, 0x8F, 0x1D, 0x39, 0xA9, 0x5C, 0x0B, 0x0D, 0x7C, 0x65, 0xD0,
+            0x84, 0x58, 0xE8, 0x80, 0x40, 0x9B, 0xBC, 0x68, 0x34, 0x57, 0x10, 0x42, 0x37, 0xC7,
+            0xF8, 0xEC, 0x8D,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "".parse().unwrap(),
+            Class::IN,
+            86400,
+            RecordData::DS(DS {
+                key_tag: 20326,
+                algorithm: DNSSECAlgorithm::RSASHA256,
+                digest_type: DigestType::SHA256,
+                digest: vec![
+                    0xE0, 0x6D, 0x44, 0xB8, 0x0B, 0x8F, 0x1D, 0x39, 0xA9, 0x5C, 0x0B, 0x0D, 0x7C,
+                    0x65, 0xD0, 0x84, 0x58, 0xE8, 0x80, 0x40, 0x9B, 0xBC, 0x68, 0x34, 0x57, 0x10,
+                    0x42, 0x37, 0xC7, 0xF8, 0xEC, 0x8D,
+                ],
+            }),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 36);
+        assert_eq!(parsed_record, expected_record);
+    }
+
+    #[test]
+    fn test_write_ds() {
+        let ds_record = Record::new(
+            "".parse().unwrap(),
+            Class::IN,
+            86400,
+            RecordData::DS(DS {
+                key_tag: 20326,
+                algorithm: DNSSECAlgorithm::RSASHA256,
+                digest_type: DigestType::SHA256,
+                digest: vec![
+                    0xE0, 0x6D, 0x44, 0xB8, 0x0B, 0x8F, 0x1D, 0x39, 0xA9, 0x5C, 0x0B, 0x0D, 0x7C,
+                    0x65, 0xD0, 0x84, 0x58, 0xE8, 0x80, 0x40, 0x9B, 0xBC, 0x68, 0x34, 0x57, 0x10,
+                    0x42, 0x37, 0xC7, 0xF8, 0xEC, 0x8D,
+                ],
+            }),
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        ds_record.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x2B, 0x00, 0x01, 0x00, 0x01, 0x51, 0x80, 0x00, 0x24, 0x4F, 0x66, 0x08, 0x02, 0xE0,
+            0x6D, 0x44, 0xB8, 0x0B, 0x8F, 0x1D, 0x39, 0xA9, 0x5C, 0x0B, 0x0D, 0x7C, 0x65, 0xD0,
+            0x84, 0x58, 0xE8, 0x80, 0x40, 0x9B, 0xBC, 0x68, 0x34, 0x57, 0x10, 0x42, 0x37, 0xC7,
+            0xF8, 0xEC, 0x8D,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_display_ds() {
+        let ds = DS {
+            key_tag: 3613,
+            algorithm: DNSSECAlgorithm::ED25519,
+            digest_type: DigestType::SHA256,
+            digest: vec![0x3A, 0xA5, 0xAB, 0x37],
+        };
+
+        assert_eq!(ds.to_string(), "3613 15 2 3AA5AB37");
+    }
+}
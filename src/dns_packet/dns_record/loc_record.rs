@@ -0,0 +1,334 @@
+use super::{
+    DNSError, DNSPacketBuffer, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+
+/// Wire value of the equator and of the prime meridian.
+pub const LOC_EQUATOR: u32 = 1 << 31;
+/// Wire value of the WGS 84 reference spheroid, 100 000 m above the base altitude.
+pub const LOC_ALTITUDE_BASE: u32 = 10_000_000;
+
+const THOUSANDTHS_PER_DEGREE: f64 = 3_600_000.0;
+const MAX_LATITUDE: i64 = 90 * 3_600_000;
+const MAX_LONGITUDE: i64 = 180 * 3_600_000;
+const MAX_PRECISION: u64 = 9_000_000_000;
+
+/// Default size of 1 m, horizontal precision of 10 km and vertical precision of 10 m
+/// (RFC 1876 section 3).
+const DEFAULT_SIZE: u8 = 0x12;
+const DEFAULT_HORIZ_PRE: u8 = 0x16;
+const DEFAULT_VERT_PRE: u8 = 0x13;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LOC {
+    pub version: u8,    // Always 0
+    pub size: u8, // Diameter in cm, as a mantissa (high nibble) and a power of 10 (low nibble)
+    pub horiz_pre: u8, // Same encoding as the size
+    pub vert_pre: u8, // Same encoding as the size
+    pub latitude: u32, // Thousandths of arc seconds, LOC_EQUATOR at the equator
+    pub longitude: u32, // Thousandths of arc seconds, LOC_EQUATOR at the prime meridian
+    pub altitude: u32, // Centimeters, LOC_ALTITUDE_BASE at the reference spheroid
+}
+
+/// Decode a size or precision into centimeters.
+fn decode_precision(precision: u8) -> u64 {
+    let mantissa = (precision >> 4) as u64;
+    let exponent = (precision & 0x0F) as u32;
+    mantissa * 10u64.saturating_pow(exponent)
+}
+
+/// Encode a size or precision in centimeters, rounded down to a single significant digit. None if
+/// it is over 90 000 km, the largest encodable value.
+pub(crate) fn encode_precision(centimeters: u64) -> Option<u8> {
+    if centimeters > MAX_PRECISION {
+        return None;
+    }
+    let mut mantissa = centimeters;
+    let mut exponent = 0u8;
+    while mantissa > 9 {
+        mantissa /= 10;
+        exponent += 1;
+    }
+    Some(((mantissa as u8) << 4) | exponent)
+}
+
+fn format_coordinate(
+    f: &mut fmt::Formatter<'_>,
+    coordinate: u32,
+    hemispheres: [char; 2],
+) -> fmt::Result {
+    let thousandths = coordinate as i64 - LOC_EQUATOR as i64;
+    let hemisphere = if thousandths < 0 {
+        hemispheres[1]
+    } else {
+        hemispheres[0]
+    };
+    let thousandths = thousandths.abs();
+    write!(
+        f,
+        "{} {} {}.{:03} {}",
+        thousandths / 3_600_000,
+        thousandths / 60_000 % 60,
+        thousandths / 1000 % 60,
+        thousandths % 1000,
+        hemisphere
+    )
+}
+
+fn format_centimeters(f: &mut fmt::Formatter<'_>, centimeters: u64) -> fmt::Result {
+    match centimeters % 100 {
+        0 => write!(f, "{}m", centimeters / 100),
+        rest => write!(f, "{}.{:02}m", centimeters / 100, rest),
+    }
+}
+
+impl LOC {
+    /// Build a location from decimal degrees, positive north and east, and an altitude in meters
+    /// above the reference spheroid, with the default size and precisions.
+    pub fn new(latitude: f64, longitude: f64, altitude: f64) -> Result<Self, DNSError> {
+        let latitude = (latitude * THOUSANDTHS_PER_DEGREE).round();
+        let longitude = (longitude * THOUSANDTHS_PER_DEGREE).round();
+        let altitude = (altitude * 100.0).round() + LOC_ALTITUDE_BASE as f64;
+        if !(-(MAX_LATITUDE as f64)..=MAX_LATITUDE as f64).contains(&latitude) {
+            return Err(DNSError::InvalidLOC("latitude out of range".to_string()));
+        }
+        if !(-(MAX_LONGITUDE as f64)..=MAX_LONGITUDE as f64).contains(&longitude) {
+            return Err(DNSError::InvalidLOC("longitude out of range".to_string()));
+        }
+        if !(0.0..=u32::MAX as f64).contains(&altitude) {
+            return Err(DNSError::InvalidLOC("altitude out of range".to_string()));
+        }
+
+        Ok(LOC {
+            version: 0,
+            size: DEFAULT_SIZE,
+            horiz_pre: DEFAULT_HORIZ_PRE,
+            vert_pre: DEFAULT_VERT_PRE,
+            latitude: (LOC_EQUATOR as i64 + latitude as i64) as u32,
+            longitude: (LOC_EQUATOR as i64 + longitude as i64) as u32,
+            altitude: altitude as u32,
+        })
+    }
+
+    /// Latitude in decimal degrees, positive north of the equator.
+    pub fn latitude_degrees(&self) -> f64 {
+        (self.latitude as i64 - LOC_EQUATOR as i64) as f64 / THOUSANDTHS_PER_DEGREE
+    }
+
+    /// Longitude in decimal degrees, positive east of the prime meridian.
+    pub fn longitude_degrees(&self) -> f64 {
+        (self.longitude as i64 - LOC_EQUATOR as i64) as f64 / THOUSANDTHS_PER_DEGREE
+    }
+
+    /// Altitude in meters above the reference spheroid.
+    pub fn altitude_meters(&self) -> f64 {
+        (self.altitude as i64 - LOC_ALTITUDE_BASE as i64) as f64 / 100.0
+    }
+
+    pub fn size_meters(&self) -> f64 {
+        decode_precision(self.size) as f64 / 100.0
+    }
+
+    pub fn horiz_pre_meters(&self) -> f64 {
+        decode_precision(self.horiz_pre) as f64 / 100.0
+    }
+
+    pub fn vert_pre_meters(&self) -> f64 {
+        decode_precision(self.vert_pre) as f64 / 100.0
+    }
+}
+
+impl RecordDataRead for LOC {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        if preamble.len != 16 {
+            return Err(DNSError::InvalidLOC(format!(
+                "RDATA of {} bytes instead of 16",
+                preamble.len
+            )));
+        }
+
+        // The format of other versions is unknown (RFC 1876 section 2)
+        let version = buffer.read_u8()?;
+        if version != 0 {
+            return Err(DNSError::InvalidLOC(format!(
+                "unsupported version {}",
+                version
+            )));
+        }
+
+        Ok(LOC {
+            version,
+            size: buffer.read_u8()?,
+            horiz_pre: buffer.read_u8()?,
+            vert_pre: buffer.read_u8()?,
+            latitude: buffer.read_u32()?,
+            longitude: buffer.read_u32()?,
+            altitude: buffer.read_u32()?,
+        })
+    }
+}
+
+impl RecordDataWrite for LOC {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u8(self.version)?;
+        buffer.write_u8(self.size)?;
+        buffer.write_u8(self.horiz_pre)?;
+        buffer.write_u8(self.vert_pre)?;
+        buffer.write_u32(self.latitude)?;
+        buffer.write_u32(self.longitude)?;
+        buffer.write_u32(self.altitude)?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::LOC
+    }
+}
+
+/// Presentation format (RFC 1876 section 3): <d1> <m1> <s1> <N|S> <d2> <m2> <s2> <E|W> <alt>m
+/// <siz>m <hp>m <vp>m
+impl fmt::Display for LOC {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        format_coordinate(f, self.latitude, ['N', 'S'])?;
+        write!(f, " ")?;
+        format_coordinate(f, self.longitude, ['E', 'W'])?;
+
+        let altitude = self.altitude as i64 - LOC_ALTITUDE_BASE as i64;
+        let sign = if altitude < 0 { "-" } else { "" };
+        let altitude = altitude.unsigned_abs();
+        write!(f, " {}{}.{:02}m", sign, altitude / 100, altitude % 100)?;
+
+        for precision in [self.size, self.horiz_pre, self.vert_pre] {
+            write!(f, " ")?;
+            format_centimeters(f, decode_precision(precision))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Record, RecordData, Unknown, HEADER_SIZE};
+    use super::*;
+
+    fn amsterdam() -> LOC {
+        LOC {
+            version: 0,
+            size: 0x12,
+            horiz_pre: 0x16,
+            vert_pre: 0x13,
+            latitude: 0x8B3C_F018,
+            longitude: 0x810C_BCE0,
+            altitude: 0x0098_95B8,
+        }
+    }
+
+    #[test]
+    fn test_read_write_loc() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62,
+            0x61, 0x72, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D,
+            0x00, 0x00, 0x1D, 0x00, 0x01, 0x00, 0x00, 0x00, 0xFE, 0x00, 0x10, 0x00, 0x12, 0x16,
+            0x13, 0x8B, 0x3C, 0xF0, 0x18, 0x81, 0x0C, 0xBC, 0xE0, 0x00, 0x98, 0x95, 0xB8,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
+            254,
+            RecordData::LOC(amsterdam()),
+        );
+        assert_eq!(parsed_record.preamble.len, 16);
+        assert_eq!(parsed_record, expected_record);
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        expected_record.write_to_buffer(&mut buffer).unwrap();
+        assert_eq!(buffer.get_data(), &dns_packet_data[..]);
+
+        // Versions other than 0 are kept as unknown data
+        let mut data = dns_packet_data;
+        data[39] = 1;
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+        assert_eq!(
+            parsed_record.data(),
+            &RecordData::Unknown(Unknown {
+                code: 29,
+                data: data[39..].to_vec(),
+            })
+        );
+
+        let mut data = dns_packet_data;
+        data[38] = 0x0F;
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+        assert_eq!(
+            Record::parse_from_buffer(&mut dns_packet_buffer),
+            Err(DNSError::InvalidLOC(
+                "RDATA of 15 bytes instead of 16".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_display_loc() {
+        let loc = LOC {
+            size: 0x00,
+            horiz_pre: 0x25,
+            vert_pre: 0x52,
+            latitude: LOC_EQUATOR - 123_456,
+            longitude: LOC_EQUATOR - 648_000_000,
+            altitude: LOC_ALTITUDE_BASE + 4_205,
+            ..amsterdam()
+        };
+
+        assert_eq!(
+            amsterdam().to_string(),
+            "52 22 23.000 N 4 53 32.000 E -2.00m 1m 10000m 10m"
+        );
+        assert_eq!(
+            loc.to_string(),
+            "0 2 3.456 S 180 0 0.000 W 42.05m 0m 2000m 5m"
+        );
+    }
+
+    #[test]
+    fn test_loc_degrees() {
+        let loc = amsterdam();
+
+        assert!((loc.latitude_degrees() - 52.373_055_555).abs() < 1e-9);
+        assert!((loc.longitude_degrees() - 4.892_222_222).abs() < 1e-9);
+        assert_eq!(loc.altitude_meters(), -2.0);
+        assert_eq!(
+            (
+                loc.size_meters(),
+                loc.horiz_pre_meters(),
+                loc.vert_pre_meters()
+            ),
+            (1.0, 10_000.0, 10.0)
+        );
+        assert_eq!(LOC::new(52.373_055_555_6, 4.892_222_222_2, -2.0), Ok(loc));
+        assert!(LOC::new(-90.5, 0.0, 0.0).is_err());
+        assert!(LOC::new(0.0, 0.0, -100_000.01).is_err());
+
+        assert_eq!(encode_precision(150), Some(0x12));
+        assert_eq!(encode_precision(9_000_000_000), Some(0x99));
+        assert_eq!(encode_precision(9_000_000_001), None);
+    }
+}
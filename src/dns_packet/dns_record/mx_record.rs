@@ -1,8 +1,9 @@
 use super::{
     DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct MX {
     pub preference: u16,
     pub exchange: Domain,
@@ -15,7 +16,7 @@ impl RecordDataRead for MX {
     ) -> Result<Self, DNSError> {
         Ok(MX {
             preference: buffer.read_u16()?,
-            exchange: Domain::parse_domain(buffer, 0)?,
+            exchange: Domain::parse_from_buffer(buffer)?,
         })
     }
 }
@@ -39,9 +40,16 @@ impl RecordDataWrite for MX {
     }
 }
 
+/// Presentation format: <preference> <exchange>
+impl fmt::Display for MX {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.preference, self.exchange)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
     #[test]
@@ -59,12 +67,12 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             253,
             RecordData::MX(MX {
                 preference: 3154,
-                exchange: Domain("foo.example.com".to_string()),
+                exchange: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -75,12 +83,12 @@ mod tests {
     #[test]
     fn test_write_mx() {
         let mx_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             253,
             RecordData::MX(MX {
                 preference: 3154,
-                exchange: Domain("foo.example.com".to_string()),
+                exchange: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -101,4 +109,14 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_mx() {
+        let mx = MX {
+            preference: 10,
+            exchange: "mail.example.com".parse().unwrap(),
+        };
+
+        assert_eq!(mx.to_string(), "10 mail.example.com.");
+    }
 }
@@ -0,0 +1,196 @@
+use super::naptr_regexp::Regexp;
+use super::{
+    quote_character_string, DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead,
+    RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NAPTR {
+    pub order: u16,
+    pub preference: u16,
+    pub flags: String,       // <character-string>
+    pub services: String,    // <character-string>
+    pub regexp: String,      // <character-string>, empty if the replacement is used
+    pub replacement: Domain, // Root if the regexp is used
+}
+
+fn read_character_string(buffer: &mut DNSPacketBuffer) -> Result<String, DNSError> {
+    let len = buffer.read_u8()? as usize;
+    String::from_utf8(buffer.read_bytes(len)?).map_err(|_| DNSError::NonUTF8)
+}
+
+fn write_character_string(buffer: &mut DNSPacketBuffer, data: &str) -> Result<(), DNSError> {
+    let len = u8::try_from(data.len())
+        .map_err(|_| DNSError::InvalidNAPTR(format!("{} is longer than 255 bytes", data)))?;
+    buffer.write_u8(len)?;
+    buffer.write_bytes(data.as_bytes())
+}
+
+impl NAPTR {
+    /// Whether the flags hold the given one, which is case insensitive.
+    pub fn has_flag(&self, flag: char) -> bool {
+        self.flags.chars().any(|c| c.eq_ignore_ascii_case(&flag))
+    }
+
+    /// Apply the substitution expression to an input string, e.g. the E.164 number of an ENUM
+    /// query (RFC 3402 section 3.2). None if the record has no regexp or it doesn't match.
+    pub fn apply_regexp(&self, input: &str) -> Result<Option<String>, DNSError> {
+        if self.regexp.is_empty() {
+            return Ok(None);
+        }
+        Regexp::parse(&self.regexp)?.substitute(input)
+    }
+}
+
+/// Sort NAPTR records in the order clients must process them (RFC 3403 section 4.1): by
+/// increasing order, then by increasing preference. The sort is stable.
+pub fn sort_naptr(records: &mut [NAPTR]) {
+    records.sort_by_key(|naptr| (naptr.order, naptr.preference));
+}
+
+impl RecordDataRead for NAPTR {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        _preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        Ok(NAPTR {
+            order: buffer.read_u16()?,
+            preference: buffer.read_u16()?,
+            flags: read_character_string(buffer)?,
+            services: read_character_string(buffer)?,
+            regexp: read_character_string(buffer)?,
+            replacement: Domain::parse_from_buffer(buffer)?,
+        })
+    }
+}
+
+impl RecordDataWrite for NAPTR {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u16(self.order)?;
+        buffer.write_u16(self.preference)?;
+        write_character_string(buffer, &self.flags)?;
+        write_character_string(buffer, &self.services)?;
+        write_character_string(buffer, &self.regexp)?;
+        // The replacement must not be compressed (RFC 3403 section 4.1)
+        self.replacement.write_uncompressed_to_buffer(buffer)?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::NAPTR
+    }
+}
+
+/// Presentation format: <order> <preference> <flags> <services> <regexp> <replacement>, the
+/// flags, services and regexp being quoted.
+impl fmt::Display for NAPTR {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.order,
+            self.preference,
+            quote_character_string(self.flags.as_bytes()),
+            quote_character_string(self.services.as_bytes()),
+            quote_character_string(self.regexp.as_bytes()),
+            self.replacement
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    fn enum_naptr(order: u16, preference: u16, regexp: &str) -> NAPTR {
+        NAPTR {
+            order,
+            preference,
+            flags: "u".to_string(),
+            services: "E2U+sip".to_string(),
+            regexp: regexp.to_string(),
+            replacement: Domain::root(),
+        }
+    }
+
+    #[test]
+    fn test_read_write_naptr() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62,
+            0x61, 0x72, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D,
+            0x00, 0x00, 0x23, 0x00, 0x01, 0x00, 0x00, 0x00, 0xFE, 0x00, 0x1A, 0x00, 0x64, 0x00,
+            0x0A, 0x01, 0x53, 0x07, 0x53, 0x49, 0x50, 0x2B, 0x44, 0x32, 0x55, 0x00, 0x04, 0x5F,
+            0x73, 0x69, 0x70, 0x04, 0x5F, 0x75, 0x64, 0x70, 0x00,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
+            254,
+            RecordData::NAPTR(NAPTR {
+                order: 100,
+                preference: 10,
+                flags: "S".to_string(),
+                services: "SIP+D2U".to_string(),
+                regexp: String::new(),
+                replacement: "_sip._udp".parse().unwrap(),
+            }),
+        );
+        assert_eq!(parsed_record.preamble.len, 26);
+        assert_eq!(parsed_record, expected_record);
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        expected_record.write_to_buffer(&mut buffer).unwrap();
+        assert_eq!(buffer.get_data(), &dns_packet_data[..]);
+    }
+
+    #[test]
+    fn test_display_naptr() {
+        let naptr = enum_naptr(100, 10, "!^.*$!sip:info@example.com!");
+
+        assert_eq!(
+            naptr.to_string(),
+            "100 10 \"u\" \"E2U+sip\" \"!^.*$!sip:info@example.com!\" ."
+        );
+    }
+
+    #[test]
+    fn test_apply_regexp_and_sort() {
+        let mut records = vec![
+            enum_naptr(200, 10, "!^.*$!mailto:info@example.com!"),
+            enum_naptr(100, 20, "!^\\+44(.*)$!sip:\\1@example.com!"),
+            enum_naptr(100, 10, "!^\\+1(.*)$!sip:\\1@example.net!"),
+        ];
+        sort_naptr(&mut records);
+
+        let results: Vec<Option<String>> = records
+            .iter()
+            .map(|naptr| naptr.apply_regexp("+441632960083").unwrap())
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                None,
+                Some("sip:1632960083@example.com".to_string()),
+                Some("mailto:info@example.com".to_string()),
+            ]
+        );
+        assert!(records[0].has_flag('U'));
+        assert_eq!(enum_naptr(1, 1, "").apply_regexp("+44"), Ok(None));
+        assert!(enum_naptr(1, 1, "!^(.*$!x!").apply_regexp("+44").is_err());
+    }
+}
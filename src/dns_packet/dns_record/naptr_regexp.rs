@@ -0,0 +1,530 @@
+use super::DNSError;
+use std::cell::Cell;
+use std::fmt;
+
+/// Bounds on the work spent matching a regular expression, which comes from untrusted records.
+const MAX_STEPS: usize = 100_000;
+const MAX_DEPTH: usize = 1024;
+
+const MAX_BACKREF: usize = 9;
+
+#[derive(Debug, Clone)]
+enum ClassItem {
+    Range(char, char),
+    Named(fn(&char) -> bool),
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class {
+        negated: bool,
+        items: Vec<ClassItem>,
+    },
+    Start,
+    End,
+    Group(Box<Node>, usize),
+    Alternation(Vec<Node>),
+    Concat(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+fn invalid(msg: impl fmt::Display) -> DNSError {
+    DNSError::InvalidNAPTR(format!("{} in regexp", msg))
+}
+
+/// Parser of POSIX extended regular expressions (IEEE Std 1003.1 section 9.4). Only the syntax is
+/// POSIX, matching follows different rules (see Matcher).
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    groups: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn parse_alternation(&mut self) -> Result<Node, DNSError> {
+        let mut alternatives = vec![self.parse_concat()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.parse_concat()?);
+        }
+        Ok(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => Node::Alternation(alternatives),
+        })
+    }
+
+    fn parse_concat(&mut self) -> Result<Node, DNSError> {
+        let mut nodes = Vec::<Node>::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            nodes.push(self.parse_repeat()?);
+        }
+        Ok(Node::Concat(nodes))
+    }
+
+    fn parse_repeat(&mut self) -> Result<Node, DNSError> {
+        let mut node = self.parse_atom()?;
+        while let Some(c) = self.peek() {
+            let (min, max) = match c {
+                '*' => (0, None),
+                '+' => (1, None),
+                '?' => (0, Some(1)),
+                '{' => {
+                    self.pos += 1;
+                    self.parse_bounds()?
+                }
+                _ => break,
+            };
+            if c != '{' {
+                self.pos += 1;
+            }
+            node = Node::Repeat {
+                node: Box::new(node),
+                min,
+                max,
+            };
+        }
+        Ok(node)
+    }
+
+    fn parse_decimal(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse::<usize>().ok()
+    }
+
+    /// Parse the bounds of an interval expression, after its opening brace: {m}, {m,} or {m,n}.
+    fn parse_bounds(&mut self) -> Result<(usize, Option<usize>), DNSError> {
+        let min = self
+            .parse_decimal()
+            .ok_or_else(|| invalid("invalid interval"))?;
+        let max = match self.next() {
+            Some('}') => return Ok((min, Some(min))),
+            Some(',') => self.parse_decimal(),
+            _ => return Err(invalid("invalid interval")),
+        };
+        if self.next() != Some('}') || max.is_some_and(|max| max < min) {
+            return Err(invalid("invalid interval"));
+        }
+        Ok((min, max))
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, DNSError> {
+        match self.next() {
+            Some('(') => {
+                self.groups += 1;
+                let index = self.groups;
+                let inner = self.parse_alternation()?;
+                if self.next() != Some(')') {
+                    return Err(invalid("unbalanced parenthesis"));
+                }
+                Ok(Node::Group(Box::new(inner), index))
+            }
+            Some('[') => self.parse_class(),
+            Some('.') => Ok(Node::Any),
+            Some('^') => Ok(Node::Start),
+            Some('$') => Ok(Node::End),
+            Some('\\') => self
+                .next()
+                .map(Node::Char)
+                .ok_or_else(|| invalid("trailing backslash")),
+            Some(c @ ('*' | '+' | '?' | '{')) => {
+                Err(invalid(format!("nothing to repeat with {}", c)))
+            }
+            Some(c) => Ok(Node::Char(c)),
+            None => Err(invalid("unexpected end")),
+        }
+    }
+
+    /// Parse a bracket expression, after its opening bracket.
+    fn parse_class(&mut self) -> Result<Node, DNSError> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut items = Vec::<ClassItem>::new();
+        let mut first = true;
+        loop {
+            let c = self.next().ok_or_else(|| invalid("unbalanced bracket"))?;
+            match c {
+                ']' if !first => break,
+                '[' if self.peek() == Some(':') => {
+                    let rest: String = self.chars[self.pos..].iter().collect();
+                    let end = rest
+                        .find(":]")
+                        .ok_or_else(|| invalid("unbalanced bracket"))?;
+                    let name = &rest[1..end];
+                    let class: fn(&char) -> bool = match name {
+                        "alnum" => char::is_ascii_alphanumeric,
+                        "alpha" => char::is_ascii_alphabetic,
+                        "digit" => char::is_ascii_digit,
+                        "lower" => char::is_ascii_lowercase,
+                        "upper" => char::is_ascii_uppercase,
+                        "space" => char::is_ascii_whitespace,
+                        "punct" => char::is_ascii_punctuation,
+                        "xdigit" => char::is_ascii_hexdigit,
+                        _ => return Err(invalid(format!("unknown class {}", name))),
+                    };
+                    items.push(ClassItem::Named(class));
+                    self.pos += name.chars().count() + 3;
+                }
+                start => {
+                    let is_range = self.peek() == Some('-')
+                        && self.chars.get(self.pos + 1).is_some_and(|c| *c != ']');
+                    if is_range {
+                        let end = self.chars[self.pos + 1];
+                        self.pos += 2;
+                        if end < start {
+                            return Err(invalid("invalid range"));
+                        }
+                        items.push(ClassItem::Range(start, end));
+                    } else {
+                        items.push(ClassItem::Range(start, start));
+                    }
+                }
+            }
+            first = false;
+        }
+
+        Ok(Node::Class { negated, items })
+    }
+}
+
+type Captures = Vec<Option<(usize, usize)>>;
+
+/// Backtracking matcher, greedy and leftmost first as Perl's, instead of leftmost longest as
+/// POSIX requires: alternatives are tried in order and the first one leading to a match is kept.
+/// On "ab", ^(a|ab)(.*)$ captures "a" and "b", where POSIX would capture "ab" and "".
+struct Matcher<'a> {
+    input: &'a [char],
+    ignore_case: bool,
+    steps: Cell<usize>,
+    depth: Cell<usize>,
+    exhausted: Cell<bool>,
+}
+
+impl Matcher<'_> {
+    fn same_char(&self, a: char, b: char) -> bool {
+        a == b || self.ignore_case && a.to_lowercase().eq(b.to_lowercase())
+    }
+
+    fn in_class(&self, c: char, items: &[ClassItem]) -> bool {
+        let candidates = match self.ignore_case {
+            true => vec![c, c.to_ascii_lowercase(), c.to_ascii_uppercase()],
+            false => vec![c],
+        };
+        candidates.iter().any(|c| {
+            items.iter().any(|item| match item {
+                ClassItem::Range(start, end) => (*start..=*end).contains(c),
+                ClassItem::Named(class) => class(c),
+            })
+        })
+    }
+
+    fn match_node(
+        &self,
+        node: &Node,
+        pos: usize,
+        caps: &mut Captures,
+        k: &mut dyn FnMut(usize, &mut Captures) -> bool,
+    ) -> bool {
+        self.steps.set(self.steps.get() + 1);
+        if self.exhausted.get() || self.steps.get() > MAX_STEPS || self.depth.get() >= MAX_DEPTH {
+            self.exhausted.set(true);
+            return false;
+        }
+        self.depth.set(self.depth.get() + 1);
+
+        let input = self.input;
+        let matched = match node {
+            Node::Char(c) => {
+                pos < input.len() && self.same_char(input[pos], *c) && k(pos + 1, caps)
+            }
+            Node::Any => pos < input.len() && k(pos + 1, caps),
+            Node::Class { negated, items } => {
+                pos < input.len()
+                    && self.in_class(input[pos], items) != *negated
+                    && k(pos + 1, caps)
+            }
+            Node::Start => pos == 0 && k(pos, caps),
+            Node::End => pos == input.len() && k(pos, caps),
+            Node::Group(inner, index) => {
+                let index = *index;
+                self.match_node(inner, pos, caps, &mut |end, caps| {
+                    let previous = caps[index];
+                    caps[index] = Some((pos, end));
+                    if k(end, caps) {
+                        return true;
+                    }
+                    caps[index] = previous;
+                    false
+                })
+            }
+            Node::Alternation(alternatives) => alternatives
+                .iter()
+                .any(|alternative| self.match_node(alternative, pos, caps, k)),
+            Node::Concat(nodes) => self.match_concat(nodes, pos, caps, k),
+            Node::Repeat { node, min, max } => self.match_repeat(node, *min, *max, 0, pos, caps, k),
+        };
+
+        self.depth.set(self.depth.get() - 1);
+        matched
+    }
+
+    fn match_concat(
+        &self,
+        nodes: &[Node],
+        pos: usize,
+        caps: &mut Captures,
+        k: &mut dyn FnMut(usize, &mut Captures) -> bool,
+    ) -> bool {
+        match nodes.split_first() {
+            None => k(pos, caps),
+            Some((first, rest)) => self.match_node(first, pos, caps, &mut |next, caps| {
+                self.match_concat(rest, next, caps, k)
+            }),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn match_repeat(
+        &self,
+        node: &Node,
+        min: usize,
+        max: Option<usize>,
+        count: usize,
+        pos: usize,
+        caps: &mut Captures,
+        k: &mut dyn FnMut(usize, &mut Captures) -> bool,
+    ) -> bool {
+        // Greedy: one more repetition first. Empty repetitions only count towards the minimum,
+        // else they would repeat forever.
+        if max.is_none_or(|max| count < max)
+            && self.match_node(node, pos, caps, &mut |next, caps| {
+                (next != pos || count < min)
+                    && self.match_repeat(node, min, max, count + 1, next, caps, k)
+            })
+        {
+            return true;
+        }
+        count >= min && k(pos, caps)
+    }
+}
+
+/// Substitution expression of a NAPTR record (RFC 3402 section 3.2):
+/// <delimiter><ERE><delimiter><replacement><delimiter>[i]
+#[derive(Debug, Clone)]
+pub(crate) struct Regexp {
+    node: Node,
+    groups: usize,
+    replacement: Vec<char>,
+    ignore_case: bool,
+}
+
+impl Regexp {
+    pub(crate) fn parse(regexp: &str) -> Result<Self, DNSError> {
+        let mut chars = regexp.chars();
+        let delimiter = chars.next().ok_or_else(|| invalid("missing delimiter"))?;
+        if delimiter.is_ascii_digit() || delimiter == '\\' || delimiter == 'i' {
+            return Err(invalid(format!("invalid delimiter {}", delimiter)));
+        }
+
+        // Split on the delimiters which are not escaped
+        let mut parts = vec![Vec::<char>::new()];
+        while let Some(c) = chars.next() {
+            let part = parts.len() - 1;
+            match c {
+                '\\' => {
+                    parts[part].push(c);
+                    parts[part].extend(chars.next());
+                }
+                c if c == delimiter => parts.push(Vec::new()),
+                c => parts[part].push(c),
+            }
+        }
+        let [ere, replacement, flags] = <[Vec<char>; 3]>::try_from(parts).map_err(|_| {
+            DNSError::InvalidNAPTR(format!("{} is not a substitution expression", regexp))
+        })?;
+        let ignore_case = match flags.as_slice() {
+            [] => false,
+            ['i'] => true,
+            _ => return Err(invalid("unknown flags")),
+        };
+
+        let mut parser = Parser {
+            chars: ere,
+            pos: 0,
+            groups: 0,
+        };
+        let node = parser.parse_alternation()?;
+        if parser.pos < parser.chars.len() {
+            return Err(invalid("unbalanced parenthesis"));
+        }
+
+        let regexp = Regexp {
+            node,
+            groups: parser.groups,
+            replacement,
+            ignore_case,
+        };
+        regexp.check_backrefs()?;
+        Ok(regexp)
+    }
+
+    fn check_backrefs(&self) -> Result<(), DNSError> {
+        let mut chars = self.replacement.iter();
+        while let Some(c) = chars.next() {
+            if *c != '\\' {
+                continue;
+            }
+            match chars.next().and_then(|c| c.to_digit(10)) {
+                Some(backref)
+                    if backref == 0 || backref as usize > self.groups.min(MAX_BACKREF) =>
+                {
+                    return Err(invalid(format!("invalid backreference \\{}", backref)))
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply the substitution to an input string. The result is the replacement with its
+    /// backreferences substituted, not the input with the matched part replaced. None if the
+    /// regular expression doesn't match.
+    pub(crate) fn substitute(&self, input: &str) -> Result<Option<String>, DNSError> {
+        let input: Vec<char> = input.chars().collect();
+        let matcher = Matcher {
+            input: &input,
+            ignore_case: self.ignore_case,
+            steps: Cell::new(0),
+            depth: Cell::new(0),
+            exhausted: Cell::new(false),
+        };
+
+        let mut caps: Captures = vec![None; self.groups + 1];
+        let mut found: Option<Captures> = None;
+        for start in 0..=input.len() {
+            let matched = matcher.match_node(&self.node, start, &mut caps, &mut |end, caps| {
+                caps[0] = Some((start, end));
+                found = Some(caps.clone());
+                true
+            });
+            if matched {
+                break;
+            }
+        }
+        if matcher.exhausted.get() {
+            return Err(invalid("matching needs too many steps"));
+        }
+        let Some(caps) = found else {
+            return Ok(None);
+        };
+
+        let mut result = String::new();
+        let mut chars = self.replacement.iter();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(d) if d.is_ascii_digit() => {
+                        let index = d.to_digit(10).unwrap_or_default() as usize;
+                        if let Some(Some((start, end))) = caps.get(index) {
+                            result.extend(&input[*start..*end]);
+                        }
+                    }
+                    Some(escaped) => result.push(*escaped),
+                    None => (),
+                },
+                c => result.push(*c),
+            }
+        }
+
+        Ok(Some(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn substitute(regexp: &str, input: &str) -> Option<String> {
+        Regexp::parse(regexp).unwrap().substitute(input).unwrap()
+    }
+
+    #[test]
+    fn test_substitute() {
+        // RFC 3403 section 6.2 and RFC 6116 section 3.4 examples
+        assert_eq!(
+            substitute("!^.*$!sip:information@foo.se!i", "+46858752000"),
+            Some("sip:information@foo.se".to_string())
+        );
+        assert_eq!(
+            substitute("!^\\+441632960(.*)$!sip:\\1@example.com!", "+441632960083"),
+            Some("sip:083@example.com".to_string())
+        );
+        assert_eq!(
+            substitute(
+                "!^urn:cid:.+@([^\\.]+\\.)(.*)$!\\2!i",
+                "URN:cid:199606121851.1@bar.example.com"
+            ),
+            Some("example.com".to_string())
+        );
+        assert_eq!(
+            substitute("/^(a|bc){2,3}([[:digit:]]?)x$/\\1-\\2/", "abcbc7x"),
+            Some("bc-7".to_string())
+        );
+        assert_eq!(substitute("!^\\+1(.*)$!\\1!", "+441632960083"), None);
+        assert_eq!(substitute("#a\\#b#c#", "xa#by"), Some("c".to_string()));
+        // Leftmost first, not leftmost longest
+        assert_eq!(
+            substitute("!^(a|ab)(.*)$!\\1-\\2!", "ab"),
+            Some("a-b".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_regexp() {
+        for regexp in [
+            "",
+            "1a1b1",
+            "!a!b",
+            "!a!b!x",
+            "!(a!b!",
+            "!a)!b!",
+            "!*a!b!",
+            "![a!b!",
+            "!a{2,1}!b!",
+            "!(a)!\\2!",
+        ] {
+            assert!(matches!(
+                Regexp::parse(regexp),
+                Err(DNSError::InvalidNAPTR(_))
+            ));
+        }
+
+        let pathological = Regexp::parse("!^(a*)*(a*)*(a*)*b$!x!").unwrap();
+        assert!(pathological.substitute(&"a".repeat(40)).is_err());
+    }
+}
@@ -1,8 +1,9 @@
 use super::{
     DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct NS {
     pub nsdname: Domain,
 }
@@ -13,7 +14,7 @@ impl RecordDataRead for NS {
         _preamble: &RecordPreamble,
     ) -> Result<Self, DNSError> {
         Ok(NS {
-            nsdname: Domain::parse_domain(buffer, 0)?,
+            nsdname: Domain::parse_from_buffer(buffer)?,
         })
     }
 }
@@ -32,13 +33,20 @@ impl RecordDataWrite for NS {
     }
 
     fn query_type(&self) -> QueryType {
-        QueryType::CNAME
+        QueryType::NS
+    }
+}
+
+/// Presentation format: <nsdname>
+impl fmt::Display for NS {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.nsdname)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
     #[test]
@@ -56,11 +64,11 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             254,
             RecordData::NS(NS {
-                nsdname: Domain("foo.example.com".to_string()),
+                nsdname: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -71,11 +79,11 @@ mod tests {
     #[test]
     fn test_write_ns() {
         let a_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             254,
             RecordData::NS(NS {
-                nsdname: Domain("foo.example.com".to_string()),
+                nsdname: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -87,7 +95,7 @@ mod tests {
         let expected_data = [
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62,
             0x61, 0x72, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D,
-            0x00, 0x00, 0x05, 0x00, 0x01, 0x00, 0x00, 0x00, 0xFE, 0x00, 0x06, 0x03, 0x66, 0x6F,
+            0x00, 0x00, 0x02, 0x00, 0x01, 0x00, 0x00, 0x00, 0xFE, 0x00, 0x06, 0x03, 0x66, 0x6F,
             0x6F, 0xC0, 0x10,
         ];
 
@@ -96,4 +104,13 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_ns() {
+        let ns = NS {
+            nsdname: "www.example.com".parse().unwrap(),
+        };
+
+        assert_eq!(ns.to_string(), "www.example.com.");
+    }
 }
@@ -0,0 +1,225 @@
+use super::type_bitmap::{parse_type_bitmap, write_type_bitmap};
+use super::{
+    base32hex_encode, hex_encode, DNSError, DNSPacketBuffer, NSEC3HashAlgorithm, QueryType,
+    RecordDataRead, RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+
+pub const NSEC3_OPT_OUT_FLAG: u8 = 0b0000_0001;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NSEC3 {
+    pub hash_algorithm: NSEC3HashAlgorithm, // 1 byte
+    pub flags: u8,                          // 1 byte
+    pub iterations: u16,                    // 2 bytes
+    pub salt: Vec<u8>,                      // 1 byte length + variable length
+    pub next_hashed_owner: Vec<u8>,         // 1 byte length + variable length
+    pub types: Vec<QueryType>,              // Variable length type bit maps
+}
+
+impl NSEC3 {
+    pub fn is_opt_out(&self) -> bool {
+        self.flags & NSEC3_OPT_OUT_FLAG != 0
+    }
+}
+
+impl RecordDataRead for NSEC3 {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let starting_pos = buffer.get_pos();
+
+        let hash_algorithm = NSEC3HashAlgorithm::from_num(buffer.read_u8()?);
+        let flags = buffer.read_u8()?;
+        let iterations = buffer.read_u16()?;
+        let salt_len = buffer.read_u8()?;
+        let salt = buffer.read_bytes(salt_len as usize)?;
+        let hash_len = buffer.read_u8()?;
+        let next_hashed_owner = buffer.read_bytes(hash_len as usize)?;
+
+        let read_len = buffer.get_pos() - starting_pos;
+        let bitmap_len = (preamble.len as usize)
+            .checked_sub(read_len)
+            .ok_or(DNSError::BadPointerPosition)?;
+        let types = parse_type_bitmap(buffer, bitmap_len)?;
+
+        Ok(NSEC3 {
+            hash_algorithm,
+            flags,
+            iterations,
+            salt,
+            next_hashed_owner,
+            types,
+        })
+    }
+}
+
+impl RecordDataWrite for NSEC3 {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u8(self.hash_algorithm.to_num())?;
+        buffer.write_u8(self.flags)?;
+        buffer.write_u16(self.iterations)?;
+        buffer.write_u8(self.salt.len() as u8)?;
+        buffer.write_bytes(&self.salt)?;
+        buffer.write_u8(self.next_hashed_owner.len() as u8)?;
+        buffer.write_bytes(&self.next_hashed_owner)?;
+        write_type_bitmap(&self.types, buffer)?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::NSEC3
+    }
+}
+
+/// Presentation format: <hash algorithm> <flags> <iterations> <hexadecimal salt or -> <base32
+/// next hashed owner name> <type>...
+impl fmt::Display for NSEC3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let salt = if self.salt.is_empty() {
+            "-".to_string()
+        } else {
+            hex_encode(&self.salt)
+        };
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.hash_algorithm.to_num(),
+            self.flags,
+            self.iterations,
+            salt,
+            base32hex_encode(&self.next_hashed_owner).to_uppercase()
+        )?;
+        for record_type in self.types.iter() {
+            write!(f, " {}", record_type)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    #[test]
+    fn test_read_nsec3() {
+        // RFC 5155 appendix A example
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x30,
+            0x70, 0x39, 0x6D, 0x68, 0x61, 0x76, 0x65, 0x71, 0x76, 0x6D, 0x36, 0x74, 0x37, 0x76,
+            0x62, 0x6C, 0x35, 0x6C, 0x6F, 0x70, 0x32, 0x75, 0x33, 0x74, 0x32, 0x72, 0x70, 0x33,
+            0x74, 0x6F, 0x6D, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x00, 0x00, 0x32,
+            0x00, 0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x27, 0x01, 0x01, 0x00, 0x0C, 0x04, 0xAA,
+            0xBB, 0xCC, 0xDD, 0x14, 0x17, 0x4E, 0xB2, 0x40, 0x9F, 0xE2, 0x8B, 0xCB, 0x48, 0x87,
+            0xA1, 0x83, 0x6F, 0x95, 0x7F, 0x0A, 0x84, 0x25, 0xE2, 0x7B, 0x00, 0x07, 0x22, 0x01,
+            0x00, 0x00, 0x00, 0x02, 0x90,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::NSEC3(NSEC3 {
+                hash_algorithm: NSEC3HashAlgorithm::SHA1,
+                flags: 1,
+                iterations: 12,
+                salt: vec![0xAA, 0xBB, 0xCC, 0xDD],
+                next_hashed_owner: vec![
+                    0x17, 0x4E, 0xB2, 0x40, 0x9F, 0xE2, 0x8B, 0xCB, 0x48, 0x87, 0xA1, 0x83, 0x6F,
+                    0x95, 0x7F, 0x0A, 0x84, 0x25, 0xE2, 0x7B,
+                ],
+                types: vec![
+                    QueryType::NS,
+                    QueryType::SOA,
+                    QueryType::MX,
+                    QueryType::RRSIG,
+                    QueryType::DNSKEY,
+                    QueryType::NSEC3PARAM,
+                ],
+            }),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 39);
+        assert_eq!(parsed_record, expected_record);
+        assert!(matches!(parsed_record.data, RecordData::NSEC3(ref nsec3) if nsec3.is_opt_out()));
+    }
+
+    #[test]
+    fn test_write_nsec3() {
+        let nsec3_record = Record::new(
+            "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::NSEC3(NSEC3 {
+                hash_algorithm: NSEC3HashAlgorithm::SHA1,
+                flags: 1,
+                iterations: 12,
+                salt: vec![0xAA, 0xBB, 0xCC, 0xDD],
+                next_hashed_owner: vec![
+                    0x17, 0x4E, 0xB2, 0x40, 0x9F, 0xE2, 0x8B, 0xCB, 0x48, 0x87, 0xA1, 0x83, 0x6F,
+                    0x95, 0x7F, 0x0A, 0x84, 0x25, 0xE2, 0x7B,
+                ],
+                types: vec![
+                    QueryType::MX,
+                    QueryType::DNSKEY,
+                    QueryType::NS,
+                    QueryType::SOA,
+                    QueryType::NSEC3PARAM,
+                    QueryType::RRSIG,
+                ],
+            }),
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        nsec3_record.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x30,
+            0x70, 0x39, 0x6D, 0x68, 0x61, 0x76, 0x65, 0x71, 0x76, 0x6D, 0x36, 0x74, 0x37, 0x76,
+            0x62, 0x6C, 0x35, 0x6C, 0x6F, 0x70, 0x32, 0x75, 0x33, 0x74, 0x32, 0x72, 0x70, 0x33,
+            0x74, 0x6F, 0x6D, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x00, 0x00, 0x32,
+            0x00, 0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x27, 0x01, 0x01, 0x00, 0x0C, 0x04, 0xAA,
+            0xBB, 0xCC, 0xDD, 0x14, 0x17, 0x4E, 0xB2, 0x40, 0x9F, 0xE2, 0x8B, 0xCB, 0x48, 0x87,
+            0xA1, 0x83, 0x6F, 0x95, 0x7F, 0x0A, 0x84, 0x25, 0xE2, 0x7B, 0x00, 0x07, 0x22, 0x01,
+            0x00, 0x00, 0x00, 0x02, 0x90,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_display_nsec3() {
+        let nsec3 = NSEC3 {
+            hash_algorithm: NSEC3HashAlgorithm::SHA1,
+            flags: 1,
+            iterations: 12,
+            salt: vec![0xAA, 0xBB, 0xCC, 0xDD],
+            next_hashed_owner: vec![
+                0x17, 0x4E, 0xB2, 0x40, 0x9F, 0xE2, 0x8B, 0xCB, 0x48, 0x87, 0xA1, 0x83, 0x6F, 0x95,
+                0x7F, 0x0A, 0x84, 0x25, 0xE2, 0x7B,
+            ],
+            types: vec![QueryType::A, QueryType::RRSIG],
+        };
+
+        assert_eq!(
+            nsec3.to_string(),
+            "1 1 12 AABBCCDD 2T7B4G4VSA5SMI47K61MV5BV1A22BOJR A RRSIG"
+        );
+    }
+}
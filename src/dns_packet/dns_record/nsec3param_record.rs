@@ -0,0 +1,149 @@
+use super::{
+    hex_encode, DNSError, DNSPacketBuffer, NSEC3HashAlgorithm, QueryType, RecordDataRead,
+    RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NSEC3PARAM {
+    pub hash_algorithm: NSEC3HashAlgorithm, // 1 byte
+    pub flags: u8,                          // 1 byte
+    pub iterations: u16,                    // 2 bytes
+    pub salt: Vec<u8>,                      // 1 byte length + variable length
+}
+
+impl RecordDataRead for NSEC3PARAM {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        _preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let hash_algorithm = NSEC3HashAlgorithm::from_num(buffer.read_u8()?);
+        let flags = buffer.read_u8()?;
+        let iterations = buffer.read_u16()?;
+        let salt_len = buffer.read_u8()?;
+        let salt = buffer.read_bytes(salt_len as usize)?;
+
+        Ok(NSEC3PARAM {
+            hash_algorithm,
+            flags,
+            iterations,
+            salt,
+        })
+    }
+}
+
+impl RecordDataWrite for NSEC3PARAM {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u8(self.hash_algorithm.to_num())?;
+        buffer.write_u8(self.flags)?;
+        buffer.write_u16(self.iterations)?;
+        buffer.write_u8(self.salt.len() as u8)?;
+        buffer.write_bytes(&self.salt)?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::NSEC3PARAM
+    }
+}
+
+/// Presentation format: <hash algorithm> <flags> <iterations> <hexadecimal salt or ->
+impl fmt::Display for NSEC3PARAM {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let salt = if self.salt.is_empty() {
+            "-".to_string()
+        } else {
+            hex_encode(&self.salt)
+        };
+        write!(
+            f,
+            "{} {} {} {}",
+            self.hash_algorithm.to_num(),
+            self.flags,
+            self.iterations,
+            salt
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    #[test]
+    fn test_read_nsec3param() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x00, 0x00, 0x33, 0x00, 0x01, 0x00, 0x00, 0x0E,
+            0x10, 0x00, 0x09, 0x01, 0x00, 0x00, 0x0C, 0x04, 0xAA, 0xBB, 0xCC, 0xDD,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "example".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::NSEC3PARAM(NSEC3PARAM {
+                hash_algorithm: NSEC3HashAlgorithm::SHA1,
+                flags: 0,
+                iterations: 12,
+                salt: vec![0xAA, 0xBB, 0xCC, 0xDD],
+            }),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 9);
+        assert_eq!(parsed_record, expected_record);
+    }
+
+    #[test]
+    fn test_write_nsec3param() {
+        let nsec3param_record = Record::new(
+            "example".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::NSEC3PARAM(NSEC3PARAM {
+                hash_algorithm: NSEC3HashAlgorithm::SHA1,
+                flags: 0,
+                iterations: 12,
+                salt: vec![0xAA, 0xBB, 0xCC, 0xDD],
+            }),
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        nsec3param_record.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x00, 0x00, 0x33, 0x00, 0x01, 0x00, 0x00, 0x0E,
+            0x10, 0x00, 0x09, 0x01, 0x00, 0x00, 0x0C, 0x04, 0xAA, 0xBB, 0xCC, 0xDD,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_display_nsec3param() {
+        let nsec3param = NSEC3PARAM {
+            hash_algorithm: NSEC3HashAlgorithm::SHA1,
+            flags: 0,
+            iterations: 0,
+            salt: Vec::new(),
+        };
+
+        assert_eq!(nsec3param.to_string(), "1 0 0 -");
+    }
+}
@@ -0,0 +1,149 @@
+use super::type_bitmap::{parse_type_bitmap, write_type_bitmap};
+use super::{
+    DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NSEC {
+    pub next_domain: Domain,   // Variable length, never compressed
+    pub types: Vec<QueryType>, // Variable length type bit maps
+}
+
+impl RecordDataRead for NSEC {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let starting_pos = buffer.get_pos();
+        let next_domain = Domain::parse_from_buffer(buffer)?;
+
+        let read_len = buffer.get_pos() - starting_pos;
+        let bitmap_len = (preamble.len as usize)
+            .checked_sub(read_len)
+            .ok_or(DNSError::BadPointerPosition)?;
+        let types = parse_type_bitmap(buffer, bitmap_len)?;
+
+        Ok(NSEC { next_domain, types })
+    }
+}
+
+impl RecordDataWrite for NSEC {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        self.next_domain.write_uncompressed_to_buffer(buffer)?;
+        write_type_bitmap(&self.types, buffer)?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::NSEC
+    }
+}
+
+/// Presentation format: <next domain name> <type>...
+impl fmt::Display for NSEC {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.next_domain)?;
+        for record_type in self.types.iter() {
+            write!(f, " {}", record_type)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    #[test]
+    fn test_read_nsec() {
+        // RFC 4034 section 4.3 example
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x61,
+            0x6C, 0x66, 0x61, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F,
+            0x6D, 0x00, 0x00, 0x2F, 0x00, 0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x37, 0x04, 0x68,
+            0x6F, 0x73, 0x74, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F,
+            0x6D, 0x00, 0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03, 0x04, 0x1B, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "alfa.example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::NSEC(NSEC {
+                next_domain: "host.example.com".parse().unwrap(),
+                types: vec![
+                    QueryType::A,
+                    QueryType::MX,
+                    QueryType::RRSIG,
+                    QueryType::NSEC,
+                    QueryType::Unknown(1234),
+                ],
+            }),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 55);
+        assert_eq!(parsed_record, expected_record);
+    }
+
+    #[test]
+    fn test_write_nsec() {
+        let nsec_record = Record::new(
+            "alfa.example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::NSEC(NSEC {
+                next_domain: "host.example.com".parse().unwrap(),
+                types: vec![
+                    QueryType::A,
+                    QueryType::MX,
+                    QueryType::RRSIG,
+                    QueryType::NSEC,
+                    QueryType::Unknown(1234),
+                ],
+            }),
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        nsec_record.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x61,
+            0x6C, 0x66, 0x61, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F,
+            0x6D, 0x00, 0x00, 0x2F, 0x00, 0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x37, 0x04, 0x68,
+            0x6F, 0x73, 0x74, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F,
+            0x6D, 0x00, 0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03, 0x04, 0x1B, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_display_nsec() {
+        let nsec = NSEC {
+            next_domain: "host.example.com".parse().unwrap(),
+            types: vec![QueryType::A, QueryType::MX, QueryType::Unknown(1234)],
+        };
+
+        assert_eq!(nsec.to_string(), "host.example.com. A MX TYPE1234");
+    }
+}
@@ -0,0 +1,489 @@
+use super::{
+    hex_encode, Class, DNSError, DNSPacketBuffer, Domain, QueryType, Record, RecordData,
+    RecordDataRead, RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// UDP payload size advertised by default, as recommended by DNS Flag Day 2020.
+pub const DEFAULT_EDNS_PAYLOAD_SIZE: u16 = 1232;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EdnsOption {
+    Nsid(Vec<u8>), // Name Server Identifier (RFC 5001)
+    ClientSubnet {
+        source_prefix: u8,
+        scope_prefix: u8,
+        addr: IpAddr,
+    }, // Client Subnet (RFC 7871)
+    Cookie {
+        client: [u8; 8],
+        server: Option<Vec<u8>>,
+    }, // DNS Cookies (RFC 7873)
+    TcpKeepalive(Option<u16>), // TCP keepalive timeout in units of 100ms (RFC 7828)
+    Padding(u16),  // Amount of padding bytes (RFC 7830)
+    ExtendedError {
+        info_code: u16,
+        extra_text: String,
+    }, // Extended DNS Errors (RFC 8914)
+    Unknown(u16, Vec<u8>), // Unknown option code and its raw data
+}
+
+impl EdnsOption {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Nsid(_) => 3,
+            Self::ClientSubnet { .. } => 8,
+            Self::Cookie { .. } => 10,
+            Self::TcpKeepalive(_) => 11,
+            Self::Padding(_) => 12,
+            Self::ExtendedError { .. } => 15,
+            Self::Unknown(code, _) => *code,
+        }
+    }
+
+    fn parse_from_buffer(buffer: &mut DNSPacketBuffer) -> Result<Self, DNSError> {
+        let code = buffer.read_u16()?;
+        let len = buffer.read_u16()? as usize;
+        let data = buffer.read_bytes(len)?;
+
+        let option = match code {
+            3 => Self::Nsid(data),
+            8 => {
+                if len < 4 {
+                    return Err(DNSError::MalformedEdnsOption(code));
+                }
+                let family = u16::from_be_bytes([data[0], data[1]]);
+                let source_prefix = data[2];
+                let scope_prefix = data[3];
+                let addr_bytes = &data[4..];
+                let addr = match family {
+                    1 if addr_bytes.len() <= 4 => {
+                        let mut octets = [0; 4];
+                        octets[..addr_bytes.len()].copy_from_slice(addr_bytes);
+                        IpAddr::V4(Ipv4Addr::from(octets))
+                    }
+                    2 if addr_bytes.len() <= 16 => {
+                        let mut octets = [0; 16];
+                        octets[..addr_bytes.len()].copy_from_slice(addr_bytes);
+                        IpAddr::V6(Ipv6Addr::from(octets))
+                    }
+                    _ => return Err(DNSError::MalformedEdnsOption(code)),
+                };
+                Self::ClientSubnet {
+                    source_prefix,
+                    scope_prefix,
+                    addr,
+                }
+            }
+            10 => {
+                // Client cookie is 8 bytes, server cookie between 8 and 32 bytes if present
+                if len != 8 && !(16..=40).contains(&len) {
+                    return Err(DNSError::MalformedEdnsOption(code));
+                }
+                let mut client = [0; 8];
+                client.copy_from_slice(&data[..8]);
+                let server = if len > 8 {
+                    Some(data[8..].to_vec())
+                } else {
+                    None
+                };
+                Self::Cookie { client, server }
+            }
+            11 => match len {
+                0 => Self::TcpKeepalive(None),
+                2 => Self::TcpKeepalive(Some(u16::from_be_bytes([data[0], data[1]]))),
+                _ => return Err(DNSError::MalformedEdnsOption(code)),
+            },
+            12 => Self::Padding(len as u16),
+            15 => {
+                if len < 2 {
+                    return Err(DNSError::MalformedEdnsOption(code));
+                }
+                Self::ExtendedError {
+                    info_code: u16::from_be_bytes([data[0], data[1]]),
+                    extra_text: String::from_utf8(data[2..].to_vec())
+                        .map_err(|_| DNSError::NonUTF8)?,
+                }
+            }
+            _ => Self::Unknown(code, data),
+        };
+
+        Ok(option)
+    }
+
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        buffer.write_u16(self.code())?;
+        let len_field = buffer.get_pos();
+        buffer.write_u16(0)?; // filled after writing option data
+        let starting_pos = buffer.get_pos();
+
+        match self {
+            Self::Nsid(data) | Self::Unknown(_, data) => buffer.write_bytes(data)?,
+            Self::ClientSubnet {
+                source_prefix,
+                scope_prefix,
+                addr,
+            } => {
+                // Only the significant bytes of the address according to the source prefix are
+                // sent
+                let addr_len = (*source_prefix as usize).div_ceil(8);
+                let (family, octets) = match addr {
+                    IpAddr::V4(addr) => (1, addr.octets().to_vec()),
+                    IpAddr::V6(addr) => (2, addr.octets().to_vec()),
+                };
+                if addr_len > octets.len() {
+                    return Err(DNSError::MalformedEdnsOption(self.code()));
+                }
+                buffer.write_u16(family)?;
+                buffer.write_u8(*source_prefix)?;
+                buffer.write_u8(*scope_prefix)?;
+                buffer.write_bytes(&octets[..addr_len])?;
+            }
+            Self::Cookie { client, server } => {
+                buffer.write_bytes(client)?;
+                if let Some(server) = server {
+                    if !(8..=32).contains(&server.len()) {
+                        return Err(DNSError::MalformedEdnsOption(self.code()));
+                    }
+                    buffer.write_bytes(server)?;
+                }
+            }
+            Self::TcpKeepalive(timeout) => {
+                if let Some(timeout) = timeout {
+                    buffer.write_u16(*timeout)?;
+                }
+            }
+            Self::Padding(len) => {
+                for _ in 0..*len {
+                    buffer.write_u8(0)?;
+                }
+            }
+            Self::ExtendedError {
+                info_code,
+                extra_text,
+            } => {
+                buffer.write_u16(*info_code)?;
+                buffer.write_bytes(extra_text.as_bytes())?;
+            }
+        }
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+}
+
+/// Write EDNS options as OPT record data and fill the record's length field.
+fn write_options(options: &[EdnsOption], buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+    let len_field = buffer.get_pos() - 2;
+    let starting_pos = buffer.get_pos();
+
+    for option in options.iter() {
+        option.write_to_buffer(buffer)?;
+    }
+
+    let len = buffer.get_pos() - starting_pos;
+    buffer.set_u16(len_field, len as u16)?;
+
+    Ok(())
+}
+
+/// Option as printed in dig's OPT pseudosection, e.g. "COOKIE: 0102030405060708".
+impl fmt::Display for EdnsOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nsid(data) => write!(f, "NSID: {}", hex_encode(data)),
+            Self::ClientSubnet {
+                source_prefix,
+                scope_prefix,
+                addr,
+            } => write!(
+                f,
+                "CLIENT-SUBNET: {}/{}/{}",
+                addr, source_prefix, scope_prefix
+            ),
+            Self::Cookie { client, server } => {
+                let server = server.as_deref().unwrap_or_default();
+                write!(f, "COOKIE: {}{}", hex_encode(client), hex_encode(server))
+            }
+            Self::TcpKeepalive(None) => write!(f, "TCP-KEEPALIVE"),
+            Self::TcpKeepalive(Some(timeout)) => {
+                write!(f, "TCP-KEEPALIVE: {}.{} secs", timeout / 10, timeout % 10)
+            }
+            Self::Padding(len) => write!(f, "PADDING: {} bytes", len),
+            Self::ExtendedError {
+                info_code,
+                extra_text,
+            } if extra_text.is_empty() => write!(f, "EDE: {}", info_code),
+            Self::ExtendedError {
+                info_code,
+                extra_text,
+            } => write!(f, "EDE: {} ({})", info_code, extra_text),
+            Self::Unknown(code, data) => write!(f, "OPT={}: {}", code, hex_encode(data)),
+        }
+    }
+}
+
+/// OPT pseudo-record data. Only the options are carried in the RDATA, the rest of the EDNS
+/// information is encoded in the class and TTL fields of the record preamble. See Edns.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OPT {
+    pub options: Vec<EdnsOption>,
+}
+
+impl RecordDataRead for OPT {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let end_pos = buffer.get_pos() + preamble.len as usize;
+        let mut options = Vec::<EdnsOption>::new();
+        while buffer.get_pos() < end_pos {
+            options.push(EdnsOption::parse_from_buffer(buffer)?);
+        }
+        if buffer.get_pos() != end_pos {
+            return Err(DNSError::BadPointerPosition);
+        }
+
+        Ok(OPT { options })
+    }
+}
+
+impl RecordDataWrite for OPT {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        write_options(&self.options, buffer)
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::OPT
+    }
+}
+
+/// Options separated by semicolons.
+impl fmt::Display for OPT {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options: Vec<String> = self.options.iter().map(|o| o.to_string()).collect();
+        write!(f, "{}", options.join("; "))
+    }
+}
+
+/// EDNS(0) information of a DNS packet (RFC 6891), carried on the wire by an OPT pseudo-record
+/// in the additional section.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Edns {
+    pub udp_payload_size: u16, // 2 bytes (record class)
+    pub extended_rcode: u8,    // 1 byte (upper 8 bits of the 12-bit RCODE)
+    pub version: u8,           // 1 byte
+    pub dnssec_ok: bool,       // 1 bit
+    pub options: Vec<EdnsOption>,
+}
+
+impl Default for Edns {
+    fn default() -> Self {
+        Edns {
+            udp_payload_size: DEFAULT_EDNS_PAYLOAD_SIZE,
+            extended_rcode: 0,
+            version: 0,
+            dnssec_ok: false,
+            options: Vec::new(),
+        }
+    }
+}
+
+/// EDNS information as printed in dig's OPT pseudosection, one option per line.
+impl fmt::Display for Edns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = if self.dnssec_ok { " do" } else { "" };
+        write!(
+            f,
+            "; EDNS: version: {}, flags:{}; udp: {}",
+            self.version, flags, self.udp_payload_size
+        )?;
+        for option in self.options.iter() {
+            write!(f, "\n; {}", option)?;
+        }
+        Ok(())
+    }
+}
+
+impl Edns {
+    /// Build EDNS information from an OPT pseudo-record, which must be owned by the root (RFC 6891
+    /// section 6.1.2).
+    pub(crate) fn from_record(record: Record) -> Result<Self, DNSError> {
+        let Record { preamble, data } = record;
+        let RecordData::OPT(OPT { options }) = data else {
+            return Err(DNSError::InvalidOPT(format!(
+                "record of type {:?} is not an OPT record",
+                preamble.record_type
+            )));
+        };
+        if !preamble.domain.is_root() {
+            return Err(DNSError::InvalidOPT(format!(
+                "owner {} is not the root",
+                preamble.domain
+            )));
+        }
+
+        Ok(Edns {
+            udp_payload_size: preamble.class.to_num(),
+            extended_rcode: (preamble.ttl >> 24) as u8,
+            version: (preamble.ttl >> 16) as u8,
+            dnssec_ok: preamble.ttl & 0x0000_8000 != 0,
+            options,
+        })
+    }
+
+    /// Write EDNS information to buffer as an OPT pseudo-record.
+    pub(crate) fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let ttl = (self.extended_rcode as u32) << 24
+            | (self.version as u32) << 16
+            | (self.dnssec_ok as u32) << 15;
+
+        let preamble = RecordPreamble {
+            domain: Domain::root(),
+            record_type: QueryType::OPT,
+            class: Class::from_num(self.udp_payload_size),
+            ttl,
+            len: 0,
+        };
+        preamble.write_to_buffer(buffer)?;
+        write_options(&self.options, buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::HEADER_SIZE;
+    use super::*;
+
+    #[test]
+    fn test_read_opt() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x29, 0x04, 0xD0, 0x01, 0x00, 0x80, 0x00, 0x00, 0x1C, 0x00, 0x0A, 0x00, 0x08, 0x01,
+            0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x00, 0x08, 0x00, 0x07, 0x00, 0x01, 0x18,
+            0x00, 0xC0, 0x00, 0x02, 0x00, 0x0C, 0x00, 0x01, 0x00,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+        let parsed_edns = Edns::from_record(parsed_record).unwrap();
+
+        let expected_edns = Edns {
+            udp_payload_size: 1232,
+            extended_rcode: 1,
+            version: 0,
+            dnssec_ok: true,
+            options: vec![
+                EdnsOption::Cookie {
+                    client: [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+                    server: None,
+                },
+                EdnsOption::ClientSubnet {
+                    source_prefix: 24,
+                    scope_prefix: 0,
+                    addr: IpAddr::V4(Ipv4Addr::new(192, 0, 2, 0)),
+                },
+                EdnsOption::Padding(1),
+            ],
+        };
+
+        assert_eq!(parsed_edns, expected_edns);
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+        let mut record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+        record.preamble.domain = "example.com".parse().unwrap();
+        assert_eq!(
+            Edns::from_record(record),
+            Err(DNSError::InvalidOPT(
+                "owner example.com. is not the root".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_write_opt() {
+        let edns = Edns {
+            udp_payload_size: 1232,
+            extended_rcode: 1,
+            version: 0,
+            dnssec_ok: true,
+            options: vec![
+                EdnsOption::Cookie {
+                    client: [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+                    server: None,
+                },
+                EdnsOption::ClientSubnet {
+                    source_prefix: 24,
+                    scope_prefix: 0,
+                    addr: IpAddr::V4(Ipv4Addr::new(192, 0, 2, 0)),
+                },
+                EdnsOption::Padding(1),
+            ],
+        };
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        edns.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x29, 0x04, 0xD0, 0x01, 0x00, 0x80, 0x00, 0x00, 0x1C, 0x00, 0x0A, 0x00, 0x08, 0x01,
+            0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x00, 0x08, 0x00, 0x07, 0x00, 0x01, 0x18,
+            0x00, 0xC0, 0x00, 0x02, 0x00, 0x0C, 0x00, 0x01, 0x00,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_malformed_cookie() {
+        let dns_packet_data = [0x00, 0x0A, 0x00, 0x03, 0x01, 0x02, 0x03];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        let parsed_option = EdnsOption::parse_from_buffer(&mut dns_packet_buffer);
+
+        assert_eq!(parsed_option, Err(DNSError::MalformedEdnsOption(10)));
+    }
+
+    #[test]
+    fn test_display_edns() {
+        let edns = Edns {
+            dnssec_ok: true,
+            options: vec![
+                EdnsOption::ClientSubnet {
+                    source_prefix: 24,
+                    scope_prefix: 0,
+                    addr: IpAddr::V4(Ipv4Addr::new(192, 0, 2, 0)),
+                },
+                EdnsOption::TcpKeepalive(Some(300)),
+                EdnsOption::ExtendedError {
+                    info_code: 18,
+                    extra_text: "Prohibited".to_string(),
+                },
+            ],
+            ..Edns::default()
+        };
+
+        assert_eq!(
+            edns.to_string(),
+            "; EDNS: version: 0, flags: do; udp: 1232\n\
+             ; CLIENT-SUBNET: 192.0.2.0/24/0\n\
+             ; TCP-KEEPALIVE: 30.0 secs\n\
+             ; EDE: 18 (Prohibited)"
+        );
+        assert_eq!(
+            OPT {
+                options: vec![EdnsOption::Padding(4), EdnsOption::Nsid(vec![0xAB])]
+            }
+            .to_string(),
+            "PADDING: 4 bytes; NSID: AB"
+        );
+    }
+}
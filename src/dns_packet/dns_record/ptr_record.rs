@@ -1,8 +1,9 @@
 use super::{
     DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct PTR {
     pub ptrname: Domain,
 }
@@ -13,7 +14,7 @@ impl RecordDataRead for PTR {
         _preamble: &RecordPreamble,
     ) -> Result<Self, DNSError> {
         Ok(PTR {
-            ptrname: Domain::parse_domain(buffer, 0)?,
+            ptrname: Domain::parse_from_buffer(buffer)?,
         })
     }
 }
@@ -36,9 +37,16 @@ impl RecordDataWrite for PTR {
     }
 }
 
+/// Presentation format: <ptrdname>
+impl fmt::Display for PTR {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.ptrname)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
     #[test]
@@ -56,11 +64,11 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             254,
             RecordData::PTR(PTR {
-                ptrname: Domain("foo.example.com".to_string()),
+                ptrname: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -71,11 +79,11 @@ mod tests {
     #[test]
     fn test_write_cname() {
         let a_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             254,
             RecordData::PTR(PTR {
-                ptrname: Domain("foo.example.com".to_string()),
+                ptrname: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -96,4 +104,13 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_ptr() {
+        let ptr = PTR {
+            ptrname: "www.example.com".parse().unwrap(),
+        };
+
+        assert_eq!(ptr.to_string(), "www.example.com.");
+    }
 }
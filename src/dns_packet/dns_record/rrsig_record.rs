@@ -0,0 +1,207 @@
+use super::{
+    base64_encode, format_rrsig_time, DNSError, DNSPacketBuffer, DNSSECAlgorithm, Domain,
+    QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RRSIG {
+    pub type_covered: QueryType,    // 2 bytes
+    pub algorithm: DNSSECAlgorithm, // 1 byte
+    pub labels: u8,                 // 1 byte
+    pub original_ttl: u32,          // 4 bytes
+    pub signature_expiration: u32,  // 4 bytes
+    pub signature_inception: u32,   // 4 bytes
+    pub key_tag: u16,               // 2 bytes
+    pub signer_name: Domain,        // Variable length, never compressed
+    pub signature: Vec<u8>,         // Variable length
+}
+
+impl RecordDataRead for RRSIG {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let starting_pos = buffer.get_pos();
+
+        let type_covered = QueryType::from_num(buffer.read_u16()?);
+        let algorithm = DNSSECAlgorithm::from_num(buffer.read_u8()?);
+        let labels = buffer.read_u8()?;
+        let original_ttl = buffer.read_u32()?;
+        let signature_expiration = buffer.read_u32()?;
+        let signature_inception = buffer.read_u32()?;
+        let key_tag = buffer.read_u16()?;
+        let signer_name = Domain::parse_from_buffer(buffer)?;
+
+        let read_len = buffer.get_pos() - starting_pos;
+        let signature_len = (preamble.len as usize)
+            .checked_sub(read_len)
+            .ok_or(DNSError::BadPointerPosition)?;
+        let signature = buffer.read_bytes(signature_len)?;
+
+        Ok(RRSIG {
+            type_covered,
+            algorithm,
+            labels,
+            original_ttl,
+            signature_expiration,
+            signature_inception,
+            key_tag,
+            signer_name,
+            signature,
+        })
+    }
+}
+
+impl RecordDataWrite for RRSIG {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u16(self.type_covered.to_num())?;
+        buffer.write_u8(self.algorithm.to_num())?;
+        buffer.write_u8(self.labels)?;
+        buffer.write_u32(self.original_ttl)?;
+        buffer.write_u32(self.signature_expiration)?;
+        buffer.write_u32(self.signature_inception)?;
+        buffer.write_u16(self.key_tag)?;
+        self.signer_name.write_uncompressed_to_buffer(buffer)?;
+        buffer.write_bytes(&self.signature)?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::RRSIG
+    }
+}
+
+/// Presentation format: <type covered> <algorithm> <labels> <original TTL> <expiration>
+/// <inception> <key tag> <signer name> <base64 signature>, with timestamps as YYYYMMDDHHmmSS
+impl fmt::Display for RRSIG {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {} {} {}",
+            self.type_covered,
+            self.algorithm.to_num(),
+            self.labels,
+            self.original_ttl,
+            format_rrsig_time(self.signature_expiration),
+            format_rrsig_time(self.signature_inception),
+            self.key_tag,
+            self.signer_name,
+            base64_encode(&self.signature)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    #[test]
+    fn test_read_rrsig() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x77,
+            0x77, 0x77, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D,
+            0x00, 0x00, 0x2E, 0x00, 0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x2F, 0x00, 0x01, 0x0D,
+            0x02, 0x00, 0x00, 0x0E, 0x10, 0x65, 0x53, 0xF1, 0x00, 0x65, 0x2C, 0x64, 0x00, 0x30,
+            0x39, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00,
+            0x3C, 0x9E, 0x51, 0x07, 0xA2, 0x6D, 0xF0, 0x18, 0x44, 0xB3, 0x2A, 0x90, 0x5C, 0xE7,
+            0x01, 0x7F,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "www.example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::RRSIG(RRSIG {
+                type_covered: QueryType::A,
+                algorithm: DNSSECAlgorithm::ECDSAP256SHA256,
+                labels: 2,
+                original_ttl: 3600,
+                signature_expiration: 1700000000,
+                signature_inception: 1697408000,
+                key_tag: 12345,
+                signer_name: "example.com".parse().unwrap(),
+                signature: vec![
+                    0x3C, 0x9E, 0x51, 0x07, 0xA2, 0x6D, 0xF0, 0x18, 0x44, 0xB3, 0x2A, 0x90, 0x5C,
+                    0xE7, 0x01, 0x7F,
+                ],
+            }),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 47);
+        assert_eq!(parsed_record, expected_record);
+    }
+
+    #[test]
+    fn test_write_rrsig_uncompressed_signer() {
+        let rrsig_record = Record::new(
+            "www.example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::RRSIG(RRSIG {
+                type_covered: QueryType::A,
+                algorithm: DNSSECAlgorithm::ECDSAP256SHA256,
+                labels: 2,
+                original_ttl: 3600,
+                signature_expiration: 1700000000,
+                signature_inception: 1697408000,
+                key_tag: 12345,
+                signer_name: "example.com".parse().unwrap(),
+                signature: vec![
+                    0x3C, 0x9E, 0x51, 0x07, 0xA2, 0x6D, 0xF0, 0x18, 0x44, 0xB3, 0x2A, 0x90, 0x5C,
+                    0xE7, 0x01, 0x7F,
+                ],
+            }),
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        rrsig_record.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected, signer name is not compressed even though example.com was already written
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x77,
+            0x77, 0x77, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D,
+            0x00, 0x00, 0x2E, 0x00, 0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x2F, 0x00, 0x01, 0x0D,
+            0x02, 0x00, 0x00, 0x0E, 0x10, 0x65, 0x53, 0xF1, 0x00, 0x65, 0x2C, 0x64, 0x00, 0x30,
+            0x39, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00,
+            0x3C, 0x9E, 0x51, 0x07, 0xA2, 0x6D, 0xF0, 0x18, 0x44, 0xB3, 0x2A, 0x90, 0x5C, 0xE7,
+            0x01, 0x7F,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_display_rrsig() {
+        let rrsig = RRSIG {
+            type_covered: QueryType::MX,
+            algorithm: DNSSECAlgorithm::ED25519,
+            labels: 2,
+            original_ttl: 3600,
+            signature_expiration: 1440028800,
+            signature_inception: 1438207200,
+            key_tag: 3613,
+            signer_name: "example.com".parse().unwrap(),
+            signature: vec![0xA0, 0xBF, 0x64, 0xAC],
+        };
+
+        assert_eq!(
+            rrsig.to_string(),
+            "MX 15 2 3600 20150820000000 20150729220000 3613 example.com. oL9krA=="
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use super::{
     DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct SOA {
     pub mname: Domain,
     pub rname: Domain,
@@ -19,8 +20,8 @@ impl RecordDataRead for SOA {
         _preamble: &RecordPreamble,
     ) -> Result<Self, DNSError> {
         Ok(SOA {
-            mname: Domain::parse_domain(buffer, 0)?,
-            rname: Domain::parse_domain(buffer, 0)?,
+            mname: Domain::parse_from_buffer(buffer)?,
+            rname: Domain::parse_from_buffer(buffer)?,
             serial: buffer.read_u32()?,
             refresh: buffer.read_u32()?,
             retry: buffer.read_u32()?,
@@ -54,9 +55,20 @@ impl RecordDataWrite for SOA {
     }
 }
 
+/// Presentation format: <mname> <rname> <serial> <refresh> <retry> <expire> <minimum>
+impl fmt::Display for SOA {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {}",
+            self.mname, self.rname, self.serial, self.refresh, self.retry, self.expire, self.minttl
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
     #[test]
@@ -79,12 +91,12 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("splitkb.com".to_string()),
-            1,
+            "splitkb.com".parse().unwrap(),
+            Class::IN,
             3600,
             RecordData::SOA(SOA {
-                mname: Domain("ns1.bdm.microsoftonline.com".to_string()),
-                rname: Domain("azuredns-hostmaster@microsoft.com".to_string()),
+                mname: "ns1.bdm.microsoftonline.com".parse().unwrap(),
+                rname: "azuredns-hostmaster.microsoft.com".parse().unwrap(),
                 serial: 1,
                 refresh: 3600,
                 retry: 300,
@@ -100,12 +112,12 @@ mod tests {
     #[test]
     fn test_write_soa() {
         let soa_record = Record::new(
-            Domain("splitkb.com".to_string()),
-            1,
+            "splitkb.com".parse().unwrap(),
+            Class::IN,
             3600,
             RecordData::SOA(SOA {
-                mname: Domain("ns1.bdm.microsoftonline.com".to_string()),
-                rname: Domain("azuredns-hostmaster@microsoft.com".to_string()),
+                mname: "ns1.bdm.microsoftonline.com".parse().unwrap(),
+                rname: "azuredns-hostmaster.microsoft.com".parse().unwrap(),
                 serial: 1,
                 refresh: 3600,
                 retry: 300,
@@ -136,4 +148,22 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_soa() {
+        let soa = SOA {
+            mname: "ns1.example.com".parse().unwrap(),
+            rname: "hostmaster.example.com".parse().unwrap(),
+            serial: 2024010101,
+            refresh: 7200,
+            retry: 3600,
+            expire: 1209600,
+            minttl: 300,
+        };
+
+        assert_eq!(
+            soa.to_string(),
+            "ns1.example.com. hostmaster.example.com. 2024010101 7200 3600 1209600 300"
+        );
+    }
 }
@@ -1,8 +1,9 @@
 use super::{
     DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct SRV {
     pub priority: u16,
     pub weight: u16,
@@ -19,7 +20,7 @@ impl RecordDataRead for SRV {
             priority: buffer.read_u16()?,
             weight: buffer.read_u16()?,
             port: buffer.read_u16()?,
-            target: Domain::parse_domain(buffer, 0)?,
+            target: Domain::parse_from_buffer(buffer)?,
         })
     }
 }
@@ -45,9 +46,20 @@ impl RecordDataWrite for SRV {
     }
 }
 
+/// Presentation format: <priority> <weight> <port> <target>
+impl fmt::Display for SRV {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.priority, self.weight, self.port, self.target
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
     #[test]
@@ -65,14 +77,14 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             253,
             RecordData::SRV(SRV {
                 priority: 257,
                 weight: 3331,
                 port: 37500,
-                target: Domain("foo.example.com".to_string()),
+                target: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -83,14 +95,14 @@ mod tests {
     #[test]
     fn test_write_srv() {
         let srv_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             253,
             RecordData::SRV(SRV {
                 priority: 257,
                 weight: 3331,
                 port: 37500,
-                target: Domain("foo.example.com".to_string()),
+                target: "foo.example.com".parse().unwrap(),
             }),
         );
 
@@ -111,4 +123,16 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_srv() {
+        let srv = SRV {
+            priority: 10,
+            weight: 60,
+            port: 5060,
+            target: "sip.example.com".parse().unwrap(),
+        };
+
+        assert_eq!(srv.to_string(), "10 60 5060 sip.example.com.");
+    }
 }
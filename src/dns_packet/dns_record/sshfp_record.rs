@@ -1,8 +1,9 @@
 use super::{
     DNSError, DNSPacketBuffer, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Algorithm {
     Reserved,
     RSA,
@@ -14,7 +15,7 @@ pub enum Algorithm {
 }
 
 impl Algorithm {
-    fn from_num(code_num: u8) -> Self {
+    pub fn from_num(code_num: u8) -> Self {
         match code_num {
             0 => Self::Reserved,
             1 => Self::RSA,
@@ -26,7 +27,7 @@ impl Algorithm {
         }
     }
 
-    fn to_num(&self) -> u8 {
+    pub fn to_num(&self) -> u8 {
         match self {
             Self::Reserved => 0,
             Self::RSA => 1,
@@ -39,7 +40,7 @@ impl Algorithm {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum FingerprintType {
     Reserved,
     SHA1,
@@ -48,7 +49,7 @@ pub enum FingerprintType {
 }
 
 impl FingerprintType {
-    fn from_num(code_num: u8) -> Self {
+    pub fn from_num(code_num: u8) -> Self {
         match code_num {
             0 => Self::Reserved,
             1 => Self::SHA1,
@@ -57,7 +58,7 @@ impl FingerprintType {
         }
     }
 
-    fn to_num(&self) -> u8 {
+    pub fn to_num(&self) -> u8 {
         match self {
             Self::Reserved => 0,
             Self::SHA1 => 1,
@@ -67,7 +68,7 @@ impl FingerprintType {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct SSHFP {
     pub algorithm: Algorithm,              // 1 byte
     pub fingerprint_type: FingerprintType, // 1 byte
@@ -82,13 +83,11 @@ impl RecordDataRead for SSHFP {
         let algorithm = Algorithm::from_num(buffer.read_u8()?);
         let fingerprint_type = FingerprintType::from_num(buffer.read_u8()?);
 
-        let fingerprint_start = buffer.get_pos();
-        let fingerprint_end = fingerprint_start + preamble.len as usize - 2; // minus two bytes from the two previous fields
-        let fingerprint_slice = &buffer.get_data()[fingerprint_start..fingerprint_end];
+        // Minus two bytes from the two previous fields
+        let fingerprint_len = (preamble.len as usize).saturating_sub(2);
+        let fingerprint_bytes = buffer.read_bytes(fingerprint_len)?;
         let fingerprint_str =
-            String::from_utf8(fingerprint_slice.to_vec()).map_err(|_| DNSError::NonUTF8)?;
-
-        buffer.step(preamble.len as usize);
+            String::from_utf8(fingerprint_bytes).map_err(|_| DNSError::NonUTF8)?;
 
         Ok(SSHFP {
             algorithm,
@@ -134,9 +133,22 @@ impl RecordDataWrite for SSHFP {
     }
 }
 
+/// Presentation format: <algorithm> <fingerprint type> <hexadecimal fingerprint>
+impl fmt::Display for SSHFP {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.algorithm.to_num(),
+            self.fingerprint_type.to_num(),
+            self.fingerprint.to_uppercase()
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
     #[test]
@@ -158,8 +170,8 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             16777469,
             RecordData::SSHFP(SSHFP {
                 algorithm: Algorithm::ECDSA,
@@ -176,8 +188,8 @@ mod tests {
     #[test]
     fn test_write_sshfp() {
         let sshfp_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             16777469,
             RecordData::SSHFP(SSHFP {
                 algorithm: Algorithm::ECDSA,
@@ -208,4 +220,19 @@ mod tests {
 
         assert_eq!(buffer.get_data(), expected_buffer.get_data())
     }
+
+    #[test]
+    fn test_display_sshfp() {
+        let sshfp = SSHFP {
+            algorithm: Algorithm::ED25519,
+            fingerprint_type: FingerprintType::SHA256,
+            fingerprint: "123456789abcdef67890123456789abcdef67890123456789abcdef123456789"
+                .to_string(),
+        };
+
+        assert_eq!(
+            sshfp.to_string(),
+            "4 2 123456789ABCDEF67890123456789ABCDEF67890123456789ABCDEF123456789"
+        );
+    }
 }
@@ -0,0 +1,552 @@
+use super::{
+    base64_encode, escape_byte, DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead,
+    RecordDataWrite, RecordPreamble,
+};
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Key reserved as "Invalid key" (RFC 9460 section 14.3.2).
+const INVALID_KEY: u16 = 65535;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SvcParam {
+    Mandatory(Vec<u16>),     // Keys which clients must support to use the record
+    Alpn(Vec<Vec<u8>>),      // ALPN protocol identifiers
+    NoDefaultAlpn,           // The default ALPN protocol is not supported
+    Port(u16),               // Alternative port
+    Ipv4Hint(Vec<Ipv4Addr>), // IPv4 address hints
+    Ech(Vec<u8>),            // Encrypted ClientHello configuration list
+    Ipv6Hint(Vec<Ipv6Addr>), // IPv6 address hints
+    Unknown(u16, Vec<u8>),   // Unknown key and its raw value
+}
+
+impl SvcParam {
+    pub fn key(&self) -> u16 {
+        match self {
+            Self::Mandatory(_) => 0,
+            Self::Alpn(_) => 1,
+            Self::NoDefaultAlpn => 2,
+            Self::Port(_) => 3,
+            Self::Ipv4Hint(_) => 4,
+            Self::Ech(_) => 5,
+            Self::Ipv6Hint(_) => 6,
+            Self::Unknown(key, _) => *key,
+        }
+    }
+
+    /// Presentation format name of a key, or keyNNNNN for keys without one.
+    pub fn key_mnemonic(key: u16) -> String {
+        match key {
+            0 => "mandatory".to_string(),
+            1 => "alpn".to_string(),
+            2 => "no-default-alpn".to_string(),
+            3 => "port".to_string(),
+            4 => "ipv4hint".to_string(),
+            5 => "ech".to_string(),
+            6 => "ipv6hint".to_string(),
+            key => format!("key{}", key),
+        }
+    }
+
+    /// Key of a presentation format name, including the keyNNNNN generic notation.
+    pub fn key_from_mnemonic(mnemonic: &str) -> Option<u16> {
+        if let Some(key) = mnemonic.strip_prefix("key") {
+            // Leading zeros are not allowed (RFC 9460 section 2.1)
+            if key.len() > 1 && key.starts_with('0') {
+                return None;
+            }
+            return key.parse::<u16>().ok();
+        }
+        (0..=6).find(|key| Self::key_mnemonic(*key) == mnemonic)
+    }
+
+    /// Typed parameter of a key and its value in wire format. Malformed values of known keys are
+    /// kept as Unknown, for validate to reject.
+    pub(crate) fn from_wire(key: u16, value: &[u8]) -> Self {
+        let typed = match key {
+            0 if !value.is_empty() && value.len().is_multiple_of(2) => Some(Self::Mandatory(
+                value
+                    .chunks(2)
+                    .map(|key| u16::from_be_bytes([key[0], key[1]]))
+                    .collect(),
+            )),
+            1 => {
+                let mut ids = Vec::<Vec<u8>>::new();
+                let mut rest = value;
+                while let Some((&len, tail)) = rest.split_first() {
+                    if len == 0 || tail.len() < len as usize {
+                        break;
+                    }
+                    ids.push(tail[..len as usize].to_vec());
+                    rest = &tail[len as usize..];
+                }
+                rest.is_empty().then_some(Self::Alpn(ids))
+            }
+            2 if value.is_empty() => Some(Self::NoDefaultAlpn),
+            3 if value.len() == 2 => Some(Self::Port(u16::from_be_bytes([value[0], value[1]]))),
+            4 if !value.is_empty() && value.len().is_multiple_of(4) => Some(Self::Ipv4Hint(
+                value
+                    .chunks(4)
+                    .map(|addr| Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3]))
+                    .collect(),
+            )),
+            5 => Some(Self::Ech(value.to_vec())),
+            6 if !value.is_empty() && value.len().is_multiple_of(16) => Some(Self::Ipv6Hint(
+                value
+                    .chunks(16)
+                    .map(|addr| {
+                        let mut octets = [0; 16];
+                        octets.copy_from_slice(addr);
+                        Ipv6Addr::from(octets)
+                    })
+                    .collect(),
+            )),
+            _ => None,
+        };
+
+        typed.unwrap_or_else(|| Self::Unknown(key, value.to_vec()))
+    }
+
+    /// Value of the parameter in wire format.
+    pub(crate) fn to_wire(&self) -> Result<Vec<u8>, DNSError> {
+        let mut value = Vec::<u8>::new();
+        match self {
+            Self::Mandatory(keys) => {
+                for key in keys {
+                    value.extend(key.to_be_bytes());
+                }
+            }
+            Self::Alpn(ids) => {
+                for id in ids {
+                    let len = u8::try_from(id.len()).map_err(|_| {
+                        DNSError::InvalidSvcParams("ALPN identifier too long".to_string())
+                    })?;
+                    value.push(len);
+                    value.extend(id);
+                }
+            }
+            Self::NoDefaultAlpn => (),
+            Self::Port(port) => value.extend(port.to_be_bytes()),
+            Self::Ipv4Hint(addrs) => {
+                for addr in addrs {
+                    value.extend(addr.octets());
+                }
+            }
+            Self::Ech(config) => value.extend(config),
+            Self::Ipv6Hint(addrs) => {
+                for addr in addrs {
+                    value.extend(addr.octets());
+                }
+            }
+            Self::Unknown(_, data) => value.extend(data),
+        }
+
+        Ok(value)
+    }
+}
+
+/// Escape a value for the presentation format, so that it stays a single zone file field.
+fn escape_value(value: &[u8]) -> String {
+    value.iter().map(|b| escape_byte(*b, b" \";()")).collect()
+}
+
+/// Presentation format: <key>[=<value>], with comma separated lists (RFC 9460 appendix A.1).
+impl fmt::Display for SvcParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Malformed values of known keys use the generic notation
+        let key = match self {
+            Self::Unknown(key, _) => format!("key{}", key),
+            _ => Self::key_mnemonic(self.key()),
+        };
+        let value = match self {
+            Self::Mandatory(keys) => keys
+                .iter()
+                .map(|key| Self::key_mnemonic(*key))
+                .collect::<Vec<_>>()
+                .join(","),
+            Self::Alpn(ids) => ids
+                .iter()
+                .map(|id| {
+                    // Commas and backslashes inside an identifier are escaped once for the list,
+                    // then once more for the zone file
+                    let mut escaped = Vec::<u8>::new();
+                    for b in id {
+                        if matches!(b, b',' | b'\\') {
+                            escaped.push(b'\\');
+                        }
+                        escaped.push(*b);
+                    }
+                    escape_value(&escaped)
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+            Self::NoDefaultAlpn => return write!(f, "{}", key),
+            Self::Port(port) => port.to_string(),
+            Self::Ipv4Hint(addrs) => addrs
+                .iter()
+                .map(|addr| addr.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            Self::Ech(config) => base64_encode(config),
+            Self::Ipv6Hint(addrs) => addrs
+                .iter()
+                .map(|addr| addr.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            Self::Unknown(_, data) if data.is_empty() => return write!(f, "{}", key),
+            Self::Unknown(_, data) => escape_value(data),
+        };
+        write!(f, "{}={}", key, value)
+    }
+}
+
+/// Service binding (RFC 9460). A priority of 0 is AliasMode, which points to the target name
+/// like a CNAME at the zone apex, while other priorities are ServiceMode endpoints.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SVCB {
+    pub priority: u16,         // 2 bytes
+    pub target: Domain,        // Variable length, never compressed
+    pub params: Vec<SvcParam>, // Variable length, in strictly increasing key order
+}
+
+impl SVCB {
+    pub fn is_alias_mode(&self) -> bool {
+        self.priority == 0
+    }
+
+    /// Check the rules of RFC 9460 on the parameters: keys in strictly increasing order and not
+    /// the invalid key, well-formed values of known keys, mandatory keys listed once in increasing
+    /// order and present in the record, non empty lists, and alpn present whenever
+    /// no-default-alpn is. Records parsed from the wire are not validated, so that they can be
+    /// forwarded as they are.
+    pub fn validate(&self) -> Result<(), DNSError> {
+        let invalid = |msg: &str| Err(DNSError::InvalidSvcParams(msg.to_string()));
+        let keys: Vec<u16> = self.params.iter().map(SvcParam::key).collect();
+
+        if keys.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return invalid("keys are not in strictly increasing order");
+        }
+        if keys.contains(&INVALID_KEY) {
+            return invalid("key65535 is reserved");
+        }
+
+        for param in self.params.iter() {
+            match param {
+                SvcParam::Mandatory(mandatory) => {
+                    if mandatory.is_empty() {
+                        return invalid("mandatory is empty");
+                    }
+                    if mandatory.contains(&0) {
+                        return invalid("mandatory lists itself");
+                    }
+                    if mandatory.windows(2).any(|pair| pair[0] >= pair[1]) {
+                        return invalid("mandatory keys are not in strictly increasing order");
+                    }
+                    if let Some(missing) = mandatory.iter().find(|key| !keys.contains(key)) {
+                        return Err(DNSError::InvalidSvcParams(format!(
+                            "mandatory key {} is missing",
+                            SvcParam::key_mnemonic(*missing)
+                        )));
+                    }
+                }
+                SvcParam::Alpn(ids)
+                    if ids.is_empty() || ids.iter().any(|id| id.is_empty() || id.len() > 255) =>
+                {
+                    return invalid("alpn has an empty or oversized identifier");
+                }
+                SvcParam::NoDefaultAlpn if !keys.contains(&1) => {
+                    return invalid("no-default-alpn requires alpn");
+                }
+                SvcParam::Ipv4Hint(addrs) if addrs.is_empty() => {
+                    return invalid("ipv4hint is empty")
+                }
+                SvcParam::Ipv6Hint(addrs) if addrs.is_empty() => {
+                    return invalid("ipv6hint is empty")
+                }
+                SvcParam::Unknown(key, _) if *key <= 6 => {
+                    return Err(DNSError::InvalidSvcParams(format!(
+                        "malformed {}",
+                        SvcParam::key_mnemonic(*key)
+                    )));
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl RecordDataRead for SVCB {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let starting_pos = buffer.get_pos();
+        let priority = buffer.read_u16()?;
+        let target = Domain::parse_from_buffer(buffer)?;
+
+        let mut params = Vec::<SvcParam>::new();
+        while buffer.get_pos() - starting_pos < preamble.len as usize {
+            let key = buffer.read_u16()?;
+            let len = buffer.read_u16()? as usize;
+            params.push(SvcParam::from_wire(key, &buffer.read_bytes(len)?));
+        }
+        if buffer.get_pos() - starting_pos != preamble.len as usize {
+            return Err(DNSError::BadPointerPosition);
+        }
+
+        // Parameters breaking the rules of RFC 9460 are kept rather than failing the whole
+        // packet, see validate
+        Ok(SVCB {
+            priority,
+            target,
+            params,
+        })
+    }
+}
+
+impl RecordDataWrite for SVCB {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u16(self.priority)?;
+        self.target.write_uncompressed_to_buffer(buffer)?;
+        for param in self.params.iter() {
+            let value = param.to_wire()?;
+            let len = u16::try_from(value.len()).map_err(|_| {
+                DNSError::InvalidSvcParams(format!(
+                    "{} value too long",
+                    SvcParam::key_mnemonic(param.key())
+                ))
+            })?;
+            buffer.write_u16(param.key())?;
+            buffer.write_u16(len)?;
+            buffer.write_bytes(&value)?;
+        }
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::SVCB
+    }
+}
+
+/// Presentation format: <priority> <target name> <key>[=<value>]...
+impl fmt::Display for SVCB {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.priority, self.target)?;
+        for param in self.params.iter() {
+            write!(f, " {}", param)?;
+        }
+        Ok(())
+    }
+}
+
+/// Service binding for HTTP origins (RFC 9460 section 9). Its RDATA is identical to a SVCB
+/// record's.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HTTPS {
+    pub svcb: SVCB,
+}
+
+impl RecordDataRead for HTTPS {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        Ok(HTTPS {
+            svcb: SVCB::parse_from_buffer(buffer, preamble)?,
+        })
+    }
+}
+
+impl RecordDataWrite for HTTPS {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        self.svcb.write_to_buffer(buffer)
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::HTTPS
+    }
+}
+
+/// Presentation format: same as SVCB
+impl fmt::Display for HTTPS {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.svcb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Domain, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    fn https_record() -> Record {
+        Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            300,
+            RecordData::HTTPS(HTTPS {
+                svcb: SVCB {
+                    priority: 1,
+                    target: Domain::root(),
+                    params: vec![
+                        SvcParam::Mandatory(vec![1]),
+                        SvcParam::Alpn(vec![b"h2".to_vec(), b"h3".to_vec()]),
+                        SvcParam::Port(8443),
+                        SvcParam::Ipv4Hint(vec![Ipv4Addr::new(192, 0, 2, 1)]),
+                    ],
+                },
+            }),
+        )
+    }
+
+    #[test]
+    fn test_read_https() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00, 0x41, 0x00,
+            0x01, 0x00, 0x00, 0x01, 0x2C, 0x00, 0x21, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02,
+            0x00, 0x01, 0x00, 0x01, 0x00, 0x06, 0x02, 0x68, 0x32, 0x02, 0x68, 0x33, 0x00, 0x03,
+            0x00, 0x02, 0x20, 0xFB, 0x00, 0x04, 0x00, 0x04, 0xC0, 0x00, 0x02, 0x01,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        assert_eq!(parsed_record.preamble.len, 33);
+        assert_eq!(parsed_record, https_record());
+
+        // port before alpn, which is kept and written back as it is
+        let unordered_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x40, 0x00, 0x01, 0x00, 0x00, 0x01, 0x2C, 0x00, 0x10, 0x00, 0x01, 0x00, 0x00, 0x03,
+            0x00, 0x02, 0x00, 0x35, 0x00, 0x01, 0x00, 0x03, 0x02, 0x68, 0x32,
+        ];
+        let mut buffer = DNSPacketBuffer::new(&unordered_data);
+        buffer.seek(HEADER_SIZE);
+        let unordered_record = Record::parse_from_buffer(&mut buffer).unwrap();
+        let RecordData::SVCB(svcb) = unordered_record.data() else {
+            panic!("expected a SVCB record, got {:?}", unordered_record.data());
+        };
+        assert_eq!(
+            svcb.params,
+            vec![SvcParam::Port(53), SvcParam::Alpn(vec![b"h2".to_vec()])]
+        );
+
+        assert!(svcb.validate().is_err());
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        unordered_record.write_to_buffer(&mut buffer).unwrap();
+        assert_eq!(buffer.get_data(), &unordered_data[..]);
+
+        // 3 byte port, kept as an unknown value
+        let malformed_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x40, 0x00, 0x01, 0x00, 0x00, 0x01, 0x2C, 0x00, 0x0A, 0x00, 0x01, 0x00, 0x00, 0x03,
+            0x00, 0x03, 0x01, 0x02, 0x03,
+        ];
+        let mut buffer = DNSPacketBuffer::new(&malformed_data);
+        buffer.seek(HEADER_SIZE);
+        let malformed_record = Record::parse_from_buffer(&mut buffer).unwrap();
+        let RecordData::SVCB(svcb) = malformed_record.data() else {
+            panic!("expected a SVCB record, got {:?}", malformed_record.data());
+        };
+        assert_eq!(svcb.params, vec![SvcParam::Unknown(3, vec![1, 2, 3])]);
+        assert_eq!(svcb.to_string(), "1 . key3=\\001\\002\\003");
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        malformed_record.write_to_buffer(&mut buffer).unwrap();
+        assert_eq!(buffer.get_data(), &malformed_data[..]);
+    }
+
+    #[test]
+    fn test_write_https() {
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        https_record().write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00, 0x41, 0x00,
+            0x01, 0x00, 0x00, 0x01, 0x2C, 0x00, 0x21, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02,
+            0x00, 0x01, 0x00, 0x01, 0x00, 0x06, 0x02, 0x68, 0x32, 0x02, 0x68, 0x33, 0x00, 0x03,
+            0x00, 0x02, 0x20, 0xFB, 0x00, 0x04, 0x00, 0x04, 0xC0, 0x00, 0x02, 0x01,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_display_svcb() {
+        let RecordData::HTTPS(https) = https_record().data else {
+            unreachable!()
+        };
+        assert_eq!(
+            https.to_string(),
+            "1 . mandatory=alpn alpn=h2,h3 port=8443 ipv4hint=192.0.2.1"
+        );
+
+        let svcb = SVCB {
+            priority: 16,
+            target: "foo.example.org".parse().unwrap(),
+            params: vec![
+                SvcParam::Alpn(vec![b"f\\oo,bar".to_vec(), b"h2".to_vec()]),
+                SvcParam::NoDefaultAlpn,
+                SvcParam::Unknown(667, b"hello world".to_vec()),
+            ],
+        };
+        assert_eq!(
+            svcb.to_string(),
+            r#"16 foo.example.org. alpn=f\\\\oo\\,bar,h2 no-default-alpn key667=hello\ world"#
+        );
+    }
+
+    #[test]
+    fn test_validate_svcb() {
+        let svcb = |params: Vec<SvcParam>| SVCB {
+            priority: 1,
+            target: Domain::root(),
+            params,
+        };
+
+        assert!(svcb(vec![SvcParam::Port(53)]).validate().is_ok());
+        assert!(svcb(vec![SvcParam::Port(53), SvcParam::Port(54)])
+            .validate()
+            .is_err());
+        assert!(svcb(vec![SvcParam::Mandatory(vec![3])]).validate().is_err());
+        assert!(
+            svcb(vec![SvcParam::Mandatory(vec![0, 3]), SvcParam::Port(53)])
+                .validate()
+                .is_err()
+        );
+        assert!(svcb(vec![SvcParam::NoDefaultAlpn]).validate().is_err());
+        assert!(svcb(vec![SvcParam::Unknown(INVALID_KEY, Vec::new())])
+            .validate()
+            .is_err());
+        let malformed = SvcParam::from_wire(3, &[0x00]);
+        assert_eq!(malformed, SvcParam::Unknown(3, vec![0x00]));
+        assert_eq!(
+            svcb(vec![malformed]).validate(),
+            Err(DNSError::InvalidSvcParams("malformed port".to_string()))
+        );
+        assert_eq!(
+            SvcParam::from_wire(1, &[0x02, b'h']),
+            SvcParam::Unknown(1, vec![0x02, b'h'])
+        );
+        assert_eq!(SvcParam::key_from_mnemonic("ipv6hint"), Some(6));
+        assert_eq!(SvcParam::key_from_mnemonic("key0667"), None);
+    }
+}
@@ -0,0 +1,446 @@
+use super::{
+    hex_encode, DNSError, DNSPacketBuffer, Domain, QueryType, RecordDataRead, RecordDataWrite,
+    RecordPreamble,
+};
+use ring::digest;
+use std::fmt;
+
+/// Bytes of the SHA-256 digest of a local part kept in SMIMEA owner names (RFC 8162 section 3).
+const SMIMEA_HASH_SIZE: usize = 28;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum CertificateUsage {
+    PKIXTA,         // CA constraint
+    PKIXEE,         // Service certificate constraint
+    DANETA,         // Trust anchor assertion
+    DANEEE,         // Domain-issued certificate
+    PrivCert,       // Private use
+    Unassigned(u8), // Unassigned usage
+}
+
+impl CertificateUsage {
+    pub fn from_num(code_num: u8) -> Self {
+        match code_num {
+            0 => Self::PKIXTA,
+            1 => Self::PKIXEE,
+            2 => Self::DANETA,
+            3 => Self::DANEEE,
+            255 => Self::PrivCert,
+            _ => Self::Unassigned(code_num),
+        }
+    }
+
+    pub fn to_num(&self) -> u8 {
+        match self {
+            Self::PKIXTA => 0,
+            Self::PKIXEE => 1,
+            Self::DANETA => 2,
+            Self::DANEEE => 3,
+            Self::PrivCert => 255,
+            Self::Unassigned(num) => *num,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Selector {
+    FullCertificate,      // Full certificate
+    SubjectPublicKeyInfo, // DER-encoded SubjectPublicKeyInfo
+    PrivSel,              // Private use
+    Unassigned(u8),       // Unassigned selector
+}
+
+impl Selector {
+    pub fn from_num(code_num: u8) -> Self {
+        match code_num {
+            0 => Self::FullCertificate,
+            1 => Self::SubjectPublicKeyInfo,
+            255 => Self::PrivSel,
+            _ => Self::Unassigned(code_num),
+        }
+    }
+
+    pub fn to_num(&self) -> u8 {
+        match self {
+            Self::FullCertificate => 0,
+            Self::SubjectPublicKeyInfo => 1,
+            Self::PrivSel => 255,
+            Self::Unassigned(num) => *num,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum MatchingType {
+    Full,           // Exact match on the selected content
+    SHA256,         // SHA-256 digest of the selected content
+    SHA512,         // SHA-512 digest of the selected content
+    PrivMatch,      // Private use
+    Unassigned(u8), // Unassigned matching type
+}
+
+impl MatchingType {
+    pub fn from_num(code_num: u8) -> Self {
+        match code_num {
+            0 => Self::Full,
+            1 => Self::SHA256,
+            2 => Self::SHA512,
+            255 => Self::PrivMatch,
+            _ => Self::Unassigned(code_num),
+        }
+    }
+
+    pub fn to_num(&self) -> u8 {
+        match self {
+            Self::Full => 0,
+            Self::SHA256 => 1,
+            Self::SHA512 => 2,
+            Self::PrivMatch => 255,
+            Self::Unassigned(num) => *num,
+        }
+    }
+}
+
+/// Split a DER element (X.690) from the start of the data: its tag, its whole encoding and its
+/// content.
+fn der_element(data: &[u8]) -> Result<(u8, &[u8], &[u8]), DNSError> {
+    let malformed = || DNSError::MalformedCertificate;
+    let (&tag, rest) = data.split_first().ok_or_else(malformed)?;
+    let (&first_len, rest) = rest.split_first().ok_or_else(malformed)?;
+
+    let (len, header_len) = if first_len & 0x80 == 0 {
+        (first_len as usize, 2)
+    } else {
+        // Long form: the low bits count the bytes of the length
+        let len_bytes = (first_len & 0x7F) as usize;
+        if len_bytes == 0 || len_bytes > 4 || rest.len() < len_bytes {
+            return Err(malformed());
+        }
+        let len = rest[..len_bytes]
+            .iter()
+            .fold(0usize, |len, b| len << 8 | *b as usize);
+        (len, 2 + len_bytes)
+    };
+
+    let end = header_len.checked_add(len).ok_or_else(malformed)?;
+    if data.len() < end {
+        return Err(malformed());
+    }
+    Ok((tag, &data[..end], &data[header_len..end]))
+}
+
+/// DER encoded SubjectPublicKeyInfo of a DER encoded X.509 certificate (RFC 5280 section 4.1):
+/// the seventh field of the TBSCertificate, or the sixth when the version is absent.
+pub(crate) fn certificate_spki(certificate: &[u8]) -> Result<&[u8], DNSError> {
+    const SEQUENCE: u8 = 0x30;
+    const VERSION: u8 = 0xA0; // [0] EXPLICIT
+
+    let (tag, _, certificate) = der_element(certificate)?;
+    if tag != SEQUENCE {
+        return Err(DNSError::MalformedCertificate);
+    }
+    let (tag, _, mut tbs_certificate) = der_element(certificate)?;
+    if tag != SEQUENCE {
+        return Err(DNSError::MalformedCertificate);
+    }
+
+    // serialNumber, signature, issuer, validity and subject precede the key
+    let mut skipped = 0;
+    loop {
+        let (tag, element, _) = der_element(tbs_certificate)?;
+        tbs_certificate = &tbs_certificate[element.len()..];
+        if tag == VERSION && skipped == 0 {
+            continue;
+        }
+        if skipped == 5 {
+            return match tag {
+                SEQUENCE => Ok(element),
+                _ => Err(DNSError::MalformedCertificate),
+            };
+        }
+        skipped += 1;
+    }
+}
+
+/// Owner name of the SMIMEA records of an email address (RFC 8162 section 3): the SHA-256 digest
+/// of its local part truncated to 28 bytes, in hexadecimal, under the _smimecert subdomain of its
+/// domain. The local part is hashed as given, without any canonicalization. Fails if the owner
+/// name would be longer than 255 bytes.
+pub fn smimea_owner(local_part: &str, domain: &Domain) -> Result<Domain, DNSError> {
+    let hash = digest::digest(&digest::SHA256, local_part.as_bytes());
+    let hash = hex_encode(&hash.as_ref()[..SMIMEA_HASH_SIZE]).to_lowercase();
+    Domain::from_labels([hash.as_bytes(), b"_smimecert"])?.append(domain)
+}
+
+/// Certificate association of a TLS server (RFC 6698), used by DANE.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TLSA {
+    pub certificate_usage: CertificateUsage, // 1 byte
+    pub selector: Selector,                  // 1 byte
+    pub matching_type: MatchingType,         // 1 byte
+    pub association_data: Vec<u8>,           // Variable length
+}
+
+impl TLSA {
+    /// Check whether a DER encoded certificate matches the association data, comparing the full
+    /// certificate or its SubjectPublicKeyInfo as the selector requires, or its digest as the
+    /// matching type requires. Records with a selector or matching type which is not understood
+    /// are unusable, so they match no certificate.
+    pub fn matches_certificate(&self, certificate: &[u8]) -> Result<bool, DNSError> {
+        let selected = match self.selector {
+            Selector::FullCertificate => certificate,
+            Selector::SubjectPublicKeyInfo => certificate_spki(certificate)?,
+            _ => return Ok(false),
+        };
+
+        let matched = match self.matching_type {
+            MatchingType::Full => selected == self.association_data.as_slice(),
+            MatchingType::SHA256 => {
+                digest::digest(&digest::SHA256, selected).as_ref() == self.association_data
+            }
+            MatchingType::SHA512 => {
+                digest::digest(&digest::SHA512, selected).as_ref() == self.association_data
+            }
+            _ => false,
+        };
+        Ok(matched)
+    }
+}
+
+impl RecordDataRead for TLSA {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let certificate_usage = CertificateUsage::from_num(buffer.read_u8()?);
+        let selector = Selector::from_num(buffer.read_u8()?);
+        let matching_type = MatchingType::from_num(buffer.read_u8()?);
+        // Minus three bytes from the previous fields
+        let data_len = (preamble.len as usize)
+            .checked_sub(3)
+            .ok_or(DNSError::BadPointerPosition)?;
+        let association_data = buffer.read_bytes(data_len)?;
+
+        Ok(TLSA {
+            certificate_usage,
+            selector,
+            matching_type,
+            association_data,
+        })
+    }
+}
+
+impl RecordDataWrite for TLSA {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u8(self.certificate_usage.to_num())?;
+        buffer.write_u8(self.selector.to_num())?;
+        buffer.write_u8(self.matching_type.to_num())?;
+        buffer.write_bytes(&self.association_data)?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::TLSA
+    }
+}
+
+/// Presentation format: <usage> <selector> <matching type> <hexadecimal association data>
+impl fmt::Display for TLSA {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.certificate_usage.to_num(),
+            self.selector.to_num(),
+            self.matching_type.to_num(),
+            hex_encode(&self.association_data)
+        )
+    }
+}
+
+/// Certificate association of an S/MIME user (RFC 8162). Its RDATA is identical to a TLSA
+/// record's.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SMIMEA {
+    pub tlsa: TLSA,
+}
+
+impl RecordDataRead for SMIMEA {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        Ok(SMIMEA {
+            tlsa: TLSA::parse_from_buffer(buffer, preamble)?,
+        })
+    }
+}
+
+impl RecordDataWrite for SMIMEA {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        self.tlsa.write_to_buffer(buffer)
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::SMIMEA
+    }
+}
+
+/// Presentation format: same as TLSA
+impl fmt::Display for SMIMEA {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tlsa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Domain, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    /// DER element of a tag and content, with a long form length when needed.
+    fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut element = vec![tag];
+        match content.len() {
+            len if len < 0x80 => element.push(len as u8),
+            len => element.extend([0x82, (len >> 8) as u8, len as u8]),
+        }
+        element.extend(content);
+        element
+    }
+
+    /// Structure of an X.509 certificate, with placeholder fields around the given key.
+    fn certificate(spki: &[u8]) -> Vec<u8> {
+        let name = der(0x30, &der(0x31, &[0x05, 0x00]));
+        let tbs_certificate = [
+            der(0xA0, &der(0x02, &[0x02])),
+            der(0x02, &[0x01]),
+            der(0x30, &der(0x06, &[0x2A, 0x86, 0x48])),
+            name.clone(),
+            der(0x30, &[]),
+            name,
+            spki.to_vec(),
+            der(0xA3, &[0xFF; 200]),
+        ]
+        .concat();
+        der(
+            0x30,
+            &[
+                der(0x30, &tbs_certificate),
+                der(0x30, &der(0x06, &[0x2A, 0x86, 0x48])),
+                der(0x03, &[0x00, 0x01, 0x02]),
+            ]
+            .concat(),
+        )
+    }
+
+    #[test]
+    fn test_read_write_tlsa() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x5F,
+            0x34, 0x34, 0x33, 0x04, 0x5F, 0x74, 0x63, 0x70, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70,
+            0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00, 0x34, 0x00, 0x01, 0x00, 0x00, 0x0E,
+            0x10, 0x00, 0x07, 0x03, 0x01, 0x01, 0xDE, 0xAD, 0xBE, 0xEF,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "_443._tcp.example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::TLSA(TLSA {
+                certificate_usage: CertificateUsage::DANEEE,
+                selector: Selector::SubjectPublicKeyInfo,
+                matching_type: MatchingType::SHA256,
+                association_data: vec![0xDE, 0xAD, 0xBE, 0xEF],
+            }),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 7);
+        assert_eq!(parsed_record, expected_record);
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        expected_record.write_to_buffer(&mut buffer).unwrap();
+        assert_eq!(buffer.get_data(), dns_packet_data);
+        assert_eq!(expected_record.data().to_string(), "3 1 1 DEADBEEF");
+    }
+
+    #[test]
+    fn test_matches_certificate() {
+        let spki = der(
+            0x30,
+            &[der(0x30, &[0x06, 0x01, 0x2A]), der(0x03, &[0x00; 65])].concat(),
+        );
+        let certificate = certificate(&spki);
+        assert_eq!(certificate_spki(&certificate), Ok(spki.as_slice()));
+
+        let tlsa = |selector, matching_type, association_data: &[u8]| TLSA {
+            certificate_usage: CertificateUsage::DANEEE,
+            selector,
+            matching_type,
+            association_data: association_data.to_vec(),
+        };
+        let spki_sha256 = digest::digest(&digest::SHA256, &spki);
+        let cert_sha512 = digest::digest(&digest::SHA512, &certificate);
+
+        let matching = [
+            tlsa(
+                Selector::SubjectPublicKeyInfo,
+                MatchingType::SHA256,
+                spki_sha256.as_ref(),
+            ),
+            tlsa(Selector::SubjectPublicKeyInfo, MatchingType::Full, &spki),
+            tlsa(
+                Selector::FullCertificate,
+                MatchingType::SHA512,
+                cert_sha512.as_ref(),
+            ),
+            tlsa(Selector::FullCertificate, MatchingType::Full, &certificate),
+        ];
+        for tlsa in matching {
+            assert_eq!(tlsa.matches_certificate(&certificate), Ok(true));
+        }
+
+        let other = tlsa(
+            Selector::FullCertificate,
+            MatchingType::SHA256,
+            spki_sha256.as_ref(),
+        );
+        assert_eq!(other.matches_certificate(&certificate), Ok(false));
+        let unknown = tlsa(Selector::Unassigned(7), MatchingType::Full, &certificate);
+        assert_eq!(unknown.matches_certificate(&certificate), Ok(false));
+
+        let spki_tlsa = tlsa(Selector::SubjectPublicKeyInfo, MatchingType::Full, &spki);
+        assert_eq!(
+            spki_tlsa.matches_certificate(&certificate[..100]),
+            Err(DNSError::MalformedCertificate)
+        );
+    }
+
+    #[test]
+    fn test_smimea_owner() {
+        // RFC 8162 section 3 example
+        assert_eq!(
+            smimea_owner("hugh", &"example.com".parse().unwrap()),
+            "c93f1e400f26708f98cb19d936620da35eec8f72e57f9eec01c1afd6._smimecert.example.com"
+                .parse()
+        );
+        let long_domain = Domain::from_labels(vec!["a".repeat(63); 3]).unwrap();
+        assert!(matches!(
+            smimea_owner("hugh", &long_domain),
+            Err(DNSError::DomainNameTooLarge(_, _))
+        ));
+    }
+}
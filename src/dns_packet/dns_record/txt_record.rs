@@ -1,10 +1,14 @@
 use super::{
-    DNSError, DNSPacketBuffer, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
+    quote_character_string, DNSError, DNSPacketBuffer, QueryType, RecordDataRead, RecordDataWrite,
+    RecordPreamble,
 };
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+const MAX_CHARACTER_STRING_SIZE: usize = 255;
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct TXT {
-    pub txt_data: String,
+    pub txt_data: Vec<Vec<u8>>, // One or more <character-string>, each up to 255 bytes
 }
 
 impl RecordDataRead for TXT {
@@ -12,13 +16,20 @@ impl RecordDataRead for TXT {
         buffer: &mut DNSPacketBuffer,
         preamble: &RecordPreamble,
     ) -> Result<Self, DNSError> {
-        let curr_pos = buffer.get_pos();
-        let txt_slice = &buffer.get_data()[curr_pos..curr_pos + preamble.len as usize];
-        let txt_str = String::from_utf8(txt_slice.to_vec()).map_err(|_| DNSError::NonUTF8)?;
-
-        buffer.step(preamble.len as usize);
+        let mut txt_data = Vec::<Vec<u8>>::new();
+        let mut remaining = preamble.len as usize;
+        while remaining > 0 {
+            let len = buffer.read_u8()? as usize;
+            if len >= remaining {
+                return Err(DNSError::InvalidTXT(
+                    "character string overruns the RDATA".to_string(),
+                ));
+            }
+            txt_data.push(buffer.read_bytes(len)?);
+            remaining -= len + 1;
+        }
 
-        Ok(TXT { txt_data: txt_str })
+        Ok(TXT { txt_data })
     }
 }
 
@@ -27,8 +38,15 @@ impl RecordDataWrite for TXT {
         let len_field = buffer.get_pos() - 2;
         let starting_pos = buffer.get_pos();
 
-        for b in self.txt_data.as_bytes() {
-            buffer.write_u8(*b)?;
+        for character_string in self.txt_data.iter() {
+            if character_string.len() > MAX_CHARACTER_STRING_SIZE {
+                return Err(DNSError::InvalidTXT(format!(
+                    "character string of {} bytes exceeds 255 bytes",
+                    character_string.len()
+                )));
+            }
+            buffer.write_u8(character_string.len() as u8)?;
+            buffer.write_bytes(character_string)?;
         }
         let len = buffer.get_pos() - starting_pos;
         buffer.set_u16(len_field, len as u16)?;
@@ -41,70 +59,140 @@ impl RecordDataWrite for TXT {
     }
 }
 
+/// Presentation format: one or more quoted <character-string>.
+impl fmt::Display for TXT {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.txt_data.is_empty() {
+            return write!(f, "\"\"");
+        }
+
+        let quoted: Vec<String> = self
+            .txt_data
+            .iter()
+            .map(|character_string| quote_character_string(character_string))
+            .collect();
+        write!(f, "{}", quoted.join(" "))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
 
+    const TXT_PACKET: [u8; 92] = [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62, 0x61,
+        0x72, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00,
+        0x10, 0x00, 0x01, 0x00, 0x00, 0x00, 0xFE, 0x00, 0x35, 0x27, 0x54, 0x68, 0x69, 0x73, 0x20,
+        0x69, 0x73, 0x20, 0x61, 0x6E, 0x20, 0x61, 0x77, 0x65, 0x73, 0x6F, 0x6D, 0x65, 0x20, 0x64,
+        0x6F, 0x6D, 0x61, 0x69, 0x6E, 0x21, 0x20, 0x22, 0x44, 0x65, 0x66, 0x69, 0x6E, 0x69, 0x74,
+        0x65, 0x6C, 0x79, 0x22, 0x00, 0x0B, 0x6E, 0x6F, 0x74, 0x20, 0x73, 0x70, 0x61, 0x6D, 0x6D,
+        0x79, 0x2E,
+    ];
+
+    fn txt_record() -> Record {
+        Record::new(
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
+            254,
+            RecordData::TXT(TXT {
+                txt_data: vec![
+                    b"This is an awesome domain! \"Definitely\"".to_vec(),
+                    Vec::new(),
+                    b"not spammy.".to_vec(),
+                ],
+            }),
+        )
+    }
+
     #[test]
     fn test_read_txt() {
-        let dns_packet_data = [
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62,
-            0x61, 0x72, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D,
-            0x00, 0x00, 0x10, 0x00, 0x01, 0x00, 0x00, 0x00, 0xFE, 0x00, 0x31, 0x54, 0x68, 0x69,
-            0x73, 0x20, 0x69, 0x73, 0x20, 0x61, 0x6E, 0x20, 0x61, 0x77, 0x65, 0x73, 0x6F, 0x6D,
-            0x65, 0x20, 0x64, 0x6F, 0x6D, 0x61, 0x69, 0x6E, 0x21, 0x20, 0x44, 0x65, 0x66, 0x69,
-            0x6E, 0x69, 0x74, 0x65, 0x6C, 0x79, 0x20, 0x6E, 0x6F, 0x74, 0x20, 0x73, 0x70, 0x61,
-            0x6D, 0x6D, 0x79, 0x2E,
-        ];
-
-        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&TXT_PACKET);
         dns_packet_buffer.seek(HEADER_SIZE);
 
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
-        let expected_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
-            254,
-            RecordData::TXT(TXT {
-                txt_data: "This is an awesome domain! Definitely not spammy.".to_string(),
-            }),
-        );
+        assert_eq!(parsed_record.preamble.len, 53);
+        assert_eq!(parsed_record, txt_record());
+    }
 
-        assert_eq!(parsed_record.preamble.len, 49);
-        assert_eq!(parsed_record, expected_record);
+    #[test]
+    fn test_read_txt_overrun() {
+        let mut dns_packet_data = TXT_PACKET.to_vec();
+        // Last character string claims one more byte than the RDATA holds
+        dns_packet_data[80] = 0x0C;
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        assert_eq!(
+            Record::parse_from_buffer(&mut dns_packet_buffer),
+            Err(DNSError::InvalidTXT(
+                "character string overruns the RDATA".to_string()
+            ))
+        );
     }
 
     #[test]
     fn test_write_txt() {
-        let a_record = Record::new(
-            Domain("bar.example.com".to_string()),
-            1,
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        txt_record().write_to_buffer(&mut buffer).unwrap();
+
+        assert_eq!(buffer.get_data(), &TXT_PACKET[..]);
+
+        let long_txt = Record::new(
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
             254,
             RecordData::TXT(TXT {
-                txt_data: "This is an awesome domain! Definitely not spammy.".to_string(),
+                txt_data: vec![vec![b'a'; 256]],
             }),
         );
-
         let mut buffer = DNSPacketBuffer::new(&[]);
         buffer.seek(HEADER_SIZE);
-        a_record.write_to_buffer(&mut buffer).unwrap();
-
-        // Expected
-        let expected_data = [
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62,
-            0x61, 0x72, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D,
-            0x00, 0x00, 0x10, 0x00, 0x01, 0x00, 0x00, 0x00, 0xFE, 0x00, 0x31, 0x54, 0x68, 0x69,
-            0x73, 0x20, 0x69, 0x73, 0x20, 0x61, 0x6E, 0x20, 0x61, 0x77, 0x65, 0x73, 0x6F, 0x6D,
-            0x65, 0x20, 0x64, 0x6F, 0x6D, 0x61, 0x69, 0x6E, 0x21, 0x20, 0x44, 0x65, 0x66, 0x69,
-            0x6E, 0x69, 0x74, 0x65, 0x6C, 0x79, 0x20, 0x6E, 0x6F, 0x74, 0x20, 0x73, 0x70, 0x61,
-            0x6D, 0x6D, 0x79, 0x2E,
-        ];
-
-        let mut expected_buffer = DNSPacketBuffer::new(&expected_data);
-        expected_buffer.seek(expected_data.len());
-
-        assert_eq!(buffer.get_data(), expected_buffer.get_data())
+        assert_eq!(
+            long_txt.write_to_buffer(&mut buffer),
+            Err(DNSError::InvalidTXT(
+                "character string of 256 bytes exceeds 255 bytes".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_display_txt() {
+        let txt = TXT {
+            txt_data: vec![b"v=spf1 \"quoted\" \\ -all".to_vec()],
+        };
+        let long_txt = TXT {
+            txt_data: vec![vec![b'a'; 255], vec![b'a'; 45]],
+        };
+
+        assert_eq!(txt.to_string(), "\"v=spf1 \\\"quoted\\\" \\\\ -all\"");
+        assert_eq!(
+            long_txt.to_string(),
+            format!("\"{}\" \"{}\"", "a".repeat(255), "a".repeat(45))
+        );
+        assert_eq!(
+            TXT {
+                txt_data: vec![Vec::new()]
+            }
+            .to_string(),
+            "\"\""
+        );
+    }
+
+    #[test]
+    fn test_display_parsed_txt() {
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&TXT_PACKET);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        assert_eq!(
+            parsed_record.to_string(),
+            "bar.example.com.\t254\tIN\tTXT\t\"This is an awesome domain! \\\"Definitely\\\"\" \"\" \
+             \"not spammy.\""
+        );
     }
 }
@@ -0,0 +1,134 @@
+use super::{DNSError, DNSPacketBuffer, QueryType};
+
+/// Parse the type bit maps field used by NSEC and NSEC3 records (RFC 4034 section 4.1.2),
+/// spanning len bytes from the current buffer pointer's position.
+pub(crate) fn parse_type_bitmap(
+    buffer: &mut DNSPacketBuffer,
+    len: usize,
+) -> Result<Vec<QueryType>, DNSError> {
+    let end_pos = buffer.get_pos() + len;
+    let mut types = Vec::<QueryType>::new();
+    let mut last_window: Option<u8> = None;
+
+    while buffer.get_pos() < end_pos {
+        let window = buffer.read_u8()?;
+        let bitmap_len = buffer.read_u8()?;
+
+        // Windows must appear in increasing order and hold between 1 and 32 bytes
+        if last_window.is_some_and(|last| window <= last) || !(1..=32).contains(&bitmap_len) {
+            return Err(DNSError::MalformedTypeBitmap);
+        }
+        last_window = Some(window);
+
+        for (i, byte) in buffer.read_bytes(bitmap_len as usize)?.iter().enumerate() {
+            for bit in 0..8 {
+                if byte & (0b1000_0000 >> bit) != 0 {
+                    let code = (window as u16) << 8 | (i as u16 * 8 + bit);
+                    types.push(QueryType::from_num(code));
+                }
+            }
+        }
+    }
+
+    if buffer.get_pos() != end_pos {
+        return Err(DNSError::MalformedTypeBitmap);
+    }
+
+    Ok(types)
+}
+
+/// Write the type bit maps field used by NSEC and NSEC3 records (RFC 4034 section 4.1.2).
+pub(crate) fn write_type_bitmap(
+    types: &[QueryType],
+    buffer: &mut DNSPacketBuffer,
+) -> Result<(), DNSError> {
+    let mut codes: Vec<u16> = types.iter().map(|qtype| qtype.to_num()).collect();
+    codes.sort_unstable();
+    codes.dedup();
+
+    let mut window_start = 0;
+    while window_start < codes.len() {
+        let window = (codes[window_start] >> 8) as u8;
+        let window_end = codes[window_start..]
+            .iter()
+            .position(|code| (code >> 8) as u8 != window)
+            .map_or(codes.len(), |offset| window_start + offset);
+
+        let mut bitmap = [0u8; 32];
+        let mut bitmap_len = 0;
+        for code in codes[window_start..window_end].iter() {
+            let low = (code & 0x00FF) as usize;
+            bitmap[low / 8] |= 0b1000_0000 >> (low % 8);
+            bitmap_len = low / 8 + 1;
+        }
+
+        buffer.write_u8(window)?;
+        buffer.write_u8(bitmap_len as u8)?;
+        buffer.write_bytes(&bitmap[..bitmap_len])?;
+
+        window_start = window_end;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_type_bitmap() {
+        // A MX RRSIG NSEC TYPE1234 (RFC 4034 section 4.3)
+        let bitmap_data = [
+            0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03, 0x04, 0x1B, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+        ];
+
+        let mut buffer = DNSPacketBuffer::new(&bitmap_data);
+        let types = parse_type_bitmap(&mut buffer, bitmap_data.len()).unwrap();
+
+        assert_eq!(
+            types,
+            vec![
+                QueryType::A,
+                QueryType::MX,
+                QueryType::RRSIG,
+                QueryType::NSEC,
+                QueryType::Unknown(1234)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_type_bitmap() {
+        let types = [
+            QueryType::Unknown(1234),
+            QueryType::NSEC,
+            QueryType::A,
+            QueryType::RRSIG,
+            QueryType::MX,
+        ];
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        write_type_bitmap(&types, &mut buffer).unwrap();
+
+        let expected_data = [
+            0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03, 0x04, 0x1B, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+        ];
+
+        assert_eq!(buffer.get_data(), expected_data);
+    }
+
+    #[test]
+    fn test_malformed_type_bitmap() {
+        let bitmap_data = [0x01, 0x01, 0x40, 0x00, 0x01, 0x40];
+
+        let mut buffer = DNSPacketBuffer::new(&bitmap_data);
+        let res = parse_type_bitmap(&mut buffer, bitmap_data.len());
+
+        assert_eq!(res, Err(DNSError::MalformedTypeBitmap));
+    }
+}
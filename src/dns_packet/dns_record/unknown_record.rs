@@ -1,41 +1,68 @@
 use super::{
     DNSError, DNSPacketBuffer, QueryType, RecordDataRead, RecordDataWrite, RecordPreamble,
 };
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
-pub struct Unknown {}
+/// Record of a type this crate does not understand. The RDATA is kept as raw bytes so the record
+/// can be re-serialized as-is (RFC 3597).
+#[derive(Debug, PartialEq, Clone)]
+pub struct Unknown {
+    pub code: u16,
+    pub data: Vec<u8>,
+}
 
 impl RecordDataRead for Unknown {
     fn parse_from_buffer(
         buffer: &mut DNSPacketBuffer,
         preamble: &RecordPreamble,
     ) -> Result<Self, DNSError> {
-        // Skip reading package
-        buffer.step(preamble.len as usize);
-        Ok(Unknown {})
+        Ok(Unknown {
+            code: preamble.record_type.to_num(),
+            data: buffer.read_bytes(preamble.len as usize)?,
+        })
     }
 }
+
 impl RecordDataWrite for Unknown {
-    fn write_to_buffer(&self, _buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
-        Err(DNSError::UnknownRecordWrite)
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        buffer.set_u16(len_field, self.data.len() as u16)?;
+
+        buffer.write_bytes(&self.data)?;
+        Ok(())
     }
 
     fn query_type(&self) -> QueryType {
-        QueryType::Unknown(0)
+        QueryType::from_num(self.code)
+    }
+}
+
+/// RFC 3597 generic presentation format: \# <length> <hex data>
+impl fmt::Display for Unknown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\\# {}", self.data.len())?;
+        if !self.data.is_empty() {
+            write!(f, " ")?;
+            for b in self.data.iter() {
+                write!(f, "{:02x}", b)?;
+            }
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::{Domain, Record, RecordData, HEADER_SIZE, PACKET_SIZE};
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
     use super::*;
+    use crate::dns_packet::DNSPacket;
 
     #[test]
     fn test_read_unknown() {
         let dns_packet_data = [
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x67,
             0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x00, 0xFF, 0x00, 0x01,
-            0x00, 0x00, 0x01, 0x25, 0x00, 0x05, 0xFF, 0xFF, 0xFF, 0xFF,
+            0x00, 0x00, 0x01, 0x25, 0x00, 0x05, 0xFF, 0xFF, 0xFF, 0xFF, 0x0A,
         ];
 
         let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
@@ -44,10 +71,13 @@ mod tests {
         let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
 
         let expected_record = Record::new(
-            Domain("google.com".to_string()),
-            1,
+            "google.com".parse().unwrap(),
+            Class::IN,
             293,
-            RecordData::Unknown(Unknown {}),
+            RecordData::Unknown(Unknown {
+                code: 255,
+                data: vec![0xFF, 0xFF, 0xFF, 0xFF, 0x0A],
+            }),
         );
 
         assert_eq!(parsed_record.preamble.len, 5);
@@ -57,14 +87,72 @@ mod tests {
 
     #[test]
     fn test_write_unknown() {
-        let unknown_record = Unknown {};
+        let unknown_record = Record::new(
+            "google.com".parse().unwrap(),
+            Class::IN,
+            293,
+            RecordData::Unknown(Unknown {
+                code: 255,
+                data: vec![0xFF, 0xFF, 0xFF, 0xFF, 0x0A],
+            }),
+        );
 
-        let mut buffer = DNSPacketBuffer::new(&[0; PACKET_SIZE]);
+        let mut buffer = DNSPacketBuffer::new(&[]);
         buffer.seek(HEADER_SIZE);
-        let err = unknown_record.write_to_buffer(&mut buffer);
+        unknown_record.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x67,
+            0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x00, 0xFF, 0x00, 0x01,
+            0x00, 0x00, 0x01, 0x25, 0x00, 0x05, 0xFF, 0xFF, 0xFF, 0xFF, 0x0A,
+        ];
 
-        let expected_err = Err(DNSError::UnknownRecordWrite);
+        assert_eq!(buffer.get_data(), expected_data)
+    }
+
+    #[test]
+    fn test_read_unimplemented_type() {
+        // Response with an HINFO answer, a type without a RecordData implementation
+        let dns_packet_data = [
+            0x12, 0x34, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+            0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D, 0x00, 0x00, 0x0D, 0x00,
+            0x01, 0xC0, 0x0C, 0x00, 0x0D, 0x00, 0x01, 0x00, 0x00, 0x0E, 0x10, 0x00, 0x0A, 0x03,
+            0x78, 0x38, 0x36, 0x05, 0x4C, 0x69, 0x6E, 0x75, 0x78,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        let packet = DNSPacket::parse_dns_packet(&mut dns_packet_buffer).unwrap();
+
+        let hinfo = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::Unknown(Unknown {
+                code: 13,
+                data: b"\x03x86\x05Linux".to_vec(),
+            }),
+        );
+        assert_eq!(packet.answers(), [hinfo]);
+        assert_eq!(packet.answers()[0].record_type(), QueryType::HINFO);
+        assert_eq!(
+            packet.write_dns_packet().unwrap().get_data(),
+            dns_packet_data
+        );
+    }
+
+    #[test]
+    fn test_display_unknown() {
+        let unknown = Unknown {
+            code: 731,
+            data: vec![0x0A, 0x00, 0x00, 0x01],
+        };
+        let empty_unknown = Unknown {
+            code: 731,
+            data: Vec::new(),
+        };
 
-        assert_eq!(err, expected_err)
+        assert_eq!(unknown.to_string(), "\\# 4 0a000001");
+        assert_eq!(empty_unknown.to_string(), "\\# 0");
     }
 }
@@ -0,0 +1,142 @@
+use super::{
+    quote_character_string, DNSError, DNSPacketBuffer, QueryType, RecordDataRead, RecordDataWrite,
+    RecordPreamble,
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct URI {
+    pub priority: u16,
+    pub weight: u16,
+    pub target: String, // Variable length, up to the end of the RDATA
+}
+
+impl RecordDataRead for URI {
+    fn parse_from_buffer(
+        buffer: &mut DNSPacketBuffer,
+        preamble: &RecordPreamble,
+    ) -> Result<Self, DNSError> {
+        let priority = buffer.read_u16()?;
+        let weight = buffer.read_u16()?;
+
+        // Minus the priority and weight fields
+        let target_len = (preamble.len as usize)
+            .checked_sub(4)
+            .ok_or(DNSError::BadPointerPosition)?;
+        let target =
+            String::from_utf8(buffer.read_bytes(target_len)?).map_err(|_| DNSError::NonUTF8)?;
+
+        Ok(URI {
+            priority,
+            weight,
+            target,
+        })
+    }
+}
+
+impl RecordDataWrite for URI {
+    fn write_to_buffer(&self, buffer: &mut DNSPacketBuffer) -> Result<(), DNSError> {
+        let len_field = buffer.get_pos() - 2;
+        let starting_pos = buffer.get_pos();
+
+        buffer.write_u16(self.priority)?;
+        buffer.write_u16(self.weight)?;
+        buffer.write_bytes(self.target.as_bytes())?;
+
+        let len = buffer.get_pos() - starting_pos;
+        buffer.set_u16(len_field, len as u16)?;
+
+        Ok(())
+    }
+
+    fn query_type(&self) -> QueryType {
+        QueryType::URI
+    }
+}
+
+/// Presentation format: <priority> <weight> <target>, the target being quoted.
+impl fmt::Display for URI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.priority,
+            self.weight,
+            quote_character_string(self.target.as_bytes())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Class, Record, RecordData, HEADER_SIZE};
+    use super::*;
+
+    #[test]
+    fn test_read_uri() {
+        let dns_packet_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62,
+            0x61, 0x72, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D,
+            0x00, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0xFE, 0x00, 0x11, 0x00, 0x0A, 0x00,
+            0x01, 0x73, 0x69, 0x70, 0x3A, 0x61, 0x40, 0x62, 0x61, 0x72, 0x2E, 0x63, 0x6F, 0x6D,
+        ];
+
+        let mut dns_packet_buffer = DNSPacketBuffer::new(&dns_packet_data);
+        dns_packet_buffer.seek(HEADER_SIZE);
+
+        let parsed_record = Record::parse_from_buffer(&mut dns_packet_buffer).unwrap();
+
+        let expected_record = Record::new(
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
+            254,
+            RecordData::URI(URI {
+                priority: 10,
+                weight: 1,
+                target: "sip:a@bar.com".to_string(),
+            }),
+        );
+
+        assert_eq!(parsed_record.preamble.len, 17);
+        assert_eq!(parsed_record, expected_record);
+    }
+
+    #[test]
+    fn test_write_uri() {
+        let uri_record = Record::new(
+            "bar.example.com".parse().unwrap(),
+            Class::IN,
+            254,
+            RecordData::URI(URI {
+                priority: 10,
+                weight: 1,
+                target: "sip:a@bar.com".to_string(),
+            }),
+        );
+
+        let mut buffer = DNSPacketBuffer::new(&[]);
+        buffer.seek(HEADER_SIZE);
+        uri_record.write_to_buffer(&mut buffer).unwrap();
+
+        // Expected
+        let expected_data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62,
+            0x61, 0x72, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63, 0x6F, 0x6D,
+            0x00, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0xFE, 0x00, 0x11, 0x00, 0x0A, 0x00,
+            0x01, 0x73, 0x69, 0x70, 0x3A, 0x61, 0x40, 0x62, 0x61, 0x72, 0x2E, 0x63, 0x6F, 0x6D,
+        ];
+
+        assert_eq!(buffer.get_data(), &expected_data[..])
+    }
+
+    #[test]
+    fn test_display_uri() {
+        let uri = URI {
+            priority: 10,
+            weight: 1,
+            target: "ftp://ftp1.example.com/public".to_string(),
+        };
+
+        assert_eq!(uri.to_string(), "10 1 \"ftp://ftp1.example.com/public\"");
+    }
+}
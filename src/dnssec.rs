@@ -0,0 +1,8 @@
+mod dnssec_canonical;
+mod dnssec_crypto;
+mod dnssec_signer;
+mod dnssec_validator;
+pub use dnssec_canonical::*;
+pub use dnssec_crypto::*;
+pub use dnssec_signer::*;
+pub use dnssec_validator::*;
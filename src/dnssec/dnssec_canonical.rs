@@ -0,0 +1,142 @@
+use crate::dns_packet::{DNSError, DNSPacketBuffer, Domain, Record, RRSIG};
+
+/// Amount of labels of a name as counted by the RRSIG labels field: neither the root nor a
+/// leading wildcard label are counted.
+pub(crate) fn rrsig_label_count(domain: &Domain) -> u8 {
+    (domain.label_count() - domain.is_wildcard() as usize) as u8
+}
+
+/// Domain name in canonical wire format: lowercased and uncompressed.
+pub(crate) fn canonical_name_wire(domain: &Domain) -> Result<Vec<u8>, DNSError> {
+    let mut buffer = DNSPacketBuffer::new_canonical();
+    domain.write_to_buffer(&mut buffer)?;
+    Ok(buffer.get_data().to_vec())
+}
+
+/// Sort the records of an RRset in canonical order (RFC 4034 section 6.3), dropping duplicates.
+pub(crate) fn canonical_rrset<'a>(rrset: &[&'a Record]) -> Result<Vec<&'a Record>, DNSError> {
+    let mut keyed_rrset = rrset
+        .iter()
+        .map(|record| Ok((record.canonical_rdata()?, *record)))
+        .collect::<Result<Vec<_>, DNSError>>()?;
+    keyed_rrset.sort_by(|(a, _), (b, _)| a.cmp(b));
+    keyed_rrset.dedup_by(|(a, _), (b, _)| a == b);
+
+    Ok(keyed_rrset.into_iter().map(|(_, record)| record).collect())
+}
+
+/// Data covered by an RRSIG (RFC 4034 section 3.1.8.1): the RRSIG RDATA without the signature
+/// followed by the RRset in canonical form and order, using the original TTL.
+pub fn rrsig_signed_data(rrsig: &RRSIG, rrset: &[&Record]) -> Result<Vec<u8>, DNSError> {
+    let mut buffer = DNSPacketBuffer::new_canonical();
+    buffer.write_u16(rrsig.type_covered.to_num())?;
+    buffer.write_u8(rrsig.algorithm.to_num())?;
+    buffer.write_u8(rrsig.labels)?;
+    buffer.write_u32(rrsig.original_ttl)?;
+    buffer.write_u32(rrsig.signature_expiration)?;
+    buffer.write_u32(rrsig.signature_inception)?;
+    buffer.write_u16(rrsig.key_tag)?;
+    rrsig.signer_name.write_to_buffer(&mut buffer)?;
+
+    let mut signed_data = buffer.get_data().to_vec();
+
+    for record in canonical_rrset(rrset)? {
+        // Records expanded from a wildcard are signed with the wildcard owner name
+        let domain = record.domain();
+        let owner = if (rrsig.labels as usize) < rrsig_label_count(domain) as usize {
+            let suffix = domain
+                .labels()
+                .skip(domain.label_count() - rrsig.labels as usize);
+            Domain::from_labels(std::iter::once(&b"*"[..]).chain(suffix))?
+        } else {
+            domain.clone()
+        };
+        signed_data.extend(record.to_canonical_wire(&owner, rrsig.original_ttl)?);
+    }
+
+    Ok(signed_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::{Class, QueryType, RecordData, A, MX, NSEC};
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_canonical_rrset() {
+        let records = [
+            Record::new(
+                "WWW.example.com".parse().unwrap(),
+                Class::IN,
+                300,
+                RecordData::A(A {
+                    addr: Ipv4Addr::new(192, 0, 2, 2),
+                }),
+            ),
+            Record::new(
+                "www.example.com".parse().unwrap(),
+                Class::IN,
+                300,
+                RecordData::A(A {
+                    addr: Ipv4Addr::new(192, 0, 2, 1),
+                }),
+            ),
+            Record::new(
+                "www.example.com".parse().unwrap(),
+                Class::IN,
+                300,
+                RecordData::A(A {
+                    addr: Ipv4Addr::new(192, 0, 2, 2),
+                }),
+            ),
+        ];
+        let rrset: Vec<&Record> = records.iter().collect();
+
+        let sorted = canonical_rrset(&rrset).unwrap();
+
+        assert_eq!(sorted.len(), 2);
+        assert_eq!(sorted[0], &records[1]);
+        assert_eq!(
+            sorted[1].to_canonical_wire(sorted[1].domain(), 60).unwrap(),
+            [
+                0x03, 0x77, 0x77, 0x77, 0x07, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x03, 0x63,
+                0x6F, 0x6D, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x3C, 0x00, 0x04, 0xC0,
+                0x00, 0x02, 0x02,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_canonical_rdata_case() {
+        // Names in the RDATA of the types of RFC 4034 section 6.2 are lowercased, but NSEC's
+        let mx = Record::new(
+            "Example.com".parse().unwrap(),
+            Class::IN,
+            300,
+            RecordData::MX(MX {
+                preference: 10,
+                exchange: "Mail.Example.com".parse().unwrap(),
+            }),
+        );
+        let nsec = Record::new(
+            "Example.com".parse().unwrap(),
+            Class::IN,
+            300,
+            RecordData::NSEC(NSEC {
+                next_domain: "Www.Example.com".parse().unwrap(),
+                types: vec![QueryType::MX],
+            }),
+        );
+
+        let mx_wire = mx.to_canonical_wire(mx.domain(), 300).unwrap();
+        assert_eq!(mx_wire[..13], *b"\x07example\x03com\x00");
+        assert_eq!(mx_wire[25..43], *b"\x04mail\x07example\x03com\x00");
+        let nsec_wire = nsec.to_canonical_wire(nsec.domain(), 300).unwrap();
+        assert_eq!(nsec_wire[..13], *b"\x07example\x03com\x00");
+        assert_eq!(
+            nsec.canonical_rdata().unwrap()[..17],
+            *b"\x03Www\x07Example\x03com\x00"
+        );
+    }
+}
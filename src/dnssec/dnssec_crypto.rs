@@ -0,0 +1,214 @@
+use super::canonical_name_wire;
+use crate::dns_packet::{DNSError, DNSSECAlgorithm, DigestType, Domain, DNSKEY};
+use ring::digest;
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+
+/// Check whether signatures made with the given algorithm can be verified.
+pub fn is_algorithm_supported(algorithm: DNSSECAlgorithm) -> bool {
+    matches!(
+        algorithm,
+        DNSSECAlgorithm::RSASHA1
+            | DNSSECAlgorithm::RSASHA1NSEC3SHA1
+            | DNSSECAlgorithm::RSASHA256
+            | DNSSECAlgorithm::RSASHA512
+            | DNSSECAlgorithm::ECDSAP256SHA256
+            | DNSSECAlgorithm::ECDSAP384SHA384
+            | DNSSECAlgorithm::ED25519
+    )
+}
+
+/// Check whether DS records with the given digest type can be verified.
+pub fn is_digest_type_supported(digest_type: DigestType) -> bool {
+    matches!(
+        digest_type,
+        DigestType::SHA1 | DigestType::SHA256 | DigestType::SHA384
+    )
+}
+
+/// Split an RSA public key in DNSKEY format (RFC 3110 section 2) into exponent and modulus.
+fn split_rsa_public_key(public_key: &[u8]) -> Result<(&[u8], &[u8]), DNSError> {
+    let (exponent_len, rest) = match public_key {
+        [0, high, low, rest @ ..] => (u16::from_be_bytes([*high, *low]) as usize, rest),
+        [len, rest @ ..] => (*len as usize, rest),
+        [] => return Err(DNSError::MalformedPublicKey),
+    };
+    if exponent_len == 0 || rest.len() <= exponent_len {
+        return Err(DNSError::MalformedPublicKey);
+    }
+
+    Ok(rest.split_at(exponent_len))
+}
+
+/// Verify a signature over data with a DNSKEY public key of the given algorithm.
+pub(crate) fn verify_signature(
+    algorithm: DNSSECAlgorithm,
+    public_key: &[u8],
+    data: &[u8],
+    signature: &[u8],
+) -> Result<(), DNSError> {
+    let rsa_params = match algorithm {
+        DNSSECAlgorithm::RSASHA1 | DNSSECAlgorithm::RSASHA1NSEC3SHA1 => {
+            Some(&signature::RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY)
+        }
+        DNSSECAlgorithm::RSASHA256 => {
+            Some(&signature::RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY)
+        }
+        DNSSECAlgorithm::RSASHA512 => {
+            Some(&signature::RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY)
+        }
+        _ => None,
+    };
+
+    if let Some(rsa_params) = rsa_params {
+        let (e, n) = split_rsa_public_key(public_key)?;
+        return RsaPublicKeyComponents { n, e }
+            .verify(rsa_params, data, signature)
+            .map_err(|_| DNSError::InvalidSignature);
+    }
+
+    match algorithm {
+        DNSSECAlgorithm::ECDSAP256SHA256 | DNSSECAlgorithm::ECDSAP384SHA384 => {
+            let (params, key_len): (&signature::EcdsaVerificationAlgorithm, usize) =
+                if algorithm == DNSSECAlgorithm::ECDSAP256SHA256 {
+                    (&signature::ECDSA_P256_SHA256_FIXED, 64)
+                } else {
+                    (&signature::ECDSA_P384_SHA384_FIXED, 96)
+                };
+            if public_key.len() != key_len {
+                return Err(DNSError::MalformedPublicKey);
+            }
+            // DNSKEY holds the raw curve point, without the uncompressed point prefix
+            let mut point = Vec::<u8>::with_capacity(key_len + 1);
+            point.push(0x04);
+            point.extend_from_slice(public_key);
+            UnparsedPublicKey::new(params, point)
+                .verify(data, signature)
+                .map_err(|_| DNSError::InvalidSignature)
+        }
+        DNSSECAlgorithm::ED25519 => {
+            if public_key.len() != 32 {
+                return Err(DNSError::MalformedPublicKey);
+            }
+            UnparsedPublicKey::new(&signature::ED25519, public_key)
+                .verify(data, signature)
+                .map_err(|_| DNSError::InvalidSignature)
+        }
+        _ => Err(DNSError::UnsupportedAlgorithm(algorithm.to_num())),
+    }
+}
+
+/// Digest of a DNSKEY as carried by DS records (RFC 4034 section 5.1.4).
+pub fn ds_digest(
+    owner: &Domain,
+    dnskey: &DNSKEY,
+    digest_type: DigestType,
+) -> Result<Vec<u8>, DNSError> {
+    let algorithm = match digest_type {
+        DigestType::SHA1 => &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        DigestType::SHA256 => &digest::SHA256,
+        DigestType::SHA384 => &digest::SHA384,
+        _ => return Err(DNSError::UnsupportedDigestType(digest_type.to_num())),
+    };
+
+    let mut ctx = digest::Context::new(algorithm);
+    ctx.update(&canonical_name_wire(owner)?);
+    ctx.update(&dnskey.rdata());
+
+    Ok(ctx.finish().as_ref().to_vec())
+}
+
+/// Highest NSEC3 iteration count hashed. Zones should use 0 and validators may treat higher
+/// counts as insecure (RFC 9276 section 3).
+pub const MAX_NSEC3_ITERATIONS: u16 = 100;
+
+/// Hashed owner name of NSEC3 records (RFC 5155 section 5), using SHA-1. Fails for iteration
+/// counts above MAX_NSEC3_ITERATIONS.
+pub fn nsec3_hash(name: &Domain, salt: &[u8], iterations: u16) -> Result<Vec<u8>, DNSError> {
+    if iterations > MAX_NSEC3_ITERATIONS {
+        return Err(DNSError::NSEC3IterationsExceeded(iterations));
+    }
+
+    let mut hash = canonical_name_wire(name)?;
+    for _ in 0..=iterations {
+        let mut ctx = digest::Context::new(&digest::SHA1_FOR_LEGACY_USE_ONLY);
+        ctx.update(&hash);
+        ctx.update(salt);
+        hash = ctx.finish().as_ref().to_vec();
+    }
+
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::base32hex_encode;
+
+    #[test]
+    fn test_ds_digest() {
+        // RFC 8080 section 6.1 example
+        let dnskey = DNSKEY::new(
+            257,
+            DNSSECAlgorithm::ED25519,
+            vec![
+                0x97, 0x4D, 0x96, 0xA2, 0x2D, 0x22, 0x4B, 0xC0, 0x1A, 0xDB, 0x91, 0x50, 0x91, 0x47,
+                0x7D, 0x44, 0xCC, 0xD9, 0x1C, 0x9A, 0x41, 0xA1, 0x14, 0x30, 0x01, 0x01, 0x17, 0xD5,
+                0x2C, 0x59, 0x24, 0x0E,
+            ],
+        );
+
+        let digest =
+            ds_digest(&"example.com".parse().unwrap(), &dnskey, DigestType::SHA256).unwrap();
+
+        assert_eq!(dnskey.key_tag(), 3613);
+        assert_eq!(
+            digest,
+            [
+                0x3A, 0xA5, 0xAB, 0x37, 0xEF, 0xCE, 0x57, 0xF7, 0x37, 0xFC, 0x16, 0x27, 0x01, 0x3F,
+                0xEE, 0x07, 0xBD, 0xF2, 0x41, 0xBD, 0x10, 0xF3, 0xB1, 0x96, 0x4A, 0xB5, 0x5C, 0x78,
+                0xE7, 0x9A, 0x30, 0x4B,
+            ]
+        );
+        assert_eq!(
+            ds_digest(&"example.com".parse().unwrap(), &dnskey, DigestType::GOST),
+            Err(DNSError::UnsupportedDigestType(3))
+        );
+    }
+
+    #[test]
+    fn test_nsec3_hash() {
+        // RFC 5155 appendix A examples
+        let salt = [0xAA, 0xBB, 0xCC, 0xDD];
+        let vectors = [
+            ("example", "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom"),
+            ("a.example", "35mthgpgcu1qg68fab165klnsnk3dpvl"),
+            ("ns1.example", "2t7b4g4vsa5smi47k61mv5bv1a22bojr"),
+        ];
+
+        for (name, hash) in vectors {
+            let digest = nsec3_hash(&name.parse().unwrap(), &salt, 12).unwrap();
+            assert_eq!(base32hex_encode(&digest), hash);
+        }
+
+        assert_eq!(
+            nsec3_hash(&"example".parse().unwrap(), &salt, 2500),
+            Err(DNSError::NSEC3IterationsExceeded(2500))
+        );
+    }
+
+    #[test]
+    fn test_split_rsa_public_key() {
+        assert_eq!(
+            split_rsa_public_key(&[0x01, 0x03, 0xAB, 0xCD]),
+            Ok((&[0x03][..], &[0xAB, 0xCD][..]))
+        );
+        assert_eq!(
+            split_rsa_public_key(&[0x00, 0x00, 0x01, 0x03, 0xAB]),
+            Ok((&[0x03][..], &[0xAB][..]))
+        );
+        assert_eq!(
+            split_rsa_public_key(&[0x03, 0x01, 0x00]),
+            Err(DNSError::MalformedPublicKey)
+        );
+    }
+}
@@ -0,0 +1,950 @@
+use super::{ds_digest, nsec3_hash, rrsig_label_count, rrsig_signed_data};
+use crate::dns_packet::{
+    base32hex_encode, Class, DNSError, DNSSECAlgorithm, DigestType, Domain, NSEC3HashAlgorithm,
+    QueryType, Record, RecordData, CDNSKEY, CDS, DNSKEY, DNSKEY_SECURE_ENTRY_POINT_FLAG, DS, NSEC,
+    NSEC3, NSEC3PARAM, NSEC3_OPT_OUT_FLAG, RRSIG,
+};
+use ring::rand::SystemRandom;
+use ring::signature::{self, EcdsaKeyPair, Ed25519KeyPair, KeyPair, RsaKeyPair};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_INCEPTION_OFFSET: u32 = 60 * 60; // 1 hour
+const DEFAULT_VALIDITY_PERIOD: u32 = 30 * 24 * 60 * 60; // 30 days
+
+enum KeyPairKind {
+    Rsa(RsaKeyPair),
+    Ecdsa(EcdsaKeyPair),
+    Ed25519(Ed25519KeyPair),
+}
+
+/// Private key of a zone, used to sign its RRsets.
+pub struct SigningKey {
+    algorithm: DNSSECAlgorithm,
+    flags: u16,
+    key_pair: KeyPairKind,
+    rng: SystemRandom,
+}
+
+/// Read a DER element with the given tag. Return its content and the input following it.
+fn der_element(input: &[u8], tag: u8) -> Result<(&[u8], &[u8]), DNSError> {
+    let (len, rest) = match input {
+        [t, len, rest @ ..] if *t == tag && *len < 0x80 => (*len as usize, rest),
+        [t, 0x81, len, rest @ ..] if *t == tag => (*len as usize, rest),
+        [t, 0x82, high, low, rest @ ..] if *t == tag => {
+            (u16::from_be_bytes([*high, *low]) as usize, rest)
+        }
+        _ => return Err(DNSError::MalformedPrivateKey),
+    };
+    if rest.len() < len {
+        return Err(DNSError::MalformedPrivateKey);
+    }
+
+    Ok(rest.split_at(len))
+}
+
+/// Convert a DER encoded RSAPublicKey (RFC 8017 appendix A.1.1) to DNSKEY format (RFC 3110
+/// section 2).
+fn rsa_dnskey_public_key(der: &[u8]) -> Result<Vec<u8>, DNSError> {
+    const INTEGER_TAG: u8 = 0x02;
+    const SEQUENCE_TAG: u8 = 0x30;
+
+    let (sequence, _) = der_element(der, SEQUENCE_TAG)?;
+    let (modulus, rest) = der_element(sequence, INTEGER_TAG)?;
+    let (exponent, _) = der_element(rest, INTEGER_TAG)?;
+
+    // DER integers are signed, positive ones may start with a zero byte
+    let strip =
+        |integer: &[u8]| -> Vec<u8> { integer.iter().copied().skip_while(|b| *b == 0).collect() };
+    let (modulus, exponent) = (strip(modulus), strip(exponent));
+
+    let mut public_key = Vec::<u8>::with_capacity(3 + exponent.len() + modulus.len());
+    if exponent.len() > u8::MAX as usize {
+        public_key.push(0);
+        public_key.extend_from_slice(&(exponent.len() as u16).to_be_bytes());
+    } else {
+        public_key.push(exponent.len() as u8);
+    }
+    public_key.extend(exponent);
+    public_key.extend(modulus);
+
+    Ok(public_key)
+}
+
+impl SigningKey {
+    /// Load a private key in PKCS#8 format, to be published in a DNSKEY record with the given
+    /// flags. RSASHA256, RSASHA512, ECDSAP256SHA256, ECDSAP384SHA384 and ED25519 are supported.
+    pub fn from_pkcs8(
+        algorithm: DNSSECAlgorithm,
+        flags: u16,
+        pkcs8: &[u8],
+    ) -> Result<Self, DNSError> {
+        let rng = SystemRandom::new();
+        let key_pair = match algorithm {
+            DNSSECAlgorithm::RSASHA256 | DNSSECAlgorithm::RSASHA512 => KeyPairKind::Rsa(
+                RsaKeyPair::from_pkcs8(pkcs8).map_err(|_| DNSError::MalformedPrivateKey)?,
+            ),
+            DNSSECAlgorithm::ECDSAP256SHA256 => KeyPairKind::Ecdsa(
+                EcdsaKeyPair::from_pkcs8(&signature::ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8, &rng)
+                    .map_err(|_| DNSError::MalformedPrivateKey)?,
+            ),
+            DNSSECAlgorithm::ECDSAP384SHA384 => KeyPairKind::Ecdsa(
+                EcdsaKeyPair::from_pkcs8(&signature::ECDSA_P384_SHA384_FIXED_SIGNING, pkcs8, &rng)
+                    .map_err(|_| DNSError::MalformedPrivateKey)?,
+            ),
+            DNSSECAlgorithm::ED25519 => KeyPairKind::Ed25519(
+                Ed25519KeyPair::from_pkcs8(pkcs8).map_err(|_| DNSError::MalformedPrivateKey)?,
+            ),
+            _ => return Err(DNSError::UnsupportedAlgorithm(algorithm.to_num())),
+        };
+
+        Ok(SigningKey {
+            algorithm,
+            flags,
+            key_pair,
+            rng,
+        })
+    }
+
+    /// Generate a new private key in PKCS#8 format. Only ECDSAP256SHA256, ECDSAP384SHA384 and
+    /// ED25519 keys can be generated.
+    pub fn generate_pkcs8(algorithm: DNSSECAlgorithm) -> Result<Vec<u8>, DNSError> {
+        let rng = SystemRandom::new();
+        let pkcs8 = match algorithm {
+            DNSSECAlgorithm::ECDSAP256SHA256 => {
+                EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+            }
+            DNSSECAlgorithm::ECDSAP384SHA384 => {
+                EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P384_SHA384_FIXED_SIGNING, &rng)
+            }
+            DNSSECAlgorithm::ED25519 => Ed25519KeyPair::generate_pkcs8(&rng),
+            _ => return Err(DNSError::UnsupportedAlgorithm(algorithm.to_num())),
+        }
+        .map_err(|_| DNSError::MalformedPrivateKey)?;
+
+        Ok(pkcs8.as_ref().to_vec())
+    }
+
+    pub fn algorithm(&self) -> DNSSECAlgorithm {
+        self.algorithm
+    }
+
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    pub fn is_secure_entry_point(&self) -> bool {
+        self.flags & DNSKEY_SECURE_ENTRY_POINT_FLAG != 0
+    }
+
+    /// DNSKEY record data publishing the public key.
+    pub fn dnskey(&self) -> Result<DNSKEY, DNSError> {
+        let public_key = match &self.key_pair {
+            KeyPairKind::Rsa(key_pair) => rsa_dnskey_public_key(key_pair.public_key().as_ref())?,
+            // DNSKEY holds the raw curve point, without the uncompressed point prefix
+            KeyPairKind::Ecdsa(key_pair) => key_pair.public_key().as_ref()[1..].to_vec(),
+            KeyPairKind::Ed25519(key_pair) => key_pair.public_key().as_ref().to_vec(),
+        };
+
+        Ok(DNSKEY::new(self.flags, self.algorithm, public_key))
+    }
+
+    /// Sign data with the private key, producing a signature in RRSIG format.
+    pub(crate) fn sign(&self, data: &[u8]) -> Result<Vec<u8>, DNSError> {
+        match &self.key_pair {
+            KeyPairKind::Rsa(key_pair) => {
+                let padding: &'static dyn signature::RsaEncoding =
+                    if self.algorithm == DNSSECAlgorithm::RSASHA512 {
+                        &signature::RSA_PKCS1_SHA512
+                    } else {
+                        &signature::RSA_PKCS1_SHA256
+                    };
+                let mut sig = vec![0; key_pair.public().modulus_len()];
+                key_pair
+                    .sign(padding, &self.rng, data, &mut sig)
+                    .map_err(|_| DNSError::MalformedPrivateKey)?;
+                Ok(sig)
+            }
+            KeyPairKind::Ecdsa(key_pair) => Ok(key_pair
+                .sign(&self.rng, data)
+                .map_err(|_| DNSError::MalformedPrivateKey)?
+                .as_ref()
+                .to_vec()),
+            KeyPairKind::Ed25519(key_pair) => Ok(key_pair.sign(data).as_ref().to_vec()),
+        }
+    }
+}
+
+/// Method used to authenticate denial of existence in a signed zone.
+#[derive(Debug, PartialEq)]
+pub enum DenialOfExistence {
+    NSEC,
+    NSEC3 {
+        salt: Vec<u8>,
+        iterations: u16,
+        opt_out: bool, // Leave unsigned delegations out of the NSEC3 chain
+    },
+}
+
+/// Types of the RRsets present at each name of the NSEC or NSEC3 chain.
+struct OwnerTypes {
+    name: Domain,
+    types: Vec<QueryType>,
+}
+
+/// Signs an in-memory zone: adds its DNSKEY, CDS and CDNSKEY records, an NSEC or NSEC3 chain and
+/// RRSIGs for every authoritative RRset.
+pub struct ZoneSigner {
+    zone: Domain,
+    keys: Vec<SigningKey>,
+    denial: DenialOfExistence,
+    inception: u32,
+    expiration: u32,
+}
+
+impl ZoneSigner {
+    /// Create a signer for a zone using NSEC and signatures valid for 30 days from now. Keys with
+    /// the secure entry point flag sign the DNSKEY RRset, other keys sign the rest of the zone.
+    /// If all keys are of the same kind, they sign everything.
+    pub fn new(zone: Domain, keys: Vec<SigningKey>) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs() as u32);
+
+        ZoneSigner {
+            zone: zone.to_lowercase(),
+            keys,
+            denial: DenialOfExistence::NSEC,
+            inception: now.wrapping_sub(DEFAULT_INCEPTION_OFFSET),
+            expiration: now.wrapping_add(DEFAULT_VALIDITY_PERIOD),
+        }
+    }
+
+    /// Set the validity period of the signatures, in seconds since the UNIX epoch.
+    pub fn set_validity(&mut self, inception: u32, expiration: u32) {
+        self.inception = inception;
+        self.expiration = expiration;
+    }
+
+    pub fn set_denial(&mut self, denial: DenialOfExistence) {
+        self.denial = denial;
+    }
+
+    /// Sign the records of the zone. RRSIG, NSEC, NSEC3 and NSEC3PARAM records, as well as
+    /// DNSKEY, CDS and CDNSKEY records at the apex, are generated anew. Return the zone records
+    /// along with the generated ones.
+    pub fn sign_zone(&self, records: Vec<Record>) -> Result<Vec<Record>, DNSError> {
+        let apex = &self.zone;
+
+        let mut records: Vec<Record> = records
+            .into_iter()
+            .filter(|record| {
+                let generated_at_apex = matches!(
+                    record.record_type(),
+                    QueryType::DNSKEY | QueryType::CDS | QueryType::CDNSKEY
+                ) && record.domain() == apex;
+                let generated = matches!(
+                    record.record_type(),
+                    QueryType::RRSIG | QueryType::NSEC | QueryType::NSEC3 | QueryType::NSEC3PARAM
+                );
+                !generated_at_apex && !generated
+            })
+            .collect();
+
+        if let Some(record) = records
+            .iter()
+            .find(|record| !record.domain().is_subdomain_of(apex))
+        {
+            return Err(DNSError::OutOfZone(record.domain().to_string()));
+        }
+
+        let (class, soa_ttl, negative_ttl) =
+            match records.iter().find_map(|record| match record.data() {
+                RecordData::SOA(soa) if record.domain() == apex => Some((record, soa)),
+                _ => None,
+            }) {
+                // Negative answers are cached for the minimum of the SOA TTL and minimum field
+                // (RFC 9077)
+                Some((record, soa)) => (record.class(), record.ttl(), record.ttl().min(soa.minttl)),
+                None => return Err(DNSError::MissingSOA(apex.to_string())),
+            };
+
+        records.extend(self.key_records(class, soa_ttl)?);
+
+        let owners = self.owner_types(&records);
+        let denial_records = match &self.denial {
+            DenialOfExistence::NSEC => self.nsec_chain(&owners, class, negative_ttl),
+            DenialOfExistence::NSEC3 {
+                salt,
+                iterations,
+                opt_out,
+            } => self.nsec3_chain(owners, salt, *iterations, *opt_out, class, negative_ttl)?,
+        };
+        records.extend(denial_records);
+
+        let rrsigs = self.sign_rrsets(&records)?;
+        records.extend(rrsigs);
+
+        Ok(records)
+    }
+
+    /// DNSKEY records of every key, along with CDNSKEY and CDS records of the secure entry
+    /// points for the parent zone to pick up (RFC 7344).
+    fn key_records(&self, class: Class, ttl: u32) -> Result<Vec<Record>, DNSError> {
+        let mut records = Vec::<Record>::new();
+
+        for key in self.keys.iter() {
+            let dnskey = key.dnskey()?;
+            if key.is_secure_entry_point() {
+                let ds = DS {
+                    key_tag: dnskey.key_tag(),
+                    algorithm: dnskey.algorithm,
+                    digest_type: DigestType::SHA256,
+                    digest: ds_digest(&self.zone, &dnskey, DigestType::SHA256)?,
+                };
+                records.push(Record::new(
+                    self.zone.clone(),
+                    class,
+                    ttl,
+                    RecordData::CDS(CDS { ds }),
+                ));
+                records.push(Record::new(
+                    self.zone.clone(),
+                    class,
+                    ttl,
+                    RecordData::CDNSKEY(CDNSKEY {
+                        dnskey: dnskey.clone(),
+                    }),
+                ));
+            }
+            records.push(Record::new(
+                self.zone.clone(),
+                class,
+                ttl,
+                RecordData::DNSKEY(dnskey),
+            ));
+        }
+
+        Ok(records)
+    }
+
+    /// Delegation points of the zone: names below the apex owning NS records.
+    fn delegations<'a>(&self, records: &'a [Record]) -> Vec<&'a Domain> {
+        records
+            .iter()
+            .filter(|record| record.record_type() == QueryType::NS && record.domain() != &self.zone)
+            .map(|record| record.domain())
+            .collect()
+    }
+
+    /// Check whether the zone is authoritative for a RRset, i.e. it must be signed. Names below
+    /// delegation points are glue, and delegation points only own the parent's DS and NSEC.
+    fn is_authoritative(&self, owner: &Domain, qtype: &QueryType, delegations: &[&Domain]) -> bool {
+        delegations.iter().all(|delegation| {
+            if owner == *delegation {
+                matches!(qtype, QueryType::DS | QueryType::NSEC)
+            } else {
+                !owner.is_subdomain_of(delegation)
+            }
+        })
+    }
+
+    /// Names of the zone in canonical order, with the types of the RRsets they own. Names below
+    /// delegation points are left out, delegation points only own their NS and DS RRsets.
+    fn owner_types(&self, records: &[Record]) -> Vec<OwnerTypes> {
+        let delegations = self.delegations(records);
+
+        let mut owners = Vec::<OwnerTypes>::new();
+        for record in records.iter() {
+            let qtype = record.record_type();
+            let is_delegation_ns = qtype == QueryType::NS
+                && delegations
+                    .iter()
+                    .any(|delegation| record.domain() == *delegation);
+            if !is_delegation_ns && !self.is_authoritative(record.domain(), &qtype, &delegations) {
+                continue;
+            }
+
+            match owners
+                .iter_mut()
+                .find(|owner| &owner.name == record.domain())
+            {
+                Some(owner) if !owner.types.contains(&qtype) => owner.types.push(qtype),
+                Some(_) => (),
+                None => owners.push(OwnerTypes {
+                    name: record.domain().to_lowercase(),
+                    types: vec![qtype],
+                }),
+            }
+        }
+
+        owners.sort_by(|a, b| a.name.cmp(&b.name));
+        owners
+    }
+
+    /// NSEC records linking every name of the zone in canonical order (RFC 4035 section 2.3).
+    fn nsec_chain(&self, owners: &[OwnerTypes], class: Class, ttl: u32) -> Vec<Record> {
+        owners
+            .iter()
+            .enumerate()
+            .map(|(i, owner)| {
+                let next = &owners[(i + 1) % owners.len()];
+                let mut types = owner.types.clone();
+                types.extend([QueryType::RRSIG, QueryType::NSEC]);
+
+                Record::new(
+                    owner.name.clone(),
+                    class,
+                    ttl,
+                    RecordData::NSEC(NSEC {
+                        next_domain: next.name.clone(),
+                        types,
+                    }),
+                )
+            })
+            .collect()
+    }
+
+    /// NSEC3 records linking the hashed names of the zone, including empty non-terminals, along
+    /// with the NSEC3PARAM record at the apex (RFC 5155 section 7.1).
+    fn nsec3_chain(
+        &self,
+        mut owners: Vec<OwnerTypes>,
+        salt: &[u8],
+        iterations: u16,
+        opt_out: bool,
+        class: Class,
+        ttl: u32,
+    ) -> Result<Vec<Record>, DNSError> {
+        let is_unsigned_delegation = |owner: &OwnerTypes| {
+            owner.types.contains(&QueryType::NS)
+                && !owner.types.contains(&QueryType::DS)
+                && owner.name != self.zone
+        };
+        if opt_out {
+            owners.retain(|owner| !is_unsigned_delegation(owner));
+        }
+
+        for owner in owners.iter_mut() {
+            // Unsigned delegations have no signed RRset
+            if !owner.types.is_empty() && !is_unsigned_delegation(owner) {
+                owner.types.push(QueryType::RRSIG);
+            }
+            if owner.name == self.zone {
+                owner.types.push(QueryType::NSEC3PARAM);
+            }
+        }
+
+        // Empty non-terminals, between each name and the apex
+        let mut empty_non_terminals = Vec::<OwnerTypes>::new();
+        for owner in owners.iter() {
+            let mut ancestor = owner.name.parent();
+            while let Some(name) =
+                ancestor.filter(|name| name.label_count() > self.zone.label_count())
+            {
+                ancestor = name.parent();
+                let known = owners
+                    .iter()
+                    .chain(empty_non_terminals.iter())
+                    .any(|other| other.name == name);
+                if !known {
+                    empty_non_terminals.push(OwnerTypes {
+                        name,
+                        types: Vec::new(),
+                    });
+                }
+            }
+        }
+        owners.extend(empty_non_terminals);
+
+        let mut hashed_owners = owners
+            .into_iter()
+            .map(|owner| Ok((nsec3_hash(&owner.name, salt, iterations)?, owner.types)))
+            .collect::<Result<Vec<_>, DNSError>>()?;
+        hashed_owners.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let flags = if opt_out { NSEC3_OPT_OUT_FLAG } else { 0 };
+        let mut records = Vec::<Record>::new();
+        for (i, (hash, types)) in hashed_owners.iter().enumerate() {
+            let (next_hash, _) = &hashed_owners[(i + 1) % hashed_owners.len()];
+            let owner = Domain::from_labels([base32hex_encode(hash)])?.append(&self.zone)?;
+
+            records.push(Record::new(
+                owner,
+                class,
+                ttl,
+                RecordData::NSEC3(NSEC3 {
+                    hash_algorithm: NSEC3HashAlgorithm::SHA1,
+                    flags,
+                    iterations,
+                    salt: salt.to_vec(),
+                    next_hashed_owner: next_hash.clone(),
+                    types: types.clone(),
+                }),
+            ));
+        }
+
+        // NSEC3PARAM flags must be zero, its TTL is not used by resolvers
+        records.push(Record::new(
+            self.zone.clone(),
+            class,
+            0,
+            RecordData::NSEC3PARAM(NSEC3PARAM {
+                hash_algorithm: NSEC3HashAlgorithm::SHA1,
+                flags: 0,
+                iterations,
+                salt: salt.to_vec(),
+            }),
+        ));
+
+        Ok(records)
+    }
+
+    /// RRSIG records for every authoritative RRset of the zone.
+    fn sign_rrsets(&self, records: &[Record]) -> Result<Vec<Record>, DNSError> {
+        let delegations = self.delegations(records);
+
+        let mut rrsets = BTreeMap::<(Domain, u16), Vec<&Record>>::new();
+        for record in records.iter() {
+            let qtype = record.record_type();
+            if self.is_authoritative(record.domain(), &qtype, &delegations) {
+                rrsets
+                    .entry((record.domain().clone(), qtype.to_num()))
+                    .or_default()
+                    .push(record);
+            }
+        }
+
+        let (entry_keys, other_keys): (Vec<&SigningKey>, Vec<&SigningKey>) = self
+            .keys
+            .iter()
+            .partition(|key| key.is_secure_entry_point());
+        let key_signing_keys = if entry_keys.is_empty() {
+            &other_keys
+        } else {
+            &entry_keys
+        };
+        let zone_signing_keys = if other_keys.is_empty() {
+            &entry_keys
+        } else {
+            &other_keys
+        };
+
+        let mut rrsigs = Vec::<Record>::new();
+        for rrset in rrsets.values() {
+            let first = rrset[0];
+            let keys = match first.record_type() {
+                QueryType::DNSKEY | QueryType::CDS | QueryType::CDNSKEY => key_signing_keys,
+                _ => zone_signing_keys,
+            };
+
+            for key in keys.iter() {
+                let mut rrsig = RRSIG {
+                    type_covered: first.record_type(),
+                    algorithm: key.algorithm(),
+                    labels: rrsig_label_count(first.domain()),
+                    original_ttl: first.ttl(),
+                    signature_expiration: self.expiration,
+                    signature_inception: self.inception,
+                    key_tag: key.dnskey()?.key_tag(),
+                    signer_name: self.zone.clone(),
+                    signature: Vec::new(),
+                };
+                rrsig.signature = key.sign(&rrsig_signed_data(&rrsig, rrset)?)?;
+
+                rrsigs.push(Record::new(
+                    first.domain().clone(),
+                    first.class(),
+                    first.ttl(),
+                    RecordData::RRSIG(rrsig),
+                ));
+            }
+        }
+
+        Ok(rrsigs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{verify_rrsig, ChainLink, ValidationState, Validator};
+    use super::*;
+    use crate::dns_packet::{A, DNSKEY_ZONE_KEY_FLAG, NS, SOA};
+    use std::net::Ipv4Addr;
+
+    const INCEPTION: u32 = 1700000000;
+    const EXPIRATION: u32 = 1702592000;
+    const NOW: u32 = 1701000000;
+    const KSK_FLAGS: u16 = DNSKEY_ZONE_KEY_FLAG | DNSKEY_SECURE_ENTRY_POINT_FLAG;
+
+    fn generate_key(algorithm: DNSSECAlgorithm, flags: u16) -> SigningKey {
+        let pkcs8 = SigningKey::generate_pkcs8(algorithm).unwrap();
+        SigningKey::from_pkcs8(algorithm, flags, &pkcs8).unwrap()
+    }
+
+    fn domain(name: &str) -> Domain {
+        name.parse().unwrap()
+    }
+
+    fn a_record(name: &str) -> Record {
+        Record::new(
+            domain(name),
+            Class::IN,
+            300,
+            RecordData::A(A {
+                addr: Ipv4Addr::new(192, 0, 2, 1),
+            }),
+        )
+    }
+
+    fn ns_record(name: &str, host: &str) -> Record {
+        Record::new(
+            domain(name),
+            Class::IN,
+            3600,
+            RecordData::NS(NS {
+                nsdname: domain(host),
+            }),
+        )
+    }
+
+    /// Zone with an apex, a wildcard, a host in an empty non-terminal, and an unsigned delegation
+    /// with glue.
+    fn example_zone() -> Vec<Record> {
+        vec![
+            Record::new(
+                domain("example.com"),
+                Class::IN,
+                3600,
+                RecordData::SOA(SOA {
+                    mname: domain("ns1.example.com"),
+                    rname: domain("hostmaster.example.com"),
+                    serial: 1,
+                    refresh: 7200,
+                    retry: 3600,
+                    expire: 1209600,
+                    minttl: 300,
+                }),
+            ),
+            ns_record("example.com", "ns1.example.com"),
+            a_record("ns1.example.com"),
+            a_record("www.example.com"),
+            a_record("*.example.com"),
+            a_record("host.dept.example.com"),
+            ns_record("sub.example.com", "ns.sub.example.com"),
+            a_record("ns.sub.example.com"),
+        ]
+    }
+
+    fn signer(denial: DenialOfExistence) -> ZoneSigner {
+        let mut signer = ZoneSigner::new(
+            domain("example.com"),
+            vec![
+                generate_key(DNSSECAlgorithm::ED25519, KSK_FLAGS),
+                generate_key(DNSSECAlgorithm::ECDSAP256SHA256, DNSKEY_ZONE_KEY_FLAG),
+            ],
+        );
+        signer.set_validity(INCEPTION, EXPIRATION);
+        signer.set_denial(denial);
+        signer
+    }
+
+    fn owned_by<'a>(records: &'a [Record], name: &str, qtype: QueryType) -> Vec<&'a Record> {
+        records
+            .iter()
+            .filter(|record| record.domain() == &domain(name))
+            .filter(|record| record.record_type() == qtype)
+            .collect()
+    }
+
+    fn rrsigs_of<'a>(records: &'a [Record], name: &str, qtype: QueryType) -> Vec<&'a Record> {
+        owned_by(records, name, QueryType::RRSIG)
+            .into_iter()
+            .filter(|record| matches!(record.data(), RecordData::RRSIG(rrsig) if rrsig.type_covered == qtype))
+            .collect()
+    }
+
+    /// Link of the chain of trust for the signed zone, anchored by its CDS record.
+    fn validator_and_link(signed: &[Record]) -> (Validator, ChainLink) {
+        let cds = owned_by(signed, "example.com", QueryType::CDS);
+        let RecordData::CDS(CDS { ds }) = cds[0].data() else {
+            unreachable!()
+        };
+        let mut validator = Validator::new(vec![Record::new(
+            domain("example.com"),
+            Class::IN,
+            3600,
+            RecordData::DS(ds.clone()),
+        )]);
+        validator.set_time(Some(NOW));
+
+        let dnskeys = signed
+            .iter()
+            .filter(|record| {
+                record.record_type() == QueryType::DNSKEY
+                    || matches!(record.data(), RecordData::RRSIG(rrsig) if rrsig.type_covered == QueryType::DNSKEY)
+            })
+            .map(|record| (*record).clone())
+            .collect();
+        let link = ChainLink {
+            zone: domain("example.com"),
+            dnskeys,
+            delegation: Vec::new(),
+        };
+
+        (validator, link)
+    }
+
+    #[test]
+    fn test_rsa_dnskey_public_key() {
+        // SEQUENCE { INTEGER 0x00C1F3, INTEGER 0x010001 }
+        let der = [
+            0x30, 0x0A, 0x02, 0x03, 0x00, 0xC1, 0xF3, 0x02, 0x03, 0x01, 0x00, 0x01,
+        ];
+
+        assert_eq!(
+            rsa_dnskey_public_key(&der),
+            Ok(vec![0x03, 0x01, 0x00, 0x01, 0xC1, 0xF3])
+        );
+        assert_eq!(
+            rsa_dnskey_public_key(&der[..8]),
+            Err(DNSError::MalformedPrivateKey)
+        );
+    }
+
+    #[test]
+    fn test_signing_key_dnskey() {
+        let ed25519 = generate_key(DNSSECAlgorithm::ED25519, KSK_FLAGS);
+        let p384 = generate_key(DNSSECAlgorithm::ECDSAP384SHA384, DNSKEY_ZONE_KEY_FLAG);
+
+        let ed25519_dnskey = ed25519.dnskey().unwrap();
+        assert_eq!(ed25519_dnskey.public_key.len(), 32);
+        assert!(ed25519_dnskey.is_secure_entry_point());
+        let p384_dnskey = p384.dnskey().unwrap();
+        assert_eq!(p384_dnskey.public_key.len(), 96);
+        assert!(!p384_dnskey.is_secure_entry_point());
+
+        assert_eq!(
+            SigningKey::generate_pkcs8(DNSSECAlgorithm::RSASHA1),
+            Err(DNSError::UnsupportedAlgorithm(5))
+        );
+        assert!(SigningKey::from_pkcs8(DNSSECAlgorithm::ED25519, KSK_FLAGS, &[0x00]).is_err());
+    }
+
+    #[test]
+    fn test_sign_zone_nsec() {
+        let signed = signer(DenialOfExistence::NSEC)
+            .sign_zone(example_zone())
+            .unwrap();
+
+        // NSEC chain in canonical order, without glue nor empty non-terminals
+        let chain: Vec<(Domain, Domain)> = signed
+            .iter()
+            .filter_map(|record| match record.data() {
+                RecordData::NSEC(nsec) => Some((record.domain().clone(), nsec.next_domain.clone())),
+                _ => None,
+            })
+            .collect();
+        let expected_chain = [
+            ("example.com", "*.example.com"),
+            ("*.example.com", "host.dept.example.com"),
+            ("host.dept.example.com", "ns1.example.com"),
+            ("ns1.example.com", "sub.example.com"),
+            ("sub.example.com", "www.example.com"),
+            ("www.example.com", "example.com"),
+        ];
+        assert_eq!(
+            chain,
+            expected_chain.map(|(name, next)| (domain(name), domain(next)))
+        );
+
+        let RecordData::NSEC(apex_nsec) =
+            owned_by(&signed, "example.com", QueryType::NSEC)[0].data()
+        else {
+            unreachable!()
+        };
+        for qtype in [
+            QueryType::SOA,
+            QueryType::NS,
+            QueryType::DNSKEY,
+            QueryType::CDS,
+        ] {
+            assert!(apex_nsec.types.contains(&qtype));
+        }
+        assert_eq!(
+            owned_by(&signed, "example.com", QueryType::NSEC)[0].ttl(),
+            300
+        );
+
+        // Delegation NS and glue belong to the child zone
+        assert!(rrsigs_of(&signed, "sub.example.com", QueryType::NS).is_empty());
+        assert!(rrsigs_of(&signed, "ns.sub.example.com", QueryType::A).is_empty());
+        assert_eq!(
+            rrsigs_of(&signed, "sub.example.com", QueryType::NSEC).len(),
+            1
+        );
+
+        // The DNSKEY RRset is signed by the key signing key only
+        assert_eq!(
+            rrsigs_of(&signed, "example.com", QueryType::DNSKEY).len(),
+            1
+        );
+
+        let (validator, link) = validator_and_link(&signed);
+        let wildcard = owned_by(&signed, "*.example.com", QueryType::A);
+        let wildcard_rrsig = rrsigs_of(&signed, "*.example.com", QueryType::A);
+        let RecordData::RRSIG(rrsig) = wildcard_rrsig[0].data() else {
+            unreachable!()
+        };
+        assert_eq!(rrsig.labels, 2);
+        assert_eq!(
+            validator.validate(
+                &[link],
+                &[Record::clone(wildcard[0]), Record::clone(wildcard_rrsig[0])]
+            ),
+            ValidationState::Secure
+        );
+
+        // The parent side of the delegation proves it is unsigned
+        let (validator, link) = validator_and_link(&signed);
+        let mut delegation = vec![Record::clone(
+            owned_by(&signed, "sub.example.com", QueryType::NSEC)[0],
+        )];
+        delegation.extend(
+            rrsigs_of(&signed, "sub.example.com", QueryType::NSEC)
+                .into_iter()
+                .cloned(),
+        );
+        let chain = [
+            link,
+            ChainLink {
+                zone: domain("sub.example.com"),
+                dnskeys: Vec::new(),
+                delegation,
+            },
+        ];
+        assert_eq!(
+            validator.validate_chain(&chain),
+            Err(ValidationState::Insecure)
+        );
+    }
+
+    #[test]
+    fn test_sign_zone_nsec3_opt_out() {
+        let signed = signer(DenialOfExistence::NSEC3 {
+            salt: vec![0xAA, 0xBB, 0xCC, 0xDD],
+            iterations: 0,
+            opt_out: true,
+        })
+        .sign_zone(example_zone())
+        .unwrap();
+
+        let nsec3_records: Vec<&Record> = signed
+            .iter()
+            .filter(|record| record.record_type() == QueryType::NSEC3)
+            .collect();
+        // Apex, wildcard, host, its empty non-terminal, ns1 and www. The unsigned delegation is
+        // opted out.
+        assert_eq!(nsec3_records.len(), 6);
+        assert_eq!(
+            owned_by(&signed, "example.com", QueryType::NSEC3PARAM).len(),
+            1
+        );
+
+        let sub_hash =
+            nsec3_hash(&domain("sub.example.com"), &[0xAA, 0xBB, 0xCC, 0xDD], 0).unwrap();
+        let sub_owner = format!("{}.example.com", base32hex_encode(&sub_hash));
+        assert!(owned_by(&signed, &sub_owner, QueryType::NSEC3).is_empty());
+
+        let ent_hash =
+            nsec3_hash(&domain("dept.example.com"), &[0xAA, 0xBB, 0xCC, 0xDD], 0).unwrap();
+        let ent_owner = format!("{}.example.com", base32hex_encode(&ent_hash));
+        let RecordData::NSEC3(ent_nsec3) =
+            owned_by(&signed, &ent_owner, QueryType::NSEC3)[0].data()
+        else {
+            unreachable!()
+        };
+        assert!(ent_nsec3.types.is_empty());
+        assert!(ent_nsec3.is_opt_out());
+
+        // Every NSEC3 record is signed by the zone signing key
+        let dnskeys = owned_by(&signed, "example.com", QueryType::DNSKEY);
+        for nsec3 in nsec3_records.iter() {
+            let rrsigs = rrsigs_of(&signed, &nsec3.domain().to_string(), QueryType::NSEC3);
+            assert_eq!(rrsigs.len(), 1);
+            assert!(dnskeys
+                .iter()
+                .any(|dnskey| verify_rrsig(&[nsec3], rrsigs[0], dnskey, NOW).is_ok()));
+        }
+
+        // The opt-out chain proves the delegation is unsigned
+        let (validator, link) = validator_and_link(&signed);
+        let mut delegation = Vec::<Record>::new();
+        for nsec3 in nsec3_records.iter() {
+            delegation.push(Record::clone(nsec3));
+            delegation.extend(
+                rrsigs_of(&signed, &nsec3.domain().to_string(), QueryType::NSEC3)
+                    .into_iter()
+                    .cloned(),
+            );
+        }
+        let chain = [
+            link,
+            ChainLink {
+                zone: domain("sub.example.com"),
+                dnskeys: Vec::new(),
+                delegation,
+            },
+        ];
+        assert_eq!(
+            validator.validate_chain(&chain),
+            Err(ValidationState::Insecure)
+        );
+    }
+
+    #[test]
+    fn test_sign_zone_nsec3_without_opt_out() {
+        let signed = signer(DenialOfExistence::NSEC3 {
+            salt: Vec::new(),
+            iterations: 0,
+            opt_out: false,
+        })
+        .sign_zone(example_zone())
+        .unwrap();
+
+        let sub_hash = nsec3_hash(&domain("sub.example.com"), &[], 0).unwrap();
+        let sub_owner = format!("{}.example.com", base32hex_encode(&sub_hash));
+        let sub_nsec3 = owned_by(&signed, &sub_owner, QueryType::NSEC3);
+        let RecordData::NSEC3(nsec3) = sub_nsec3[0].data() else {
+            unreachable!()
+        };
+        assert_eq!(nsec3.types, vec![QueryType::NS]);
+        assert!(!nsec3.is_opt_out());
+
+        let apex_hash = nsec3_hash(&domain("example.com"), &[], 0).unwrap();
+        let apex_owner = format!("{}.example.com", base32hex_encode(&apex_hash));
+        let RecordData::NSEC3(apex_nsec3) =
+            owned_by(&signed, &apex_owner, QueryType::NSEC3)[0].data()
+        else {
+            unreachable!()
+        };
+        assert!(apex_nsec3.types.contains(&QueryType::NSEC3PARAM));
+        assert!(apex_nsec3.types.contains(&QueryType::RRSIG));
+    }
+
+    #[test]
+    fn test_sign_zone_errors() {
+        let zone = vec![a_record("www.example.com")];
+        assert!(matches!(
+            signer(DenialOfExistence::NSEC).sign_zone(zone),
+            Err(DNSError::MissingSOA(_))
+        ));
+
+        let mut zone = example_zone();
+        zone.push(a_record("www.example.org"));
+        assert_eq!(
+            signer(DenialOfExistence::NSEC).sign_zone(zone).map(|_| ()),
+            Err(DNSError::OutOfZone("www.example.org.".to_string()))
+        );
+    }
+}
@@ -0,0 +1,944 @@
+use super::{
+    ds_digest, is_algorithm_supported, is_digest_type_supported, nsec3_hash, rrsig_label_count,
+    rrsig_signed_data, verify_signature, MAX_NSEC3_ITERATIONS,
+};
+use crate::dns_packet::{
+    base32hex_decode, DNSError, Domain, QueryType, Record, RecordData, DS, NSEC3,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Security status of DNS data (RFC 4033 section 5).
+#[derive(Debug, PartialEq)]
+pub enum ValidationState {
+    Secure,          // Chain of trust from a trust anchor to the data is verified
+    Insecure,        // Data is proven to be outside of a signed zone
+    Bogus(DNSError), // Data should be signed but could not be verified
+    Indeterminate,   // No trust anchor covers the data
+}
+
+/// Keys of a zone in a chain of trust, together with the records from the parent zone
+/// authenticating them.
+#[derive(Debug, PartialEq)]
+pub struct ChainLink {
+    pub zone: Domain,
+    /// DNSKEY RRset of the zone and the RRSIGs covering it.
+    pub dnskeys: Vec<Record>,
+    /// DS RRset of the zone and its RRSIGs as served by the parent zone. For unsigned
+    /// delegations, the NSEC or NSEC3 records proving the DS RRset does not exist, with their
+    /// RRSIGs. Ignored for the first link, which is authenticated by a trust anchor.
+    pub delegation: Vec<Record>,
+}
+
+/// Check whether a time is within the validity period of a signature, using serial number
+/// arithmetic (RFC 4034 section 3.1.5).
+fn check_validity_period(inception: u32, expiration: u32, now: u32) -> Result<(), DNSError> {
+    if (now.wrapping_sub(inception) as i32) < 0 {
+        return Err(DNSError::SignatureNotYetValid(inception));
+    }
+    if (expiration.wrapping_sub(now) as i32) < 0 {
+        return Err(DNSError::SignatureExpired(expiration));
+    }
+    Ok(())
+}
+
+/// Verify that an RRSIG record is a valid signature of an RRset made with a DNSKEY record, at
+/// the given time in seconds since the UNIX epoch (RFC 4035 section 5.3).
+pub fn verify_rrsig(
+    rrset: &[&Record],
+    rrsig_record: &Record,
+    dnskey_record: &Record,
+    now: u32,
+) -> Result<(), DNSError> {
+    let (RecordData::RRSIG(rrsig), RecordData::DNSKEY(dnskey)) =
+        (rrsig_record.data(), dnskey_record.data())
+    else {
+        return Err(DNSError::RRSIGMismatch);
+    };
+    let Some(first) = rrset.first() else {
+        return Err(DNSError::RRSIGMismatch);
+    };
+
+    let rrset_matches = rrset.iter().all(|record| {
+        record.domain() == rrsig_record.domain()
+            && record.class() == rrsig_record.class()
+            && record.record_type() == rrsig.type_covered
+    });
+    let key_matches = &rrsig.signer_name == dnskey_record.domain()
+        && first.domain().is_subdomain_of(&rrsig.signer_name)
+        && rrsig.key_tag == dnskey.key_tag()
+        && rrsig.algorithm == dnskey.algorithm
+        && dnskey.is_zone_key()
+        && dnskey.protocol == 3;
+    if !rrset_matches || !key_matches || rrsig.labels > rrsig_label_count(first.domain()) {
+        return Err(DNSError::RRSIGMismatch);
+    }
+
+    check_validity_period(rrsig.signature_inception, rrsig.signature_expiration, now)?;
+
+    let signed_data = rrsig_signed_data(rrsig, rrset)?;
+    verify_signature(
+        rrsig.algorithm,
+        &dnskey.public_key,
+        &signed_data,
+        &rrsig.signature,
+    )
+}
+
+/// Verify that a DS record authenticates a DNSKEY record (RFC 4035 section 5.2).
+pub fn verify_ds(ds: &DS, dnskey_record: &Record) -> Result<(), DNSError> {
+    let RecordData::DNSKEY(dnskey) = dnskey_record.data() else {
+        return Err(DNSError::DigestMismatch);
+    };
+    if ds.key_tag != dnskey.key_tag() || ds.algorithm != dnskey.algorithm || !dnskey.is_zone_key() {
+        return Err(DNSError::DigestMismatch);
+    }
+
+    if ds_digest(dnskey_record.domain(), dnskey, ds.digest_type)? != ds.digest {
+        return Err(DNSError::DigestMismatch);
+    }
+    Ok(())
+}
+
+/// Records of an RRset with the given owner and type.
+fn find_rrset<'a>(records: &'a [Record], owner: &Domain, qtype: &QueryType) -> Vec<&'a Record> {
+    records
+        .iter()
+        .filter(|record| record.domain() == owner && &record.record_type() == qtype)
+        .collect()
+}
+
+/// RRSIG records covering the RRset with the given owner and type.
+fn find_rrsigs<'a>(records: &'a [Record], owner: &Domain, qtype: &QueryType) -> Vec<&'a Record> {
+    records
+        .iter()
+        .filter(|record| {
+            record.domain() == owner
+                && matches!(record.data(), RecordData::RRSIG(rrsig) if &rrsig.type_covered == qtype)
+        })
+        .collect()
+}
+
+/// Verify an RRset against any of its RRSIGs made by any of the given keys.
+fn verify_with_keys(
+    rrset: &[&Record],
+    rrsigs: &[&Record],
+    keys: &[&Record],
+    now: u32,
+) -> Result<(), DNSError> {
+    let mut last_err = DNSError::NoValidSignature;
+    for rrsig in rrsigs {
+        for key in keys {
+            match verify_rrsig(rrset, rrsig, key, now) {
+                Ok(()) => return Ok(()),
+                // Keep the most informative error, key mismatches are expected
+                Err(DNSError::RRSIGMismatch) => (),
+                Err(err) => last_err = err,
+            }
+        }
+    }
+    Err(last_err)
+}
+
+/// Check whether an NSEC3 hash is covered by an NSEC3 record, i.e. it is strictly between its
+/// owner hash and its next hashed owner, taking into account the end of the chain.
+fn nsec3_covers(owner_hash: &[u8], nsec3: &NSEC3, hash: &[u8]) -> bool {
+    let next_hash = nsec3.next_hashed_owner.as_slice();
+    if owner_hash < next_hash {
+        owner_hash < hash && hash < next_hash
+    } else {
+        hash > owner_hash || hash < next_hash
+    }
+}
+
+/// Validates DNS data against a chain of trust starting at a set of trust anchors.
+pub struct Validator {
+    trust_anchors: Vec<Record>,
+    now: Option<u32>,
+}
+
+impl Validator {
+    /// Create a validator trusting the given DS or DNSKEY records.
+    pub fn new(trust_anchors: Vec<Record>) -> Self {
+        Validator {
+            trust_anchors,
+            now: None,
+        }
+    }
+
+    /// Set the time used to check signature validity periods, in seconds since the UNIX epoch.
+    /// None uses the system clock.
+    pub fn set_time(&mut self, now: Option<u32>) {
+        self.now = now;
+    }
+
+    fn now(&self) -> u32 {
+        self.now.unwrap_or_else(|| {
+            // Signature times wrap around every 2^32 seconds
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs() as u32)
+        })
+    }
+
+    /// Authenticate the DNSKEY RRset of a zone using DS or DNSKEY records as entry points. Return
+    /// the zone keys of the RRset if it is secure.
+    fn validate_dnskeys<'a>(
+        &self,
+        link: &'a ChainLink,
+        entry_points: &[&Record],
+    ) -> Result<Vec<&'a Record>, ValidationState> {
+        let usable_entry_points: Vec<&&Record> = entry_points
+            .iter()
+            .filter(|entry_point| match entry_point.data() {
+                RecordData::DS(ds) => {
+                    is_algorithm_supported(ds.algorithm) && is_digest_type_supported(ds.digest_type)
+                }
+                RecordData::DNSKEY(dnskey) => is_algorithm_supported(dnskey.algorithm),
+                _ => false,
+            })
+            .collect();
+        // Zones only signed with unsupported algorithms are treated as unsigned
+        if usable_entry_points.is_empty() {
+            return Err(ValidationState::Insecure);
+        }
+
+        let dnskeys = find_rrset(&link.dnskeys, &link.zone, &QueryType::DNSKEY);
+        let rrsigs = find_rrsigs(&link.dnskeys, &link.zone, &QueryType::DNSKEY);
+
+        let trusted_keys: Vec<&Record> = dnskeys
+            .iter()
+            .filter(|dnskey| {
+                usable_entry_points.iter().any(|entry_point| {
+                    match (entry_point.data(), dnskey.data()) {
+                        (RecordData::DS(ds), _) => verify_ds(ds, dnskey).is_ok(),
+                        (RecordData::DNSKEY(anchor), RecordData::DNSKEY(key)) => anchor == key,
+                        _ => false,
+                    }
+                })
+            })
+            .copied()
+            .collect();
+        if trusted_keys.is_empty() {
+            return Err(ValidationState::Bogus(DNSError::DigestMismatch));
+        }
+
+        verify_with_keys(&dnskeys, &rrsigs, &trusted_keys, self.now())
+            .map_err(ValidationState::Bogus)?;
+
+        Ok(dnskeys
+            .into_iter()
+            .filter(|dnskey| matches!(dnskey.data(), RecordData::DNSKEY(key) if key.is_zone_key()))
+            .collect())
+    }
+
+    /// Check that the parent zone proves, with authenticated NSEC or NSEC3 records, that a
+    /// delegation has no DS RRset (RFC 4035 section 5.2, RFC 5155 section 8.6).
+    fn verify_insecure_delegation(
+        &self,
+        link: &ChainLink,
+        parent: &Domain,
+        parent_keys: &[&Record],
+    ) -> Result<(), DNSError> {
+        let now = self.now();
+        let authenticated = |record: &Record| {
+            let rrset = find_rrset(&link.delegation, record.domain(), &record.record_type());
+            let rrsigs = find_rrsigs(&link.delegation, record.domain(), &record.record_type());
+            verify_with_keys(&rrset, &rrsigs, parent_keys, now).is_ok()
+        };
+        // Parent side of the delegation: NS present, but neither DS nor SOA
+        let unsigned_delegation = |types: &[QueryType]| {
+            types.contains(&QueryType::NS)
+                && !types.contains(&QueryType::DS)
+                && !types.contains(&QueryType::SOA)
+        };
+
+        let nsec_proof = link.delegation.iter().any(|record| {
+            matches!(record.data(), RecordData::NSEC(nsec) if unsigned_delegation(&nsec.types))
+                && record.domain() == &link.zone
+                && authenticated(record)
+        });
+        if nsec_proof {
+            return Ok(());
+        }
+
+        let nsec3_records: Vec<(Vec<u8>, &NSEC3)> = link
+            .delegation
+            .iter()
+            .filter(|record| authenticated(record))
+            .filter_map(|record| match record.data() {
+                RecordData::NSEC3(nsec3) => {
+                    let owner_label = record.domain().labels().next()?;
+                    let owner_hash =
+                        base32hex_decode(std::str::from_utf8(owner_label).ok()?).ok()?;
+                    Some((owner_hash, nsec3))
+                }
+                _ => None,
+            })
+            .collect();
+        // Too many iterations to hash: the delegation is treated as insecure (RFC 9276 section
+        // 3.2)
+        if nsec3_records
+            .iter()
+            .any(|(_, nsec3)| nsec3.iterations > MAX_NSEC3_ITERATIONS)
+        {
+            return Ok(());
+        }
+        let hash_of =
+            |name: &Domain, nsec3: &NSEC3| nsec3_hash(name, &nsec3.salt, nsec3.iterations);
+
+        // NSEC3 matching the delegation name
+        for (owner_hash, nsec3) in nsec3_records.iter() {
+            if hash_of(&link.zone, nsec3)? == *owner_hash && unsigned_delegation(&nsec3.types) {
+                return Ok(());
+            }
+        }
+
+        // Opt-out: closest provable encloser matched, next closer name covered by an opt-out
+        // NSEC3
+        let mut next_closer = link.zone.clone();
+        while let Some(encloser) = next_closer
+            .parent()
+            .filter(|encloser| encloser.label_count() >= parent.label_count())
+        {
+            let mut encloser_matched = false;
+            for (owner_hash, nsec3) in nsec3_records.iter() {
+                encloser_matched |= hash_of(&encloser, nsec3)? == *owner_hash;
+            }
+            if !encloser_matched {
+                next_closer = encloser;
+                continue;
+            }
+
+            for (owner_hash, nsec3) in nsec3_records.iter() {
+                let next_closer_hash = hash_of(&next_closer, nsec3)?;
+                if nsec3.is_opt_out() && nsec3_covers(owner_hash, nsec3, &next_closer_hash) {
+                    return Ok(());
+                }
+            }
+            break;
+        }
+
+        Err(DNSError::MissingDenialProof)
+    }
+
+    /// Walk a chain of trust from a trust anchor down to the last link. Return the authenticated
+    /// zone keys of the last link if the chain is secure, else the validation state reached.
+    pub fn validate_chain<'a>(
+        &self,
+        chain: &'a [ChainLink],
+    ) -> Result<Vec<&'a Record>, ValidationState> {
+        let Some(first_link) = chain.first() else {
+            return Err(ValidationState::Indeterminate);
+        };
+
+        let anchors: Vec<&Record> = self
+            .trust_anchors
+            .iter()
+            .filter(|anchor| anchor.domain() == &first_link.zone)
+            .collect();
+        if anchors.is_empty() {
+            return Err(ValidationState::Indeterminate);
+        }
+
+        let mut keys = self.validate_dnskeys(first_link, &anchors)?;
+
+        for (parent_link, link) in chain.iter().zip(chain.iter().skip(1)) {
+            if !link.zone.is_subdomain_of(&parent_link.zone) || link.zone == parent_link.zone {
+                return Err(ValidationState::Bogus(DNSError::RRSIGMismatch));
+            }
+
+            let ds_rrset = find_rrset(&link.delegation, &link.zone, &QueryType::DS);
+            if ds_rrset.is_empty() {
+                return match self.verify_insecure_delegation(link, &parent_link.zone, &keys) {
+                    Ok(()) => Err(ValidationState::Insecure),
+                    Err(err) => Err(ValidationState::Bogus(err)),
+                };
+            }
+
+            let ds_rrsigs = find_rrsigs(&link.delegation, &link.zone, &QueryType::DS);
+            verify_with_keys(&ds_rrset, &ds_rrsigs, &keys, self.now())
+                .map_err(ValidationState::Bogus)?;
+
+            keys = self.validate_dnskeys(link, &ds_rrset)?;
+        }
+
+        Ok(keys)
+    }
+
+    /// Validate the RRsets in a set of records, along with their RRSIGs, using the chain of trust
+    /// leading to the zone they belong to.
+    pub fn validate(&self, chain: &[ChainLink], records: &[Record]) -> ValidationState {
+        let keys = match self.validate_chain(chain) {
+            Ok(keys) => keys,
+            Err(state) => return state,
+        };
+
+        let mut validated_any = false;
+        for (i, record) in records.iter().enumerate() {
+            let qtype = record.record_type();
+            // Handle each RRset once, at its first record
+            let seen = records[..i]
+                .iter()
+                .any(|prev| prev.domain() == record.domain() && prev.record_type() == qtype);
+            if qtype == QueryType::RRSIG || seen {
+                continue;
+            }
+
+            let rrset = find_rrset(records, record.domain(), &qtype);
+            let rrsigs = find_rrsigs(records, record.domain(), &qtype);
+            if let Err(err) = verify_with_keys(&rrset, &rrsigs, &keys, self.now()) {
+                return ValidationState::Bogus(err);
+            }
+            validated_any = true;
+        }
+
+        if validated_any {
+            ValidationState::Secure
+        } else {
+            ValidationState::Indeterminate
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::{
+        Class, DNSSECAlgorithm, DigestType, RecordData, A, DNSKEY, MX, NSEC, RRSIG,
+    };
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use std::net::Ipv4Addr;
+
+    const INCEPTION: u32 = 1438207200;
+    const EXPIRATION: u32 = 1440021600;
+    const NOW: u32 = 1439000000;
+
+    fn rfc8080_dnskey() -> Record {
+        Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::DNSKEY(DNSKEY::new(
+                257,
+                DNSSECAlgorithm::ED25519,
+                vec![
+                    0x97, 0x4D, 0x96, 0xA2, 0x2D, 0x22, 0x4B, 0xC0, 0x1A, 0xDB, 0x91, 0x50, 0x91,
+                    0x47, 0x7D, 0x44, 0xCC, 0xD9, 0x1C, 0x9A, 0x41, 0xA1, 0x14, 0x30, 0x01, 0x01,
+                    0x17, 0xD5, 0x2C, 0x59, 0x24, 0x0E,
+                ],
+            )),
+        )
+    }
+
+    fn rfc8080_mx() -> Record {
+        Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::MX(MX {
+                preference: 10,
+                exchange: "mail.example.com".parse().unwrap(),
+            }),
+        )
+    }
+
+    fn rfc8080_rrsig() -> Record {
+        Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::RRSIG(RRSIG {
+                type_covered: QueryType::MX,
+                algorithm: DNSSECAlgorithm::ED25519,
+                labels: 2,
+                original_ttl: 3600,
+                signature_expiration: EXPIRATION,
+                signature_inception: INCEPTION,
+                key_tag: 3613,
+                signer_name: "example.com".parse().unwrap(),
+                signature: vec![
+                    0xA0, 0xBF, 0x64, 0xAC, 0x9B, 0xA7, 0xEF, 0x17, 0xC1, 0x38, 0x85, 0x9C, 0x18,
+                    0x78, 0xBB, 0x99, 0xA8, 0x39, 0xFE, 0x17, 0x59, 0xAC, 0xA5, 0xB0, 0xD7, 0x98,
+                    0xCF, 0x1A, 0xB1, 0xE9, 0x8D, 0x07, 0x91, 0x02, 0xF4, 0xDD, 0xB3, 0x36, 0x8F,
+                    0x0F, 0xE4, 0x0B, 0xB3, 0x77, 0xF1, 0xF0, 0x0E, 0x0C, 0xDD, 0xED, 0xB7, 0x99,
+                    0x16, 0x7D, 0x56, 0xB6, 0xE9, 0x32, 0x78, 0x30, 0x72, 0xBA, 0x8D, 0x02,
+                ],
+            }),
+        )
+    }
+
+    /// Zone key generated for the test, able to sign RRsets.
+    struct TestKey {
+        zone: Domain,
+        key_pair: Ed25519KeyPair,
+    }
+
+    impl TestKey {
+        fn generate(zone: &str) -> Self {
+            let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+            TestKey {
+                zone: zone.parse().unwrap(),
+                key_pair: Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap(),
+            }
+        }
+
+        fn dnskey(&self) -> DNSKEY {
+            DNSKEY::new(
+                257,
+                DNSSECAlgorithm::ED25519,
+                self.key_pair.public_key().as_ref().to_vec(),
+            )
+        }
+
+        fn dnskey_record(&self) -> Record {
+            Record::new(
+                self.zone.clone(),
+                Class::IN,
+                3600,
+                RecordData::DNSKEY(self.dnskey()),
+            )
+        }
+
+        fn ds(&self) -> DS {
+            let dnskey = self.dnskey();
+            DS {
+                key_tag: dnskey.key_tag(),
+                algorithm: dnskey.algorithm,
+                digest_type: DigestType::SHA256,
+                digest: ds_digest(&self.zone, &dnskey, DigestType::SHA256).unwrap(),
+            }
+        }
+
+        fn sign(&self, rrset: &[&Record]) -> Record {
+            let mut rrsig = RRSIG {
+                type_covered: rrset[0].record_type(),
+                algorithm: DNSSECAlgorithm::ED25519,
+                labels: rrsig_label_count(rrset[0].domain()),
+                original_ttl: rrset[0].ttl(),
+                signature_expiration: EXPIRATION,
+                signature_inception: INCEPTION,
+                key_tag: self.dnskey().key_tag(),
+                signer_name: self.zone.clone(),
+                signature: Vec::new(),
+            };
+            let signed_data = rrsig_signed_data(&rrsig, rrset).unwrap();
+            rrsig.signature = self.key_pair.sign(&signed_data).as_ref().to_vec();
+
+            Record::new(
+                rrset[0].domain().clone(),
+                Class::IN,
+                rrset[0].ttl(),
+                RecordData::RRSIG(rrsig),
+            )
+        }
+
+        /// DNSKEY RRset of the key's zone, self-signed.
+        fn signed_dnskeys(&self) -> Vec<Record> {
+            let dnskey = self.dnskey_record();
+            let rrsig = self.sign(&[&dnskey]);
+            vec![dnskey, rrsig]
+        }
+    }
+
+    fn a_record(name: &str) -> Record {
+        Record::new(
+            name.parse().unwrap(),
+            Class::IN,
+            300,
+            RecordData::A(A {
+                addr: Ipv4Addr::new(192, 0, 2, 1),
+            }),
+        )
+    }
+
+    fn validator_for(anchor: Record) -> Validator {
+        let mut validator = Validator::new(vec![anchor]);
+        validator.set_time(Some(NOW));
+        validator
+    }
+
+    #[test]
+    fn test_verify_rrsig_rfc8080() {
+        let mx = rfc8080_mx();
+
+        assert_eq!(
+            verify_rrsig(&[&mx], &rfc8080_rrsig(), &rfc8080_dnskey(), NOW),
+            Ok(())
+        );
+        assert_eq!(
+            verify_rrsig(&[&mx], &rfc8080_rrsig(), &rfc8080_dnskey(), EXPIRATION + 1),
+            Err(DNSError::SignatureExpired(EXPIRATION))
+        );
+        assert_eq!(
+            verify_rrsig(&[&mx], &rfc8080_rrsig(), &rfc8080_dnskey(), INCEPTION - 1),
+            Err(DNSError::SignatureNotYetValid(INCEPTION))
+        );
+
+        let tampered_mx = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::MX(MX {
+                preference: 20,
+                exchange: "mail.example.com".parse().unwrap(),
+            }),
+        );
+        assert_eq!(
+            verify_rrsig(&[&tampered_mx], &rfc8080_rrsig(), &rfc8080_dnskey(), NOW),
+            Err(DNSError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_verify_ds_rfc8080() {
+        let ds = DS {
+            key_tag: 3613,
+            algorithm: DNSSECAlgorithm::ED25519,
+            digest_type: DigestType::SHA256,
+            digest: ds_digest(
+                &"example.com".parse().unwrap(),
+                match rfc8080_dnskey().data() {
+                    RecordData::DNSKEY(dnskey) => dnskey,
+                    _ => unreachable!(),
+                },
+                DigestType::SHA256,
+            )
+            .unwrap(),
+        };
+        assert_eq!(verify_ds(&ds, &rfc8080_dnskey()), Ok(()));
+
+        let wrong_ds = DS {
+            digest: vec![0x00; 32],
+            ..ds
+        };
+        assert_eq!(
+            verify_ds(&wrong_ds, &rfc8080_dnskey()),
+            Err(DNSError::DigestMismatch)
+        );
+    }
+
+    #[test]
+    fn test_validate_rfc8080_with_ds_anchor() {
+        let anchor = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            3600,
+            RecordData::DS(DS {
+                key_tag: 3613,
+                algorithm: DNSSECAlgorithm::ED25519,
+                digest_type: DigestType::SHA256,
+                digest: vec![
+                    0x3A, 0xA5, 0xAB, 0x37, 0xEF, 0xCE, 0x57, 0xF7, 0x37, 0xFC, 0x16, 0x27, 0x01,
+                    0x3F, 0xEE, 0x07, 0xBD, 0xF2, 0x41, 0xBD, 0x10, 0xF3, 0xB1, 0x96, 0x4A, 0xB5,
+                    0x5C, 0x78, 0xE7, 0x9A, 0x30, 0x4B,
+                ],
+            }),
+        );
+        let RecordData::DS(anchor_ds) = anchor.data() else {
+            unreachable!()
+        };
+        assert_eq!(verify_ds(anchor_ds, &rfc8080_dnskey()), Ok(()));
+
+        let validator = validator_for(anchor);
+        let chain = [ChainLink {
+            zone: "example.com".parse().unwrap(),
+            dnskeys: vec![rfc8080_dnskey()],
+            delegation: Vec::new(),
+        }];
+
+        // The example does not include an RRSIG over the DNSKEY RRset, which must be signed
+        assert_eq!(
+            validator.validate(&chain, &[rfc8080_mx(), rfc8080_rrsig()]),
+            ValidationState::Bogus(DNSError::NoValidSignature)
+        );
+    }
+
+    #[test]
+    fn test_validate_chain_secure() {
+        let com_key = TestKey::generate("com");
+        let example_key = TestKey::generate("example.com");
+
+        let ds = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            86400,
+            RecordData::DS(example_key.ds()),
+        );
+        let ds_rrsig = com_key.sign(&[&ds]);
+
+        let chain = [
+            ChainLink {
+                zone: "com".parse().unwrap(),
+                dnskeys: com_key.signed_dnskeys(),
+                delegation: Vec::new(),
+            },
+            ChainLink {
+                zone: "example.com".parse().unwrap(),
+                dnskeys: example_key.signed_dnskeys(),
+                delegation: vec![ds, ds_rrsig],
+            },
+        ];
+
+        let answer = a_record("www.example.com");
+        let answer_rrsig = example_key.sign(&[&answer]);
+
+        let validator = validator_for(Record::new(
+            "com".parse().unwrap(),
+            Class::IN,
+            86400,
+            RecordData::DS(com_key.ds()),
+        ));
+
+        assert_eq!(
+            validator.validate(&chain, &[answer, answer_rrsig]),
+            ValidationState::Secure
+        );
+
+        // Signed by the parent instead of the zone itself
+        let answer = a_record("www.example.com");
+        let wrong_rrsig = com_key.sign(&[&answer]);
+        assert_eq!(
+            validator.validate(&chain, &[answer, wrong_rrsig]),
+            ValidationState::Bogus(DNSError::NoValidSignature)
+        );
+    }
+
+    #[test]
+    fn test_verify_rrsig_mixed_case_nsec() {
+        // The next domain name of NSEC keeps its case in the signed data (RFC 6840 section 5.1)
+        let key = TestKey::generate("example.com");
+        let nsec = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            300,
+            RecordData::NSEC(NSEC {
+                next_domain: "Www.Example.com".parse().unwrap(),
+                types: vec![QueryType::NSEC, QueryType::RRSIG],
+            }),
+        );
+        let rrsig = key.sign(&[&nsec]);
+        let RecordData::RRSIG(rrsig_data) = rrsig.data() else {
+            unreachable!()
+        };
+        let signed_data = rrsig_signed_data(rrsig_data, &[&nsec]).unwrap();
+        assert!(signed_data.windows(4).any(|window| window == b"\x03Www"));
+
+        assert_eq!(
+            verify_rrsig(&[&nsec], &rrsig, &key.dnskey_record(), NOW),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_chain_bogus_ds() {
+        let com_key = TestKey::generate("com");
+        let example_key = TestKey::generate("example.com");
+        let impostor_key = TestKey::generate("example.com");
+
+        let ds = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            86400,
+            RecordData::DS(example_key.ds()),
+        );
+        let ds_rrsig = com_key.sign(&[&ds]);
+
+        let chain = [
+            ChainLink {
+                zone: "com".parse().unwrap(),
+                dnskeys: com_key.signed_dnskeys(),
+                delegation: Vec::new(),
+            },
+            ChainLink {
+                zone: "example.com".parse().unwrap(),
+                dnskeys: impostor_key.signed_dnskeys(),
+                delegation: vec![ds, ds_rrsig],
+            },
+        ];
+
+        let validator = validator_for(com_key.dnskey_record());
+
+        assert_eq!(
+            validator.validate_chain(&chain),
+            Err(ValidationState::Bogus(DNSError::DigestMismatch))
+        );
+    }
+
+    #[test]
+    fn test_validate_chain_insecure_delegation() {
+        let com_key = TestKey::generate("com");
+
+        let nsec = Record::new(
+            "example.com".parse().unwrap(),
+            Class::IN,
+            86400,
+            RecordData::NSEC(NSEC {
+                next_domain: "example2.com".parse().unwrap(),
+                types: vec![QueryType::NS, QueryType::RRSIG, QueryType::NSEC],
+            }),
+        );
+        let nsec_rrsig = com_key.sign(&[&nsec]);
+
+        let mut chain = vec![
+            ChainLink {
+                zone: "com".parse().unwrap(),
+                dnskeys: com_key.signed_dnskeys(),
+                delegation: Vec::new(),
+            },
+            ChainLink {
+                zone: "example.com".parse().unwrap(),
+                dnskeys: Vec::new(),
+                delegation: vec![nsec],
+            },
+        ];
+
+        let validator = validator_for(com_key.dnskey_record());
+
+        // Denial without signature is not enough
+        assert_eq!(
+            validator.validate(&chain, &[a_record("www.example.com")]),
+            ValidationState::Bogus(DNSError::MissingDenialProof)
+        );
+
+        chain[1].delegation.push(nsec_rrsig);
+        assert_eq!(
+            validator.validate(&chain, &[a_record("www.example.com")]),
+            ValidationState::Insecure
+        );
+    }
+
+    #[test]
+    fn test_validate_chain_nsec3_opt_out() {
+        let com_key = TestKey::generate("com");
+        let salt = vec![0xAB];
+
+        let hash = |name: &str| nsec3_hash(&name.parse().unwrap(), &salt, 1).unwrap();
+        let hashed_owner = |hash: &[u8]| -> Domain {
+            format!("{}.com", crate::dns_packet::base32hex_encode(hash))
+                .parse()
+                .unwrap()
+        };
+
+        // NSEC3 matching the closest encloser com, and an opt-out NSEC3 covering example.com
+        let com_hash = hash("com");
+        let example_hash = hash("example.com");
+        let mut before = example_hash.clone();
+        *before.last_mut().unwrap() -= 1;
+        let mut after = example_hash.clone();
+        *after.last_mut().unwrap() += 1;
+
+        let nsec3_records = [
+            Record::new(
+                hashed_owner(&com_hash),
+                Class::IN,
+                86400,
+                RecordData::NSEC3(NSEC3 {
+                    hash_algorithm: crate::dns_packet::NSEC3HashAlgorithm::SHA1,
+                    flags: 0,
+                    iterations: 1,
+                    salt: salt.clone(),
+                    next_hashed_owner: com_hash.clone(),
+                    types: vec![QueryType::SOA, QueryType::NS],
+                }),
+            ),
+            Record::new(
+                hashed_owner(&before),
+                Class::IN,
+                86400,
+                RecordData::NSEC3(NSEC3 {
+                    hash_algorithm: crate::dns_packet::NSEC3HashAlgorithm::SHA1,
+                    flags: crate::dns_packet::NSEC3_OPT_OUT_FLAG,
+                    iterations: 1,
+                    salt: salt.clone(),
+                    next_hashed_owner: after,
+                    types: vec![QueryType::NS],
+                }),
+            ),
+        ];
+        let mut delegation = Vec::<Record>::new();
+        for nsec3 in nsec3_records {
+            let rrsig = com_key.sign(&[&nsec3]);
+            delegation.push(nsec3);
+            delegation.push(rrsig);
+        }
+
+        let chain = [
+            ChainLink {
+                zone: "com".parse().unwrap(),
+                dnskeys: com_key.signed_dnskeys(),
+                delegation: Vec::new(),
+            },
+            ChainLink {
+                zone: "example.com".parse().unwrap(),
+                dnskeys: Vec::new(),
+                delegation,
+            },
+        ];
+
+        let validator = validator_for(com_key.dnskey_record());
+
+        assert_eq!(
+            validator.validate_chain(&chain),
+            Err(ValidationState::Insecure)
+        );
+    }
+
+    #[test]
+    fn test_validate_chain_nsec3_too_many_iterations() {
+        let com_key = TestKey::generate("com");
+
+        // Not hashed: the owner and next hashed owner don't need to match anything
+        let nsec3 = Record::new(
+            "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.com".parse().unwrap(),
+            Class::IN,
+            86400,
+            RecordData::NSEC3(NSEC3 {
+                hash_algorithm: crate::dns_packet::NSEC3HashAlgorithm::SHA1,
+                flags: 0,
+                iterations: MAX_NSEC3_ITERATIONS + 1,
+                salt: Vec::new(),
+                next_hashed_owner: vec![0xFF; 20],
+                types: vec![QueryType::NS],
+            }),
+        );
+        let nsec3_rrsig = com_key.sign(&[&nsec3]);
+
+        let chain = [
+            ChainLink {
+                zone: "com".parse().unwrap(),
+                dnskeys: com_key.signed_dnskeys(),
+                delegation: Vec::new(),
+            },
+            ChainLink {
+                zone: "example.com".parse().unwrap(),
+                dnskeys: Vec::new(),
+                delegation: vec![nsec3, nsec3_rrsig],
+            },
+        ];
+
+        let validator = validator_for(com_key.dnskey_record());
+
+        assert_eq!(
+            validator.validate_chain(&chain),
+            Err(ValidationState::Insecure)
+        );
+    }
+
+    #[test]
+    fn test_validate_no_trust_anchor() {
+        let com_key = TestKey::generate("com");
+        let chain = [ChainLink {
+            zone: "com".parse().unwrap(),
+            dnskeys: com_key.signed_dnskeys(),
+            delegation: Vec::new(),
+        }];
+
+        let validator = validator_for(TestKey::generate("org").dnskey_record());
+
+        assert_eq!(
+            validator.validate(&chain, &[a_record("com")]),
+            ValidationState::Indeterminate
+        );
+    }
+}
@@ -1,3 +1,8 @@
 #![allow(dead_code)]
 
 pub mod dns_packet;
+pub mod dnssec;
+pub mod resolver;
+pub mod server;
+pub mod transport;
+pub mod zone;
@@ -0,0 +1,13 @@
+#[cfg(feature = "tokio")]
+mod resolver_async;
+mod resolver_caa;
+mod resolver_cache;
+mod resolver_recursive;
+mod resolver_stub;
+
+#[cfg(feature = "tokio")]
+pub use resolver_async::*;
+pub use resolver_caa::*;
+pub use resolver_cache::*;
+pub use resolver_recursive::*;
+pub use resolver_stub::*;
@@ -0,0 +1,759 @@
+use super::resolver_stub::{
+    is_final_response, is_response_to, query_packet, random_id, DEFAULT_ATTEMPTS, DEFAULT_TIMEOUT,
+};
+use crate::dns_packet::{
+    Class, DNSError, DNSPacket, DNSPacketBuffer, Domain, Edns, QueryType, Question, Record,
+    ResponseCode, MAX_PACKET_SIZE,
+};
+use crate::transport::DNSCodec;
+use futures_util::{SinkExt, StreamExt};
+use ring::rand::SystemRandom;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+/// Queries in flight on a connection, by ID.
+#[derive(Default)]
+struct PendingQueries {
+    queries: HashMap<u16, (Question, oneshot::Sender<DNSPacket>)>,
+    closed: bool,
+}
+
+/// Matches the responses received on a connection with the queries in flight, so several queries
+/// can share a single socket.
+#[derive(Clone)]
+struct Multiplexer {
+    pending: Arc<Mutex<PendingQueries>>,
+    rng: SystemRandom,
+}
+
+impl Multiplexer {
+    fn new() -> Self {
+        Multiplexer {
+            pending: Arc::default(),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PendingQueries> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Register a query for the question, under a random ID not used by another query in flight.
+    fn register(&self, question: &Question) -> Result<PendingQuery, DNSError> {
+        let mut pending = self.lock();
+        if pending.closed {
+            return Err(DNSError::Io("connection closed by nameserver".to_string()));
+        }
+        // Leave enough free IDs for a random one to be found quickly
+        if pending.queries.len() > u16::MAX as usize / 2 {
+            return Err(DNSError::Io("too many queries in flight".to_string()));
+        }
+
+        loop {
+            let id = random_id(&self.rng)?;
+            if let Entry::Vacant(entry) = pending.queries.entry(id) {
+                let (sender, receiver) = oneshot::channel();
+                entry.insert((question.clone(), sender));
+                return Ok(PendingQuery {
+                    id,
+                    receiver,
+                    pending: self.pending.clone(),
+                });
+            }
+        }
+    }
+
+    /// Hand a response to the query in flight it answers. Responses which don't match the ID and
+    /// question of a query are discarded.
+    fn dispatch(&self, response: DNSPacket) {
+        let mut pending = self.lock();
+        let id = response.header().id;
+        let matches = pending
+            .queries
+            .get(&id)
+            .is_some_and(|(question, _)| is_response_to(&response, id, question));
+        if let Some((_, sender)) = matches.then(|| pending.queries.remove(&id)).flatten() {
+            // The query may have been abandoned in the meantime
+            let _ = sender.send(response);
+        }
+    }
+
+    /// Fail the queries in flight and any later query, once the connection is closed.
+    fn close(&self) {
+        let mut pending = self.lock();
+        pending.closed = true;
+        pending.queries.clear();
+    }
+}
+
+/// Query registered with a multiplexer, unregistered when dropped.
+struct PendingQuery {
+    id: u16,
+    receiver: oneshot::Receiver<DNSPacket>,
+    pending: Arc<Mutex<PendingQueries>>,
+}
+
+impl PendingQuery {
+    async fn response(&mut self) -> Result<DNSPacket, DNSError> {
+        (&mut self.receiver)
+            .await
+            .map_err(|_| DNSError::Io("connection closed by nameserver".to_string()))
+    }
+}
+
+impl Drop for PendingQuery {
+    fn drop(&mut self) {
+        self.receiver.close();
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+        // The ID may already have been reused by another query, whose receiver is still open
+        if let Entry::Occupied(entry) = pending.queries.entry(self.id) {
+            if entry.get().1.is_closed() {
+                entry.remove();
+            }
+        }
+    }
+}
+
+fn question(name: &Domain, record_type: QueryType) -> Question {
+    Question {
+        domain: name.clone(),
+        record_type,
+        class: Class::IN,
+    }
+}
+
+/// Async client sending queries to a nameserver over UDP. Concurrent queries share one socket, and
+/// responses are matched with their query by ID and question.
+pub struct AsyncUdpClient {
+    nameserver: SocketAddr,
+    socket: Arc<UdpSocket>,
+    multiplexer: Multiplexer,
+    receiver: JoinHandle<()>,
+    timeout: Duration,
+    edns: Option<Edns>,
+}
+
+impl AsyncUdpClient {
+    /// Bind a socket to send queries to the nameserver, with EDNS enabled. The socket is connected
+    /// to the nameserver, so datagrams from other sources are discarded. Must be called from
+    /// within a tokio runtime.
+    pub async fn connect(nameserver: SocketAddr) -> Result<Self, DNSError> {
+        let local_addr: SocketAddr = match nameserver {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = UdpSocket::bind(local_addr).await?;
+        socket.connect(nameserver).await?;
+
+        let socket = Arc::new(socket);
+        let multiplexer = Multiplexer::new();
+        let receiver = tokio::spawn(receive_udp(socket.clone(), multiplexer.clone()));
+        Ok(AsyncUdpClient {
+            nameserver,
+            socket,
+            multiplexer,
+            receiver,
+            timeout: DEFAULT_TIMEOUT,
+            edns: Some(Edns::default()),
+        })
+    }
+
+    pub fn nameserver(&self) -> SocketAddr {
+        self.nameserver
+    }
+
+    /// Set how long to wait for a response.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Set the EDNS information sent with queries, or None to send plain DNS queries.
+    pub fn set_edns(&mut self, edns: Option<Edns>) {
+        self.edns = edns;
+    }
+
+    /// Send a query and wait for its response. Truncated responses are returned as is.
+    pub async fn query(
+        &self,
+        name: &Domain,
+        record_type: QueryType,
+    ) -> Result<DNSPacket, DNSError> {
+        let question = question(name, record_type);
+        let mut pending = self.multiplexer.register(&question)?;
+        let query =
+            query_packet(pending.id, &question, true, self.edns.clone()).write_dns_packet()?;
+
+        let exchange = async {
+            self.socket.send(query.get_data()).await?;
+            pending.response().await
+        };
+        tokio::time::timeout(self.timeout, exchange)
+            .await
+            .map_err(|_| DNSError::Timeout)?
+    }
+}
+
+impl Drop for AsyncUdpClient {
+    fn drop(&mut self) {
+        self.receiver.abort();
+    }
+}
+
+/// Receive the datagrams sent by the nameserver, and hand them to the queries they answer.
+async fn receive_udp(socket: Arc<UdpSocket>, multiplexer: Multiplexer) {
+    let mut recv_data = vec![0; MAX_PACKET_SIZE];
+    loop {
+        let recv_len = match socket.recv(&mut recv_data).await {
+            Ok(recv_len) => recv_len,
+            // ICMP error for an earlier query, later queries may still succeed
+            Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => continue,
+            Err(_) => break,
+        };
+
+        let mut buffer = DNSPacketBuffer::new(&recv_data[..recv_len]);
+        if let Ok(response) = DNSPacket::parse_dns_packet(&mut buffer) {
+            multiplexer.dispatch(response);
+        }
+    }
+    multiplexer.close();
+}
+
+/// Async client sending queries to a nameserver over a TCP connection. Concurrent queries are
+/// pipelined on the connection, and responses may arrive in any order (RFC 7766 section 6.2).
+pub struct AsyncTcpClient {
+    nameserver: SocketAddr,
+    writer: tokio::sync::Mutex<FramedWrite<OwnedWriteHalf, DNSCodec>>,
+    multiplexer: Multiplexer,
+    receiver: JoinHandle<()>,
+    timeout: Duration,
+    edns: Option<Edns>,
+}
+
+impl AsyncTcpClient {
+    /// Connect to a nameserver, waiting at most the given time for the connection to be
+    /// established. Must be called from within a tokio runtime.
+    pub async fn connect(nameserver: SocketAddr, timeout: Duration) -> Result<Self, DNSError> {
+        let stream = tokio::time::timeout(timeout, TcpStream::connect(nameserver))
+            .await
+            .map_err(|_| DNSError::Timeout)??;
+        stream.set_nodelay(true)?;
+
+        let (reader, writer) = stream.into_split();
+        let multiplexer = Multiplexer::new();
+        let receiver = tokio::spawn(receive_tcp(
+            FramedRead::new(reader, DNSCodec),
+            multiplexer.clone(),
+        ));
+        Ok(AsyncTcpClient {
+            nameserver,
+            writer: tokio::sync::Mutex::new(FramedWrite::new(writer, DNSCodec)),
+            multiplexer,
+            receiver,
+            timeout: DEFAULT_TIMEOUT,
+            edns: Some(Edns::default()),
+        })
+    }
+
+    pub fn nameserver(&self) -> SocketAddr {
+        self.nameserver
+    }
+
+    /// Check whether the connection was closed, in which case every query fails.
+    pub fn is_closed(&self) -> bool {
+        self.multiplexer.lock().closed
+    }
+
+    /// Set how long to wait for a response.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Set the EDNS information sent with queries, or None to send plain DNS queries.
+    pub fn set_edns(&mut self, edns: Option<Edns>) {
+        self.edns = edns;
+    }
+
+    /// Send a query and wait for its response.
+    pub async fn query(
+        &self,
+        name: &Domain,
+        record_type: QueryType,
+    ) -> Result<DNSPacket, DNSError> {
+        let question = question(name, record_type);
+        let mut pending = self.multiplexer.register(&question)?;
+        let query = query_packet(pending.id, &question, true, self.edns.clone());
+
+        let exchange = async {
+            self.writer.lock().await.send(&query).await?;
+            pending.response().await
+        };
+        tokio::time::timeout(self.timeout, exchange)
+            .await
+            .map_err(|_| DNSError::Timeout)?
+    }
+}
+
+impl Drop for AsyncTcpClient {
+    fn drop(&mut self) {
+        self.receiver.abort();
+    }
+}
+
+/// Receive the messages sent by the nameserver, and hand them to the queries they answer.
+async fn receive_tcp(mut reader: FramedRead<OwnedReadHalf, DNSCodec>, multiplexer: Multiplexer) {
+    while let Some(result) = reader.next().await {
+        match result {
+            Ok(response) => multiplexer.dispatch(response),
+            Err(DNSError::Timeout | DNSError::Io(_)) => break,
+            // Malformed message, the framing is still intact
+            Err(_) => continue,
+        }
+    }
+    multiplexer.close();
+}
+
+/// Async client for a nameserver, sending queries over UDP and retrying truncated responses over
+/// TCP (RFC 7766 section 5). The TCP connection is opened on the first truncated response, and
+/// reused for later ones while it stays open.
+pub struct AsyncClient {
+    udp: AsyncUdpClient,
+    tcp: tokio::sync::Mutex<Option<Arc<AsyncTcpClient>>>,
+    timeout: Duration,
+    edns: Option<Edns>,
+}
+
+impl AsyncClient {
+    /// Create a client for the nameserver, with EDNS enabled. Must be called from within a tokio
+    /// runtime.
+    pub async fn connect(nameserver: SocketAddr) -> Result<Self, DNSError> {
+        Ok(AsyncClient {
+            udp: AsyncUdpClient::connect(nameserver).await?,
+            tcp: tokio::sync::Mutex::new(None),
+            timeout: DEFAULT_TIMEOUT,
+            edns: Some(Edns::default()),
+        })
+    }
+
+    pub fn nameserver(&self) -> SocketAddr {
+        self.udp.nameserver()
+    }
+
+    /// Set how long to wait for a response, over each transport.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+        self.udp.set_timeout(timeout);
+        *self.tcp.get_mut() = None;
+    }
+
+    /// Set the EDNS information sent with queries, or None to send plain DNS queries.
+    pub fn set_edns(&mut self, edns: Option<Edns>) {
+        self.edns = edns.clone();
+        self.udp.set_edns(edns);
+        *self.tcp.get_mut() = None;
+    }
+
+    /// Send a query and return the full response.
+    pub async fn query(
+        &self,
+        name: &Domain,
+        record_type: QueryType,
+    ) -> Result<DNSPacket, DNSError> {
+        let response = self.udp.query(name, record_type.clone()).await?;
+        if !response.header().truncated_message {
+            return Ok(response);
+        }
+        self.tcp_client().await?.query(name, record_type).await
+    }
+
+    /// The open TCP connection to the nameserver, or a new one.
+    async fn tcp_client(&self) -> Result<Arc<AsyncTcpClient>, DNSError> {
+        let mut tcp = self.tcp.lock().await;
+        match tcp.as_ref() {
+            Some(client) if !client.is_closed() => Ok(client.clone()),
+            _ => {
+                let mut client = AsyncTcpClient::connect(self.nameserver(), self.timeout).await?;
+                client.set_timeout(self.timeout);
+                client.set_edns(self.edns.clone());
+                let client = Arc::new(client);
+                *tcp = Some(client.clone());
+                Ok(client)
+            }
+        }
+    }
+}
+
+/// Async stub resolver, the counterpart of the blocking Resolver. Each nameserver gets its own
+/// client, so concurrent lookups share their sockets.
+pub struct AsyncResolver {
+    clients: Vec<AsyncClient>,
+    attempts: usize,
+}
+
+impl AsyncResolver {
+    /// Create a resolver querying the given nameservers in order, with EDNS enabled. Must be
+    /// called from within a tokio runtime.
+    pub async fn new(nameservers: Vec<SocketAddr>) -> Result<Self, DNSError> {
+        let mut clients = Vec::<AsyncClient>::with_capacity(nameservers.len());
+        for nameserver in nameservers {
+            clients.push(AsyncClient::connect(nameserver).await?);
+        }
+        Ok(AsyncResolver {
+            clients,
+            attempts: DEFAULT_ATTEMPTS,
+        })
+    }
+
+    pub fn nameservers(&self) -> Vec<SocketAddr> {
+        self.clients.iter().map(AsyncClient::nameserver).collect()
+    }
+
+    /// Set how long to wait for each nameserver to respond.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        for client in self.clients.iter_mut() {
+            client.set_timeout(timeout);
+        }
+    }
+
+    /// Set how many times each nameserver is tried, at least once.
+    pub fn set_attempts(&mut self, attempts: usize) {
+        self.attempts = attempts.max(1);
+    }
+
+    /// Set the EDNS information sent with queries, or None to send plain DNS queries.
+    pub fn set_edns(&mut self, edns: Option<Edns>) {
+        for client in self.clients.iter_mut() {
+            client.set_edns(edns.clone());
+        }
+    }
+
+    /// Look up the records of the given name and type, returning the answer section. Responses
+    /// other than NOERROR are returned as an ErrorResponse error.
+    pub async fn lookup(
+        &self,
+        name: &Domain,
+        record_type: QueryType,
+    ) -> Result<Vec<Record>, DNSError> {
+        let response = self.query(name, record_type).await?;
+        match response.response_code()? {
+            ResponseCode::NoError => Ok(response.answers().to_vec()),
+            response_code => Err(DNSError::ErrorResponse(response_code)),
+        }
+    }
+
+    /// Send a query and return the full response, trying the nameservers in turn like
+    /// Resolver::query.
+    pub async fn query(
+        &self,
+        name: &Domain,
+        record_type: QueryType,
+    ) -> Result<DNSPacket, DNSError> {
+        if self.clients.is_empty() {
+            return Err(DNSError::NoNameservers);
+        }
+
+        let mut fallback: Option<DNSPacket> = None;
+        let mut last_err = DNSError::Timeout;
+        for _ in 0..self.attempts {
+            for client in self.clients.iter() {
+                match client.query(name, record_type.clone()).await {
+                    Ok(response) if is_final_response(&response) => return Ok(response),
+                    Ok(response) => fallback = Some(response),
+                    Err(err) => last_err = err,
+                }
+            }
+        }
+
+        fallback.ok_or(last_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::{Header, RecordData, A};
+    use std::time::Instant;
+    use tokio::net::TcpListener;
+    use tokio_util::codec::Framed;
+
+    const TEST_TIMEOUT: Duration = Duration::from_millis(200);
+
+    fn domain(name: &str) -> Domain {
+        name.parse().unwrap()
+    }
+
+    fn a_record(name: &str, addr: [u8; 4]) -> Record {
+        Record::new(
+            domain(name),
+            Class::IN,
+            300,
+            RecordData::A(A {
+                addr: Ipv4Addr::from(addr),
+            }),
+        )
+    }
+
+    /// Response to the query, answering its question with the given records.
+    fn response(query: &DNSPacket, response_code: ResponseCode, answers: Vec<Record>) -> DNSPacket {
+        let header = Header {
+            query_response: true,
+            recursion_available: true,
+            response_code,
+            answer_count: answers.len() as u16,
+            ..query.header().clone()
+        };
+        DNSPacket::new(
+            header,
+            Some(query.questions().to_vec()),
+            Some(answers),
+            None,
+            None,
+        )
+    }
+
+    /// Answer for each name "<n>.example" is 192.0.2.<n>.
+    fn answer(query: &DNSPacket) -> DNSPacket {
+        let name = query.questions()[0].domain.to_string();
+        let n: u8 = name.split('.').next().unwrap().parse().unwrap();
+        response(
+            query,
+            ResponseCode::NoError,
+            vec![a_record(&name, [192, 0, 2, n])],
+        )
+    }
+
+    async fn send_to(socket: &UdpSocket, packet: &DNSPacket, addr: SocketAddr) {
+        let data = packet.write_dns_packet().unwrap();
+        socket.send_to(data.get_data(), addr).await.unwrap();
+    }
+
+    async fn receive_query(socket: &UdpSocket) -> (DNSPacket, SocketAddr) {
+        let mut recv_data = vec![0; MAX_PACKET_SIZE];
+        let (recv_len, source) = socket.recv_from(&mut recv_data).await.unwrap();
+        let mut buffer = DNSPacketBuffer::new(&recv_data[..recv_len]);
+        (DNSPacket::parse_dns_packet(&mut buffer).unwrap(), source)
+    }
+
+    /// Local UDP nameserver receiving a number of queries, and answering them in reverse order.
+    async fn reversing_nameserver(queries: usize) -> (SocketAddr, JoinHandle<()>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let mut received = Vec::<(DNSPacket, SocketAddr)>::new();
+            for _ in 0..queries {
+                received.push(receive_query(&socket).await);
+            }
+            for (query, source) in received.iter().rev() {
+                send_to(&socket, &answer(query), *source).await;
+            }
+        });
+        (addr, handle)
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_udp_queries() {
+        let (addr, handle) = reversing_nameserver(3).await;
+        let client = AsyncUdpClient::connect(addr).await.unwrap();
+
+        let names = [
+            domain("1.example"),
+            domain("2.example"),
+            domain("3.example"),
+        ];
+        let (first, second, third) = tokio::join!(
+            client.query(&names[0], QueryType::A),
+            client.query(&names[1], QueryType::A),
+            client.query(&names[2], QueryType::A),
+        );
+        handle.await.unwrap();
+
+        for (response, n) in [(first, 1), (second, 2), (third, 3)] {
+            let name = format!("{}.example", n);
+            assert_eq!(
+                response.unwrap().answers(),
+                &[a_record(&name, [192, 0, 2, n])]
+            );
+        }
+        assert!(client.multiplexer.lock().queries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_udp_ignore_mismatched_responses() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let (query, source) = receive_query(&socket).await;
+            // Same ID, but answering another question
+            let mut other_question = query.questions()[0].clone();
+            other_question.domain = domain("2.example");
+            let other = DNSPacket::new(
+                query.header().clone(),
+                Some(vec![other_question]),
+                None,
+                None,
+                None,
+            );
+            send_to(&socket, &answer(&other), source).await;
+            send_to(&socket, &answer(&query), source).await;
+        });
+
+        let client = AsyncUdpClient::connect(addr).await.unwrap();
+        let response = client
+            .query(&domain("1.example"), QueryType::A)
+            .await
+            .unwrap();
+        handle.await.unwrap();
+
+        assert_eq!(response.answers(), &[a_record("1.example", [192, 0, 2, 1])]);
+    }
+
+    #[tokio::test]
+    async fn test_udp_timeout() {
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let mut client = AsyncUdpClient::connect(silent.local_addr().unwrap())
+            .await
+            .unwrap();
+        client.set_timeout(Duration::from_millis(50));
+
+        let start = Instant::now();
+        let result = client.query(&domain("1.example"), QueryType::A).await;
+
+        assert_eq!(result, Err(DNSError::Timeout));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        // The abandoned query no longer holds its ID
+        assert!(client.multiplexer.lock().queries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_tcp_queries() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut framed = Framed::new(stream, DNSCodec);
+            let mut queries = Vec::<DNSPacket>::new();
+            for _ in 0..3 {
+                queries.push(framed.next().await.unwrap().unwrap());
+            }
+            for query in queries.iter().rev() {
+                framed.send(answer(query)).await.unwrap();
+            }
+        });
+
+        let client = AsyncTcpClient::connect(addr, TEST_TIMEOUT).await.unwrap();
+        let names = [
+            domain("1.example"),
+            domain("2.example"),
+            domain("3.example"),
+        ];
+        let (first, second, third) = tokio::join!(
+            client.query(&names[0], QueryType::A),
+            client.query(&names[1], QueryType::A),
+            client.query(&names[2], QueryType::A),
+        );
+        handle.await.unwrap();
+
+        for (response, n) in [(first, 1), (second, 2), (third, 3)] {
+            let name = format!("{}.example", n);
+            assert_eq!(
+                response.unwrap().answers(),
+                &[a_record(&name, [192, 0, 2, n])]
+            );
+        }
+
+        // The nameserver closed the connection
+        tokio::time::timeout(TEST_TIMEOUT, async {
+            while !client.is_closed() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(matches!(
+            client.query(&domain("1.example"), QueryType::A).await,
+            Err(DNSError::Io(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tcp_fallback() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        let listener = TcpListener::bind(addr).await.unwrap();
+        let handle = tokio::spawn(async move {
+            for _ in 0..2 {
+                let (query, source) = receive_query(&socket).await;
+                let full = response(&query, ResponseCode::NoError, Vec::new());
+                let header = Header {
+                    truncated_message: true,
+                    ..full.header().clone()
+                };
+                let truncated =
+                    DNSPacket::new(header, Some(query.questions().to_vec()), None, None, None);
+                send_to(&socket, &truncated, source).await;
+            }
+        });
+        let tcp_handle = tokio::spawn(async move {
+            // Both truncated responses are retried on the same connection
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut framed = Framed::new(stream, DNSCodec);
+            while let Some(query) = framed.next().await {
+                framed.send(answer(&query.unwrap())).await.unwrap();
+            }
+        });
+
+        let mut resolver = AsyncResolver::new(vec![addr]).await.unwrap();
+        resolver.set_timeout(TEST_TIMEOUT);
+        for n in 1..=2 {
+            let name = format!("{}.example", n);
+            let records = resolver.lookup(&domain(&name), QueryType::A).await.unwrap();
+            assert_eq!(records, vec![a_record(&name, [192, 0, 2, n])]);
+        }
+        handle.await.unwrap();
+        drop(resolver);
+        tcp_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retry_next_nameserver() {
+        let servfail = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let servfail_addr = servfail.local_addr().unwrap();
+        let servfail_handle = tokio::spawn(async move {
+            let (query, source) = receive_query(&servfail).await;
+            send_to(
+                &servfail,
+                &response(&query, ResponseCode::ServFail, Vec::new()),
+                source,
+            )
+            .await;
+        });
+        let (addr, handle) = reversing_nameserver(1).await;
+
+        let mut resolver = AsyncResolver::new(vec![servfail_addr, addr]).await.unwrap();
+        resolver.set_timeout(TEST_TIMEOUT);
+        let records = resolver
+            .lookup(&domain("1.example"), QueryType::A)
+            .await
+            .unwrap();
+        servfail_handle.await.unwrap();
+        handle.await.unwrap();
+
+        assert_eq!(records, vec![a_record("1.example", [192, 0, 2, 1])]);
+        assert_eq!(
+            AsyncResolver::new(Vec::new())
+                .await
+                .unwrap()
+                .lookup(&domain("1.example"), QueryType::A)
+                .await,
+            Err(DNSError::NoNameservers)
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use super::resolver_cache::Upstream;
+use crate::dns_packet::{caa_permits, DNSError, Domain, QueryType, RecordData, ResponseCode, CAA};
+
+/// Find the relevant CAA RRset of a name (RFC 8659 section 3): the CAA records of the name, else
+/// of its closest ancestor having some, the root excluded. Aliases are followed by the upstream.
+/// The RRset of a wildcard name is the one of the name without its wildcard label. An empty
+/// RRset means no CAA policy applies.
+pub fn relevant_caa_set<U: Upstream + ?Sized>(
+    upstream: &U,
+    name: &Domain,
+) -> Result<Vec<CAA>, DNSError> {
+    let mut candidate = if name.is_wildcard() {
+        name.parent()
+    } else {
+        Some(name.clone())
+    };
+
+    while let Some(name) = candidate.take().filter(|name| !name.is_root()) {
+        candidate = name.parent();
+        let response = match upstream.query(&name, QueryType::CAA) {
+            Err(DNSError::ErrorResponse(ResponseCode::NXDomain)) => continue,
+            response => response?,
+        };
+        match response.response_code()? {
+            ResponseCode::NoError => (),
+            ResponseCode::NXDomain => continue,
+            response_code => return Err(DNSError::ErrorResponse(response_code)),
+        }
+
+        let caa_set: Vec<CAA> = response
+            .answers()
+            .iter()
+            .filter_map(|record| match record.data() {
+                RecordData::CAA(caa) => Some(caa.clone()),
+                _ => None,
+            })
+            .collect();
+        if !caa_set.is_empty() {
+            return Ok(caa_set);
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+/// Decide whether the CA identified by the given issuer domain may issue a certificate for a
+/// name, which is a wildcard certificate if the name starts with a wildcard label. Failing to
+/// look up the CAA records is an error, upon which CAs must not issue.
+pub fn check_caa<U: Upstream + ?Sized>(
+    upstream: &U,
+    name: &Domain,
+    issuer: &Domain,
+) -> Result<bool, DNSError> {
+    let relevant = relevant_caa_set(upstream, name)?;
+    Ok(caa_permits(&relevant, issuer, name.is_wildcard()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::{Class, DNSPacket, Header, Opcode, Question, Record};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Upstream answering CAA queries from a fixed set of records, and NXDOMAIN for the names
+    /// listed as nonexistent.
+    struct MockUpstream {
+        records: HashMap<Domain, Vec<CAA>>,
+        nonexistent: Vec<Domain>,
+        queried: Mutex<Vec<Domain>>,
+    }
+
+    impl MockUpstream {
+        fn new(records: &[(&str, CAA)], nonexistent: &[&str]) -> Self {
+            let mut map = HashMap::<Domain, Vec<CAA>>::new();
+            for (name, caa) in records {
+                map.entry(domain(name)).or_default().push(caa.clone());
+            }
+            MockUpstream {
+                records: map,
+                nonexistent: nonexistent.iter().map(|name| domain(name)).collect(),
+                queried: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn queried(&self) -> Vec<Domain> {
+            self.queried.lock().unwrap().clone()
+        }
+    }
+
+    impl Upstream for MockUpstream {
+        fn query(&self, name: &Domain, record_type: QueryType) -> Result<DNSPacket, DNSError> {
+            self.queried.lock().unwrap().push(name.clone());
+            let response_code = if self.nonexistent.contains(name) {
+                ResponseCode::NXDomain
+            } else {
+                ResponseCode::NoError
+            };
+            let answers: Vec<Record> = self
+                .records
+                .get(name)
+                .into_iter()
+                .flatten()
+                .map(|caa| Record::new(name.clone(), Class::IN, 300, RecordData::CAA(caa.clone())))
+                .collect();
+
+            let header = Header {
+                id: 1,
+                query_response: true,
+                opcode: Opcode::Query,
+                authoritative_answer: false,
+                truncated_message: false,
+                recursion_desired: true,
+                recursion_available: true,
+                reserved: false,
+                authentic_data: false,
+                checking_disabled: false,
+                response_code,
+                question_count: 1,
+                answer_count: answers.len() as u16,
+                authority_count: 0,
+                additional_count: 0,
+            };
+            let question = Question {
+                domain: name.clone(),
+                record_type,
+                class: Class::IN,
+            };
+            Ok(DNSPacket::new(
+                header,
+                Some(vec![question]),
+                Some(answers),
+                None,
+                None,
+            ))
+        }
+    }
+
+    fn caa(tag: &str, value: &str) -> CAA {
+        CAA {
+            flags: 0,
+            tag: tag.to_string(),
+            value: value.as_bytes().to_vec(),
+        }
+    }
+
+    fn domain(name: &str) -> Domain {
+        name.parse().unwrap()
+    }
+
+    #[test]
+    fn test_relevant_caa_set() {
+        let upstream = MockUpstream::new(
+            &[
+                ("example.com", caa("issue", "ca.example.net")),
+                ("example.com", caa("issuewild", ";")),
+                ("sub.example.com", caa("iodef", "mailto:a@example.com")),
+            ],
+            &["x.y.example.com", "y.example.com"],
+        );
+
+        assert_eq!(
+            relevant_caa_set(&upstream, &domain("x.y.example.com")).unwrap(),
+            vec![caa("issue", "ca.example.net"), caa("issuewild", ";")]
+        );
+        assert_eq!(
+            upstream.queried(),
+            vec![
+                domain("x.y.example.com"),
+                domain("y.example.com"),
+                domain("example.com")
+            ]
+        );
+        assert_eq!(
+            relevant_caa_set(&upstream, &domain("www.sub.example.com")).unwrap(),
+            vec![caa("iodef", "mailto:a@example.com")]
+        );
+        assert_eq!(
+            relevant_caa_set(&upstream, &domain("example.org")).unwrap(),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn test_check_caa() {
+        let upstream = MockUpstream::new(
+            &[
+                ("example.com", caa("issue", "ca.example.net")),
+                ("example.com", caa("issuewild", ";")),
+            ],
+            &[],
+        );
+        let ca = domain("ca.example.net");
+
+        assert_eq!(
+            check_caa(&upstream, &domain("www.example.com"), &ca),
+            Ok(true)
+        );
+        assert_eq!(
+            check_caa(
+                &upstream,
+                &domain("www.example.com"),
+                &domain("other.example")
+            ),
+            Ok(false)
+        );
+        assert_eq!(
+            check_caa(&upstream, &domain("*.example.com"), &ca),
+            Ok(false)
+        );
+        assert_eq!(check_caa(&upstream, &domain("example.org"), &ca), Ok(true));
+    }
+}
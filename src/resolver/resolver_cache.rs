@@ -0,0 +1,1056 @@
+use crate::dns_packet::{
+    Class, DNSError, DNSPacket, Domain, Header, Opcode, QueryType, Question, Record, RecordData,
+    ResponseCode,
+};
+use crate::resolver::{RecursiveResolver, Resolver};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+/// Longest time records are cached, whatever their TTL (RFC 8767 section 4).
+pub const MAX_CACHE_TTL: u32 = 604_800;
+/// Longest time negative answers are cached (RFC 2308 section 5).
+pub const MAX_NEGATIVE_TTL: u32 = 10_800;
+/// TTL of expired answers served while they can't be refreshed (RFC 8767 section 4).
+pub const STALE_ANSWER_TTL: u32 = 30;
+/// Entries are prefetched once less than this percentage of their TTL remains.
+pub const PREFETCH_THRESHOLD_PERCENT: u32 = 10;
+const MAX_ALIASES: usize = 8;
+
+/// Source of the current time for a cache, so tests can control how entries age.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// Clock of the system, used by default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Answer found in the cache. TTLs are decremented by the time spent in the cache.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CachedAnswer {
+    /// Records of the RRset.
+    Records(Vec<Record>),
+    /// The name does not exist, as stated by the SOA record of its zone.
+    NXDomain(Record),
+    /// The name exists but has no records of the type, as stated by the SOA record of its zone.
+    NoData(Record),
+}
+
+/// Entries are looked up case-insensitively. Names which don't exist are cached for all types.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+struct CacheKey {
+    name: Domain,
+    record_type: Option<u16>,
+    class: Class,
+}
+
+impl CacheKey {
+    fn new(name: &Domain, record_type: Option<&QueryType>, class: Class) -> Self {
+        CacheKey {
+            name: name.clone(),
+            record_type: record_type.map(QueryType::to_num),
+            class,
+        }
+    }
+}
+
+struct CacheEntry {
+    answer: CachedAnswer,
+    ttl: u32,
+    inserted: Instant,
+    expires: Instant,
+    last_used: u64,
+    hits: u64,
+    prefetching: bool,
+}
+
+/// Counters of what happened to a cache since it was created.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct CacheStats {
+    /// Lookups answered with unexpired entries.
+    pub hits: u64,
+    /// Lookups finding no usable entry.
+    pub misses: u64,
+    /// Lookups answered with expired entries, while refreshing them failed.
+    pub stale_hits: u64,
+    /// Entries due for a refresh before they expire.
+    pub prefetches: u64,
+    pub insertions: u64,
+    /// Entries removed to make room for new ones.
+    pub evictions: u64,
+    /// Entries removed once expired, and past their stale period.
+    pub expirations: u64,
+}
+
+#[derive(Default)]
+struct CacheEntries {
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// Keys by last use, the least recently used first.
+    usage: BTreeMap<u64, CacheKey>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl CacheEntries {
+    fn touch(&mut self, key: &CacheKey) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.usage.remove(&entry.last_used);
+            entry.last_used = tick;
+            self.usage.insert(tick, key.clone());
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) -> Option<CacheEntry> {
+        let entry = self.entries.remove(key)?;
+        self.usage.remove(&entry.last_used);
+        Some(entry)
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, key)) = self.usage.pop_first() {
+            self.entries.remove(&key);
+            self.stats.evictions += 1;
+        }
+    }
+}
+
+/// Thread-safe cache of RRsets and negative answers (RFC 2308), keyed by name, type and class.
+/// Entries expire after their TTL, and the least recently used ones are evicted once the cache
+/// is full. Expired entries may be kept for a stale period, to be served when they can't be
+/// refreshed (RFC 8767).
+pub struct Cache {
+    entries: Mutex<CacheEntries>,
+    capacity: usize,
+    clock: Arc<dyn Clock>,
+    stale_period: Duration,
+    prefetch_hits: Option<u64>,
+}
+
+impl Cache {
+    /// Create a cache holding at most the given number of entries.
+    pub fn new(capacity: usize) -> Self {
+        Cache::with_clock(capacity, Arc::new(SystemClock))
+    }
+
+    /// Create a cache reading the current time from the given clock.
+    pub fn with_clock(capacity: usize, clock: Arc<dyn Clock>) -> Self {
+        Cache {
+            entries: Mutex::default(),
+            capacity,
+            clock,
+            stale_period: Duration::ZERO,
+            prefetch_hits: None,
+        }
+    }
+
+    /// Set how long entries are kept once expired, to be served by get_stale. Expired entries are
+    /// removed right away by default.
+    pub fn set_stale_period(&mut self, stale_period: Duration) {
+        self.stale_period = stale_period;
+    }
+
+    /// Set how many hits make an entry popular enough to be prefetched, or None to never prefetch
+    /// entries, which is the default.
+    pub fn set_prefetch_hits(&mut self, prefetch_hits: Option<u64>) {
+        self.prefetch_hits = prefetch_hits;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheEntries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Amount of entries in the cache, including expired ones not removed yet.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        entries.entries.clear();
+        entries.usage.clear();
+    }
+
+    /// Look up the answer for a name, type and class. Records of the type are preferred over a
+    /// CNAME of the name, which is preferred over a cached NXDOMAIN for the name.
+    pub fn get(
+        &self,
+        name: &Domain,
+        record_type: &QueryType,
+        class: Class,
+    ) -> Option<CachedAnswer> {
+        self.lookup(name, record_type, class, false)
+    }
+
+    /// Look up the answer for a name, type and class like get, also returning expired answers
+    /// still in their stale period. Those have a TTL of STALE_ANSWER_TTL.
+    pub fn get_stale(
+        &self,
+        name: &Domain,
+        record_type: &QueryType,
+        class: Class,
+    ) -> Option<CachedAnswer> {
+        self.lookup(name, record_type, class, true)
+    }
+
+    /// Whether the answer for a name, type and class should be refreshed before it expires: it is
+    /// popular, and less than PREFETCH_THRESHOLD_PERCENT of its TTL remains. It is only due once,
+    /// until the refreshed answer is inserted.
+    pub fn prefetch_due(&self, name: &Domain, record_type: &QueryType, class: Class) -> bool {
+        let Some(prefetch_hits) = self.prefetch_hits else {
+            return false;
+        };
+        let now = self.clock.now();
+        let mut entries = self.lock();
+        let key = [Some(record_type), Some(&QueryType::CNAME), None]
+            .into_iter()
+            .map(|record_type| CacheKey::new(name, record_type, class))
+            .find(|key| {
+                entries
+                    .entries
+                    .get(key)
+                    .is_some_and(|entry| entry.expires > now)
+            });
+        let Some(entry) = key.and_then(|key| entries.entries.get_mut(&key)) else {
+            return false;
+        };
+
+        let remaining = entry.expires.duration_since(now).as_secs();
+        let due = !entry.prefetching
+            && entry.hits >= prefetch_hits
+            && remaining * 100 < entry.ttl as u64 * PREFETCH_THRESHOLD_PERCENT as u64;
+        if due {
+            entry.prefetching = true;
+            entries.stats.prefetches += 1;
+        }
+        due
+    }
+
+    /// Cache the records of an RRset, which must share their name, type and class. The RRset
+    /// expires after the smallest TTL of its records, and RRsets with a TTL of 0 are not cached.
+    pub fn insert_rrset(&self, rrset: &[Record]) {
+        let Some(first) = rrset.first() else {
+            return;
+        };
+        let ttl = rrset.iter().map(Record::ttl).min().unwrap_or(0);
+        let key = CacheKey::new(first.domain(), Some(&first.record_type()), first.class());
+        self.insert(key, CachedAnswer::Records(rrset.to_vec()), ttl);
+    }
+
+    /// Cache every RRset of a list of records.
+    pub fn insert_records(&self, records: &[Record]) {
+        for rrset in group_rrsets(records) {
+            self.insert_rrset(&rrset);
+        }
+    }
+
+    /// Cache an NXDOMAIN answer for the name, or a NODATA answer for its type if given. Negative
+    /// answers expire after the smaller of the SOA record's TTL and minimum TTL (RFC 2308 section
+    /// 5).
+    pub fn insert_negative(
+        &self,
+        name: &Domain,
+        record_type: Option<&QueryType>,
+        class: Class,
+        soa: &Record,
+    ) {
+        let ttl = match soa.data() {
+            RecordData::SOA(soa_data) => soa.ttl().min(soa_data.minttl),
+            _ => return,
+        };
+        let key = CacheKey::new(name, record_type, class);
+        let answer = match record_type {
+            Some(_) => CachedAnswer::NoData(soa.clone()),
+            None => CachedAnswer::NXDomain(soa.clone()),
+        };
+        self.insert(key, answer, ttl.min(MAX_NEGATIVE_TTL));
+    }
+
+    /// Cache the answer of a response: the RRsets of its answer section and, for NXDOMAIN and
+    /// NODATA responses, the negative answer for the last name of the CNAME chain.
+    pub fn insert_response(&self, response: &DNSPacket) {
+        let [question] = response.questions() else {
+            return;
+        };
+        self.insert_records(response.answers());
+
+        let Some(soa) = response
+            .authorities()
+            .iter()
+            .find(|record| record.record_type() == QueryType::SOA)
+        else {
+            return;
+        };
+
+        // Each CNAME is followed at most once, so looping chains end
+        let mut name = &question.domain;
+        for _ in response.answers() {
+            let cname = response
+                .answers()
+                .iter()
+                .find_map(|record| match record.data() {
+                    RecordData::CNAME(cname) if record.domain() == name => Some(cname),
+                    _ => None,
+                });
+            match cname {
+                Some(cname) => name = &cname.cname,
+                None => break,
+            }
+        }
+
+        match response.response_code() {
+            Ok(ResponseCode::NXDomain) => self.insert_negative(name, None, question.class, soa),
+            Ok(ResponseCode::NoError)
+                if !response.answers().iter().any(|record| {
+                    record.domain() == name && record.record_type() == question.record_type
+                }) =>
+            {
+                self.insert_negative(name, Some(&question.record_type), question.class, soa)
+            }
+            _ => (),
+        }
+    }
+
+    /// Remove the entry of a name, type and class, or the cached NXDOMAIN of the name.
+    pub fn remove(&self, name: &Domain, record_type: Option<&QueryType>, class: Class) {
+        self.lock().remove(&CacheKey::new(name, record_type, class));
+    }
+
+    fn lookup(
+        &self,
+        name: &Domain,
+        record_type: &QueryType,
+        class: Class,
+        stale: bool,
+    ) -> Option<CachedAnswer> {
+        let now = self.clock.now();
+        let mut entries = self.lock();
+        let answer = [Some(record_type), Some(&QueryType::CNAME), None]
+            .into_iter()
+            .find_map(|record_type| {
+                let key = CacheKey::new(name, record_type, class);
+                let entry = entries.entries.get_mut(&key)?;
+                if entry.expires > now {
+                    let age = u32::try_from(now.duration_since(entry.inserted).as_secs())
+                        .unwrap_or(u32::MAX);
+                    let answer = map_answer(&entry.answer, |record| {
+                        with_ttl(record, record.ttl().saturating_sub(age))
+                    });
+                    entry.hits += 1;
+                    entries.stats.hits += 1;
+                    entries.touch(&key);
+                    return Some(answer);
+                }
+
+                if entry.expires + self.stale_period <= now {
+                    entries.remove(&key);
+                    entries.stats.expirations += 1;
+                    return None;
+                }
+                if !stale {
+                    return None;
+                }
+                let answer = map_answer(&entry.answer, |record| with_ttl(record, STALE_ANSWER_TTL));
+                entries.stats.stale_hits += 1;
+                entries.touch(&key);
+                Some(answer)
+            });
+        if answer.is_none() {
+            entries.stats.misses += 1;
+        }
+        answer
+    }
+
+    fn insert(&self, key: CacheKey, answer: CachedAnswer, ttl: u32) {
+        let ttl = ttl.min(MAX_CACHE_TTL);
+        if ttl == 0 || self.capacity == 0 {
+            return;
+        }
+
+        // Records of an RRset share its TTL (RFC 2181 section 5.2)
+        let answer = map_answer(&answer, |record| with_ttl(record, ttl));
+
+        let now = self.clock.now();
+        let mut entries = self.lock();
+        entries.remove(&key);
+        while entries.entries.len() >= self.capacity {
+            entries.evict_least_recently_used();
+        }
+        entries.entries.insert(
+            key.clone(),
+            CacheEntry {
+                answer,
+                ttl,
+                inserted: now,
+                expires: now + Duration::from_secs(ttl as u64),
+                last_used: 0,
+                hits: 0,
+                prefetching: false,
+            },
+        );
+        entries.touch(&key);
+        entries.stats.insertions += 1;
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Cache::new(DEFAULT_CACHE_CAPACITY)
+    }
+}
+
+/// Source of the responses cached by a CachingResolver.
+pub trait Upstream: Send + Sync {
+    fn query(&self, name: &Domain, record_type: QueryType) -> Result<DNSPacket, DNSError>;
+}
+
+impl Upstream for Resolver {
+    fn query(&self, name: &Domain, record_type: QueryType) -> Result<DNSPacket, DNSError> {
+        Resolver::query(self, name, record_type)
+    }
+}
+
+impl Upstream for RecursiveResolver {
+    fn query(&self, name: &Domain, record_type: QueryType) -> Result<DNSPacket, DNSError> {
+        RecursiveResolver::query(self, name, record_type)
+    }
+}
+
+/// Resolver answering from a cache, and querying its upstream when the cache has no answer. When
+/// the upstream fails, expired answers still in the stale period of the cache are served instead
+/// (RFC 8767). Popular answers about to expire are refreshed in the background.
+pub struct CachingResolver<U> {
+    upstream: Arc<U>,
+    cache: Arc<Cache>,
+}
+
+impl<U: Upstream + 'static> CachingResolver<U> {
+    pub fn new(upstream: U, cache: Cache) -> Self {
+        CachingResolver {
+            upstream: Arc::new(upstream),
+            cache: Arc::new(cache),
+        }
+    }
+
+    pub fn upstream(&self) -> &U {
+        &self.upstream
+    }
+
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    /// Look up the records of the given name and type, returning the answers along with the
+    /// aliases leading to them. Responses other than NOERROR are returned as an ErrorResponse
+    /// error.
+    pub fn lookup(&self, name: &Domain, record_type: QueryType) -> Result<Vec<Record>, DNSError> {
+        let response = self.query(name, record_type)?;
+        match response.response_code()? {
+            ResponseCode::NoError => Ok(response.answers().to_vec()),
+            response_code => Err(DNSError::ErrorResponse(response_code)),
+        }
+    }
+
+    /// Answer a query from the cache, or from the upstream if the cache can't. Responses built
+    /// from the cache hold the chain of aliases in their answer section, and the SOA record of
+    /// negative answers in their authority section.
+    pub fn query(&self, name: &Domain, record_type: QueryType) -> Result<DNSPacket, DNSError> {
+        if let Some(response) = cached_response(&self.cache, name, &record_type, false) {
+            if self.cache.prefetch_due(name, &record_type, Class::IN) {
+                self.prefetch(name, &record_type);
+            }
+            return Ok(response);
+        }
+
+        match self.upstream.query(name, record_type.clone()) {
+            Ok(response) if is_cacheable(&response) => {
+                self.cache.insert_response(&response);
+                Ok(response)
+            }
+            result => match cached_response(&self.cache, name, &record_type, true) {
+                Some(response) => Ok(response),
+                None => result,
+            },
+        }
+    }
+
+    /// Refresh the answer of a query from another thread.
+    fn prefetch(&self, name: &Domain, record_type: &QueryType) {
+        let upstream = self.upstream.clone();
+        let cache = self.cache.clone();
+        let name = name.clone();
+        let record_type = record_type.clone();
+        thread::spawn(move || {
+            if let Ok(response) = upstream.query(&name, record_type) {
+                if is_cacheable(&response) {
+                    cache.insert_response(&response);
+                }
+            }
+        });
+    }
+}
+
+/// Only positive and negative answers are cached, not failures.
+fn is_cacheable(response: &DNSPacket) -> bool {
+    matches!(
+        response.response_code(),
+        Ok(ResponseCode::NoError | ResponseCode::NXDomain)
+    )
+}
+
+/// Response to a query built from the cache, following aliases to the records of the type. None
+/// if the cache doesn't hold the whole chain.
+fn cached_response(
+    cache: &Cache,
+    name: &Domain,
+    record_type: &QueryType,
+    stale: bool,
+) -> Option<DNSPacket> {
+    let mut answers = Vec::<Record>::new();
+    let mut current = name.clone();
+    for _ in 0..=MAX_ALIASES {
+        let answer = match stale {
+            true => cache.get_stale(&current, record_type, Class::IN),
+            false => cache.get(&current, record_type, Class::IN),
+        }?;
+        let (response_code, authorities) = match answer {
+            CachedAnswer::Records(records) => {
+                let alias = records.iter().find_map(|record| match record.data() {
+                    RecordData::CNAME(cname) if *record_type != QueryType::CNAME => {
+                        Some(cname.cname.clone())
+                    }
+                    _ => None,
+                });
+                answers.extend(records);
+                if let Some(alias) = alias {
+                    current = alias;
+                    continue;
+                }
+                (ResponseCode::NoError, Vec::new())
+            }
+            CachedAnswer::NXDomain(soa) => (ResponseCode::NXDomain, vec![soa]),
+            CachedAnswer::NoData(soa) => (ResponseCode::NoError, vec![soa]),
+        };
+
+        let header = Header {
+            id: 0,
+            query_response: true,
+            opcode: Opcode::Query,
+            authoritative_answer: false,
+            truncated_message: false,
+            recursion_desired: true,
+            recursion_available: true,
+            reserved: false,
+            authentic_data: false,
+            checking_disabled: false,
+            response_code,
+            question_count: 1,
+            answer_count: answers.len() as u16,
+            authority_count: authorities.len() as u16,
+            additional_count: 0,
+        };
+        let question = Question {
+            domain: name.clone(),
+            record_type: record_type.clone(),
+            class: Class::IN,
+        };
+        return Some(DNSPacket::new(
+            header,
+            Some(vec![question]),
+            Some(answers),
+            Some(authorities),
+            None,
+        ));
+    }
+    None
+}
+
+/// Split records into RRsets, keeping the order in which they first appear.
+fn group_rrsets(records: &[Record]) -> Vec<Vec<Record>> {
+    let mut rrsets = Vec::<Vec<Record>>::new();
+    for record in records {
+        let rrset = rrsets.iter_mut().find(|rrset| {
+            rrset[0].domain() == record.domain()
+                && rrset[0].record_type() == record.record_type()
+                && rrset[0].class() == record.class()
+        });
+        match rrset {
+            Some(rrset) => rrset.push(record.clone()),
+            None => rrsets.push(vec![record.clone()]),
+        }
+    }
+    rrsets
+}
+
+fn with_ttl(record: &Record, ttl: u32) -> Record {
+    Record::new(
+        record.domain().clone(),
+        record.class(),
+        ttl,
+        record.data().clone(),
+    )
+}
+
+/// Answer with its records transformed by f.
+fn map_answer<F: Fn(&Record) -> Record>(answer: &CachedAnswer, f: F) -> CachedAnswer {
+    match answer {
+        CachedAnswer::Records(records) => CachedAnswer::Records(records.iter().map(f).collect()),
+        CachedAnswer::NXDomain(soa) => CachedAnswer::NXDomain(f(soa)),
+        CachedAnswer::NoData(soa) => CachedAnswer::NoData(f(soa)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::{A, CNAME, SOA};
+    use std::net::Ipv4Addr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Clock only moving forward when told to.
+    struct ManualClock(Mutex<Instant>);
+
+    impl ManualClock {
+        fn new() -> Arc<Self> {
+            Arc::new(ManualClock(Mutex::new(Instant::now())))
+        }
+
+        fn advance(&self, secs: u64) {
+            *self.0.lock().unwrap() += Duration::from_secs(secs);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn domain(name: &str) -> Domain {
+        name.parse().unwrap()
+    }
+
+    fn a_record(name: &str, ttl: u32, last_byte: u8) -> Record {
+        Record::new(
+            domain(name),
+            Class::IN,
+            ttl,
+            RecordData::A(A {
+                addr: Ipv4Addr::new(192, 0, 2, last_byte),
+            }),
+        )
+    }
+
+    fn soa_record(ttl: u32, minttl: u32) -> Record {
+        Record::new(
+            domain("example.com"),
+            Class::IN,
+            ttl,
+            RecordData::SOA(SOA {
+                mname: domain("ns1.example.com"),
+                rname: domain("hostmaster.example.com"),
+                serial: 1,
+                refresh: 7200,
+                retry: 3600,
+                expire: 1209600,
+                minttl,
+            }),
+        )
+    }
+
+    fn ttls(answer: Option<CachedAnswer>) -> Vec<u32> {
+        match answer {
+            Some(CachedAnswer::Records(records)) => records.iter().map(Record::ttl).collect(),
+            Some(CachedAnswer::NXDomain(soa) | CachedAnswer::NoData(soa)) => vec![soa.ttl()],
+            None => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_ttl_expiry() {
+        let clock = ManualClock::new();
+        let cache = Cache::with_clock(10, clock.clone());
+        cache.insert_records(&[
+            a_record("www.example.com", 300, 1),
+            a_record("www.example.com", 200, 2),
+            a_record("mail.example.com", 0, 3),
+        ]);
+
+        // The RRset lives as long as its shortest TTL, records with a TTL of 0 are not cached
+        assert_eq!(cache.len(), 1);
+        let name = domain("WWW.Example.com");
+        assert_eq!(
+            ttls(cache.get(&name, &QueryType::A, Class::IN)),
+            vec![200, 200]
+        );
+        assert_eq!(cache.get(&name, &QueryType::AAAA, Class::IN), None);
+        assert_eq!(cache.get(&name, &QueryType::A, Class::CH), None);
+
+        clock.advance(150);
+        assert_eq!(
+            ttls(cache.get(&name, &QueryType::A, Class::IN)),
+            vec![50, 50]
+        );
+        clock.advance(50);
+        assert_eq!(cache.get(&name, &QueryType::A, Class::IN), None);
+        assert!(cache.is_empty());
+
+        // TTLs are capped
+        cache.insert_rrset(&[a_record("www.example.com", u32::MAX, 1)]);
+        assert_eq!(
+            ttls(cache.get(&name, &QueryType::A, Class::IN)),
+            vec![MAX_CACHE_TTL]
+        );
+    }
+
+    #[test]
+    fn test_negative_caching() {
+        let clock = ManualClock::new();
+        let cache = Cache::with_clock(10, clock.clone());
+        let header = Header {
+            id: 1,
+            query_response: true,
+            opcode: Opcode::Query,
+            authoritative_answer: true,
+            truncated_message: false,
+            recursion_desired: true,
+            recursion_available: true,
+            reserved: false,
+            authentic_data: false,
+            checking_disabled: false,
+            response_code: ResponseCode::NXDomain,
+            question_count: 1,
+            answer_count: 1,
+            authority_count: 1,
+            additional_count: 0,
+        };
+        let cname = Record::new(
+            domain("alias.example.com"),
+            Class::IN,
+            600,
+            RecordData::CNAME(CNAME {
+                cname: domain("missing.example.com"),
+            }),
+        );
+        let nxdomain = DNSPacket::new(
+            header.clone(),
+            Some(vec![Question {
+                domain: domain("alias.example.com"),
+                record_type: QueryType::A,
+                class: Class::IN,
+            }]),
+            Some(vec![cname.clone()]),
+            Some(vec![soa_record(3600, 300)]),
+            None,
+        );
+        cache.insert_response(&nxdomain);
+
+        // The NXDOMAIN applies to the target of the CNAME, for every type
+        let missing = domain("missing.example.com");
+        assert_eq!(
+            cache.get(&domain("alias.example.com"), &QueryType::A, Class::IN),
+            Some(CachedAnswer::Records(vec![cname]))
+        );
+        assert_eq!(
+            cache.get(&missing, &QueryType::TXT, Class::IN),
+            Some(CachedAnswer::NXDomain(soa_record(300, 300)))
+        );
+
+        let nodata = DNSPacket::new(
+            Header {
+                response_code: ResponseCode::NoError,
+                answer_count: 0,
+                ..header
+            },
+            Some(vec![Question {
+                domain: domain("www.example.com"),
+                record_type: QueryType::AAAA,
+                class: Class::IN,
+            }]),
+            None,
+            Some(vec![soa_record(60, 300)]),
+            None,
+        );
+        cache.insert_response(&nodata);
+        let www = domain("www.example.com");
+        assert_eq!(ttls(cache.get(&www, &QueryType::AAAA, Class::IN)), vec![60]);
+        assert!(matches!(
+            cache.get(&www, &QueryType::AAAA, Class::IN),
+            Some(CachedAnswer::NoData(_))
+        ));
+        assert_eq!(cache.get(&www, &QueryType::A, Class::IN), None);
+
+        clock.advance(60);
+        assert_eq!(cache.get(&www, &QueryType::AAAA, Class::IN), None);
+        assert_eq!(
+            ttls(cache.get(&missing, &QueryType::A, Class::IN)),
+            vec![240]
+        );
+
+        // Records cached later for the name take precedence over the NXDOMAIN
+        cache.insert_rrset(&[a_record("missing.example.com", 300, 1)]);
+        assert_eq!(
+            ttls(cache.get(&missing, &QueryType::A, Class::IN)),
+            vec![300]
+        );
+        assert!(matches!(
+            cache.get(&missing, &QueryType::TXT, Class::IN),
+            Some(CachedAnswer::NXDomain(_))
+        ));
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = Cache::new(2);
+        cache.insert_rrset(&[a_record("a.example.com", 300, 1)]);
+        cache.insert_rrset(&[a_record("b.example.com", 300, 2)]);
+        // a is used, which makes b the least recently used entry
+        assert!(cache
+            .get(&domain("a.example.com"), &QueryType::A, Class::IN)
+            .is_some());
+        cache.insert_rrset(&[a_record("c.example.com", 300, 3)]);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache
+            .get(&domain("a.example.com"), &QueryType::A, Class::IN)
+            .is_some());
+        assert!(cache
+            .get(&domain("b.example.com"), &QueryType::A, Class::IN)
+            .is_none());
+        assert!(cache
+            .get(&domain("c.example.com"), &QueryType::A, Class::IN)
+            .is_some());
+
+        // Replacing an entry doesn't evict another one
+        cache.insert_rrset(&[a_record("c.example.com", 600, 4)]);
+        assert_eq!(cache.len(), 2);
+        cache.remove(&domain("a.example.com"), Some(&QueryType::A), Class::IN);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_serve_stale() {
+        let clock = ManualClock::new();
+        let mut cache = Cache::with_clock(10, clock.clone());
+        cache.set_stale_period(Duration::from_secs(3600));
+        cache.insert_rrset(&[a_record("www.example.com", 300, 1)]);
+        let name = domain("www.example.com");
+
+        clock.advance(100);
+        assert_eq!(
+            ttls(cache.get_stale(&name, &QueryType::A, Class::IN)),
+            vec![200]
+        );
+        clock.advance(200);
+        assert_eq!(cache.get(&name, &QueryType::A, Class::IN), None);
+        assert_eq!(
+            ttls(cache.get_stale(&name, &QueryType::A, Class::IN)),
+            vec![STALE_ANSWER_TTL]
+        );
+        assert_eq!(cache.len(), 1);
+
+        clock.advance(3600);
+        assert_eq!(cache.get_stale(&name, &QueryType::A, Class::IN), None);
+        assert!(cache.is_empty());
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 2,
+                stale_hits: 1,
+                prefetches: 0,
+                insertions: 1,
+                evictions: 0,
+                expirations: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_prefetch_due() {
+        let clock = ManualClock::new();
+        let mut cache = Cache::with_clock(10, clock.clone());
+        cache.insert_rrset(&[a_record("www.example.com", 100, 1)]);
+        let name = domain("www.example.com");
+        for _ in 0..3 {
+            cache.get(&name, &QueryType::A, Class::IN);
+        }
+        clock.advance(95);
+        assert!(!cache.prefetch_due(&name, &QueryType::A, Class::IN));
+
+        cache.set_prefetch_hits(Some(3));
+        clock.advance(95);
+        // Unpopular entries are left to expire
+        cache.insert_rrset(&[a_record("mail.example.com", 100, 2)]);
+        assert!(!cache.prefetch_due(&domain("mail.example.com"), &QueryType::A, Class::IN));
+        cache.insert_rrset(&[a_record("www.example.com", 100, 1)]);
+        for _ in 0..3 {
+            cache.get(&name, &QueryType::A, Class::IN);
+        }
+        assert!(!cache.prefetch_due(&name, &QueryType::A, Class::IN));
+        clock.advance(89);
+        assert!(!cache.prefetch_due(&name, &QueryType::A, Class::IN));
+        clock.advance(2);
+        assert!(cache.prefetch_due(&name, &QueryType::A, Class::IN));
+        assert!(!cache.prefetch_due(&name, &QueryType::A, Class::IN));
+        assert_eq!(cache.stats().prefetches, 1);
+
+        // The refreshed entry can be prefetched again
+        cache.insert_rrset(&[a_record("www.example.com", 100, 1)]);
+        for _ in 0..3 {
+            cache.get(&name, &QueryType::A, Class::IN);
+        }
+        clock.advance(91);
+        assert!(cache.prefetch_due(&name, &QueryType::A, Class::IN));
+    }
+
+    /// Upstream answering with the given records, or timing out.
+    struct MockUpstream {
+        records: Mutex<Option<Vec<Record>>>,
+        queries: AtomicUsize,
+    }
+
+    impl MockUpstream {
+        fn set_records(&self, records: Option<Vec<Record>>) {
+            *self.records.lock().unwrap() = records;
+        }
+
+        fn queries(&self) -> usize {
+            self.queries.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Upstream for MockUpstream {
+        fn query(&self, name: &Domain, record_type: QueryType) -> Result<DNSPacket, DNSError> {
+            self.queries.fetch_add(1, Ordering::SeqCst);
+            let records = self.records.lock().unwrap().clone();
+            let answers = records.ok_or(DNSError::Timeout)?;
+            let header = Header {
+                id: 1,
+                query_response: true,
+                opcode: Opcode::Query,
+                authoritative_answer: false,
+                truncated_message: false,
+                recursion_desired: true,
+                recursion_available: true,
+                reserved: false,
+                authentic_data: false,
+                checking_disabled: false,
+                response_code: ResponseCode::NoError,
+                question_count: 1,
+                answer_count: answers.len() as u16,
+                authority_count: 0,
+                additional_count: 0,
+            };
+            let question = Question {
+                domain: name.clone(),
+                record_type,
+                class: Class::IN,
+            };
+            Ok(DNSPacket::new(
+                header,
+                Some(vec![question]),
+                Some(answers),
+                None,
+                None,
+            ))
+        }
+    }
+
+    #[test]
+    fn test_caching_resolver() {
+        let clock = ManualClock::new();
+        let mut cache = Cache::with_clock(10, clock.clone());
+        cache.set_stale_period(Duration::from_secs(3600));
+        cache.set_prefetch_hits(Some(2));
+        let cname = Record::new(
+            domain("alias.example.com"),
+            Class::IN,
+            600,
+            RecordData::CNAME(CNAME {
+                cname: domain("www.example.com"),
+            }),
+        );
+        let upstream = MockUpstream {
+            records: Mutex::new(Some(vec![
+                cname.clone(),
+                a_record("www.example.com", 300, 1),
+            ])),
+            queries: AtomicUsize::new(0),
+        };
+        let resolver = CachingResolver::new(upstream, cache);
+        let alias = domain("alias.example.com");
+
+        // The chain of aliases is answered from the cache
+        let answers = resolver.lookup(&alias, QueryType::A).unwrap();
+        assert_eq!(answers.len(), 2);
+        clock.advance(100);
+        assert_eq!(
+            resolver.lookup(&alias, QueryType::A).unwrap(),
+            vec![with_ttl(&cname, 500), a_record("www.example.com", 200, 1)]
+        );
+        assert_eq!(resolver.upstream().queries(), 1);
+
+        // Expired records are served stale when the upstream fails
+        resolver.upstream().set_records(None);
+        clock.advance(300);
+        let response = resolver.query(&alias, QueryType::A).unwrap();
+        assert_eq!(response.questions()[0].domain, alias);
+        assert_eq!(
+            response.answers(),
+            [
+                with_ttl(&cname, 200),
+                a_record("www.example.com", STALE_ANSWER_TTL, 1)
+            ]
+        );
+        assert_eq!(resolver.upstream().queries(), 2);
+        assert_eq!(resolver.cache().stats().stale_hits, 1);
+        assert!(matches!(
+            resolver.lookup(&domain("mail.example.com"), QueryType::A),
+            Err(DNSError::Timeout)
+        ));
+
+        // Popular records are refreshed before they expire
+        resolver
+            .upstream()
+            .set_records(Some(vec![a_record("www.example.com", 300, 2)]));
+        let www = domain("www.example.com");
+        resolver.lookup(&www, QueryType::A).unwrap();
+        resolver.lookup(&www, QueryType::A).unwrap();
+        clock.advance(280);
+        assert_eq!(resolver.upstream().queries(), 4);
+        resolver.lookup(&www, QueryType::A).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while resolver.upstream().queries() < 5 || resolver.cache().stats().insertions < 4 {
+            assert!(Instant::now() < deadline, "prefetch did not happen");
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(
+            resolver.lookup(&www, QueryType::A).unwrap(),
+            vec![a_record("www.example.com", 300, 2)]
+        );
+        assert_eq!(resolver.cache().stats().prefetches, 1);
+    }
+}
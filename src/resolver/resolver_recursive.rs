@@ -0,0 +1,911 @@
+use super::resolver_stub::Resolver;
+use crate::dns_packet::{
+    Class, DNSError, DNSPacket, Domain, Edns, Header, QueryType, Question, Record, RecordData,
+    ResponseCode, CNAME,
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+pub const DEFAULT_MAX_QUERIES: usize = 100;
+pub const DEFAULT_MAX_DEPTH: usize = 5;
+/// Longest chain of CNAME and DNAME aliases followed, protecting against loops.
+const MAX_ALIASES: usize = 8;
+const DNS_PORT: u16 = 53;
+/// Query type matching all records.
+const ANY: u16 = 255;
+
+/// IPv4 addresses of the root servers, a to m.root-servers.net.
+pub const ROOT_HINTS: [IpAddr; 13] = [
+    IpAddr::V4(Ipv4Addr::new(198, 41, 0, 4)),
+    IpAddr::V4(Ipv4Addr::new(170, 247, 170, 2)),
+    IpAddr::V4(Ipv4Addr::new(192, 33, 4, 12)),
+    IpAddr::V4(Ipv4Addr::new(199, 7, 91, 13)),
+    IpAddr::V4(Ipv4Addr::new(192, 203, 230, 10)),
+    IpAddr::V4(Ipv4Addr::new(192, 5, 5, 241)),
+    IpAddr::V4(Ipv4Addr::new(192, 112, 36, 4)),
+    IpAddr::V4(Ipv4Addr::new(198, 97, 190, 53)),
+    IpAddr::V4(Ipv4Addr::new(192, 36, 148, 17)),
+    IpAddr::V4(Ipv4Addr::new(192, 58, 128, 30)),
+    IpAddr::V4(Ipv4Addr::new(193, 0, 14, 129)),
+    IpAddr::V4(Ipv4Addr::new(199, 7, 83, 42)),
+    IpAddr::V4(Ipv4Addr::new(202, 12, 27, 33)),
+];
+
+/// Nameservers of a zone, as learned from a referral.
+struct Delegation {
+    zone: Domain,
+    nameservers: Vec<Domain>,
+    /// Addresses of the nameservers given as glue, tried before resolving their names.
+    addresses: Vec<IpAddr>,
+}
+
+enum Outcome {
+    Answer(DNSPacket),
+    Referral(Delegation),
+}
+
+/// Resolver finding the answer to a query itself, starting from the root servers and following
+/// the referrals of authoritative nameservers down to the zone of the name (RFC 1034 section
+/// 5.3.3). The work done for a query is bounded by a number of queries sent, and by how deep
+/// resolving the addresses of nameservers may nest.
+pub struct RecursiveResolver {
+    root_hints: Vec<IpAddr>,
+    port: u16,
+    /// Where to query the nameservers at some addresses instead of at the port.
+    redirects: HashMap<IpAddr, SocketAddr>,
+    client: Resolver,
+    max_queries: usize,
+    max_depth: usize,
+    ipv6: bool,
+}
+
+impl RecursiveResolver {
+    /// Create a resolver starting from the root servers at the given addresses, such as
+    /// ROOT_HINTS.
+    pub fn new(root_hints: Vec<IpAddr>) -> Self {
+        let mut client = Resolver::new(Vec::new());
+        client.set_recursion_desired(false);
+        RecursiveResolver {
+            root_hints,
+            port: DNS_PORT,
+            redirects: HashMap::new(),
+            client,
+            max_queries: DEFAULT_MAX_QUERIES,
+            max_depth: DEFAULT_MAX_DEPTH,
+            ipv6: false,
+        }
+    }
+
+    pub fn root_hints(&self) -> &[IpAddr] {
+        &self.root_hints
+    }
+
+    /// Set the port nameservers are queried on, 53 by default.
+    pub fn set_port(&mut self, port: u16) {
+        self.port = port;
+    }
+
+    /// Query the nameserver at an address, as given by the root hints or glue, at another socket
+    /// address, e.g. to run a hierarchy of nameservers on the ports of a single host.
+    pub fn redirect_nameserver(&mut self, address: IpAddr, socket_addr: SocketAddr) {
+        self.redirects.insert(address, socket_addr);
+    }
+
+    /// Set how long to wait for each nameserver to respond.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.client.set_timeout(timeout);
+    }
+
+    /// Set the EDNS information sent with queries, or None to send plain DNS queries.
+    pub fn set_edns(&mut self, edns: Option<Edns>) {
+        self.client.set_edns(edns);
+    }
+
+    /// Set how many queries may be sent to resolve a name, including the ones resolving the
+    /// addresses of nameservers and the targets of aliases.
+    pub fn set_max_queries(&mut self, max_queries: usize) {
+        self.max_queries = max_queries;
+    }
+
+    /// Set how deep the resolution of nameserver addresses may nest, when a delegation comes
+    /// without glue.
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// Set whether nameservers are also queried over IPv6, which they are not by default.
+    pub fn set_ipv6(&mut self, ipv6: bool) {
+        self.ipv6 = ipv6;
+    }
+
+    /// Look up the records of the given name and type, returning the answers along with the
+    /// aliases leading to them. Responses other than NOERROR are returned as an ErrorResponse
+    /// error.
+    pub fn lookup(&self, name: &Domain, record_type: QueryType) -> Result<Vec<Record>, DNSError> {
+        let response = self.query(name, record_type)?;
+        match response.response_code()? {
+            ResponseCode::NoError => Ok(response.answers().to_vec()),
+            response_code => Err(DNSError::ErrorResponse(response_code)),
+        }
+    }
+
+    /// Resolve a query and return the response of the last authoritative nameserver asked. Its
+    /// answer section holds the whole chain of aliases from the name to the records.
+    pub fn query(&self, name: &Domain, record_type: QueryType) -> Result<DNSPacket, DNSError> {
+        let mut queries = 0;
+        self.resolve(name, &record_type, 0, &mut queries)
+    }
+
+    /// Resolve a name, restarting from the root for each alias whose target is not answered by
+    /// the nameserver of the alias.
+    fn resolve(
+        &self,
+        name: &Domain,
+        record_type: &QueryType,
+        depth: usize,
+        queries: &mut usize,
+    ) -> Result<DNSPacket, DNSError> {
+        let mut chain = Vec::<Record>::new();
+        let mut target = name.clone();
+        // The aliases followed in every zone count against the same limit, and each new target
+        // comes from at least one alias, so this ends
+        let mut aliases = MAX_ALIASES;
+        loop {
+            let (response, zone) =
+                self.resolve_iteratively(&target, record_type, depth, queries)?;
+            let (records, unresolved) =
+                follow_aliases(&response, &target, record_type, &zone, &mut aliases)?;
+            chain.extend(records);
+
+            let negative = response.response_code()? != ResponseCode::NoError
+                || response
+                    .authorities()
+                    .iter()
+                    .any(|record| record.record_type() == QueryType::SOA);
+            match unresolved {
+                Some(next) if !negative && next != target => target = next,
+                _ => return Ok(final_response(&response, name, record_type, chain)),
+            }
+        }
+    }
+
+    /// Resolve a name from the root, without following aliases. Return the answer along with the
+    /// zone of the nameserver which gave it.
+    fn resolve_iteratively(
+        &self,
+        name: &Domain,
+        record_type: &QueryType,
+        depth: usize,
+        queries: &mut usize,
+    ) -> Result<(DNSPacket, Domain), DNSError> {
+        let question = Question {
+            domain: name.clone(),
+            record_type: record_type.clone(),
+            class: Class::IN,
+        };
+        let mut delegation = Delegation {
+            zone: Domain::root(),
+            nameservers: Vec::new(),
+            addresses: self.root_hints.clone(),
+        };
+        // Each referral gets closer to the name, so this ends
+        loop {
+            match self.query_delegation(&delegation, &question, depth, queries)? {
+                Outcome::Answer(response) => return Ok((response, delegation.zone)),
+                Outcome::Referral(referral) => delegation = referral,
+            }
+        }
+    }
+
+    /// Ask the nameservers of a zone in turn, until one of them answers or refers to a zone closer
+    /// to the name. Nameservers given as glue are tried first, then the names of the other ones
+    /// are resolved.
+    fn query_delegation(
+        &self,
+        delegation: &Delegation,
+        question: &Question,
+        depth: usize,
+        queries: &mut usize,
+    ) -> Result<Outcome, DNSError> {
+        let mut last_err = DNSError::UnreachableZone(delegation.zone.to_string());
+        let mut tried = Vec::<IpAddr>::new();
+
+        for address in delegation.addresses.iter() {
+            if let Some(outcome) = self.try_nameserver(
+                *address,
+                question,
+                delegation,
+                queries,
+                &mut tried,
+                &mut last_err,
+            )? {
+                return Ok(outcome);
+            }
+        }
+
+        for nameserver in delegation.nameservers.iter() {
+            // Without glue, names below the zone can only be resolved through this delegation
+            if nameserver.is_subdomain_of(&delegation.zone) {
+                continue;
+            }
+            if depth >= self.max_depth {
+                last_err = DNSError::MaxDepth(self.max_depth);
+                continue;
+            }
+
+            let addresses = match self.resolve_addresses(nameserver, depth + 1, queries) {
+                Ok(addresses) => addresses,
+                Err(err @ DNSError::MaxQueries(_)) => return Err(err),
+                Err(err) => {
+                    last_err = err;
+                    continue;
+                }
+            };
+            for address in addresses {
+                if let Some(outcome) = self.try_nameserver(
+                    address,
+                    question,
+                    delegation,
+                    queries,
+                    &mut tried,
+                    &mut last_err,
+                )? {
+                    return Ok(outcome);
+                }
+            }
+        }
+
+        Err(last_err)
+    }
+
+    /// Send the question to a nameserver of the delegation. Return None if it is not usable,
+    /// e.g. unreachable, failing or lame, and fail only once too many queries were sent.
+    fn try_nameserver(
+        &self,
+        address: IpAddr,
+        question: &Question,
+        delegation: &Delegation,
+        queries: &mut usize,
+        tried: &mut Vec<IpAddr>,
+        last_err: &mut DNSError,
+    ) -> Result<Option<Outcome>, DNSError> {
+        if !self.ipv6 && address.is_ipv6() || tried.contains(&address) {
+            return Ok(None);
+        }
+        tried.push(address);
+
+        if *queries >= self.max_queries {
+            return Err(DNSError::MaxQueries(self.max_queries));
+        }
+        *queries += 1;
+
+        let socket_addr = self
+            .redirects
+            .get(&address)
+            .copied()
+            .unwrap_or(SocketAddr::new(address, self.port));
+        let outcome = self
+            .client
+            .exchange(question, socket_addr)
+            .and_then(|response| self.classify(response, question, &delegation.zone));
+        match outcome {
+            Ok(outcome) => Ok(Some(outcome)),
+            Err(err) => {
+                *last_err = err;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Tell whether the response of a nameserver of the zone answers the question or refers to
+    /// another zone. Errors and referrals which don't get closer to the name are rejected.
+    fn classify(
+        &self,
+        response: DNSPacket,
+        question: &Question,
+        zone: &Domain,
+    ) -> Result<Outcome, DNSError> {
+        match response.response_code()? {
+            ResponseCode::NoError if response.answers().is_empty() => (),
+            ResponseCode::NoError | ResponseCode::NXDomain => return Ok(Outcome::Answer(response)),
+            response_code => return Err(DNSError::ErrorResponse(response_code)),
+        }
+
+        let nameservers: Vec<(&Domain, &Domain)> = response
+            .authorities()
+            .iter()
+            .filter_map(|record| match record.data() {
+                RecordData::NS(ns) => Some((record.domain(), &ns.nsdname)),
+                _ => None,
+            })
+            .collect();
+        let Some((child, _)) = nameservers.first() else {
+            // No data, with or without the SOA of the zone
+            return Ok(Outcome::Answer(response));
+        };
+        if *child == zone {
+            // No data, with the NS RRset of the zone instead of its SOA
+            return Ok(Outcome::Answer(response));
+        }
+
+        let closer = question.domain.is_subdomain_of(child) && child.is_subdomain_of(zone);
+        if !closer
+            || response
+                .authorities()
+                .iter()
+                .any(|record| record.record_type() == QueryType::SOA)
+        {
+            // Upward or sideways referral from a nameserver which is not authoritative
+            return Err(DNSError::ErrorResponse(ResponseCode::ServFail));
+        }
+
+        let nameservers: Vec<Domain> = nameservers
+            .iter()
+            .filter(|(owner, _)| owner == child)
+            .map(|(_, nameserver)| (*nameserver).clone())
+            .collect();
+        // Only glue within the zone of the nameserver can be trusted
+        let addresses = response
+            .additional_records()
+            .iter()
+            .filter(|record| {
+                record.domain().is_subdomain_of(zone)
+                    && nameservers
+                        .iter()
+                        .any(|nameserver| record.domain() == nameserver)
+            })
+            .filter_map(record_address)
+            .collect();
+
+        Ok(Outcome::Referral(Delegation {
+            zone: (*child).clone(),
+            nameservers,
+            addresses,
+        }))
+    }
+
+    /// Resolve the addresses of a nameserver from the root.
+    fn resolve_addresses(
+        &self,
+        nameserver: &Domain,
+        depth: usize,
+        queries: &mut usize,
+    ) -> Result<Vec<IpAddr>, DNSError> {
+        let mut record_types = vec![QueryType::A];
+        if self.ipv6 {
+            record_types.push(QueryType::AAAA);
+        }
+
+        let mut addresses = Vec::<IpAddr>::new();
+        for record_type in record_types.iter() {
+            let response = self.resolve(nameserver, record_type, depth, queries)?;
+            addresses.extend(response.answers().iter().filter_map(record_address));
+        }
+        Ok(addresses)
+    }
+}
+
+fn record_address(record: &Record) -> Option<IpAddr> {
+    match record.data() {
+        RecordData::A(a) => Some(IpAddr::V4(a.addr)),
+        RecordData::AAAA(aaaa) => Some(IpAddr::V6(aaaa.addr)),
+        _ => None,
+    }
+}
+
+/// Collect the records answering the question for a name from the response, following the CNAME
+/// and DNAME aliases it contains. Records of names outside of the zone of the nameserver are not
+/// trusted. Each alias followed is taken from the remaining ones, shared by the whole resolution.
+/// Return the chain of records, and the name left unanswered if any.
+fn follow_aliases(
+    response: &DNSPacket,
+    name: &Domain,
+    record_type: &QueryType,
+    zone: &Domain,
+    aliases: &mut usize,
+) -> Result<(Vec<Record>, Option<Domain>), DNSError> {
+    let mut records = Vec::<Record>::new();
+    let mut current = name.clone();
+    loop {
+        if !current.is_subdomain_of(zone) {
+            return Ok((records, Some(current)));
+        }
+
+        let owned: Vec<&Record> = response
+            .answers()
+            .iter()
+            .filter(|record| record.domain() == &current)
+            .collect();
+        let matching: Vec<Record> = owned
+            .iter()
+            .filter(|record| record.record_type() == *record_type || record_type.to_num() == ANY)
+            .map(|record| (*record).clone())
+            .collect();
+        if !matching.is_empty() {
+            records.extend(matching);
+            return Ok((records, None));
+        }
+
+        // A DNAME of an ancestor comes with the CNAME it synthesizes, which is derived again in
+        // case it is missing (RFC 6672 section 3.4)
+        let dname = response
+            .answers()
+            .iter()
+            .find_map(|record| match record.data() {
+                RecordData::DNAME(dname)
+                    if current.is_subdomain_of(record.domain()) && &current != record.domain() =>
+                {
+                    Some((record, dname))
+                }
+                _ => None,
+            });
+        let cname = owned.iter().find_map(|record| match record.data() {
+            RecordData::CNAME(cname) => Some((*record, cname)),
+            _ => None,
+        });
+
+        let target = match (dname, cname) {
+            (_, Some((record, cname))) => {
+                if let Some((dname_record, _)) = dname {
+                    records.push(dname_record.clone());
+                }
+                records.push(record.clone());
+                cname.cname.clone()
+            }
+            (Some((record, dname)), None) => {
+                let target = current.replace_ancestor(record.domain(), &dname.dname)?;
+                records.push(record.clone());
+                records.push(Record::new(
+                    current.clone(),
+                    record.class(),
+                    record.ttl(),
+                    RecordData::CNAME(CNAME {
+                        cname: target.clone(),
+                    }),
+                ));
+                target
+            }
+            (None, None) => return Ok((records, Some(current))),
+        };
+        if *aliases == 0 {
+            return Err(DNSError::MaxAliases(MAX_ALIASES));
+        }
+        *aliases -= 1;
+        current = target;
+    }
+}
+
+/// Response to the original question, with the chain of answers collected along the aliases.
+fn final_response(
+    response: &DNSPacket,
+    name: &Domain,
+    record_type: &QueryType,
+    answers: Vec<Record>,
+) -> DNSPacket {
+    let question = Question {
+        domain: name.clone(),
+        record_type: record_type.clone(),
+        class: Class::IN,
+    };
+    let header = Header {
+        question_count: 1,
+        answer_count: answers.len() as u16,
+        authority_count: response.authorities().len() as u16,
+        additional_count: response.additional_records().len() as u16,
+        ..response.header().clone()
+    };
+    let mut packet = DNSPacket::new(
+        header,
+        Some(vec![question]),
+        Some(answers),
+        Some(response.authorities().to_vec()),
+        Some(response.additional_records().to_vec()),
+    );
+    packet.set_edns(response.edns().cloned());
+    packet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::Opcode;
+    use crate::server::{Server, ServerHandle, Zone, ZoneStore};
+    use crate::zone::parse_zone;
+    use std::net::UdpSocket;
+
+    const ROOT: &str = r#"
+$TTL 3600
+.               IN SOA ns.root. hostmaster.root. 1 7200 3600 1209600 300
+.               IN NS  ns.root.
+ns.root.        IN A   127.0.0.1
+com.            IN NS  ns.com.
+ns.com.         IN A   127.0.0.2
+net.            IN NS  ns.net.
+ns.net.         IN A   127.0.0.3
+"#;
+
+    const COM: &str = r#"
+$TTL 3600
+@               IN SOA ns hostmaster 1 7200 3600 1209600 300
+                IN NS  ns
+ns              IN A   127.0.0.2
+example         IN NS  ns1.example
+ns1.example     IN A   127.0.0.4
+other           IN NS  ns.hosting.net.
+"#;
+
+    const NET: &str = r#"
+$TTL 3600
+@               IN SOA ns hostmaster 1 7200 3600 1209600 300
+                IN NS  ns
+ns              IN A   127.0.0.3
+hosting         IN NS  ns.hosting
+ns.hosting      IN A   127.0.0.5
+"#;
+
+    const EXAMPLE_COM: &str = r#"
+$TTL 3600
+@               IN SOA ns1 hostmaster 1 7200 3600 1209600 300
+                IN NS  ns1
+ns1             IN A   127.0.0.4
+www             IN A   192.0.2.1
+alias           IN CNAME www.other.com.
+local           IN CNAME www
+old             IN DNAME other.com.
+c0              IN CNAME c1
+c1              IN CNAME c2.other.com.
+c4              IN CNAME c5
+c5              IN CNAME c6.other.com.
+c8              IN CNAME c9
+c9              IN CNAME www
+"#;
+
+    const HOSTING_NET: &str = r#"
+$TTL 3600
+@               IN SOA ns hostmaster 1 7200 3600 1209600 300
+                IN NS  ns
+ns              IN A   127.0.0.5
+"#;
+
+    const OTHER_COM: &str = r#"
+$TTL 3600
+@               IN SOA ns.hosting.net. hostmaster 1 7200 3600 1209600 300
+                IN NS  ns.hosting.net.
+www             IN A   198.51.100.1
+c2              IN CNAME c3
+c3              IN CNAME c4.example.com.
+c6              IN CNAME c7
+c7              IN CNAME c8.example.com.
+"#;
+
+    fn domain(name: &str) -> Domain {
+        name.parse().unwrap()
+    }
+
+    /// Nameservers of a small hierarchy, each on its own port of the loopback address. The
+    /// addresses given to them by hints and glue are redirected to their ports.
+    struct Network {
+        redirects: Vec<(IpAddr, SocketAddr)>,
+        servers: Vec<ServerHandle>,
+    }
+
+    impl Network {
+        fn start() -> Self {
+            let zones: [(u8, &[(&str, &str)]); 5] = [
+                (1, &[("", ROOT)]),
+                (2, &[("com", COM)]),
+                (3, &[("net", NET)]),
+                (4, &[("example.com", EXAMPLE_COM)]),
+                (5, &[("hosting.net", HOSTING_NET), ("other.com", OTHER_COM)]),
+            ];
+
+            let mut redirects = Vec::<(IpAddr, SocketAddr)>::new();
+            let mut servers = Vec::<ServerHandle>::new();
+            for (host, zones) in zones.iter() {
+                let mut store = ZoneStore::new();
+                for (origin, text) in zones.iter() {
+                    let records = parse_zone(text, &domain(origin)).unwrap();
+                    store.insert(Zone::new(domain(origin), records).unwrap());
+                }
+                let server = Server::bind("127.0.0.1:0".parse().unwrap(), store)
+                    .unwrap()
+                    .spawn()
+                    .unwrap();
+                redirects.push((Ipv4Addr::new(127, 0, 0, *host).into(), server.local_addr()));
+                servers.push(server);
+            }
+            Network { redirects, servers }
+        }
+
+        fn resolver(&self, root_hints: Vec<IpAddr>) -> RecursiveResolver {
+            let mut resolver = RecursiveResolver::new(root_hints);
+            for (address, socket_addr) in self.redirects.iter() {
+                resolver.redirect_nameserver(*address, *socket_addr);
+            }
+            resolver.set_timeout(Duration::from_millis(200));
+            resolver
+        }
+
+        fn shutdown(self) {
+            for server in self.servers {
+                server.shutdown();
+            }
+        }
+    }
+
+    fn root() -> Vec<IpAddr> {
+        vec![Ipv4Addr::new(127, 0, 0, 1).into()]
+    }
+
+    /// Records in presentation format, one per line.
+    fn lines(records: &[Record]) -> Vec<String> {
+        records.iter().map(|record| record.to_string()).collect()
+    }
+
+    #[test]
+    fn test_follow_referrals() {
+        let network = Network::start();
+        let resolver = network.resolver(root());
+
+        let records = resolver
+            .lookup(&domain("www.example.com"), QueryType::A)
+            .unwrap();
+        assert_eq!(
+            lines(&records),
+            vec!["www.example.com.\t3600\tIN\tA\t192.0.2.1"]
+        );
+
+        let nxdomain = resolver
+            .query(&domain("nothing.example.com"), QueryType::A)
+            .unwrap();
+        assert_eq!(nxdomain.response_code(), Ok(ResponseCode::NXDomain));
+        assert!(nxdomain.header().authoritative_answer);
+        assert_eq!(nxdomain.authorities()[0].record_type(), QueryType::SOA);
+
+        // Root, com and example.com nameservers are asked in turn
+        let mut limited = network.resolver(root());
+        limited.set_max_queries(2);
+        assert_eq!(
+            limited.lookup(&domain("www.example.com"), QueryType::A),
+            Err(DNSError::MaxQueries(2))
+        );
+        limited.set_max_queries(3);
+        assert!(limited
+            .lookup(&domain("www.example.com"), QueryType::A)
+            .is_ok());
+        network.shutdown();
+    }
+
+    #[test]
+    fn test_out_of_bailiwick_nameserver() {
+        let network = Network::start();
+        let resolver = network.resolver(root());
+
+        // The other.com delegation has no glue, ns.hosting.net is resolved from the root
+        let records = resolver
+            .lookup(&domain("www.other.com"), QueryType::A)
+            .unwrap();
+        assert_eq!(
+            lines(&records),
+            vec!["www.other.com.\t3600\tIN\tA\t198.51.100.1"]
+        );
+
+        let mut shallow = network.resolver(root());
+        shallow.set_max_depth(0);
+        assert_eq!(
+            shallow.lookup(&domain("www.other.com"), QueryType::A),
+            Err(DNSError::MaxDepth(0))
+        );
+        network.shutdown();
+    }
+
+    #[test]
+    fn test_aliases() {
+        let network = Network::start();
+        let resolver = network.resolver(root());
+
+        // Target answered by the same nameserver
+        let local = resolver
+            .lookup(&domain("local.example.com"), QueryType::A)
+            .unwrap();
+        assert_eq!(
+            lines(&local),
+            vec![
+                "local.example.com.\t3600\tIN\tCNAME\twww.example.com.",
+                "www.example.com.\t3600\tIN\tA\t192.0.2.1",
+            ]
+        );
+
+        // Target in another zone, resolved from the root
+        let alias = resolver
+            .lookup(&domain("alias.example.com"), QueryType::A)
+            .unwrap();
+        assert_eq!(
+            lines(&alias),
+            vec![
+                "alias.example.com.\t3600\tIN\tCNAME\twww.other.com.",
+                "www.other.com.\t3600\tIN\tA\t198.51.100.1",
+            ]
+        );
+
+        let dname = resolver
+            .lookup(&domain("www.old.example.com"), QueryType::A)
+            .unwrap();
+        assert_eq!(
+            lines(&dname),
+            vec![
+                "old.example.com.\t3600\tIN\tDNAME\tother.com.",
+                "www.old.example.com.\t3600\tIN\tCNAME\twww.other.com.",
+                "www.other.com.\t3600\tIN\tA\t198.51.100.1",
+            ]
+        );
+
+        // Aliases followed in every zone count against the same limit
+        let chain = resolver
+            .lookup(&domain("c2.other.com"), QueryType::A)
+            .unwrap();
+        assert_eq!(chain.len(), MAX_ALIASES + 1);
+        assert_eq!(
+            lines(&chain[MAX_ALIASES..]),
+            vec!["www.example.com.\t3600\tIN\tA\t192.0.2.1"]
+        );
+        assert_eq!(
+            resolver.lookup(&domain("c0.example.com"), QueryType::A),
+            Err(DNSError::MaxAliases(MAX_ALIASES))
+        );
+        network.shutdown();
+    }
+
+    #[test]
+    fn test_unusable_nameservers() {
+        let network = Network::start();
+
+        // The first root server never responds
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut resolver = network.resolver(vec![
+            Ipv4Addr::new(127, 0, 0, 9).into(),
+            Ipv4Addr::new(127, 0, 0, 1).into(),
+        ]);
+        resolver.redirect_nameserver(
+            Ipv4Addr::new(127, 0, 0, 9).into(),
+            silent.local_addr().unwrap(),
+        );
+        assert!(resolver
+            .lookup(&domain("www.example.com"), QueryType::A)
+            .is_ok());
+
+        // The example.com nameserver refuses queries for other zones
+        let lame = network.resolver(vec![Ipv4Addr::new(127, 0, 0, 4).into()]);
+        assert_eq!(
+            lame.lookup(&domain("www.example.net"), QueryType::A),
+            Err(DNSError::ErrorResponse(ResponseCode::Refused))
+        );
+        network.shutdown();
+    }
+
+    #[test]
+    fn test_classify_nodata_with_zone_nameservers() {
+        let resolver = RecursiveResolver::new(Vec::new());
+        let response = |owner: &str| {
+            let header = Header {
+                id: 0,
+                query_response: true,
+                opcode: Opcode::Query,
+                authoritative_answer: true,
+                truncated_message: false,
+                recursion_desired: false,
+                recursion_available: false,
+                reserved: false,
+                authentic_data: false,
+                checking_disabled: false,
+                response_code: ResponseCode::NoError,
+                question_count: 0,
+                answer_count: 0,
+                authority_count: 1,
+                additional_count: 0,
+            };
+            let ns = Record::new(
+                domain(owner),
+                Class::IN,
+                3600,
+                RecordData::NS(crate::dns_packet::NS {
+                    nsdname: domain("ns1.example.com"),
+                }),
+            );
+            DNSPacket::new(header, None, None, Some(vec![ns]), None)
+        };
+        let question = Question {
+            domain: domain("www.sub.example.com"),
+            record_type: QueryType::AAAA,
+            class: Class::IN,
+        };
+
+        // Some nameservers add the NS RRset of their zone to NODATA answers, without the SOA
+        assert!(matches!(
+            resolver.classify(response("example.com"), &question, &domain("example.com")),
+            Ok(Outcome::Answer(_))
+        ));
+        assert!(matches!(
+            resolver.classify(response("sub.example.com"), &question, &domain("example.com")),
+            Ok(Outcome::Referral(delegation)) if delegation.zone == domain("sub.example.com")
+        ));
+        assert!(matches!(
+            resolver.classify(response("com"), &question, &domain("example.com")),
+            Err(DNSError::ErrorResponse(ResponseCode::ServFail))
+        ));
+    }
+
+    #[test]
+    fn test_follow_aliases() {
+        let response = |answers: Vec<Record>| {
+            let header = Header {
+                id: 0,
+                query_response: true,
+                opcode: Opcode::Query,
+                authoritative_answer: true,
+                truncated_message: false,
+                recursion_desired: false,
+                recursion_available: false,
+                reserved: false,
+                authentic_data: false,
+                checking_disabled: false,
+                response_code: ResponseCode::NoError,
+                question_count: 0,
+                answer_count: answers.len() as u16,
+                authority_count: 0,
+                additional_count: 0,
+            };
+            DNSPacket::new(header, None, Some(answers), None, None)
+        };
+        let cname = |owner: &str, target: &str| {
+            Record::new(
+                domain(owner),
+                Class::IN,
+                300,
+                RecordData::CNAME(CNAME {
+                    cname: domain(target),
+                }),
+            )
+        };
+
+        // Records outside of the zone of the nameserver are ignored
+        let spoofed = response(vec![
+            cname("www.example.com", "www.example.net"),
+            Record::new(
+                domain("www.example.net"),
+                Class::IN,
+                300,
+                RecordData::A(crate::dns_packet::A {
+                    addr: Ipv4Addr::new(192, 0, 2, 66),
+                }),
+            ),
+        ]);
+        let mut aliases = MAX_ALIASES;
+        let (records, unresolved) = follow_aliases(
+            &spoofed,
+            &domain("www.example.com"),
+            &QueryType::A,
+            &domain("example.com"),
+            &mut aliases,
+        )
+        .unwrap();
+        assert_eq!(records, vec![cname("www.example.com", "www.example.net")]);
+        assert_eq!(unresolved, Some(domain("www.example.net")));
+
+        let looping = response(vec![
+            cname("a.example.com", "b.example.com"),
+            cname("b.example.com", "a.example.com"),
+        ]);
+        assert_eq!(
+            follow_aliases(
+                &looping,
+                &domain("a.example.com"),
+                &QueryType::A,
+                &domain("example.com"),
+                &mut aliases
+            ),
+            Err(DNSError::MaxAliases(MAX_ALIASES))
+        );
+    }
+}
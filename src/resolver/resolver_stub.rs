@@ -0,0 +1,580 @@
+use crate::dns_packet::{
+    Class, DNSError, DNSPacket, DNSPacketBuffer, Domain, Edns, Header, Opcode, QueryType, Question,
+    Record, ResponseCode, MAX_PACKET_SIZE,
+};
+use crate::transport::TcpConnection;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+pub const DEFAULT_ATTEMPTS: usize = 2;
+
+/// Blocking stub resolver, sending queries to recursive nameservers over UDP (RFC 1035 section
+/// 7). Truncated responses are retried over TCP.
+pub struct Resolver {
+    nameservers: Vec<SocketAddr>,
+    timeout: Duration,
+    attempts: usize,
+    edns: Option<Edns>,
+    recursion_desired: bool,
+    rng: SystemRandom,
+}
+
+impl Resolver {
+    /// Create a resolver querying the given nameservers in order, with EDNS enabled.
+    pub fn new(nameservers: Vec<SocketAddr>) -> Self {
+        Resolver {
+            nameservers,
+            timeout: DEFAULT_TIMEOUT,
+            attempts: DEFAULT_ATTEMPTS,
+            edns: Some(Edns::default()),
+            recursion_desired: true,
+            rng: SystemRandom::new(),
+        }
+    }
+
+    pub fn nameservers(&self) -> &[SocketAddr] {
+        &self.nameservers
+    }
+
+    /// Set how long to wait for each nameserver to respond.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Set how many times each nameserver is tried, at least once.
+    pub fn set_attempts(&mut self, attempts: usize) {
+        self.attempts = attempts.max(1);
+    }
+
+    /// Set the EDNS information sent with queries, or None to send plain DNS queries.
+    pub fn set_edns(&mut self, edns: Option<Edns>) {
+        self.edns = edns;
+    }
+
+    /// Set whether queries ask the nameservers to recurse, which they do by default. Iterative
+    /// queries to authoritative nameservers don't.
+    pub fn set_recursion_desired(&mut self, recursion_desired: bool) {
+        self.recursion_desired = recursion_desired;
+    }
+
+    /// Look up the records of the given name and type, returning the answer section. Responses
+    /// other than NOERROR are returned as an ErrorResponse error.
+    pub fn lookup(&self, name: &Domain, record_type: QueryType) -> Result<Vec<Record>, DNSError> {
+        let response = self.query(name, record_type)?;
+        match response.response_code()? {
+            ResponseCode::NoError => Ok(response.answers().to_vec()),
+            response_code => Err(DNSError::ErrorResponse(response_code)),
+        }
+    }
+
+    /// Send a query and return the full response. Nameservers are tried in turn, moving on to the
+    /// next one on timeout or when it answers with SERVFAIL, REFUSED, NOTIMP or FORMERR. If no
+    /// nameserver gives a better answer, the last of those responses is returned.
+    pub fn query(&self, name: &Domain, record_type: QueryType) -> Result<DNSPacket, DNSError> {
+        if self.nameservers.is_empty() {
+            return Err(DNSError::NoNameservers);
+        }
+
+        let question = Question {
+            domain: name.clone(),
+            record_type,
+            class: Class::IN,
+        };
+
+        let mut fallback: Option<DNSPacket> = None;
+        let mut last_err = DNSError::Timeout;
+        for _ in 0..self.attempts {
+            for nameserver in self.nameservers.iter() {
+                match self.exchange(&question, *nameserver) {
+                    Ok(response) if is_final_response(&response) => return Ok(response),
+                    Ok(response) => fallback = Some(response),
+                    Err(err) => last_err = err,
+                }
+            }
+        }
+
+        fallback.ok_or(last_err)
+    }
+
+    fn query_id(&self) -> Result<u16, DNSError> {
+        random_id(&self.rng)
+    }
+
+    fn query_packet(&self, id: u16, question: &Question) -> DNSPacket {
+        query_packet(id, question, self.recursion_desired, self.edns.clone())
+    }
+
+    /// Send a query to a nameserver over UDP, retrying over TCP if the response is truncated
+    /// (RFC 7766 section 5).
+    pub(crate) fn exchange(
+        &self,
+        question: &Question,
+        nameserver: SocketAddr,
+    ) -> Result<DNSPacket, DNSError> {
+        let response = self.exchange_udp(question, nameserver)?;
+        if response.header().truncated_message {
+            return self.exchange_tcp(question, nameserver);
+        }
+        Ok(response)
+    }
+
+    /// Send a query to a nameserver over UDP from a fresh socket, and wait for its response.
+    /// Datagrams which don't come from the nameserver or don't answer the query are discarded, so
+    /// they can't be used to spoof a response.
+    fn exchange_udp(
+        &self,
+        question: &Question,
+        nameserver: SocketAddr,
+    ) -> Result<DNSPacket, DNSError> {
+        let id = self.query_id()?;
+        let query = self.query_packet(id, question).write_dns_packet()?;
+
+        let local_addr: SocketAddr = match nameserver {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = UdpSocket::bind(local_addr)?;
+        socket.send_to(query.get_data(), nameserver)?;
+
+        let deadline = Instant::now() + self.timeout;
+        let mut recv_data = vec![0; MAX_PACKET_SIZE];
+        loop {
+            socket.set_read_timeout(Some(remaining_time(deadline)?))?;
+            let (recv_len, source) = socket.recv_from(&mut recv_data)?;
+            if source != nameserver {
+                continue;
+            }
+
+            let mut buffer = DNSPacketBuffer::new(&recv_data[..recv_len]);
+            match DNSPacket::parse_dns_packet(&mut buffer) {
+                Ok(response) if is_response_to(&response, id, question) => return Ok(response),
+                _ => continue,
+            }
+        }
+    }
+
+    /// Send a query to a nameserver over a new TCP connection, and wait for its response. Other
+    /// messages received on the connection are discarded.
+    fn exchange_tcp(
+        &self,
+        question: &Question,
+        nameserver: SocketAddr,
+    ) -> Result<DNSPacket, DNSError> {
+        let id = self.query_id()?;
+        let query = self.query_packet(id, question);
+
+        let deadline = Instant::now() + self.timeout;
+        let mut connection = TcpConnection::connect(nameserver, self.timeout)?;
+        connection.set_timeout(Some(remaining_time(deadline)?))?;
+        connection.send(&query)?;
+        loop {
+            connection.set_timeout(Some(remaining_time(deadline)?))?;
+            match connection.receive() {
+                Ok(Some(response)) if is_response_to(&response, id, question) => {
+                    return Ok(response)
+                }
+                Ok(Some(_)) => continue,
+                Ok(None) => {
+                    return Err(DNSError::Io("connection closed by nameserver".to_string()))
+                }
+                Err(err @ (DNSError::Timeout | DNSError::Io(_))) => return Err(err),
+                // Malformed message, the framing is still intact
+                Err(_) => continue,
+            }
+        }
+    }
+}
+
+/// Pick an unpredictable query ID, making responses harder to spoof (RFC 5452 section 9.2).
+pub(crate) fn random_id(rng: &SystemRandom) -> Result<u16, DNSError> {
+    let mut id = [0u8; 2];
+    rng.fill(&mut id)
+        .map_err(|_| DNSError::Io("random number generator failed".to_string()))?;
+    Ok(u16::from_be_bytes(id))
+}
+
+/// Build a query packet for the question.
+pub(crate) fn query_packet(
+    id: u16,
+    question: &Question,
+    recursion_desired: bool,
+    edns: Option<Edns>,
+) -> DNSPacket {
+    let mut query = DNSPacket::new(
+        Header {
+            id,
+            query_response: false,
+            opcode: Opcode::Query,
+            authoritative_answer: false,
+            truncated_message: false,
+            recursion_desired,
+            recursion_available: false,
+            reserved: false,
+            authentic_data: false,
+            checking_disabled: false,
+            response_code: ResponseCode::NoError,
+            question_count: 1,
+            answer_count: 0,
+            authority_count: 0,
+            additional_count: 0,
+        },
+        Some(vec![question.clone()]),
+        None,
+        None,
+        None,
+    );
+    query.set_edns(edns);
+    query
+}
+
+/// Time left until the deadline, or a Timeout error if it passed.
+fn remaining_time(deadline: Instant) -> Result<Duration, DNSError> {
+    deadline
+        .checked_duration_since(Instant::now())
+        .filter(|remaining| !remaining.is_zero())
+        .ok_or(DNSError::Timeout)
+}
+
+/// Check that a packet is a response to the query with the given ID and question. Names are
+/// compared case-insensitively, as nameservers may not preserve their case.
+pub(crate) fn is_response_to(response: &DNSPacket, id: u16, question: &Question) -> bool {
+    let header = response.header();
+    header.query_response
+        && header.id == id
+        && match response.questions() {
+            [response_question] => {
+                response_question.record_type == question.record_type
+                    && response_question.class == question.class
+                    && response_question
+                        .domain
+                        .to_string()
+                        .eq_ignore_ascii_case(&question.domain.to_string())
+            }
+            _ => false,
+        }
+}
+
+/// A response is final unless the nameserver failed to process the query, in which case another
+/// nameserver should be tried.
+pub(crate) fn is_final_response(response: &DNSPacket) -> bool {
+    !matches!(
+        response.response_code(),
+        Ok(ResponseCode::ServFail
+            | ResponseCode::Refused
+            | ResponseCode::NoTimp
+            | ResponseCode::FormErr)
+            | Err(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::{RecordData, A};
+    use crate::transport::TcpConnection;
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    const TEST_TIMEOUT: Duration = Duration::from_millis(200);
+
+    fn domain(name: &str) -> Domain {
+        name.parse().unwrap()
+    }
+
+    fn a_record(name: &str, addr: [u8; 4]) -> Record {
+        Record::new(
+            domain(name),
+            Class::IN,
+            300,
+            RecordData::A(A {
+                addr: Ipv4Addr::from(addr),
+            }),
+        )
+    }
+
+    /// Response with the given ID and question, response code and answers.
+    fn response_with(
+        id: u16,
+        question: Question,
+        response_code: ResponseCode,
+        answers: Vec<Record>,
+    ) -> DNSPacket {
+        let header = Header {
+            id,
+            query_response: true,
+            opcode: Opcode::Query,
+            authoritative_answer: false,
+            truncated_message: false,
+            recursion_desired: true,
+            recursion_available: true,
+            reserved: false,
+            authentic_data: false,
+            checking_disabled: false,
+            response_code,
+            question_count: 1,
+            answer_count: answers.len() as u16,
+            authority_count: 0,
+            additional_count: 0,
+        };
+        DNSPacket::new(header, Some(vec![question]), Some(answers), None, None)
+    }
+
+    fn response(query: &DNSPacket, response_code: ResponseCode, answers: Vec<Record>) -> DNSPacket {
+        let question = query.questions()[0].clone();
+        response_with(query.header().id, question, response_code, answers)
+    }
+
+    fn receive_query(socket: &UdpSocket) -> (DNSPacket, SocketAddr) {
+        let mut recv_data = [0; MAX_PACKET_SIZE];
+        let (recv_len, source) = socket.recv_from(&mut recv_data).unwrap();
+        let mut buffer = DNSPacketBuffer::new(&recv_data[..recv_len]);
+        (DNSPacket::parse_dns_packet(&mut buffer).unwrap(), source)
+    }
+
+    /// Local nameserver answering a number of queries with the packets returned by respond.
+    fn fake_nameserver<F>(queries: usize, respond: F) -> (SocketAddr, JoinHandle<()>)
+    where
+        F: Fn(&DNSPacket) -> Vec<DNSPacket> + Send + 'static,
+    {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            for _ in 0..queries {
+                let (query, source) = receive_query(&socket);
+                for packet in respond(&query) {
+                    let data = packet.write_dns_packet().unwrap();
+                    socket.send_to(data.get_data(), source).unwrap();
+                }
+            }
+        });
+        (addr, handle)
+    }
+
+    fn resolver(nameservers: Vec<SocketAddr>) -> Resolver {
+        let mut resolver = Resolver::new(nameservers);
+        resolver.set_timeout(TEST_TIMEOUT);
+        resolver
+    }
+
+    #[test]
+    fn test_lookup() {
+        let (addr, handle) = fake_nameserver(1, |query| {
+            assert!(query.header().recursion_desired);
+            assert!(query.edns().is_some());
+            let question = &query.questions()[0];
+            assert_eq!(question.domain, domain("example.com"));
+            assert_eq!(question.record_type, QueryType::A);
+            vec![response(
+                query,
+                ResponseCode::NoError,
+                vec![a_record("example.com", [192, 0, 2, 1])],
+            )]
+        });
+
+        let records = resolver(vec![addr])
+            .lookup(&domain("example.com"), QueryType::A)
+            .unwrap();
+        handle.join().unwrap();
+
+        assert_eq!(records, vec![a_record("example.com", [192, 0, 2, 1])]);
+    }
+
+    #[test]
+    fn test_ignore_mismatched_responses() {
+        let (addr, handle) = fake_nameserver(1, |query| {
+            let id = query.header().id;
+            let question = |name: &str, record_type| Question {
+                domain: domain(name),
+                record_type,
+                class: Class::IN,
+            };
+
+            let wrong_id = response_with(
+                id.wrapping_add(1),
+                question("example.com", QueryType::A),
+                ResponseCode::NoError,
+                vec![a_record("example.com", [192, 0, 2, 66])],
+            );
+            let wrong_name = response_with(
+                id,
+                question("example.net", QueryType::A),
+                ResponseCode::NoError,
+                vec![a_record("example.net", [192, 0, 2, 66])],
+            );
+            let wrong_type = response_with(
+                id,
+                question("example.com", QueryType::AAAA),
+                ResponseCode::NoError,
+                Vec::new(),
+            );
+            let not_a_response = query.clone();
+            // Name servers may not preserve the case of the question
+            let answer = response_with(
+                id,
+                question("EXAMPLE.com", QueryType::A),
+                ResponseCode::NoError,
+                vec![a_record("example.com", [192, 0, 2, 1])],
+            );
+
+            vec![wrong_id, wrong_name, wrong_type, not_a_response, answer]
+        });
+
+        let records = resolver(vec![addr])
+            .lookup(&domain("Example.com"), QueryType::A)
+            .unwrap();
+        handle.join().unwrap();
+
+        assert_eq!(records, vec![a_record("example.com", [192, 0, 2, 1])]);
+    }
+
+    #[test]
+    fn test_ignore_other_source() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let (query, source) = receive_query(&socket);
+            // Well-formed response, but sent from another port than the one queried
+            let spoofer = UdpSocket::bind("127.0.0.1:0").unwrap();
+            let data = response(&query, ResponseCode::NoError, Vec::new())
+                .write_dns_packet()
+                .unwrap();
+            spoofer.send_to(data.get_data(), source).unwrap();
+        });
+
+        let mut resolver = resolver(vec![addr]);
+        resolver.set_attempts(1);
+        let result = resolver.lookup(&domain("example.com"), QueryType::A);
+        handle.join().unwrap();
+
+        assert_eq!(result, Err(DNSError::Timeout));
+    }
+
+    #[test]
+    fn test_retry_next_nameserver() {
+        // Nameserver which never answers
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let (servfail_addr, servfail_handle) = fake_nameserver(1, |query| {
+            vec![response(query, ResponseCode::ServFail, Vec::new())]
+        });
+        let (addr, handle) = fake_nameserver(1, |query| {
+            vec![response(
+                query,
+                ResponseCode::NoError,
+                vec![a_record("example.com", [192, 0, 2, 1])],
+            )]
+        });
+
+        let nameservers = vec![silent.local_addr().unwrap(), servfail_addr, addr];
+        let records = resolver(nameservers)
+            .lookup(&domain("example.com"), QueryType::A)
+            .unwrap();
+        servfail_handle.join().unwrap();
+        handle.join().unwrap();
+
+        assert_eq!(records, vec![a_record("example.com", [192, 0, 2, 1])]);
+    }
+
+    #[test]
+    fn test_tcp_fallback() {
+        let answers: Vec<Record> = (0..60)
+            .map(|i| a_record("example.com", [192, 0, 2, i]))
+            .collect();
+
+        let (addr, udp_handle) = fake_nameserver(1, |query| {
+            let full = response(query, ResponseCode::NoError, Vec::new());
+            let header = Header {
+                truncated_message: true,
+                ..full.header().clone()
+            };
+            vec![DNSPacket::new(
+                header,
+                Some(full.questions().to_vec()),
+                None,
+                None,
+                None,
+            )]
+        });
+        let listener = TcpListener::bind(addr).unwrap();
+        let tcp_answers = answers.clone();
+        let tcp_handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut connection = TcpConnection::new(stream);
+            let query = connection.receive().unwrap().unwrap();
+            // Unrelated message on the connection, to be skipped
+            let mut other = query.questions()[0].clone();
+            other.domain = domain("example.net");
+            connection
+                .send(&response_with(
+                    query.header().id,
+                    other,
+                    ResponseCode::NoError,
+                    Vec::new(),
+                ))
+                .unwrap();
+            connection
+                .send(&response(&query, ResponseCode::NoError, tcp_answers))
+                .unwrap();
+        });
+
+        let records = resolver(vec![addr])
+            .lookup(&domain("example.com"), QueryType::A)
+            .unwrap();
+        udp_handle.join().unwrap();
+        tcp_handle.join().unwrap();
+
+        assert_eq!(records, answers);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut resolver = resolver(vec![silent.local_addr().unwrap()]);
+        resolver.set_timeout(Duration::from_millis(50));
+        resolver.set_attempts(3);
+
+        let start = Instant::now();
+        let result = resolver.lookup(&domain("example.com"), QueryType::A);
+
+        assert_eq!(result, Err(DNSError::Timeout));
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        // Each attempt sent a query
+        silent.set_nonblocking(true).unwrap();
+        let mut recv_data = [0; MAX_PACKET_SIZE];
+        let queries = (0..)
+            .take_while(|_| silent.recv_from(&mut recv_data).is_ok())
+            .count();
+        assert_eq!(queries, 3);
+    }
+
+    #[test]
+    fn test_error_response() {
+        let (nxdomain_addr, nxdomain_handle) = fake_nameserver(1, |query| {
+            vec![response(query, ResponseCode::NXDomain, Vec::new())]
+        });
+        let (servfail_addr, servfail_handle) = fake_nameserver(2, |query| {
+            vec![response(query, ResponseCode::ServFail, Vec::new())]
+        });
+
+        let nxdomain = resolver(vec![nxdomain_addr]).lookup(&domain("example.com"), QueryType::A);
+        // Every attempt fails, the last SERVFAIL response is returned
+        let servfail = resolver(vec![servfail_addr]).query(&domain("example.com"), QueryType::A);
+        nxdomain_handle.join().unwrap();
+        servfail_handle.join().unwrap();
+
+        assert_eq!(
+            nxdomain,
+            Err(DNSError::ErrorResponse(ResponseCode::NXDomain))
+        );
+        assert_eq!(
+            servfail.unwrap().response_code(),
+            Ok(ResponseCode::ServFail)
+        );
+        assert_eq!(
+            Resolver::new(Vec::new()).lookup(&domain("example.com"), QueryType::A),
+            Err(DNSError::NoNameservers)
+        );
+    }
+}
@@ -0,0 +1,5 @@
+mod server_listener;
+mod server_zone;
+
+pub use server_listener::*;
+pub use server_zone::*;
@@ -0,0 +1,361 @@
+use super::ZoneStore;
+use crate::dns_packet::{
+    DNSError, DNSPacket, DNSPacketBuffer, Header, ResponseCode, DEFAULT_EDNS_PAYLOAD_SIZE,
+    MAX_PACKET_SIZE, PACKET_SIZE,
+};
+use crate::transport::{read_tcp_message, write_tcp_message};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Time after which idle TCP connections are closed (RFC 7766 section 6.2.3).
+pub const TCP_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Authoritative nameserver answering queries over UDP and TCP from the zones of a store.
+pub struct Server {
+    zones: Arc<ZoneStore>,
+    udp: UdpSocket,
+    tcp: TcpListener,
+}
+
+impl Server {
+    /// Bind the UDP and TCP sockets of the server to the address. When the port is 0, both
+    /// sockets get the same port picked by the system.
+    pub fn bind(addr: SocketAddr, zones: ZoneStore) -> Result<Self, DNSError> {
+        let udp = UdpSocket::bind(addr)?;
+        let tcp = TcpListener::bind(udp.local_addr()?)?;
+        Ok(Server {
+            zones: Arc::new(zones),
+            udp,
+            tcp,
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, DNSError> {
+        Ok(self.udp.local_addr()?)
+    }
+
+    pub fn zones(&self) -> &ZoneStore {
+        &self.zones
+    }
+
+    /// Serve queries in background threads, until the returned handle is used to shut the server
+    /// down. Each TCP connection is served by its own thread.
+    pub fn spawn(self) -> Result<ServerHandle, DNSError> {
+        let addr = self.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let udp_thread = {
+            let zones = self.zones.clone();
+            let shutdown = shutdown.clone();
+            let socket = self.udp;
+            thread::spawn(move || serve_udp(socket, zones, shutdown))
+        };
+        let tcp_thread = {
+            let zones = self.zones;
+            let shutdown = shutdown.clone();
+            let listener = self.tcp;
+            thread::spawn(move || serve_tcp(listener, zones, shutdown))
+        };
+
+        Ok(ServerHandle {
+            addr,
+            shutdown,
+            threads: vec![udp_thread, tcp_thread],
+        })
+    }
+
+    /// Serve queries, blocking the current thread.
+    pub fn run(self) -> Result<(), DNSError> {
+        self.spawn()?.join();
+        Ok(())
+    }
+}
+
+/// Handle of a server running in background threads.
+pub struct ServerHandle {
+    addr: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl ServerHandle {
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stop accepting queries and connections, and wait for the server threads to end. TCP
+    /// connections already accepted are served until they are closed or idle.
+    pub fn shutdown(self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+
+        // Wake up the threads blocked on their sockets, so they notice the shutdown
+        let wake_addr: SocketAddr = match self.addr {
+            SocketAddr::V4(addr) if addr.ip().is_unspecified() => {
+                (Ipv4Addr::LOCALHOST, addr.port()).into()
+            }
+            SocketAddr::V6(addr) if addr.ip().is_unspecified() => {
+                (Ipv6Addr::LOCALHOST, addr.port()).into()
+            }
+            addr => addr,
+        };
+        let local_addr: SocketAddr = match wake_addr {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        if let Ok(socket) = UdpSocket::bind(local_addr) {
+            let _ = socket.send_to(&[], wake_addr);
+        }
+        let _ = TcpStream::connect(wake_addr);
+
+        self.join();
+    }
+
+    /// Wait for the server threads to end.
+    pub fn join(self) {
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn serve_udp(socket: UdpSocket, zones: Arc<ZoneStore>, shutdown: Arc<AtomicBool>) {
+    let mut recv_data = vec![0; MAX_PACKET_SIZE];
+    while !shutdown.load(Ordering::SeqCst) {
+        // Errors are caused by a single datagram, e.g. an ICMP error for an earlier response
+        let Ok((recv_len, source)) = socket.recv_from(&mut recv_data) else {
+            continue;
+        };
+        if let Some(response) = respond(&zones, &recv_data[..recv_len], true) {
+            let _ = socket.send_to(&response, source);
+        }
+    }
+}
+
+fn serve_tcp(listener: TcpListener, zones: Arc<ZoneStore>, shutdown: Arc<AtomicBool>) {
+    for stream in listener.incoming() {
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        if let Ok(stream) = stream {
+            let zones = zones.clone();
+            thread::spawn(move || serve_tcp_connection(stream, &zones));
+        }
+    }
+}
+
+/// Answer the queries sent on a TCP connection in turn, until the client closes it or it stays
+/// idle for too long.
+fn serve_tcp_connection(mut stream: TcpStream, zones: &ZoneStore) {
+    if stream.set_read_timeout(Some(TCP_IDLE_TIMEOUT)).is_err() {
+        return;
+    }
+    while let Ok(Some(message)) = read_tcp_message(&mut stream) {
+        if let Some(response) = respond(zones, &message, false) {
+            if write_tcp_message(&mut stream, &response).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Response to a message received by the server, in wire format. Messages which are not queries
+/// get no response. UDP responses which don't fit in the size supported by the client are
+/// truncated, so it retries over TCP.
+fn respond(zones: &ZoneStore, message: &[u8], udp: bool) -> Option<Vec<u8>> {
+    let mut buffer = DNSPacketBuffer::new(message);
+    let query = match DNSPacket::parse_dns_packet(&mut buffer) {
+        Ok(query) if !query.header().query_response => query,
+        Ok(_) => return None,
+        Err(_) => return format_error(message),
+    };
+    let response = zones.answer(&query).ok()?;
+
+    // Clients without EDNS support at least 512 bytes (RFC 6891 section 6.2.5)
+    let max_size = match (udp, query.edns()) {
+        (false, _) => MAX_PACKET_SIZE,
+        (true, None) => PACKET_SIZE,
+        (true, Some(edns)) => {
+            (edns.udp_payload_size.min(DEFAULT_EDNS_PAYLOAD_SIZE) as usize).max(PACKET_SIZE)
+        }
+    };
+    match response.write_dns_packet_with_max_size(max_size) {
+        Ok(buffer) => Some(buffer.get_data().to_vec()),
+        Err(DNSError::EndOfBuffer) => {
+            let header = Header {
+                truncated_message: true,
+                answer_count: 0,
+                authority_count: 0,
+                additional_count: 0,
+                ..response.header().clone()
+            };
+            let mut truncated = DNSPacket::new(
+                header,
+                Some(response.questions().to_vec()),
+                None,
+                None,
+                None,
+            );
+            truncated.set_edns(response.edns().cloned());
+            let buffer = truncated.write_dns_packet_with_max_size(max_size).ok()?;
+            Some(buffer.get_data().to_vec())
+        }
+        Err(_) => None,
+    }
+}
+
+/// FORMERR response to a query which could not be parsed, if at least its header is readable.
+fn format_error(message: &[u8]) -> Option<Vec<u8>> {
+    let mut buffer = DNSPacketBuffer::new(message);
+    let query_header = Header::parse_from_buffer(&mut buffer).ok()?;
+    if query_header.query_response {
+        return None;
+    }
+
+    let header = Header {
+        id: query_header.id,
+        query_response: true,
+        opcode: query_header.opcode,
+        authoritative_answer: false,
+        truncated_message: false,
+        recursion_desired: query_header.recursion_desired,
+        recursion_available: false,
+        reserved: false,
+        authentic_data: false,
+        checking_disabled: query_header.checking_disabled,
+        response_code: ResponseCode::FormErr,
+        question_count: 0,
+        answer_count: 0,
+        authority_count: 0,
+        additional_count: 0,
+    };
+    let buffer = DNSPacket::new(header, None, None, None, None)
+        .write_dns_packet()
+        .ok()?;
+    Some(buffer.get_data().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::{Class, Domain, QueryType, Question, Record};
+    use crate::resolver::{query_packet, Resolver};
+    use crate::server::Zone;
+    use crate::transport::TcpConnection;
+    use crate::zone::parse_zone;
+
+    const ZONE: &str = r#"
+$TTL 3600
+@         IN SOA  ns1 hostmaster 1 7200 3600 1209600 300
+          IN NS   ns1
+ns1       IN A    192.0.2.1
+$GENERATE 1-100 many IN A 192.0.2.$
+"#;
+
+    fn domain(name: &str) -> Domain {
+        name.parse().unwrap()
+    }
+
+    fn spawn_server() -> ServerHandle {
+        let records = parse_zone(ZONE, &domain("example.com")).unwrap();
+        let mut zones = ZoneStore::new();
+        zones.insert(Zone::new(domain("example.com"), records).unwrap());
+        Server::bind("127.0.0.1:0".parse().unwrap(), zones)
+            .unwrap()
+            .spawn()
+            .unwrap()
+    }
+
+    fn resolver(server: &ServerHandle) -> Resolver {
+        let mut resolver = Resolver::new(vec![server.local_addr()]);
+        resolver.set_timeout(Duration::from_secs(1));
+        resolver
+    }
+
+    #[test]
+    fn test_udp_query() {
+        let server = spawn_server();
+        let response = resolver(&server)
+            .query(&domain("ns1.example.com"), QueryType::A)
+            .unwrap();
+        server.shutdown();
+
+        assert!(response.header().authoritative_answer);
+        assert_eq!(response.answers().len(), 1);
+        assert!(response.edns().is_some());
+    }
+
+    #[test]
+    fn test_truncated_response() {
+        let server = spawn_server();
+        let mut resolver = resolver(&server);
+        // Without EDNS, the 100 addresses don't fit in 512 bytes
+        resolver.set_edns(None);
+        let records: Vec<Record> = resolver
+            .lookup(&domain("many.example.com"), QueryType::A)
+            .unwrap();
+        server.shutdown();
+
+        // The resolver retried over TCP
+        assert_eq!(records.len(), 100);
+    }
+
+    #[test]
+    fn test_tcp_connection() {
+        let server = spawn_server();
+        let addr = server.local_addr();
+        let query = |id: u16, name: &str| {
+            query_packet(
+                id,
+                &Question {
+                    domain: domain(name),
+                    record_type: QueryType::A,
+                    class: Class::IN,
+                },
+                true,
+                None,
+            )
+        };
+
+        let mut connection = TcpConnection::connect(addr, Duration::from_secs(1)).unwrap();
+        connection
+            .set_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        // Several queries are answered on the same connection
+        connection.send(&query(1, "ns1.example.com")).unwrap();
+        connection.send(&query(2, "nothing.example.com")).unwrap();
+        let first = connection.receive().unwrap().unwrap();
+        let second = connection.receive().unwrap().unwrap();
+        server.shutdown();
+
+        assert_eq!(first.header().id, 1);
+        assert_eq!(first.response_code(), Ok(ResponseCode::NoError));
+        assert_eq!(first.answers().len(), 1);
+        assert_eq!(second.header().id, 2);
+        assert_eq!(second.response_code(), Ok(ResponseCode::NXDomain));
+    }
+
+    #[test]
+    fn test_malformed_query() {
+        let server = spawn_server();
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+
+        // Header of a query announcing a question which is missing
+        let message = [0xAB, 0xCD, 0x01, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0];
+        socket.send_to(&message, server.local_addr()).unwrap();
+        let mut recv_data = [0; MAX_PACKET_SIZE];
+        let (recv_len, _) = socket.recv_from(&mut recv_data).unwrap();
+        server.shutdown();
+
+        let mut buffer = DNSPacketBuffer::new(&recv_data[..recv_len]);
+        let response = DNSPacket::parse_dns_packet(&mut buffer).unwrap();
+        assert_eq!(response.header().id, 0xABCD);
+        assert!(response.header().query_response);
+        assert_eq!(response.response_code(), Ok(ResponseCode::FormErr));
+    }
+}
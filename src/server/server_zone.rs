@@ -0,0 +1,713 @@
+use crate::dns_packet::{
+    Class, DNSError, DNSPacket, Domain, Edns, Header, Opcode, QueryType, Question, Record,
+    RecordData, ResponseCode, CNAME, DNAME,
+};
+use std::collections::BTreeMap;
+
+/// Longest chain of aliases followed when answering a query, protecting against loops.
+const MAX_ALIASES: usize = 8;
+/// Query type matching all records.
+const ANY: u16 = 255;
+
+/// Labels of a name from the rightmost one, lowercased: names of a zone sort in canonical order,
+/// and the names below a node directly follow it.
+fn node_key(name: &Domain) -> Vec<Vec<u8>> {
+    name.labels()
+        .rev()
+        .map(|label| label.to_ascii_lowercase())
+        .collect()
+}
+
+fn matches_type(record: &Record, record_type: &QueryType) -> bool {
+    record.record_type() == *record_type || record_type.to_num() == ANY
+}
+
+/// Records of a zone held in memory, served authoritatively.
+#[derive(Debug, Clone)]
+pub struct Zone {
+    origin: Domain,
+    apex: Vec<Vec<u8>>,
+    soa: Record,
+    nodes: BTreeMap<Vec<Vec<u8>>, Vec<Record>>,
+}
+
+impl Zone {
+    /// Build a zone from its records, e.g. as returned by parse_zone. The zone must have an SOA
+    /// record at its apex, and every record must be at or below it.
+    pub fn new(origin: Domain, records: Vec<Record>) -> Result<Self, DNSError> {
+        let apex = node_key(&origin);
+        let mut nodes = BTreeMap::<Vec<Vec<u8>>, Vec<Record>>::new();
+        for record in records {
+            let key = node_key(record.domain());
+            if !key.starts_with(&apex) {
+                return Err(DNSError::OutOfZone(record.domain().to_string()));
+            }
+            nodes.entry(key).or_default().push(record);
+        }
+
+        let soa = nodes
+            .get(&apex)
+            .and_then(|records| {
+                records
+                    .iter()
+                    .find(|record| record.record_type() == QueryType::SOA)
+            })
+            .cloned()
+            .ok_or_else(|| DNSError::MissingSOA(origin.to_string()))?;
+
+        Ok(Zone {
+            origin,
+            apex,
+            soa,
+            nodes,
+        })
+    }
+
+    pub fn origin(&self) -> &Domain {
+        &self.origin
+    }
+
+    pub fn class(&self) -> Class {
+        self.soa.class()
+    }
+
+    /// Records of the zone, in canonical order of their names.
+    pub fn records(&self) -> impl Iterator<Item = &Record> {
+        self.nodes.values().flatten()
+    }
+
+    fn node(&self, key: &[Vec<u8>]) -> &[Record] {
+        self.nodes.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Check whether a name exists in the zone, either owning records or being an empty
+    /// non-terminal with names below it (RFC 8020).
+    fn name_exists(&self, key: &[Vec<u8>]) -> bool {
+        self.nodes
+            .range(key.to_vec()..)
+            .next()
+            .is_some_and(|(node_key, _)| node_key.starts_with(key))
+    }
+
+    /// SOA record proving a negative answer, with the negative caching TTL as its TTL (RFC 2308
+    /// section 3).
+    fn negative_soa(&self) -> Record {
+        let ttl = match self.soa.data() {
+            RecordData::SOA(soa) => self.soa.ttl().min(soa.minttl),
+            _ => self.soa.ttl(),
+        };
+        Record::new(
+            self.soa.domain().clone(),
+            self.soa.class(),
+            ttl,
+            self.soa.data().clone(),
+        )
+    }
+
+    /// Address records of a name held by the zone, including glue below zone cuts.
+    fn addresses(&self, name: &Domain) -> impl Iterator<Item = &Record> {
+        self.node(&node_key(name))
+            .iter()
+            .filter(|record| matches!(record.record_type(), QueryType::A | QueryType::AAAA))
+    }
+
+    /// Answer a query for a name of the zone following RFC 1034 section 4.3.2, adding the records
+    /// to the response. Return the target to restart the lookup from when the name is an alias.
+    fn lookup(
+        &self,
+        name: &Domain,
+        record_type: &QueryType,
+        response: &mut Response,
+    ) -> Option<Domain> {
+        let key = node_key(name);
+
+        // Go down the tree from the apex towards the name, looking for a zone cut or a DNAME
+        for depth in self.apex.len()..=key.len() {
+            let node = self.node(&key[..depth]);
+            let is_name = depth == key.len();
+
+            // The DS records of a delegation are held by the parent side (RFC 4035 section 3.1.4.1)
+            let delegation: Vec<&Record> = node
+                .iter()
+                .filter(|record| record.record_type() == QueryType::NS)
+                .collect();
+            if depth > self.apex.len()
+                && !delegation.is_empty()
+                && !(is_name && *record_type == QueryType::DS)
+            {
+                if response.answers.is_empty() {
+                    response.authoritative = false;
+                }
+                response.authorities.extend(delegation.into_iter().cloned());
+                return None;
+            }
+
+            if !is_name {
+                let dname = node.iter().find_map(|record| match record.data() {
+                    RecordData::DNAME(dname) => Some((record, dname)),
+                    _ => None,
+                });
+                if let Some((record, dname)) = dname {
+                    return Self::substitute_dname(name, record, dname, response);
+                }
+            }
+        }
+
+        let node = self.node(&key);
+        if !node.is_empty() {
+            return self.answer_node(record_type, node, response);
+        }
+        if self.name_exists(&key) {
+            response.authorities.push(self.negative_soa());
+            return None;
+        }
+
+        // Synthesize the answer from the wildcard at the closest encloser (RFC 4592 section 3.3)
+        let closest_encloser = (self.apex.len()..key.len())
+            .rev()
+            .find(|depth| self.name_exists(&key[..*depth]))
+            .unwrap_or(self.apex.len());
+        let mut wildcard_key = key[..closest_encloser].to_vec();
+        wildcard_key.push(b"*".to_vec());
+        let wildcard = self.node(&wildcard_key);
+        if !wildcard.is_empty() {
+            let synthesized: Vec<Record> = wildcard
+                .iter()
+                .map(|record| {
+                    Record::new(
+                        name.clone(),
+                        record.class(),
+                        record.ttl(),
+                        record.data().clone(),
+                    )
+                })
+                .collect();
+            return self.answer_node(record_type, &synthesized, response);
+        }
+
+        response.response_code = ResponseCode::NXDomain;
+        response.authorities.push(self.negative_soa());
+        None
+    }
+
+    /// Answer from the records owned by the name, following its CNAME if there is one.
+    fn answer_node(
+        &self,
+        record_type: &QueryType,
+        node: &[Record],
+        response: &mut Response,
+    ) -> Option<Domain> {
+        if *record_type != QueryType::CNAME && record_type.to_num() != ANY {
+            let cname = node.iter().find_map(|record| match record.data() {
+                RecordData::CNAME(cname) => Some((record, cname)),
+                _ => None,
+            });
+            if let Some((record, cname)) = cname {
+                response.answers.push(record.clone());
+                return Some(cname.cname.clone());
+            }
+        }
+
+        let len = response.answers.len();
+        response.answers.extend(
+            node.iter()
+                .filter(|record| matches_type(record, record_type))
+                .cloned(),
+        );
+        if response.answers.len() == len {
+            response.authorities.push(self.negative_soa());
+        }
+        None
+    }
+
+    /// Answer with the DNAME of an ancestor of the name, and the CNAME it synthesizes towards the
+    /// name's substitute (RFC 6672 section 3.3).
+    fn substitute_dname(
+        name: &Domain,
+        record: &Record,
+        dname: &DNAME,
+        response: &mut Response,
+    ) -> Option<Domain> {
+        response.answers.push(record.clone());
+
+        // The labels below the DNAME owner are kept as they were queried
+        let Ok(target) = name.replace_ancestor(record.domain(), &dname.dname) else {
+            response.response_code = ResponseCode::YXDomain;
+            return None;
+        };
+        response.answers.push(Record::new(
+            name.clone(),
+            record.class(),
+            record.ttl(),
+            RecordData::CNAME(CNAME {
+                cname: target.clone(),
+            }),
+        ));
+        Some(target)
+    }
+}
+
+/// Sections of a response being built.
+struct Response {
+    response_code: ResponseCode,
+    authoritative: bool,
+    answers: Vec<Record>,
+    authorities: Vec<Record>,
+    additional_records: Vec<Record>,
+}
+
+/// Zones served by an authoritative server, kept in memory.
+#[derive(Debug, Default, Clone)]
+pub struct ZoneStore {
+    zones: BTreeMap<Vec<Vec<u8>>, Zone>,
+}
+
+impl ZoneStore {
+    pub fn new() -> Self {
+        ZoneStore::default()
+    }
+
+    /// Add a zone to the store, replacing the zone with the same origin if any.
+    pub fn insert(&mut self, zone: Zone) -> Option<Zone> {
+        self.zones.insert(zone.apex.clone(), zone)
+    }
+
+    /// Remove the zone with the given origin.
+    pub fn remove(&mut self, origin: &Domain) -> Option<Zone> {
+        self.zones.remove(&node_key(origin))
+    }
+
+    pub fn zones(&self) -> impl Iterator<Item = &Zone> {
+        self.zones.values()
+    }
+
+    /// Zone holding the name: the one whose origin is the closest ancestor of the name.
+    pub fn find_zone(&self, name: &Domain) -> Option<&Zone> {
+        let key = node_key(name);
+        (0..=key.len())
+            .rev()
+            .find_map(|depth| self.zones.get(&key[..depth]))
+    }
+
+    /// Build the response to a query. Queries for names outside of the zones are refused, and
+    /// only standard queries with a single question are supported.
+    pub fn answer(&self, query: &DNSPacket) -> Result<DNSPacket, DNSError> {
+        let mut response = Response {
+            response_code: ResponseCode::NoError,
+            authoritative: false,
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additional_records: Vec::new(),
+        };
+
+        let query_edns = query.edns();
+        match (query.header().opcode, query.questions()) {
+            _ if query_edns.is_some_and(|edns| edns.version != 0) => {
+                response.response_code = ResponseCode::BadVers
+            }
+            (Opcode::Query, [question]) => self.resolve(question, &mut response),
+            (Opcode::Query, _) => response.response_code = ResponseCode::FormErr,
+            _ => response.response_code = ResponseCode::NoTimp,
+        }
+
+        let header = Header {
+            id: query.header().id,
+            query_response: true,
+            opcode: query.header().opcode,
+            authoritative_answer: response.authoritative,
+            truncated_message: false,
+            recursion_desired: query.header().recursion_desired,
+            recursion_available: false,
+            reserved: false,
+            authentic_data: false,
+            checking_disabled: query.header().checking_disabled,
+            response_code: ResponseCode::NoError,
+            question_count: query.questions().len() as u16,
+            answer_count: response.answers.len() as u16,
+            authority_count: response.authorities.len() as u16,
+            additional_count: response.additional_records.len() as u16,
+        };
+        let mut packet = DNSPacket::new(
+            header,
+            Some(query.questions().to_vec()),
+            Some(response.answers),
+            Some(response.authorities),
+            Some(response.additional_records),
+        );
+        packet.set_edns(query_edns.map(|edns| Edns {
+            dnssec_ok: edns.dnssec_ok,
+            ..Edns::default()
+        }));
+        packet.set_response_code(response.response_code)?;
+        Ok(packet)
+    }
+
+    fn resolve(&self, question: &Question, response: &mut Response) {
+        let zone = match self.find_zone(&question.domain) {
+            Some(zone) if question.class.matches(zone.class()) => zone,
+            _ => {
+                response.response_code = ResponseCode::Refused;
+                return;
+            }
+        };
+        response.authoritative = true;
+
+        // Restart from the target of each alias, as long as it is held by one of the zones
+        let mut name = question.domain.clone();
+        let mut next_zone = Some(zone);
+        for _ in 0..MAX_ALIASES {
+            let Some(zone) = next_zone else {
+                break;
+            };
+            match zone.lookup(&name, &question.record_type, response) {
+                Some(target) => {
+                    next_zone = self.find_zone(&target);
+                    name = target;
+                }
+                None => break,
+            }
+        }
+
+        self.add_additional_records(response);
+    }
+
+    /// Add the addresses of the names referenced by NS, MX and SRV records of the answer and
+    /// authority sections, such as the glue of a referral.
+    fn add_additional_records(&self, response: &mut Response) {
+        let targets: Vec<&Domain> = response
+            .answers
+            .iter()
+            .chain(response.authorities.iter())
+            .filter_map(|record| match record.data() {
+                RecordData::NS(ns) => Some(&ns.nsdname),
+                RecordData::MX(mx) => Some(&mx.exchange),
+                RecordData::SRV(srv) => Some(&srv.target),
+                _ => None,
+            })
+            .collect();
+
+        let mut additional_records = Vec::<Record>::new();
+        for target in targets {
+            let Some(zone) = self.find_zone(target) else {
+                continue;
+            };
+            for record in zone.addresses(target) {
+                if !response.answers.contains(record) && !additional_records.contains(record) {
+                    additional_records.push(record.clone());
+                }
+            }
+        }
+        response.additional_records = additional_records;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zone::parse_zone;
+
+    const EXAMPLE_COM: &str = r#"
+$TTL 3600
+@         IN SOA  ns1 hostmaster 1 7200 3600 1209600 300
+          IN NS   ns1
+          IN MX   10 mail
+ns1       IN A    192.0.2.1
+mail      IN A    192.0.2.2
+          IN AAAA 2001:db8::2
+www       IN CNAME web
+web       IN A    192.0.2.3
+ext       IN CNAME www.example.net.
+dangling  IN CNAME missing
+loop1     IN CNAME loop2
+loop2     IN CNAME loop1
+*.wild    IN TXT  "wildcard"
+*.alias   IN CNAME web
+host.wild IN A    192.0.2.4
+a.b.c     IN A    192.0.2.5
+sub       IN NS   ns.sub
+          IN DS   12345 13 2 2BB183AF5F22588179A53B0A98631FAD1A292118E8B2F6E5A5CC47FBFC4A0F85
+ns.sub    IN A    192.0.2.6
+old       IN DNAME new.example.net.
+"#;
+
+    const EXAMPLE_NET: &str = r#"
+$TTL 600
+@         IN SOA  ns1.example.com. hostmaster.example.com. 1 7200 3600 1209600 60
+          IN NS   ns1.example.com.
+www       IN A    198.51.100.1
+"#;
+
+    fn domain(name: &str) -> Domain {
+        name.parse().unwrap()
+    }
+
+    fn store() -> ZoneStore {
+        let mut store = ZoneStore::new();
+        for (origin, text) in [("example.com", EXAMPLE_COM), ("example.net", EXAMPLE_NET)] {
+            let records = parse_zone(text, &domain(origin)).unwrap();
+            store.insert(Zone::new(domain(origin), records).unwrap());
+        }
+        store
+    }
+
+    fn query(name: &str, record_type: QueryType) -> DNSPacket {
+        let header = Header {
+            id: 0x1234,
+            query_response: false,
+            opcode: Opcode::Query,
+            authoritative_answer: false,
+            truncated_message: false,
+            recursion_desired: true,
+            recursion_available: false,
+            reserved: false,
+            authentic_data: false,
+            checking_disabled: false,
+            response_code: ResponseCode::NoError,
+            question_count: 1,
+            answer_count: 0,
+            authority_count: 0,
+            additional_count: 0,
+        };
+        let question = Question {
+            domain: domain(name),
+            record_type,
+            class: Class::IN,
+        };
+        DNSPacket::new(header, Some(vec![question]), None, None, None)
+    }
+
+    fn answer(name: &str, record_type: QueryType) -> DNSPacket {
+        store().answer(&query(name, record_type)).unwrap()
+    }
+
+    /// Records in presentation format, one per line.
+    fn lines(records: &[Record]) -> Vec<String> {
+        records.iter().map(|record| record.to_string()).collect()
+    }
+
+    #[test]
+    fn test_answer() {
+        let response = answer("Example.COM", QueryType::MX);
+        let header = response.header();
+        assert!(header.query_response && header.authoritative_answer);
+        assert!(header.recursion_desired && !header.recursion_available);
+        assert_eq!(header.id, 0x1234);
+        assert_eq!(response.response_code(), Ok(ResponseCode::NoError));
+        assert_eq!(
+            response.questions(),
+            query("Example.COM", QueryType::MX).questions()
+        );
+        assert_eq!(
+            lines(response.answers()),
+            vec!["example.com.\t3600\tIN\tMX\t10 mail.example.com."]
+        );
+        assert!(response.authorities().is_empty());
+        // Addresses of the mail exchanger
+        assert_eq!(
+            lines(response.additional_records()),
+            vec![
+                "mail.example.com.\t3600\tIN\tA\t192.0.2.2",
+                "mail.example.com.\t3600\tIN\tAAAA\t2001:db8::2",
+            ]
+        );
+
+        let response = answer("example.com", QueryType::Unknown(ANY));
+        assert_eq!(response.answers().len(), 3);
+    }
+
+    #[test]
+    fn test_negative_answers() {
+        let soa = "example.com.\t300\tIN\tSOA\tns1.example.com. hostmaster.example.com. 1 7200 3600 1209600 300";
+
+        let nodata = answer("web.example.com", QueryType::AAAA);
+        assert_eq!(nodata.response_code(), Ok(ResponseCode::NoError));
+        assert!(nodata.header().authoritative_answer);
+        assert!(nodata.answers().is_empty());
+        assert_eq!(lines(nodata.authorities()), vec![soa]);
+
+        let nxdomain = answer("nothing.example.com", QueryType::A);
+        assert_eq!(nxdomain.response_code(), Ok(ResponseCode::NXDomain));
+        assert!(nxdomain.header().authoritative_answer);
+        assert!(nxdomain.answers().is_empty());
+        assert_eq!(lines(nxdomain.authorities()), vec![soa]);
+
+        // Empty non-terminals exist, without any records
+        let empty_non_terminal = answer("b.c.example.com", QueryType::A);
+        assert_eq!(
+            empty_non_terminal.response_code(),
+            Ok(ResponseCode::NoError)
+        );
+        assert_eq!(lines(empty_non_terminal.authorities()), vec![soa]);
+    }
+
+    #[test]
+    fn test_referral() {
+        let referral = answer("www.sub.example.com", QueryType::A);
+        assert_eq!(referral.response_code(), Ok(ResponseCode::NoError));
+        assert!(!referral.header().authoritative_answer);
+        assert!(referral.answers().is_empty());
+        assert_eq!(
+            lines(referral.authorities()),
+            vec!["sub.example.com.\t3600\tIN\tNS\tns.sub.example.com."]
+        );
+        assert_eq!(
+            lines(referral.additional_records()),
+            vec!["ns.sub.example.com.\t3600\tIN\tA\t192.0.2.6"]
+        );
+
+        // Glue is only served in referrals, names below the cut are not authoritative
+        let glue = answer("ns.sub.example.com", QueryType::A);
+        assert!(!glue.header().authoritative_answer);
+        assert!(glue.answers().is_empty());
+
+        // The parent side is authoritative for the DS records of the delegation
+        let ds = answer("sub.example.com", QueryType::DS);
+        assert!(ds.header().authoritative_answer);
+        assert_eq!(ds.answers().len(), 1);
+        assert_eq!(ds.answers()[0].record_type(), QueryType::DS);
+    }
+
+    #[test]
+    fn test_wildcard() {
+        let response = answer("anything.wild.example.com", QueryType::TXT);
+        assert!(response.header().authoritative_answer);
+        assert_eq!(
+            lines(response.answers()),
+            vec!["anything.wild.example.com.\t3600\tIN\tTXT\t\"wildcard\""]
+        );
+
+        let nodata = answer("anything.wild.example.com", QueryType::A);
+        assert_eq!(nodata.response_code(), Ok(ResponseCode::NoError));
+        assert!(nodata.answers().is_empty());
+        assert_eq!(nodata.authorities().len(), 1);
+
+        // Existing names are not matched by the wildcard
+        let host = answer("host.wild.example.com", QueryType::TXT);
+        assert!(host.answers().is_empty());
+        assert_eq!(host.response_code(), Ok(ResponseCode::NoError));
+
+        // The closest encloser of below.host.wild is host.wild, which has no wildcard
+        let below = answer("below.host.wild.example.com", QueryType::TXT);
+        assert_eq!(below.response_code(), Ok(ResponseCode::NXDomain));
+
+        // Synthesized CNAMEs are followed
+        let alias = answer("x.alias.example.com", QueryType::A);
+        assert_eq!(
+            lines(alias.answers()),
+            vec![
+                "x.alias.example.com.\t3600\tIN\tCNAME\tweb.example.com.",
+                "web.example.com.\t3600\tIN\tA\t192.0.2.3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cname() {
+        let response = answer("www.example.com", QueryType::A);
+        assert!(response.header().authoritative_answer);
+        assert_eq!(
+            lines(response.answers()),
+            vec![
+                "www.example.com.\t3600\tIN\tCNAME\tweb.example.com.",
+                "web.example.com.\t3600\tIN\tA\t192.0.2.3",
+            ]
+        );
+
+        // The CNAME itself is returned when asked for
+        let cname = answer("www.example.com", QueryType::CNAME);
+        assert_eq!(cname.answers().len(), 1);
+
+        // Targets in another zone of the store are followed
+        let other_zone = answer("ext.example.com", QueryType::A);
+        assert_eq!(
+            lines(other_zone.answers()),
+            vec![
+                "ext.example.com.\t3600\tIN\tCNAME\twww.example.net.",
+                "www.example.net.\t600\tIN\tA\t198.51.100.1",
+            ]
+        );
+
+        // The response code is the one of the last name of the chain (RFC 6604)
+        let dangling = answer("dangling.example.com", QueryType::A);
+        assert_eq!(dangling.response_code(), Ok(ResponseCode::NXDomain));
+        assert_eq!(dangling.answers().len(), 1);
+        assert_eq!(dangling.authorities().len(), 1);
+
+        let looping = answer("loop1.example.com", QueryType::A);
+        assert_eq!(looping.response_code(), Ok(ResponseCode::NoError));
+        assert_eq!(looping.answers().len(), MAX_ALIASES);
+    }
+
+    #[test]
+    fn test_dname() {
+        let response = answer("www.Old.example.com", QueryType::A);
+        assert_eq!(
+            lines(response.answers()),
+            vec![
+                "old.example.com.\t3600\tIN\tDNAME\tnew.example.net.",
+                "www.Old.example.com.\t3600\tIN\tCNAME\twww.new.example.net.",
+            ]
+        );
+        // The target is in example.net, where it does not exist
+        assert_eq!(response.response_code(), Ok(ResponseCode::NXDomain));
+
+        let owner = answer("old.example.com", QueryType::A);
+        assert!(owner.answers().is_empty());
+        assert_eq!(owner.response_code(), Ok(ResponseCode::NoError));
+    }
+
+    #[test]
+    fn test_unanswerable_queries() {
+        let store = store();
+
+        let refused = store.answer(&query("example.org", QueryType::A)).unwrap();
+        assert_eq!(refused.response_code(), Ok(ResponseCode::Refused));
+        assert!(!refused.header().authoritative_answer);
+
+        let mut notify = query("example.com", QueryType::SOA);
+        let header = Header {
+            opcode: Opcode::Notify,
+            ..notify.header().clone()
+        };
+        notify = DNSPacket::new(header, Some(notify.questions().to_vec()), None, None, None);
+        let notimp = store.answer(&notify).unwrap();
+        assert_eq!(notimp.response_code(), Ok(ResponseCode::NoTimp));
+
+        let mut questions = query("example.com", QueryType::SOA).questions().to_vec();
+        questions.push(questions[0].clone());
+        let header = Header {
+            question_count: 2,
+            ..query("example.com", QueryType::SOA).header().clone()
+        };
+        let two_questions = DNSPacket::new(header, Some(questions), None, None, None);
+        let formerr = store.answer(&two_questions).unwrap();
+        assert_eq!(formerr.response_code(), Ok(ResponseCode::FormErr));
+
+        let mut edns_query = query("example.com", QueryType::SOA);
+        edns_query.set_edns(Some(Edns {
+            version: 1,
+            ..Edns::default()
+        }));
+        let badvers = store.answer(&edns_query).unwrap();
+        assert_eq!(badvers.response_code(), Ok(ResponseCode::BadVers));
+        assert_eq!(badvers.edns().map(|edns| edns.version), Some(0));
+    }
+
+    #[test]
+    fn test_zone_errors() {
+        let records = parse_zone(EXAMPLE_NET, &domain("example.net")).unwrap();
+        assert_eq!(
+            Zone::new(domain("example.org"), records.clone()).err(),
+            Some(DNSError::OutOfZone("example.net.".to_string()))
+        );
+
+        let without_soa: Vec<Record> = records
+            .into_iter()
+            .filter(|record| record.record_type() != QueryType::SOA)
+            .collect();
+        assert_eq!(
+            Zone::new(domain("example.net"), without_soa).err(),
+            Some(DNSError::MissingSOA("example.net.".to_string()))
+        );
+    }
+}
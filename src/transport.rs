@@ -0,0 +1,7 @@
+#[cfg(feature = "tokio")]
+mod transport_codec;
+mod transport_tcp;
+
+#[cfg(feature = "tokio")]
+pub use transport_codec::*;
+pub use transport_tcp::*;
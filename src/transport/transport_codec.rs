@@ -0,0 +1,137 @@
+use crate::dns_packet::{DNSError, DNSPacket, DNSPacketBuffer};
+use bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Codec for DNS messages on a stream, each preceded by its length as a 2 byte integer (RFC 1035
+/// section 4.2.2). Use with `tokio_util::codec::Framed` to exchange packets over TCP.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DNSCodec;
+
+impl Decoder for DNSCodec {
+    type Item = DNSPacket;
+    type Error = DNSError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<DNSPacket>, DNSError> {
+        if src.len() < 2 {
+            return Ok(None);
+        }
+        let len = u16::from_be_bytes([src[0], src[1]]) as usize;
+        if src.len() < len + 2 {
+            src.reserve(len + 2 - src.len());
+            return Ok(None);
+        }
+
+        src.advance(2);
+        let message = src.split_to(len);
+        let mut buffer = DNSPacketBuffer::new(&message);
+        DNSPacket::parse_dns_packet(&mut buffer).map(Some)
+    }
+}
+
+impl Encoder<DNSPacket> for DNSCodec {
+    type Error = DNSError;
+
+    fn encode(&mut self, packet: DNSPacket, dst: &mut BytesMut) -> Result<(), DNSError> {
+        self.encode(&packet, dst)
+    }
+}
+
+impl Encoder<&DNSPacket> for DNSCodec {
+    type Error = DNSError;
+
+    fn encode(&mut self, packet: &DNSPacket, dst: &mut BytesMut) -> Result<(), DNSError> {
+        let buffer = packet.write_dns_packet()?;
+        let message = buffer.get_data();
+        let len = u16::try_from(message.len()).map_err(|_| DNSError::EndOfBuffer)?;
+
+        dst.reserve(message.len() + 2);
+        dst.put_u16(len);
+        dst.extend_from_slice(message);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::{Class, Header, Opcode, QueryType, Question, ResponseCode};
+
+    fn query(id: u16, name: &str) -> DNSPacket {
+        DNSPacket::new(
+            Header {
+                id,
+                query_response: false,
+                opcode: Opcode::Query,
+                authoritative_answer: false,
+                truncated_message: false,
+                recursion_desired: true,
+                recursion_available: false,
+                reserved: false,
+                authentic_data: false,
+                checking_disabled: false,
+                response_code: ResponseCode::NoError,
+                question_count: 1,
+                answer_count: 0,
+                authority_count: 0,
+                additional_count: 0,
+            },
+            Some(vec![Question {
+                domain: name.parse().unwrap(),
+                record_type: QueryType::A,
+                class: Class::IN,
+            }]),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_codec() {
+        let first = query(1, "a.example");
+        let second = query(2, "b.example");
+
+        let mut encoded = BytesMut::new();
+        DNSCodec.encode(&first, &mut encoded).unwrap();
+        DNSCodec.encode(second.clone(), &mut encoded).unwrap();
+
+        let message = first.write_dns_packet().unwrap();
+        let len = message.get_data().len();
+        assert_eq!(&encoded[..2], &(len as u16).to_be_bytes());
+        assert_eq!(&encoded[2..len + 2], message.get_data());
+
+        assert_eq!(DNSCodec.decode(&mut encoded).unwrap(), Some(first));
+        assert_eq!(DNSCodec.decode(&mut encoded).unwrap(), Some(second));
+        assert_eq!(DNSCodec.decode(&mut encoded).unwrap(), None);
+        assert!(encoded.is_empty());
+    }
+
+    #[test]
+    fn test_partial_message() {
+        let packet = query(1, "a.example");
+        let mut encoded = BytesMut::new();
+        DNSCodec.encode(&packet, &mut encoded).unwrap();
+
+        // Feed the message a few bytes at a time, as it may arrive in several segments
+        let mut src = BytesMut::new();
+        let mut chunks = encoded.chunks(5).peekable();
+        while let Some(chunk) = chunks.next() {
+            src.extend_from_slice(chunk);
+            let decoded = DNSCodec.decode(&mut src).unwrap();
+            if chunks.peek().is_some() {
+                assert_eq!(decoded, None);
+            } else {
+                assert_eq!(decoded, Some(packet.clone()));
+            }
+        }
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_message() {
+        // Complete frame holding a truncated header, followed by the start of the next frame
+        let mut src = BytesMut::from(&[0x00, 0x03, 0xAB, 0xCD, 0xEF, 0x00][..]);
+        assert!(DNSCodec.decode(&mut src).is_err());
+        assert_eq!(&src[..], &[0x00]);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::dns_packet::{DNSError, DNSPacket, DNSPacketBuffer};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+/// Write a DNS message to a stream, preceded by its length as a 2 byte integer (RFC 1035 section
+/// 4.2.2).
+pub fn write_tcp_message<W: Write>(writer: &mut W, message: &[u8]) -> Result<(), DNSError> {
+    let len = u16::try_from(message.len()).map_err(|_| DNSError::EndOfBuffer)?;
+
+    // Length and message are written at once, so they are not split in separate segments
+    let mut framed = Vec::<u8>::with_capacity(message.len() + 2);
+    framed.extend_from_slice(&len.to_be_bytes());
+    framed.extend_from_slice(message);
+    writer.write_all(&framed)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read a length prefixed DNS message from a stream. Return None if the stream was closed before
+/// the start of a message.
+pub fn read_tcp_message<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>, DNSError> {
+    let mut len_bytes = [0u8; 2];
+    // The stream may only end between two messages
+    let first_len = loop {
+        match reader.read(&mut len_bytes[..1]) {
+            Ok(first_len) => break first_len,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        }
+    };
+    if first_len == 0 {
+        return Ok(None);
+    }
+    reader.read_exact(&mut len_bytes[1..])?;
+
+    let mut message = vec![0u8; u16::from_be_bytes(len_bytes) as usize];
+    reader.read_exact(&mut message)?;
+    Ok(Some(message))
+}
+
+/// Connection exchanging DNS messages over a stream, usually TCP. Several messages can be sent and
+/// received on the same connection, and responses may arrive in any order (RFC 7766 section 6.2).
+pub struct TcpConnection<S: Read + Write = TcpStream> {
+    stream: S,
+}
+
+impl TcpConnection<TcpStream> {
+    /// Connect to a nameserver, waiting at most the given time for the connection to be
+    /// established.
+    pub fn connect(addr: SocketAddr, timeout: Duration) -> Result<Self, DNSError> {
+        let stream = TcpStream::connect_timeout(&addr, timeout)?;
+        stream.set_nodelay(true)?;
+        Ok(Self::new(stream))
+    }
+
+    /// Set the time after which sending or receiving a message fails with a Timeout error. None
+    /// blocks indefinitely.
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> Result<(), DNSError> {
+        self.stream.set_read_timeout(timeout)?;
+        self.stream.set_write_timeout(timeout)?;
+        Ok(())
+    }
+}
+
+impl<S: Read + Write> TcpConnection<S> {
+    pub fn new(stream: S) -> Self {
+        TcpConnection { stream }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    /// Send a DNS packet.
+    pub fn send(&mut self, packet: &DNSPacket) -> Result<(), DNSError> {
+        let buffer = packet.write_dns_packet()?;
+        write_tcp_message(&mut self.stream, buffer.get_data())
+    }
+
+    /// Receive a DNS packet. Return None if the peer closed the connection.
+    pub fn receive(&mut self) -> Result<Option<DNSPacket>, DNSError> {
+        match read_tcp_message(&mut self.stream)? {
+            Some(message) => {
+                let mut buffer = DNSPacketBuffer::new(&message);
+                Ok(Some(DNSPacket::parse_dns_packet(&mut buffer)?))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::{Class, Header, Opcode, QueryType, Question, ResponseCode};
+    use std::io::Cursor;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn query(id: u16, name: &str) -> DNSPacket {
+        DNSPacket::new(
+            Header {
+                id,
+                query_response: false,
+                opcode: Opcode::Query,
+                authoritative_answer: false,
+                truncated_message: false,
+                recursion_desired: true,
+                recursion_available: false,
+                reserved: false,
+                authentic_data: false,
+                checking_disabled: false,
+                response_code: ResponseCode::NoError,
+                question_count: 1,
+                answer_count: 0,
+                authority_count: 0,
+                additional_count: 0,
+            },
+            Some(vec![Question {
+                domain: name.parse().unwrap(),
+                record_type: QueryType::A,
+                class: Class::IN,
+            }]),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_framing() {
+        let mut stream = Cursor::new(Vec::<u8>::new());
+        write_tcp_message(&mut stream, &[0xAB, 0xCD, 0xEF]).unwrap();
+        write_tcp_message(&mut stream, &[]).unwrap();
+
+        assert_eq!(
+            stream.get_ref(),
+            &[0x00, 0x03, 0xAB, 0xCD, 0xEF, 0x00, 0x00]
+        );
+
+        stream.set_position(0);
+        assert_eq!(
+            read_tcp_message(&mut stream).unwrap(),
+            Some(vec![0xAB, 0xCD, 0xEF])
+        );
+        assert_eq!(read_tcp_message(&mut stream).unwrap(), Some(Vec::new()));
+        assert_eq!(read_tcp_message(&mut stream).unwrap(), None);
+    }
+
+    #[test]
+    fn test_truncated_stream() {
+        let mut stream = Cursor::new(vec![0x00, 0x04, 0xAB, 0xCD]);
+        assert!(matches!(
+            read_tcp_message(&mut stream),
+            Err(DNSError::Io(_))
+        ));
+
+        let mut stream = Cursor::new(vec![0x00]);
+        assert!(matches!(
+            read_tcp_message(&mut stream),
+            Err(DNSError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_multiple_messages_on_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Echo every query back, in reverse order once the client stops sending
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut connection = TcpConnection::new(stream);
+            let mut queries = Vec::<DNSPacket>::new();
+            for _ in 0..3 {
+                queries.push(connection.receive().unwrap().unwrap());
+            }
+            for query in queries.iter().rev() {
+                connection.send(query).unwrap();
+            }
+        });
+
+        let mut connection = TcpConnection::connect(addr, Duration::from_secs(1)).unwrap();
+        connection
+            .set_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let queries = [
+            query(1, "a.example"),
+            query(2, "b.example"),
+            query(3, "c.example"),
+        ];
+        for query in queries.iter() {
+            connection.send(query).unwrap();
+        }
+
+        let mut responses = Vec::<DNSPacket>::new();
+        while let Some(response) = connection.receive().unwrap() {
+            responses.push(response);
+        }
+        handle.join().unwrap();
+
+        let ids: Vec<u16> = responses.iter().map(|r| r.header().id).collect();
+        assert_eq!(ids, vec![3, 2, 1]);
+        assert_eq!(responses[2], queries[0]);
+    }
+}
@@ -0,0 +1,4 @@
+mod zone_lexer;
+mod zone_parser;
+
+pub use zone_parser::*;
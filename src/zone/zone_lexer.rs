@@ -0,0 +1,243 @@
+use crate::dns_packet::DNSError;
+
+/// Word of a zone file, either a run of non-blank characters or a quoted string. Escape sequences
+/// are kept as written, to be interpreted according to the field the token is used as.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Token {
+    pub text: String,
+    pub quoted: bool,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Logical line of a zone file: a directive or a record, possibly spanning several physical lines
+/// with parentheses.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Entry {
+    pub tokens: Vec<Token>,
+    /// The entry starts with a blank, so its owner is the previous record's one.
+    pub blank_owner: bool,
+    pub line: usize,
+}
+
+impl Entry {
+    fn new(tokens: Vec<Token>) -> Self {
+        // Entries starting with a blank have no owner field
+        Entry {
+            blank_owner: tokens[0].column != 1,
+            line: tokens[0].line,
+            tokens,
+        }
+    }
+}
+
+/// Split a zone file into entries (RFC 1035 section 5.1), removing comments and parentheses.
+pub(crate) fn tokenize(input: &str) -> Result<Vec<Entry>, DNSError> {
+    let mut entries = Vec::<Entry>::new();
+    let mut tokens = Vec::<Token>::new();
+    let mut paren_start: Option<(usize, usize)> = None;
+
+    let mut chars = input.chars().peekable();
+    let (mut line, mut column) = (1, 0);
+
+    while let Some(c) = chars.next() {
+        column += 1;
+        let (token_line, token_column) = (line, column);
+
+        match c {
+            '\n' => {
+                if paren_start.is_none() && !tokens.is_empty() {
+                    entries.push(Entry::new(std::mem::take(&mut tokens)));
+                }
+                line += 1;
+                column = 0;
+            }
+            ' ' | '\t' | '\r' => (),
+            ';' => {
+                // Comment until the end of the line
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            '(' => {
+                if paren_start.is_some() {
+                    return Err(DNSError::ZoneSyntax(
+                        line,
+                        column,
+                        "nested parentheses".into(),
+                    ));
+                }
+                paren_start = Some((line, column));
+            }
+            ')' => {
+                if paren_start.take().is_none() {
+                    return Err(DNSError::ZoneSyntax(
+                        line,
+                        column,
+                        "unbalanced closing parenthesis".into(),
+                    ));
+                }
+            }
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => {
+                            text.push('\\');
+                            if let Some(escaped) = chars.next() {
+                                text.push(escaped);
+                                column += 1;
+                                if escaped == '\n' {
+                                    line += 1;
+                                    column = 0;
+                                }
+                            }
+                        }
+                        Some('\n') => {
+                            line += 1;
+                            column = 0;
+                            text.push('\n');
+                            continue;
+                        }
+                        Some(c) => text.push(c),
+                        None => {
+                            return Err(DNSError::ZoneSyntax(
+                                token_line,
+                                token_column,
+                                "unterminated quoted string".into(),
+                            ))
+                        }
+                    }
+                    column += 1;
+                }
+                column += 1;
+                tokens.push(Token {
+                    text,
+                    quoted: true,
+                    line: token_line,
+                    column: token_column,
+                });
+            }
+            _ => {
+                let mut text = c.to_string();
+                let mut escaped = c == '\\';
+                while let Some(next) = chars.peek() {
+                    let delimiter =
+                        matches!(next, ' ' | '\t' | '\r' | '\n' | ';' | '(' | ')' | '"');
+                    if delimiter && !escaped || *next == '\n' {
+                        break;
+                    }
+                    escaped = !escaped && *next == '\\';
+                    text.push(*next);
+                    chars.next();
+                    column += 1;
+                }
+                tokens.push(Token {
+                    text,
+                    quoted: false,
+                    line: token_line,
+                    column: token_column,
+                });
+            }
+        }
+    }
+
+    if let Some((line, column)) = paren_start {
+        return Err(DNSError::ZoneSyntax(
+            line,
+            column,
+            "unbalanced opening parenthesis".into(),
+        ));
+    }
+    if !tokens.is_empty() {
+        entries.push(Entry::new(tokens));
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(entry: &Entry) -> Vec<&str> {
+        entry
+            .tokens
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let input = "$ORIGIN example.com.\n\
+                     @ IN SOA ns1 hostmaster ( 1 ; serial\n\
+                     \t7200 3600\n\
+                     \t1209600 300 )\n\
+                     \n\
+                     ; comment line\n\
+                     \tTXT \"quoted ; \\\"string\\\"\" a\\ b\n";
+
+        let entries = tokenize(input).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(texts(&entries[0]), ["$ORIGIN", "example.com."]);
+        assert_eq!(
+            texts(&entries[1]),
+            [
+                "@",
+                "IN",
+                "SOA",
+                "ns1",
+                "hostmaster",
+                "1",
+                "7200",
+                "3600",
+                "1209600",
+                "300"
+            ]
+        );
+        assert!(!entries[1].blank_owner);
+        assert_eq!(entries[1].line, 2);
+        assert_eq!(entries[1].tokens[7].line, 3);
+        assert_eq!(entries[1].tokens[7].column, 7);
+
+        assert!(entries[2].blank_owner);
+        assert_eq!(entries[2].line, 7);
+        assert_eq!(
+            texts(&entries[2]),
+            ["TXT", "quoted ; \\\"string\\\"", "a\\ b"]
+        );
+        assert!(entries[2].tokens[1].quoted);
+        assert!(!entries[2].tokens[2].quoted);
+    }
+
+    #[test]
+    fn test_tokenize_errors() {
+        assert_eq!(
+            tokenize("a A (\n1.2.3.4\n"),
+            Err(DNSError::ZoneSyntax(
+                1,
+                5,
+                "unbalanced opening parenthesis".into()
+            ))
+        );
+        assert_eq!(
+            tokenize("a A 1.2.3.4 )"),
+            Err(DNSError::ZoneSyntax(
+                1,
+                13,
+                "unbalanced closing parenthesis".into()
+            ))
+        );
+        assert_eq!(
+            tokenize("a TXT \"abc"),
+            Err(DNSError::ZoneSyntax(
+                1,
+                7,
+                "unterminated quoted string".into()
+            ))
+        );
+    }
+}
@@ -0,0 +1,1724 @@
+use super::zone_lexer::{tokenize, Entry, Token};
+use crate::dns_packet::{
+    base32hex_decode, base64_decode, encode_precision, hex_decode, hex_encode, parse_rrsig_time,
+    Algorithm, CertificateUsage, Class, DNSError, DNSSECAlgorithm, DigestType, Domain,
+    FingerprintType, MatchingType, NSEC3HashAlgorithm, QueryType, Record, RecordData, Selector,
+    SvcParam, A, AAAA, CAA, CDNSKEY, CDS, CNAME, DNAME, DNSKEY, DS, HTTPS, LOC, LOC_ALTITUDE_BASE,
+    LOC_EQUATOR, MX, NAPTR, NS, NSEC, NSEC3, NSEC3PARAM, PTR, RRSIG, SMIMEA, SOA, SRV, SSHFP, SVCB,
+    TLSA, TXT, URI,
+};
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+
+const MAX_INCLUDE_DEPTH: usize = 16;
+const MAX_GENERATE_RECORDS: u32 = 65536;
+/// Largest field width of a $GENERATE template modifier.
+const MAX_GENERATE_WIDTH: usize = 255;
+const MAX_LABEL_SIZE: usize = 63;
+const MAX_CHARACTER_STRING_SIZE: usize = 255;
+const DEFAULT_CLASS: Class = Class::IN;
+
+/// Parse a zone in master file format (RFC 1035 section 5). Relative names are completed with the
+/// given origin, until a $ORIGIN directive changes it. $INCLUDE paths are relative to the current
+/// directory.
+pub fn parse_zone(input: &str, origin: &Domain) -> Result<Vec<Record>, DNSError> {
+    let mut records = Vec::<Record>::new();
+    ZoneParser::new(origin, PathBuf::from("."), 0).parse(input, &mut records)?;
+    Ok(records)
+}
+
+/// Read and parse a zone file. $INCLUDE paths are relative to the directory of the file.
+pub fn parse_zone_file<P: AsRef<Path>>(path: P, origin: &Domain) -> Result<Vec<Record>, DNSError> {
+    let path = path.as_ref();
+    let input = fs::read_to_string(path)
+        .map_err(|err| DNSError::Io(format!("{}: {}", path.display(), err)))?;
+    let include_dir = path.parent().map_or(PathBuf::from("."), Path::to_path_buf);
+
+    let mut records = Vec::<Record>::new();
+    ZoneParser::new(origin, include_dir, 0).parse(&input, &mut records)?;
+    Ok(records)
+}
+
+fn syntax_err(token: &Token, msg: impl Into<String>) -> DNSError {
+    DNSError::ZoneSyntax(token.line, token.column, msg.into())
+}
+
+/// Decode the escape sequences of a token (RFC 1035 section 5.1): \X is X and \DDD is the byte of
+/// decimal value DDD. Each byte is returned along with whether it was escaped.
+fn unescape(token: &Token) -> Result<Vec<(u8, bool)>, DNSError> {
+    let mut bytes = Vec::<(u8, bool)>::new();
+    let mut chars = token.text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0u8; 4];
+            bytes.extend(c.encode_utf8(&mut buf).bytes().map(|b| (b, false)));
+            continue;
+        }
+
+        match chars.next() {
+            Some(d) if d.is_ascii_digit() => {
+                let digits: String = std::iter::once(d).chain(chars.by_ref().take(2)).collect();
+                let byte = (digits.len() == 3 && digits.bytes().all(|b| b.is_ascii_digit()))
+                    .then(|| digits.parse::<u8>().ok())
+                    .flatten()
+                    .ok_or_else(|| syntax_err(token, format!("invalid escape \\{}", digits)))?;
+                bytes.push((byte, true));
+            }
+            Some(escaped) => {
+                let mut buf = [0u8; 4];
+                bytes.extend(escaped.encode_utf8(&mut buf).bytes().map(|b| (b, true)));
+            }
+            None => return Err(syntax_err(token, "escape at end of field")),
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Parse a <character-string> (RFC 1035 section 3.3), quoted or not.
+fn parse_character_string(token: &Token) -> Result<Vec<u8>, DNSError> {
+    let bytes: Vec<u8> = unescape(token)?.into_iter().map(|(b, _)| b).collect();
+    if bytes.len() > MAX_CHARACTER_STRING_SIZE {
+        return Err(syntax_err(token, "character string longer than 255 bytes"));
+    }
+    Ok(bytes)
+}
+
+fn parse_number<T: std::str::FromStr>(token: &Token, what: &str) -> Result<T, DNSError> {
+    token
+        .text
+        .parse::<T>()
+        .map_err(|_| syntax_err(token, format!("invalid {} {}", what, token.text)))
+}
+
+/// Parse a TTL, either in seconds or with BIND style units, e.g. 1h30m.
+fn parse_ttl(token: &Token) -> Result<u32, DNSError> {
+    let invalid = || syntax_err(token, format!("invalid TTL {}", token.text));
+    if let Ok(ttl) = token.text.parse::<u32>() {
+        return Ok(ttl);
+    }
+
+    let mut ttl: u32 = 0;
+    let mut value: Option<u32> = None;
+    for c in token.text.chars() {
+        if let Some(digit) = c.to_digit(10) {
+            value = Some(
+                value
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add(digit))
+                    .ok_or_else(invalid)?,
+            );
+            continue;
+        }
+
+        let unit = match c.to_ascii_lowercase() {
+            'w' => 604800,
+            'd' => 86400,
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        ttl = value
+            .take()
+            .and_then(|value| value.checked_mul(unit))
+            .and_then(|secs| ttl.checked_add(secs))
+            .ok_or_else(invalid)?;
+    }
+    if value.is_some() {
+        return Err(invalid());
+    }
+
+    Ok(ttl)
+}
+
+/// Parse a class mnemonic, or the CLASSnnn generic notation (RFC 3597 section 5). Meta classes
+/// hold no records, so they are not classes of a zone file.
+fn parse_class(text: &str) -> Option<Class> {
+    Class::from_mnemonic(text).filter(|class| !class.is_meta())
+}
+
+fn parse_type(token: &Token) -> Result<QueryType, DNSError> {
+    QueryType::from_mnemonic(&token.text)
+        .ok_or_else(|| syntax_err(token, format!("unknown record type {}", token.text)))
+}
+
+/// Parse a DNSSEC algorithm, as a number or a mnemonic.
+fn parse_dnssec_algorithm(token: &Token) -> Result<DNSSECAlgorithm, DNSError> {
+    if let Ok(num) = token.text.parse::<u8>() {
+        return Ok(DNSSECAlgorithm::from_num(num));
+    }
+
+    (0..=u8::MAX)
+        .map(DNSSECAlgorithm::from_num)
+        .find(|algorithm| format!("{:?}", algorithm).eq_ignore_ascii_case(&token.text))
+        .ok_or_else(|| syntax_err(token, format!("unknown algorithm {}", token.text)))
+}
+
+/// Parse a decimal number with up to the given number of fractional digits, scaled to an integer
+/// by that many powers of 10.
+fn parse_scaled_decimal(text: &str, decimals: u32) -> Option<i64> {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text),
+    };
+    let (integer, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let is_digits = |digits: &str| digits.bytes().all(|b| b.is_ascii_digit());
+    if integer.is_empty() || !is_digits(integer) || !is_digits(fraction) {
+        return None;
+    }
+    if fraction.len() > decimals as usize || text.ends_with('.') {
+        return None;
+    }
+
+    let scale = 10i64.pow(decimals);
+    let fraction = format!("{:0<width$}", fraction, width = decimals as usize);
+    let value =
+        integer.parse::<i64>().ok()?.checked_mul(scale)? + fraction.parse::<i64>().unwrap_or(0);
+    Some(if negative { -value } else { value })
+}
+
+/// Parse a LOC latitude or longitude (RFC 1876 section 3): degrees, optionally followed by
+/// minutes and seconds, then the hemisphere, the second one being negative.
+fn parse_loc_coordinate(
+    fields: &mut Fields,
+    what: &str,
+    hemispheres: [&str; 2],
+    max_degrees: i64,
+) -> Result<u32, DNSError> {
+    let token = fields.next(what)?;
+    let invalid = || syntax_err(token, format!("invalid {} {}", what, token.text));
+    let degrees = token.text.parse::<i64>().map_err(|_| invalid())?;
+
+    let mut thousandths = degrees * 3_600_000;
+    let mut hemisphere = fields.next("hemisphere")?;
+    for (scale, decimals) in [(60_000, 0), (1, 3)] {
+        if hemispheres
+            .iter()
+            .any(|h| hemisphere.text.eq_ignore_ascii_case(h))
+        {
+            break;
+        }
+        let value = parse_scaled_decimal(&hemisphere.text, decimals)
+            .filter(|value| (0..60 * 10i64.pow(decimals)).contains(value))
+            .ok_or_else(|| {
+                syntax_err(hemisphere, format!("invalid {} {}", what, hemisphere.text))
+            })?;
+        thousandths += value * scale;
+        hemisphere = fields.next("hemisphere")?;
+    }
+
+    if !(0..=max_degrees * 3_600_000).contains(&thousandths) {
+        return Err(invalid());
+    }
+    if hemisphere.text.eq_ignore_ascii_case(hemispheres[1]) {
+        thousandths = -thousandths;
+    } else if !hemisphere.text.eq_ignore_ascii_case(hemispheres[0]) {
+        return Err(syntax_err(
+            hemisphere,
+            format!("invalid hemisphere {}", hemisphere.text),
+        ));
+    }
+    Ok((LOC_EQUATOR as i64 + thousandths) as u32)
+}
+
+/// Parse a LOC distance in meters, with an optional m suffix, into centimeters.
+fn parse_loc_meters(token: &Token, what: &str) -> Result<i64, DNSError> {
+    let text = token.text.strip_suffix(['m', 'M']).unwrap_or(&token.text);
+    parse_scaled_decimal(text, 2)
+        .ok_or_else(|| syntax_err(token, format!("invalid {} {}", what, token.text)))
+}
+
+/// Split a comma separated value list (RFC 9460 appendix A.1), where a backslash escapes the
+/// following byte.
+fn split_value_list(value: &[u8]) -> Vec<Vec<u8>> {
+    let mut items = Vec::<Vec<u8>>::new();
+    let mut item = Vec::<u8>::new();
+    let mut bytes = value.iter();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'\\' => item.extend(bytes.next()),
+            b',' => items.push(std::mem::take(&mut item)),
+            b => item.push(*b),
+        }
+    }
+    items.push(item);
+    items
+}
+
+/// Parse a SvcParam of a SVCB or HTTPS record: key=value, or key alone for an empty value. The
+/// value is either part of the token or the quoted token following key=.
+fn parse_svc_param(token: &Token, quoted_value: Option<&Token>) -> Result<SvcParam, DNSError> {
+    let (key, value) = match token.text.split_once('=') {
+        Some((key, value)) => (key, Some(value)),
+        None => (token.text.as_str(), None),
+    };
+    let key = SvcParam::key_from_mnemonic(key)
+        .ok_or_else(|| syntax_err(token, format!("unknown SvcParam key {}", key)))?;
+    let value = match (quoted_value, value) {
+        (Some(quoted), _) => Some(unescape(quoted)?),
+        (None, Some(value)) => Some(unescape(&Token {
+            text: value.to_string(),
+            ..token.clone()
+        })?),
+        (None, None) => None,
+    };
+    let value: Option<Vec<u8>> = value.map(|bytes| bytes.into_iter().map(|(b, _)| b).collect());
+    let invalid = || syntax_err(token, format!("invalid SvcParam {}", token.text));
+    let text = || {
+        value
+            .as_deref()
+            .and_then(|value| std::str::from_utf8(value).ok())
+            .ok_or_else(invalid)
+    };
+
+    let param = match key {
+        0 => SvcParam::Mandatory(
+            text()?
+                .split(',')
+                .map(|key| SvcParam::key_from_mnemonic(key).ok_or_else(invalid))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        1 => SvcParam::Alpn(split_value_list(value.as_deref().ok_or_else(invalid)?)),
+        2 if value.as_deref().is_none_or(<[u8]>::is_empty) => SvcParam::NoDefaultAlpn,
+        3 => SvcParam::Port(text()?.parse().map_err(|_| invalid())?),
+        4 => SvcParam::Ipv4Hint(
+            text()?
+                .split(',')
+                .map(|addr| addr.parse().map_err(|_| invalid()))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        5 => SvcParam::Ech(base64_decode(text()?).map_err(|_| invalid())?),
+        6 => SvcParam::Ipv6Hint(
+            text()?
+                .split(',')
+                .map(|addr| addr.parse().map_err(|_| invalid()))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        2..=6 => return Err(invalid()),
+        key => SvcParam::Unknown(key, value.unwrap_or_default()),
+    };
+
+    Ok(match param {
+        SvcParam::Mandatory(mut keys) => {
+            keys.sort_unstable();
+            SvcParam::Mandatory(keys)
+        }
+        param => param,
+    })
+}
+
+/// Fields of a record, consumed from left to right.
+struct Fields<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Fields<'a> {
+    fn next(&mut self, what: &str) -> Result<&'a Token, DNSError> {
+        match self.tokens.get(self.pos) {
+            Some(token) => {
+                self.pos += 1;
+                Ok(token)
+            }
+            None => {
+                let last = &self.tokens[self.tokens.len() - 1];
+                Err(DNSError::ZoneSyntax(
+                    last.line,
+                    last.column + last.text.len(),
+                    format!("missing {}", what),
+                ))
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Remaining fields, at least one.
+    fn rest(&mut self, what: &str) -> Result<&'a [Token], DNSError> {
+        self.next(what)?;
+        let rest = &self.tokens[self.pos - 1..];
+        self.pos = self.tokens.len();
+        Ok(rest)
+    }
+
+    /// Remaining fields concatenated, as allowed for base64 and hexadecimal data.
+    fn rest_joined(&mut self, what: &str) -> Result<(&'a Token, String), DNSError> {
+        let rest = self.rest(what)?;
+        Ok((
+            &rest[0],
+            rest.iter().map(|token| token.text.as_str()).collect(),
+        ))
+    }
+
+    fn finish(&self) -> Result<(), DNSError> {
+        match self.peek() {
+            Some(token) => Err(syntax_err(
+                token,
+                format!("unexpected field {}", token.text),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// State carried across the entries of a zone file.
+struct ZoneParser {
+    origin: Domain,
+    default_ttl: Option<u32>,
+    last_ttl: Option<u32>,
+    last_owner: Option<Domain>,
+    last_class: Class,
+    include_dir: PathBuf,
+    include_depth: usize,
+}
+
+impl ZoneParser {
+    fn new(origin: &Domain, include_dir: PathBuf, include_depth: usize) -> Self {
+        ZoneParser {
+            origin: origin.clone(),
+            default_ttl: None,
+            last_ttl: None,
+            last_owner: None,
+            last_class: DEFAULT_CLASS,
+            include_dir,
+            include_depth,
+        }
+    }
+
+    fn parse(&mut self, input: &str, records: &mut Vec<Record>) -> Result<(), DNSError> {
+        for entry in tokenize(input)? {
+            let first = &entry.tokens[0];
+            if !entry.blank_owner && !first.quoted && first.text.starts_with('$') {
+                self.parse_directive(&entry, records)?;
+            } else {
+                let record = self.parse_record(&entry.tokens, entry.blank_owner)?;
+                records.push(record);
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_directive(
+        &mut self,
+        entry: &Entry,
+        records: &mut Vec<Record>,
+    ) -> Result<(), DNSError> {
+        let directive = &entry.tokens[0];
+        let mut fields = Fields {
+            tokens: &entry.tokens,
+            pos: 1,
+        };
+
+        match directive.text.to_ascii_uppercase().as_str() {
+            "$ORIGIN" => {
+                self.origin = self.parse_name(fields.next("origin")?)?;
+            }
+            "$TTL" => {
+                self.default_ttl = Some(parse_ttl(fields.next("TTL")?)?);
+            }
+            "$INCLUDE" => {
+                let file = fields.next("file name")?;
+                let origin = match fields.peek() {
+                    Some(_) => self.parse_name(fields.next("origin")?)?,
+                    None => self.origin.clone(),
+                };
+                self.parse_include(file, &origin, records)?;
+            }
+            "$GENERATE" => {
+                let range = fields.next("range")?;
+                let lhs = fields.next("owner template")?;
+                let rest = fields.rest("record type")?;
+                self.parse_generate(range, lhs, rest, records)?;
+                return Ok(());
+            }
+            _ => {
+                return Err(syntax_err(
+                    directive,
+                    format!("unknown directive {}", directive.text),
+                ))
+            }
+        }
+
+        fields.finish()
+    }
+
+    /// Parse another zone file in place of a $INCLUDE directive. The origin and TTL changes made
+    /// by the included file do not apply to the including one.
+    fn parse_include(
+        &self,
+        file: &Token,
+        origin: &Domain,
+        records: &mut Vec<Record>,
+    ) -> Result<(), DNSError> {
+        if self.include_depth >= MAX_INCLUDE_DEPTH {
+            return Err(syntax_err(file, "too many nested $INCLUDE directives"));
+        }
+
+        let file_name = String::from_utf8(unescape(file)?.into_iter().map(|(b, _)| b).collect())
+            .map_err(|_| syntax_err(file, "file name is not valid UTF-8"))?;
+        let path = self.include_dir.join(file_name);
+        let input = fs::read_to_string(&path)
+            .map_err(|err| syntax_err(file, format!("cannot read {}: {}", path.display(), err)))?;
+        let include_dir = path
+            .parent()
+            .map_or(self.include_dir.clone(), Path::to_path_buf);
+
+        let mut parser = ZoneParser::new(origin, include_dir, self.include_depth + 1);
+        parser.default_ttl = self.default_ttl;
+        parser.last_ttl = self.last_ttl;
+        parser.last_class = self.last_class;
+        parser.parse(&input, records).map_err(|err| match err {
+            DNSError::ZoneSyntax(line, column, msg) => {
+                DNSError::ZoneSyntax(line, column, format!("in {}: {}", path.display(), msg))
+            }
+            err => err,
+        })
+    }
+
+    /// Expand a $GENERATE directive: `$GENERATE start-stop[/step] lhs [ttl] [class] type rhs`,
+    /// where `$` in lhs and rhs is replaced by the iterator, optionally formatted with
+    /// `${offset,width,base}`.
+    fn parse_generate(
+        &mut self,
+        range: &Token,
+        lhs: &Token,
+        rest: &[Token],
+        records: &mut Vec<Record>,
+    ) -> Result<(), DNSError> {
+        let invalid_range = || syntax_err(range, format!("invalid range {}", range.text));
+        let (bounds, step) = match range.text.split_once('/') {
+            Some((bounds, step)) => (bounds, step.parse::<u32>().map_err(|_| invalid_range())?),
+            None => (range.text.as_str(), 1),
+        };
+        let (start, stop) = bounds.split_once('-').ok_or_else(invalid_range)?;
+        let start = start.parse::<u32>().map_err(|_| invalid_range())?;
+        let stop = stop.parse::<u32>().map_err(|_| invalid_range())?;
+        if step == 0 || stop < start || (stop - start) / step >= MAX_GENERATE_RECORDS {
+            return Err(invalid_range());
+        }
+
+        let (rhs, middle) = rest.split_last().ok_or_else(invalid_range)?;
+        if middle.is_empty() {
+            return Err(syntax_err(rhs, "missing record type"));
+        }
+
+        for value in (start..=stop).step_by(step as usize) {
+            let mut tokens = Vec::<Token>::with_capacity(rest.len() + 1);
+            tokens.push(Token {
+                text: expand_template(lhs, value)?,
+                ..lhs.clone()
+            });
+            tokens.extend(middle.iter().cloned());
+            tokens.push(Token {
+                text: expand_template(rhs, value)?,
+                ..rhs.clone()
+            });
+
+            let record = self.parse_record(&tokens, false)?;
+            records.push(record);
+        }
+
+        Ok(())
+    }
+
+    /// Parse a domain name, relative to the origin unless it ends with a dot.
+    fn parse_name(&self, token: &Token) -> Result<Domain, DNSError> {
+        if token.text == "@" && !token.quoted {
+            return Ok(self.origin.clone());
+        }
+
+        let bytes = unescape(token)?;
+        let absolute = bytes
+            .last()
+            .is_some_and(|(b, escaped)| *b == b'.' && !escaped);
+        if bytes.len() == 1 && absolute {
+            return Ok(Domain::root());
+        }
+        let bytes = if absolute {
+            &bytes[..bytes.len() - 1]
+        } else {
+            &bytes[..]
+        };
+
+        let mut labels = Vec::<Vec<u8>>::new();
+        for label in bytes.split(|(b, escaped)| *b == b'.' && !escaped) {
+            if label.is_empty() {
+                return Err(syntax_err(token, format!("empty label in {}", token.text)));
+            }
+            if label.len() > MAX_LABEL_SIZE {
+                return Err(syntax_err(
+                    token,
+                    format!("label too long in {}", token.text),
+                ));
+            }
+            labels.push(label.iter().map(|(b, _)| *b).collect());
+        }
+
+        let too_long = || syntax_err(token, format!("domain name too long: {}", token.text));
+        let name = Domain::from_labels(labels).map_err(|_| too_long())?;
+        if absolute {
+            Ok(name)
+        } else {
+            name.append(&self.origin).map_err(|_| too_long())
+        }
+    }
+
+    /// Parse a record: `[owner] [ttl] [class] type rdata`, where TTL and class may be swapped.
+    fn parse_record(&mut self, tokens: &[Token], blank_owner: bool) -> Result<Record, DNSError> {
+        let mut fields = Fields { tokens, pos: 0 };
+
+        let owner = if blank_owner {
+            self.last_owner
+                .clone()
+                .ok_or_else(|| syntax_err(&tokens[0], "no previous owner name"))?
+        } else {
+            self.parse_name(fields.next("owner name")?)?
+        };
+
+        let (mut ttl, mut class) = (None, None);
+        let type_token = loop {
+            let token = fields.next("record type")?;
+            if ttl.is_none() && token.text.starts_with(|c: char| c.is_ascii_digit()) {
+                ttl = Some(parse_ttl(token)?);
+            } else if let Some(parsed_class) = class.is_none().then(|| parse_class(&token.text)) {
+                match parsed_class {
+                    Some(parsed_class) => class = Some(parsed_class),
+                    None => break token,
+                }
+            } else {
+                break token;
+            }
+        };
+        let record_type = parse_type(type_token)?;
+
+        // RFC 2308 $TTL default, else the last explicit TTL as in RFC 1035
+        if ttl.is_some() {
+            self.last_ttl = ttl;
+        }
+        let ttl = ttl
+            .or(self.default_ttl)
+            .or(self.last_ttl)
+            .ok_or_else(|| syntax_err(type_token, "missing TTL and no $TTL default"))?;
+        let class = class.unwrap_or(self.last_class);
+
+        let record = match fields.peek() {
+            Some(token) if token.text == "\\#" && !token.quoted => {
+                fields.next("generic RDATA")?;
+                let len = parse_number::<u16>(fields.next("RDATA length")?, "RDATA length")?;
+                let rdata = if len == 0 {
+                    Vec::new()
+                } else {
+                    let (hex_token, hex) = fields.rest_joined("RDATA")?;
+                    let rdata = hex_decode(&hex)
+                        .map_err(|_| syntax_err(hex_token, "invalid hexadecimal RDATA"))?;
+                    if rdata.len() != len as usize {
+                        return Err(syntax_err(hex_token, "RDATA length mismatch"));
+                    }
+                    rdata
+                };
+                let record =
+                    Record::from_wire_rdata(owner.clone(), class, ttl, record_type, &rdata)
+                        .map_err(|_| syntax_err(token, "RDATA is invalid for the record type"))?;
+                // SVCB parameters aren't validated when parsed from the wire
+                if let RecordData::SVCB(svcb) | RecordData::HTTPS(HTTPS { svcb }) = record.data() {
+                    svcb.validate()
+                        .map_err(|err| syntax_err(token, err.to_string()))?;
+                }
+                record
+            }
+            _ => {
+                let data = self.parse_rdata(&record_type, type_token, &mut fields)?;
+                Record::new(owner.clone(), class, ttl, data)
+            }
+        };
+        fields.finish()?;
+
+        self.last_owner = Some(owner);
+        self.last_class = class;
+        Ok(record)
+    }
+
+    /// Parse the RDATA of a record in the presentation format of its type.
+    fn parse_rdata(
+        &self,
+        record_type: &QueryType,
+        type_token: &Token,
+        fields: &mut Fields,
+    ) -> Result<RecordData, DNSError> {
+        let data = match record_type {
+            QueryType::A => {
+                let token = fields.next("address")?;
+                RecordData::A(A {
+                    addr: parse_number::<Ipv4Addr>(token, "IPv4 address")?,
+                })
+            }
+            QueryType::AAAA => {
+                let token = fields.next("address")?;
+                RecordData::AAAA(AAAA {
+                    addr: parse_number::<Ipv6Addr>(token, "IPv6 address")?,
+                })
+            }
+            QueryType::CNAME => RecordData::CNAME(CNAME {
+                cname: self.parse_name(fields.next("canonical name")?)?,
+            }),
+            QueryType::DNAME => RecordData::DNAME(DNAME {
+                dname: self.parse_name(fields.next("target name")?)?,
+            }),
+            QueryType::NS => RecordData::NS(NS {
+                nsdname: self.parse_name(fields.next("name server")?)?,
+            }),
+            QueryType::PTR => RecordData::PTR(PTR {
+                ptrname: self.parse_name(fields.next("pointer name")?)?,
+            }),
+            QueryType::MX => RecordData::MX(MX {
+                preference: parse_number(fields.next("preference")?, "preference")?,
+                exchange: self.parse_name(fields.next("exchange")?)?,
+            }),
+            QueryType::SRV => RecordData::SRV(SRV {
+                priority: parse_number(fields.next("priority")?, "priority")?,
+                weight: parse_number(fields.next("weight")?, "weight")?,
+                port: parse_number(fields.next("port")?, "port")?,
+                target: self.parse_name(fields.next("target")?)?,
+            }),
+            QueryType::LOC => {
+                let latitude = parse_loc_coordinate(fields, "latitude", ["N", "S"], 90)?;
+                let longitude = parse_loc_coordinate(fields, "longitude", ["E", "W"], 180)?;
+                let token = fields.next("altitude")?;
+                let altitude =
+                    u32::try_from(parse_loc_meters(token, "altitude")? + LOC_ALTITUDE_BASE as i64)
+                        .map_err(|_| {
+                            syntax_err(token, format!("altitude {} out of range", token.text))
+                        })?;
+
+                // The size and precisions default to 1 m, 10 km and 10 m
+                let mut precisions = [0x12, 0x16, 0x13];
+                for (precision, what) in precisions.iter_mut().zip([
+                    "size",
+                    "horizontal precision",
+                    "vertical precision",
+                ]) {
+                    let Some(token) = fields.peek() else {
+                        break;
+                    };
+                    fields.next(what)?;
+                    *precision = u64::try_from(parse_loc_meters(token, what)?)
+                        .ok()
+                        .and_then(encode_precision)
+                        .ok_or_else(|| {
+                            syntax_err(token, format!("{} {} out of range", what, token.text))
+                        })?;
+                }
+
+                let [size, horiz_pre, vert_pre] = precisions;
+                RecordData::LOC(LOC {
+                    version: 0,
+                    size,
+                    horiz_pre,
+                    vert_pre,
+                    latitude,
+                    longitude,
+                    altitude,
+                })
+            }
+            QueryType::NAPTR => {
+                let order = parse_number(fields.next("order")?, "order")?;
+                let preference = parse_number(fields.next("preference")?, "preference")?;
+                let mut string = |what: &str| -> Result<String, DNSError> {
+                    let token = fields.next(what)?;
+                    String::from_utf8(parse_character_string(token)?)
+                        .map_err(|_| syntax_err(token, format!("{} is not valid UTF-8", what)))
+                };
+                let flags = string("flags")?;
+                let services = string("services")?;
+                let regexp = string("regexp")?;
+                RecordData::NAPTR(NAPTR {
+                    order,
+                    preference,
+                    flags,
+                    services,
+                    regexp,
+                    replacement: self.parse_name(fields.next("replacement")?)?,
+                })
+            }
+            QueryType::URI => {
+                let priority = parse_number(fields.next("priority")?, "priority")?;
+                let weight = parse_number(fields.next("weight")?, "weight")?;
+                let token = fields.next("target")?;
+                let target: Vec<u8> = unescape(token)?.into_iter().map(|(b, _)| b).collect();
+                RecordData::URI(URI {
+                    priority,
+                    weight,
+                    target: String::from_utf8(target)
+                        .map_err(|_| syntax_err(token, "target is not valid UTF-8"))?,
+                })
+            }
+            QueryType::SOA => RecordData::SOA(SOA {
+                mname: self.parse_name(fields.next("primary name server")?)?,
+                rname: self.parse_name(fields.next("responsible mailbox")?)?,
+                serial: parse_number(fields.next("serial")?, "serial")?,
+                refresh: parse_ttl(fields.next("refresh")?)?,
+                retry: parse_ttl(fields.next("retry")?)?,
+                expire: parse_ttl(fields.next("expire")?)?,
+                minttl: parse_ttl(fields.next("minimum TTL")?)?,
+            }),
+            QueryType::TXT => {
+                let mut txt_data = Vec::<Vec<u8>>::new();
+                for token in fields.rest("text")? {
+                    txt_data.push(parse_character_string(token)?);
+                }
+                RecordData::TXT(TXT { txt_data })
+            }
+            QueryType::SSHFP => {
+                let algorithm = parse_number(fields.next("algorithm")?, "algorithm")?;
+                let fingerprint_type =
+                    parse_number(fields.next("fingerprint type")?, "fingerprint type")?;
+                let (token, fingerprint) = fields.rest_joined("fingerprint")?;
+                let fingerprint = hex_decode(&fingerprint)
+                    .map_err(|_| syntax_err(token, "invalid hexadecimal fingerprint"))?;
+                RecordData::SSHFP(SSHFP {
+                    algorithm: Algorithm::from_num(algorithm),
+                    fingerprint_type: FingerprintType::from_num(fingerprint_type),
+                    fingerprint: hex_encode(&fingerprint).to_lowercase(),
+                })
+            }
+            QueryType::CAA => {
+                let flags = parse_number(fields.next("flags")?, "flags")?;
+                let tag_token = fields.next("tag")?;
+                let value = unescape(fields.next("value")?)?;
+                let caa = CAA {
+                    flags,
+                    tag: tag_token.text.clone(),
+                    value: value.into_iter().map(|(b, _)| b).collect(),
+                };
+                caa.validate_tag()
+                    .map_err(|err| syntax_err(tag_token, err.to_string()))?;
+                RecordData::CAA(caa)
+            }
+            QueryType::TLSA | QueryType::SMIMEA => {
+                let usage = parse_number(fields.next("certificate usage")?, "certificate usage")?;
+                let selector = parse_number(fields.next("selector")?, "selector")?;
+                let matching_type = parse_number(fields.next("matching type")?, "matching type")?;
+                let (token, association_data) = fields.rest_joined("association data")?;
+                let tlsa = TLSA {
+                    certificate_usage: CertificateUsage::from_num(usage),
+                    selector: Selector::from_num(selector),
+                    matching_type: MatchingType::from_num(matching_type),
+                    association_data: hex_decode(&association_data)
+                        .map_err(|_| syntax_err(token, "invalid hexadecimal association data"))?,
+                };
+                match record_type {
+                    QueryType::TLSA => RecordData::TLSA(tlsa),
+                    _ => RecordData::SMIMEA(SMIMEA { tlsa }),
+                }
+            }
+            QueryType::DNSKEY | QueryType::CDNSKEY => {
+                let flags = parse_number(fields.next("flags")?, "flags")?;
+                let protocol = parse_number(fields.next("protocol")?, "protocol")?;
+                let algorithm = parse_dnssec_algorithm(fields.next("algorithm")?)?;
+                let (token, public_key) = fields.rest_joined("public key")?;
+                let dnskey = DNSKEY {
+                    flags,
+                    protocol,
+                    algorithm,
+                    public_key: base64_decode(&public_key)
+                        .map_err(|_| syntax_err(token, "invalid base64 public key"))?,
+                };
+                match record_type {
+                    QueryType::DNSKEY => RecordData::DNSKEY(dnskey),
+                    _ => RecordData::CDNSKEY(CDNSKEY { dnskey }),
+                }
+            }
+            QueryType::DS | QueryType::CDS => {
+                let key_tag = parse_number(fields.next("key tag")?, "key tag")?;
+                let algorithm = parse_dnssec_algorithm(fields.next("algorithm")?)?;
+                let digest_type = parse_number(fields.next("digest type")?, "digest type")?;
+                let (token, digest) = fields.rest_joined("digest")?;
+                let ds = DS {
+                    key_tag,
+                    algorithm,
+                    digest_type: DigestType::from_num(digest_type),
+                    digest: hex_decode(&digest)
+                        .map_err(|_| syntax_err(token, "invalid hexadecimal digest"))?,
+                };
+                match record_type {
+                    QueryType::DS => RecordData::DS(ds),
+                    _ => RecordData::CDS(CDS { ds }),
+                }
+            }
+            QueryType::RRSIG => {
+                let type_covered = parse_type(fields.next("type covered")?)?;
+                let algorithm = parse_dnssec_algorithm(fields.next("algorithm")?)?;
+                let labels = parse_number(fields.next("labels")?, "labels")?;
+                let original_ttl = parse_ttl(fields.next("original TTL")?)?;
+                let expiration_token = fields.next("signature expiration")?;
+                let inception_token = fields.next("signature inception")?;
+                let key_tag = parse_number(fields.next("key tag")?, "key tag")?;
+                let signer_name = self.parse_name(fields.next("signer name")?)?;
+                let (token, signature) = fields.rest_joined("signature")?;
+                RecordData::RRSIG(RRSIG {
+                    type_covered,
+                    algorithm,
+                    labels,
+                    original_ttl,
+                    signature_expiration: parse_rrsig_time(&expiration_token.text)
+                        .map_err(|_| syntax_err(expiration_token, "invalid timestamp"))?,
+                    signature_inception: parse_rrsig_time(&inception_token.text)
+                        .map_err(|_| syntax_err(inception_token, "invalid timestamp"))?,
+                    key_tag,
+                    signer_name,
+                    signature: base64_decode(&signature)
+                        .map_err(|_| syntax_err(token, "invalid base64 signature"))?,
+                })
+            }
+            QueryType::NSEC => {
+                let next_domain = self.parse_name(fields.next("next domain name")?)?;
+                let mut types = Vec::<QueryType>::new();
+                while let Some(token) = fields.peek() {
+                    types.push(parse_type(token)?);
+                    fields.next("type")?;
+                }
+                RecordData::NSEC(NSEC { next_domain, types })
+            }
+            QueryType::NSEC3 | QueryType::NSEC3PARAM => {
+                let hash_algorithm =
+                    parse_number(fields.next("hash algorithm")?, "hash algorithm")?;
+                let flags = parse_number(fields.next("flags")?, "flags")?;
+                let iterations = parse_number(fields.next("iterations")?, "iterations")?;
+                let salt_token = fields.next("salt")?;
+                let salt = match salt_token.text.as_str() {
+                    "-" => Vec::new(),
+                    salt => hex_decode(salt)
+                        .map_err(|_| syntax_err(salt_token, "invalid hexadecimal salt"))?,
+                };
+                let hash_algorithm = NSEC3HashAlgorithm::from_num(hash_algorithm);
+
+                if *record_type == QueryType::NSEC3PARAM {
+                    RecordData::NSEC3PARAM(NSEC3PARAM {
+                        hash_algorithm,
+                        flags,
+                        iterations,
+                        salt,
+                    })
+                } else {
+                    let next_token = fields.next("next hashed owner name")?;
+                    let next_hashed_owner = base32hex_decode(&next_token.text)
+                        .map_err(|_| syntax_err(next_token, "invalid base32 hashed owner name"))?;
+                    let mut types = Vec::<QueryType>::new();
+                    while let Some(token) = fields.peek() {
+                        types.push(parse_type(token)?);
+                        fields.next("type")?;
+                    }
+                    RecordData::NSEC3(NSEC3 {
+                        hash_algorithm,
+                        flags,
+                        iterations,
+                        salt,
+                        next_hashed_owner,
+                        types,
+                    })
+                }
+            }
+            QueryType::SVCB | QueryType::HTTPS => {
+                let priority = parse_number(fields.next("priority")?, "priority")?;
+                let target = self.parse_name(fields.next("target name")?)?;
+                let mut params = Vec::<SvcParam>::new();
+                while let Some(token) = fields.peek() {
+                    fields.next("SvcParam")?;
+                    // A quoted value is a separate token following key=
+                    let value = match fields.peek() {
+                        Some(value) if token.text.ends_with('=') && value.quoted => {
+                            fields.next("SvcParam value")?;
+                            Some(value)
+                        }
+                        _ => None,
+                    };
+                    params.push(parse_svc_param(token, value)?);
+                }
+                params.sort_by_key(SvcParam::key);
+
+                let svcb = SVCB {
+                    priority,
+                    target,
+                    params,
+                };
+                svcb.validate()
+                    .map_err(|err| syntax_err(type_token, err.to_string()))?;
+                match record_type {
+                    QueryType::SVCB => RecordData::SVCB(svcb),
+                    _ => RecordData::HTTPS(HTTPS { svcb }),
+                }
+            }
+            QueryType::OPT => {
+                return Err(syntax_err(
+                    type_token,
+                    "OPT records cannot appear in zone files",
+                ))
+            }
+            _ => {
+                return Err(syntax_err(
+                    type_token,
+                    format!(
+                        "type {} requires the generic \\# RDATA format",
+                        type_token.text
+                    ),
+                ))
+            }
+        };
+
+        Ok(data)
+    }
+}
+
+/// Substitute the iterator of a $GENERATE directive in a template.
+fn expand_template(template: &Token, value: u32) -> Result<String, DNSError> {
+    let invalid = || syntax_err(template, format!("invalid template {}", template.text));
+    let mut expanded = String::new();
+    let mut chars = template.text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                // Escaped $ is a literal $
+                Some('$') => expanded.push('$'),
+                Some(escaped) => {
+                    expanded.push('\\');
+                    expanded.push(escaped);
+                }
+                None => expanded.push('\\'),
+            },
+            '$' => {
+                let (mut offset, mut width, mut base) = (0i64, 0usize, 'd');
+                if chars.peek() == Some(&'{') {
+                    chars.next();
+                    let modifiers: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                    let mut modifiers = modifiers.split(',');
+                    if let Some(text) = modifiers.next() {
+                        offset = text.parse().map_err(|_| invalid())?;
+                    }
+                    if let Some(text) = modifiers.next() {
+                        width = text
+                            .parse()
+                            .ok()
+                            .filter(|width| *width <= MAX_GENERATE_WIDTH)
+                            .ok_or_else(invalid)?;
+                    }
+                    if let Some(text) = modifiers.next() {
+                        base = text.parse().map_err(|_| invalid())?;
+                    }
+                }
+
+                let value = (value as i64)
+                    .checked_add(offset)
+                    .and_then(|value| u32::try_from(value).ok())
+                    .ok_or_else(invalid)?;
+                let formatted = match base {
+                    'd' => format!("{:0width$}", value, width = width),
+                    'o' => format!("{:0width$o}", value, width = width),
+                    'x' => format!("{:0width$x}", value, width = width),
+                    'X' => format!("{:0width$X}", value, width = width),
+                    _ => return Err(invalid()),
+                };
+                expanded.push_str(&formatted);
+            }
+            c => expanded.push(c),
+        }
+    }
+
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns_packet::Unknown;
+    use std::process;
+
+    fn domain(name: &str) -> Domain {
+        name.parse().unwrap()
+    }
+
+    fn a_record(name: &str, ttl: u32, addr: [u8; 4]) -> Record {
+        Record::new(
+            domain(name),
+            Class::IN,
+            ttl,
+            RecordData::A(A {
+                addr: Ipv4Addr::from(addr),
+            }),
+        )
+    }
+
+    /// Records printed in presentation format parse back to the same records.
+    fn assert_round_trip(records: &[Record]) {
+        let presented: String = records
+            .iter()
+            .map(|record| format!("{}\n", record))
+            .collect();
+        assert_eq!(parse_zone(&presented, &domain("")).unwrap(), records);
+    }
+
+    #[test]
+    fn test_parse_zone() {
+        let zone = r#"
+$ORIGIN example.com.
+$TTL 1h
+@   IN  SOA ns1 hostmaster.example.com. (
+            2024010101 ; serial
+            2h         ; refresh
+            1h         ; retry
+            2w         ; expire
+            300 )      ; minimum
+    IN  NS  ns1
+    IN  MX  10 mail
+ns1 300 IN A 192.0.2.1
+mail IN 600 AAAA 2001:db8::1
+www CNAME @
+_sip._tcp SRV 10 60 5060 sip.example.org.
+txt TXT "v=spf1 -all" unquoted "\"quoted\" \059 semicolon"
+alias DNAME example.net.
+1.2.0.192.in-addr.arpa. PTR www
+sub NS ns.sub
+ns.sub A 192.0.2.53
+"#;
+
+        let records = parse_zone(zone, &domain("example.org")).unwrap();
+
+        let expected = vec![
+            Record::new(
+                domain("example.com"),
+                Class::IN,
+                3600,
+                RecordData::SOA(SOA {
+                    mname: domain("ns1.example.com"),
+                    rname: domain("hostmaster.example.com"),
+                    serial: 2024010101,
+                    refresh: 7200,
+                    retry: 3600,
+                    expire: 1209600,
+                    minttl: 300,
+                }),
+            ),
+            Record::new(
+                domain("example.com"),
+                Class::IN,
+                3600,
+                RecordData::NS(NS {
+                    nsdname: domain("ns1.example.com"),
+                }),
+            ),
+            Record::new(
+                domain("example.com"),
+                Class::IN,
+                3600,
+                RecordData::MX(MX {
+                    preference: 10,
+                    exchange: domain("mail.example.com"),
+                }),
+            ),
+            a_record("ns1.example.com", 300, [192, 0, 2, 1]),
+            Record::new(
+                domain("mail.example.com"),
+                Class::IN,
+                600,
+                RecordData::AAAA(AAAA {
+                    addr: "2001:db8::1".parse().unwrap(),
+                }),
+            ),
+            Record::new(
+                domain("www.example.com"),
+                Class::IN,
+                3600,
+                RecordData::CNAME(CNAME {
+                    cname: domain("example.com"),
+                }),
+            ),
+            Record::new(
+                domain("_sip._tcp.example.com"),
+                Class::IN,
+                3600,
+                RecordData::SRV(SRV {
+                    priority: 10,
+                    weight: 60,
+                    port: 5060,
+                    target: domain("sip.example.org"),
+                }),
+            ),
+            Record::new(
+                domain("txt.example.com"),
+                Class::IN,
+                3600,
+                RecordData::TXT(TXT {
+                    txt_data: vec![
+                        b"v=spf1 -all".to_vec(),
+                        b"unquoted".to_vec(),
+                        b"\"quoted\" ; semicolon".to_vec(),
+                    ],
+                }),
+            ),
+            Record::new(
+                domain("alias.example.com"),
+                Class::IN,
+                3600,
+                RecordData::DNAME(DNAME {
+                    dname: domain("example.net"),
+                }),
+            ),
+            Record::new(
+                domain("1.2.0.192.in-addr.arpa"),
+                Class::IN,
+                3600,
+                RecordData::PTR(PTR {
+                    ptrname: domain("www.example.com"),
+                }),
+            ),
+            Record::new(
+                domain("sub.example.com"),
+                Class::IN,
+                3600,
+                RecordData::NS(NS {
+                    nsdname: domain("ns.sub.example.com"),
+                }),
+            ),
+            a_record("ns.sub.example.com", 3600, [192, 0, 2, 53]),
+        ];
+
+        assert_eq!(records, expected);
+        assert_round_trip(&records);
+    }
+
+    #[test]
+    fn test_parse_dnssec_records() {
+        let zone = r#"
+example.com. 3600 IN DNSKEY 257 3 15 l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=
+example.com. 3600 CDNSKEY 257 3 ED25519 (
+    l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4= )
+example.com. 3600 DS 3613 15 2 3aa5ab37efce57f737fc1627013fee07bdf241bd10f3b1964ab55c78e79a304b
+example.com. 3600 CDS 3613 15 2 (
+    3aa5ab37efce57f737fc1627013fee07
+    bdf241bd10f3b1964ab55c78e79a304b )
+example.com. 3600 RRSIG MX 15 2 3600 20150820000000 1438207200 3613 example.com. (
+    oL9krJun7xfBOIWcGHi7mag5/hdZrKWw15jPGrHpjQeRAvTdszaPD+QLs3fx8A4M3e23mRZ9VrbpMngwcrqNAg== )
+alfa.example.com. 86400 NSEC host.example.com. A MX RRSIG NSEC TYPE1234
+2t7b4g4vsa5smi47k61mv5bv1a22bojr.example. 3600 NSEC3 1 1 12 aabbccdd (
+    2vptu5timamqttgl4luu9kg21e0aor3s A RRSIG )
+example. 0 NSEC3PARAM 1 0 12 -
+example.com. 3600 SSHFP 4 2 123456789ABCDEF67890123456789ABCDEF67890123456789ABCDEF123456789
+"#;
+
+        let records = parse_zone(zone, &domain("")).unwrap();
+        let public_key = base64_decode("l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=").unwrap();
+        let digest =
+            hex_decode("3aa5ab37efce57f737fc1627013fee07bdf241bd10f3b1964ab55c78e79a304b").unwrap();
+        let ds = DS {
+            key_tag: 3613,
+            algorithm: DNSSECAlgorithm::ED25519,
+            digest_type: DigestType::SHA256,
+            digest,
+        };
+        let dnskey = DNSKEY::new(257, DNSSECAlgorithm::ED25519, public_key);
+
+        assert_eq!(records.len(), 9);
+        assert_eq!(records[0].data(), &RecordData::DNSKEY(dnskey.clone()));
+        assert_eq!(records[1].data(), &RecordData::CDNSKEY(CDNSKEY { dnskey }));
+        assert_eq!(records[2].data(), &RecordData::DS(ds.clone()));
+        assert_eq!(records[3].data(), &RecordData::CDS(CDS { ds }));
+
+        let RecordData::RRSIG(rrsig) = records[4].data() else {
+            panic!("expected RRSIG")
+        };
+        assert_eq!(rrsig.type_covered, QueryType::MX);
+        assert_eq!(rrsig.signature_expiration, 1440028800);
+        assert_eq!(rrsig.signature_inception, 1438207200);
+        assert_eq!(rrsig.signer_name, domain("example.com"));
+        assert_eq!(rrsig.signature.len(), 64);
+
+        assert_eq!(
+            records[5].data(),
+            &RecordData::NSEC(NSEC {
+                next_domain: domain("host.example.com"),
+                types: vec![
+                    QueryType::A,
+                    QueryType::MX,
+                    QueryType::RRSIG,
+                    QueryType::NSEC,
+                    QueryType::Unknown(1234)
+                ],
+            })
+        );
+        let RecordData::NSEC3(nsec3) = records[6].data() else {
+            panic!("expected NSEC3")
+        };
+        assert_eq!(nsec3.salt, vec![0xAA, 0xBB, 0xCC, 0xDD]);
+        assert_eq!(nsec3.next_hashed_owner.len(), 20);
+        assert!(nsec3.is_opt_out());
+        assert_eq!(
+            records[7].data(),
+            &RecordData::NSEC3PARAM(NSEC3PARAM {
+                hash_algorithm: NSEC3HashAlgorithm::SHA1,
+                flags: 0,
+                iterations: 12,
+                salt: Vec::new(),
+            })
+        );
+        assert_eq!(
+            records[8].data(),
+            &RecordData::SSHFP(SSHFP {
+                algorithm: Algorithm::ED25519,
+                fingerprint_type: FingerprintType::SHA256,
+                fingerprint: "123456789abcdef67890123456789abcdef67890123456789abcdef123456789"
+                    .to_string(),
+            })
+        );
+        assert_round_trip(&records);
+    }
+
+    #[test]
+    fn test_parse_generic_rdata() {
+        let zone = r#"
+a.example. 300 CLASS1 TYPE1 \# 4 C0000201
+b.example. 300 IN TYPE731 \# 6 abcd (
+    ef012345 )
+c.example. 300 IN HINFO \# 0
+d.example. 300 IN CAA \# 13 0005697373756563612e6e6574
+"#;
+
+        let records = parse_zone(zone, &domain("")).unwrap();
+
+        assert_eq!(
+            records,
+            vec![
+                a_record("a.example", 300, [192, 0, 2, 1]),
+                Record::new(
+                    domain("b.example"),
+                    Class::IN,
+                    300,
+                    RecordData::Unknown(Unknown {
+                        code: 731,
+                        data: vec![0xAB, 0xCD, 0xEF, 0x01, 0x23, 0x45],
+                    }),
+                ),
+                Record::new(
+                    domain("c.example"),
+                    Class::IN,
+                    300,
+                    RecordData::Unknown(Unknown {
+                        code: 13,
+                        data: Vec::new(),
+                    }),
+                ),
+                Record::new(
+                    domain("d.example"),
+                    Class::IN,
+                    300,
+                    RecordData::CAA(CAA {
+                        flags: 0,
+                        tag: "issue".to_string(),
+                        value: b"ca.net".to_vec(),
+                    }),
+                ),
+            ]
+        );
+        assert_round_trip(&records);
+    }
+
+    #[test]
+    fn test_parse_svcb() {
+        // RFC 9460 appendix D
+        let zone = r#"
+$ORIGIN example.com.
+$TTL 300
+@ HTTPS 0 foo.example.com.
+@ SVCB 1 .
+@ SVCB 16 foo.example.com. port=53
+@ SVCB 1 foo.example.com. key667="hello\210qoo"
+@ SVCB 1 foo.example.com. ( ipv6hint="2001:db8::1,2001:db8::53:1" )
+@ SVCB 16 foo.example.org. ( alpn=h2,h3-19 mandatory=ipv4hint,alpn
+    ipv4hint=192.0.2.1 )
+@ SVCB 16 foo.example.org. alpn="f\\\\oo\\,bar,h2"
+@ HTTPS 1 . alpn=h2 no-default-alpn ech=AEj+DQBE
+"#;
+
+        let records = parse_zone(zone, &domain("")).unwrap();
+        let svcb = |record: &Record| match record.data() {
+            RecordData::SVCB(svcb) => svcb.clone(),
+            RecordData::HTTPS(https) => https.svcb.clone(),
+            data => panic!("unexpected {:?}", data),
+        };
+
+        assert_eq!(records.len(), 8);
+        assert!(svcb(&records[0]).is_alias_mode());
+        assert_eq!(records[1].record_type(), QueryType::SVCB);
+        assert_eq!(
+            svcb(&records[3]).params,
+            vec![SvcParam::Unknown(667, b"hello\xD2qoo".to_vec())]
+        );
+        assert_eq!(
+            svcb(&records[5]).params,
+            vec![
+                SvcParam::Mandatory(vec![1, 4]),
+                SvcParam::Alpn(vec![b"h2".to_vec(), b"h3-19".to_vec()]),
+                SvcParam::Ipv4Hint(vec![Ipv4Addr::new(192, 0, 2, 1)]),
+            ]
+        );
+        assert_eq!(
+            svcb(&records[6]).params,
+            vec![SvcParam::Alpn(vec![b"f\\oo,bar".to_vec(), b"h2".to_vec()])]
+        );
+        assert_eq!(
+            records[7].to_string(),
+            "example.com.\t300\tIN\tHTTPS\t1 . alpn=h2 no-default-alpn ech=AEj+DQBE"
+        );
+        assert_round_trip(&records);
+
+        for invalid in [
+            "@ 300 SVCB 1 . mandatory=mandatory",
+            "@ 300 SVCB 1 . mandatory=port",
+            "@ 300 SVCB 1 . port=53 port=54",
+            "@ 300 SVCB 1 . no-default-alpn",
+            "@ 300 SVCB 1 . port=http",
+            "@ 300 SVCB 1 . key0667=1",
+        ] {
+            assert!(matches!(
+                parse_zone(invalid, &domain("example.com")),
+                Err(DNSError::ZoneSyntax(..))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_caa() {
+        let zone = r#"
+$ORIGIN example.com.
+@ 3600 CAA 0 issue "ca.example.net; account=230123"
+@ 3600 CAA 0 issuewild ";"
+@ 3600 CAA 128 iodef "mailto:security@example.com"
+@ 3600 CAA 0 tbs Unknown
+"#;
+
+        let records = parse_zone(zone, &domain("")).unwrap();
+
+        assert_eq!(records.len(), 4);
+        assert_eq!(
+            records[0].data(),
+            &RecordData::CAA(CAA {
+                flags: 0,
+                tag: "issue".to_string(),
+                value: b"ca.example.net; account=230123".to_vec(),
+            })
+        );
+        assert_eq!(
+            records[2].to_string(),
+            "example.com.\t3600\tIN\tCAA\t128 iodef \"mailto:security@example.com\""
+        );
+        assert_round_trip(&records);
+
+        assert_eq!(
+            parse_zone("@ 3600 CAA 0 is-sue \"x\"", &domain("example.com")),
+            Err(DNSError::ZoneSyntax(
+                1,
+                14,
+                "invalid CAA property: invalid tag is-sue".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_loc() {
+        // RFC 1876 section 4 examples
+        let zone = r#"
+$ORIGIN example.com.
+cambridge-net 3600 LOC 42 21 54 N 71 06 18 W -24m 30m
+rwy04l.logan-airport 3600 LOC 42 21 28.764 N 71 00 51.617 W -44m 2000m
+amsterdam 3600 LOC 52 22 23.000 N 4 53 32.000 E -2.00m
+pole 3600 LOC 90 S 0 E 2835m 1m 1m 1m
+"#;
+
+        let records = parse_zone(zone, &domain("")).unwrap();
+
+        assert_eq!(records.len(), 4);
+        assert_eq!(
+            records[0].to_string(),
+            "cambridge-net.example.com.\t3600\tIN\tLOC\t42 21 54.000 N 71 6 18.000 W -24.00m 30m 10000m 10m"
+        );
+        let RecordData::LOC(amsterdam) = records[2].data() else {
+            panic!("not a LOC record");
+        };
+        assert!((amsterdam.latitude_degrees() - 52.373_055_555).abs() < 1e-9);
+        assert!((amsterdam.longitude_degrees() - 4.892_222_222).abs() < 1e-9);
+        assert_eq!(amsterdam.altitude_meters(), -2.0);
+        assert_eq!(
+            records[3].to_string(),
+            "pole.example.com.\t3600\tIN\tLOC\t90 0 0.000 S 0 0 0.000 E 2835.00m 1m 1m 1m"
+        );
+        assert_round_trip(&records);
+
+        for (rdata, column) in [
+            ("91 N 0 E 0m", 12),
+            ("42 60 N 0 E 0m", 15),
+            ("42 N 0 0 60.5 E 0m", 21),
+            ("42 X 0 E 0m", 15),
+            ("42 N 0 E -100000.01m", 21),
+            ("42 N 0 E 0m 90000001m", 24),
+        ] {
+            assert!(matches!(
+                parse_zone(&format!("@ 3600 LOC {}", rdata), &domain("example.com")),
+                Err(DNSError::ZoneSyntax(1, c, _)) if c == column
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_naptr_uri() {
+        // RFC 3403 section 6.2 and RFC 7553 section 4.4 examples
+        let zone = r#"
+$ORIGIN example.com.
+@ 3600 NAPTR 100 50 "a" "z3950+N2L+N2C" "" cidserver.example.com.
+@ 3600 NAPTR 100 10 "" "" "!^urn:cid:.+@([^\\.]+\\.)(.*)$!\\2!i" .
+_ftp._tcp 3600 URI 10 1 "ftp://ftp1.example.com/public"
+"#;
+
+        let records = parse_zone(zone, &domain("")).unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[1].data(),
+            &RecordData::NAPTR(NAPTR {
+                order: 100,
+                preference: 10,
+                flags: String::new(),
+                services: String::new(),
+                regexp: "!^urn:cid:.+@([^\\.]+\\.)(.*)$!\\2!i".to_string(),
+                replacement: domain(""),
+            })
+        );
+        assert_eq!(
+            records[0].to_string(),
+            "example.com.\t3600\tIN\tNAPTR\t100 50 \"a\" \"z3950+N2L+N2C\" \"\" cidserver.example.com."
+        );
+        assert_eq!(
+            records[2].data(),
+            &RecordData::URI(URI {
+                priority: 10,
+                weight: 1,
+                target: "ftp://ftp1.example.com/public".to_string(),
+            })
+        );
+        assert_round_trip(&records);
+    }
+
+    #[test]
+    fn test_parse_tlsa() {
+        // RFC 6698 section 2.3 example
+        let zone = r#"
+$ORIGIN example.com.
+_443._tcp.www 3600 IN TLSA ( 0 0 1 d2abde240d7cd3ee6b4b28c54df034b9
+                             7983a1d16e8a410e4561cb106618e971 )
+c93f1e400f26708f98cb19d936620da35eec8f72e57f9eec01c1afd6._smimecert 3600 SMIMEA 3 1 2 ABCD
+"#;
+
+        let records = parse_zone(zone, &domain("")).unwrap();
+
+        assert_eq!(
+            records[0].data(),
+            &RecordData::TLSA(TLSA {
+                certificate_usage: CertificateUsage::PKIXTA,
+                selector: Selector::FullCertificate,
+                matching_type: MatchingType::SHA256,
+                association_data: hex_decode(
+                    "d2abde240d7cd3ee6b4b28c54df034b97983a1d16e8a410e4561cb106618e971"
+                )
+                .unwrap(),
+            })
+        );
+        assert_eq!(records[1].record_type(), QueryType::SMIMEA);
+        assert_eq!(records[1].data().to_string(), "3 1 2 ABCD");
+        assert_round_trip(&records);
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        let zone = r#"
+$ORIGIN 2.0.192.in-addr.arpa.
+$TTL 300
+$GENERATE 1-3 $ PTR host-${10,3,d}.example.com.
+$GENERATE 0-4/2 host$ IN A 192.0.2.$
+$GENERATE 10-11 x${0,2,x}\$ CNAME y$
+"#;
+
+        let records = parse_zone(zone, &domain("")).unwrap();
+
+        let ptr = |owner: &str, target: &str| {
+            Record::new(
+                domain(owner),
+                Class::IN,
+                300,
+                RecordData::PTR(PTR {
+                    ptrname: domain(target),
+                }),
+            )
+        };
+        let cname = |owner: &str, target: &str| {
+            Record::new(
+                domain(owner),
+                Class::IN,
+                300,
+                RecordData::CNAME(CNAME {
+                    cname: domain(target),
+                }),
+            )
+        };
+        assert_eq!(
+            records,
+            vec![
+                ptr("1.2.0.192.in-addr.arpa", "host-011.example.com"),
+                ptr("2.2.0.192.in-addr.arpa", "host-012.example.com"),
+                ptr("3.2.0.192.in-addr.arpa", "host-013.example.com"),
+                a_record("host0.2.0.192.in-addr.arpa", 300, [192, 0, 2, 0]),
+                a_record("host2.2.0.192.in-addr.arpa", 300, [192, 0, 2, 2]),
+                a_record("host4.2.0.192.in-addr.arpa", 300, [192, 0, 2, 4]),
+                cname("x0a$.2.0.192.in-addr.arpa", "y10.2.0.192.in-addr.arpa"),
+                cname("x0b$.2.0.192.in-addr.arpa", "y11.2.0.192.in-addr.arpa"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_escapes() {
+        let zone = "a\\032b\\@c.example. 300 A 192.0.2.1\n\
+                    txt.example. 300 TXT \"caf\\195\\169\" \\\"\n";
+
+        let records = parse_zone(zone, &domain("")).unwrap();
+
+        assert_eq!(records[0].domain(), &domain("a b@c.example"));
+        assert_eq!(
+            records[1].data(),
+            &RecordData::TXT(TXT {
+                txt_data: vec!["café".as_bytes().to_vec(), b"\"".to_vec()],
+            })
+        );
+        assert_round_trip(&records);
+    }
+
+    #[test]
+    fn test_parse_include() {
+        let dir = std::env::temp_dir().join(format!("dns_rs_zone_test_{}", process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(
+            dir.join("main.zone"),
+            "$TTL 300\n\
+             www A 192.0.2.1\n\
+             $INCLUDE sub/hosts.zone hosts.example.com.\n\
+             mail A 192.0.2.2\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("sub/hosts.zone"),
+            "a A 192.0.2.10\n\
+             $ORIGIN other.example.com.\n\
+             b A 192.0.2.11\n",
+        )
+        .unwrap();
+
+        let records = parse_zone_file(dir.join("main.zone"), &domain("example.com"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            records.unwrap(),
+            vec![
+                a_record("www.example.com", 300, [192, 0, 2, 1]),
+                a_record("a.hosts.example.com", 300, [192, 0, 2, 10]),
+                a_record("b.other.example.com", 300, [192, 0, 2, 11]),
+                a_record("mail.example.com", 300, [192, 0, 2, 2]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let origin = domain("example.com");
+        let error = |zone: &str| parse_zone(zone, &origin).unwrap_err();
+
+        assert_eq!(
+            error("www 300 A 192.0.2.300"),
+            DNSError::ZoneSyntax(1, 11, "invalid IPv4 address 192.0.2.300".to_string())
+        );
+        assert_eq!(
+            error("www 300 A 192.0.2.1\nmx 300 MX 10"),
+            DNSError::ZoneSyntax(2, 13, "missing exchange".to_string())
+        );
+        assert_eq!(
+            error("www A 192.0.2.1"),
+            DNSError::ZoneSyntax(1, 5, "missing TTL and no $TTL default".to_string())
+        );
+        assert_eq!(
+            error("$TTL 300\nwww BOGUS 1"),
+            DNSError::ZoneSyntax(2, 5, "unknown record type BOGUS".to_string())
+        );
+        assert_eq!(
+            error("$TTL 300\nwww A 192.0.2.1 extra"),
+            DNSError::ZoneSyntax(2, 17, "unexpected field extra".to_string())
+        );
+        assert_eq!(
+            error("$TTL 300\n  A 192.0.2.1"),
+            DNSError::ZoneSyntax(2, 3, "no previous owner name".to_string())
+        );
+        assert_eq!(
+            error("$TTL 300\n$BOGUS"),
+            DNSError::ZoneSyntax(2, 1, "unknown directive $BOGUS".to_string())
+        );
+        assert_eq!(
+            error("$TTL 300\nwww HINFO PC Linux"),
+            DNSError::ZoneSyntax(
+                2,
+                5,
+                "type HINFO requires the generic \\# RDATA format".to_string()
+            )
+        );
+        assert_eq!(
+            error("$TTL 300\na..b A 192.0.2.1"),
+            DNSError::ZoneSyntax(2, 1, "empty label in a..b".to_string())
+        );
+        assert_eq!(
+            error("$TTL 300\n$GENERATE 1-2 h${9223372036854775807} A 192.0.2.1"),
+            DNSError::ZoneSyntax(
+                2,
+                15,
+                "invalid template h${9223372036854775807}".to_string()
+            )
+        );
+        assert_eq!(
+            error("$TTL 300\n$GENERATE 1-2 h${0,99999999999} A 192.0.2.1"),
+            DNSError::ZoneSyntax(2, 15, "invalid template h${0,99999999999}".to_string())
+        );
+        assert_eq!(
+            error("$TTL 300\nsvc SVCB \\# 8 00010000030001ff"),
+            DNSError::ZoneSyntax(2, 10, "invalid SvcParams: malformed port".to_string())
+        );
+        assert!(matches!(
+            error("$INCLUDE does-not-exist.zone"),
+            DNSError::ZoneSyntax(1, 10, _)
+        ));
+    }
+
+    #[test]
+    fn test_parse_ttl() {
+        let token = |text: &str| Token {
+            text: text.to_string(),
+            quoted: false,
+            line: 1,
+            column: 1,
+        };
+
+        assert_eq!(parse_ttl(&token("86400")), Ok(86400));
+        assert_eq!(parse_ttl(&token("1w2d3h4m5s")), Ok(788645));
+        assert_eq!(parse_ttl(&token("1H30M")), Ok(5400));
+        assert!(parse_ttl(&token("1h30")).is_err());
+        assert!(parse_ttl(&token("1y")).is_err());
+    }
+}
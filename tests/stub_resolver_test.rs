@@ -1,50 +1,66 @@
 extern crate dns_rs;
 use dns_rs::dns_packet::*;
-use std::net::UdpSocket;
+use dns_rs::resolver::Resolver;
+use std::net::{Ipv6Addr, UdpSocket};
+use std::thread;
+use std::time::Duration;
+
+fn aaaa_record() -> Record {
+    Record::new(
+        "splitkb.com".parse().unwrap(),
+        Class::IN,
+        300,
+        RecordData::AAAA(AAAA {
+            addr: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1),
+        }),
+    )
+}
 
 #[test]
 fn stub_resolver_0() {
-    let socket = UdpSocket::bind(("0.0.0.0", 43210)).unwrap();
-
-    let google_dns_server = ("8.8.8.8", 53);
+    // Local nameserver answering a single query
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let nameserver = socket.local_addr().unwrap();
+    let handle = thread::spawn(move || {
+        let mut recv_data = [0; 512];
+        let (recv_len, source) = socket.recv_from(&mut recv_data).unwrap();
+        let query =
+            DNSPacket::parse_dns_packet(&mut DNSPacketBuffer::new(&recv_data[..recv_len])).unwrap();
 
-    let dns_query_packet = DNSPacket::new(
-        Header {
-            id: 0x862a,
-            query_response: false,
-            opcode: 0,
-            authoritative_answer: false,
-            truncated_message: false,
-            recursion_desired: true,
-            recursion_available: false,
-            reserved: 2,
+        let header = Header {
+            query_response: true,
+            recursion_available: true,
             response_code: ResponseCode::NoError,
             question_count: 1,
-            answer_count: 0,
+            answer_count: 1,
             authority_count: 0,
             additional_count: 0,
-        },
-        Some(vec![Question {
-            domain: Domain("splitkb.com".to_string()),
-            record_type: QueryType::AAAA,
-            class: 0x01,
-        }]),
-        None,
-        None,
-        None,
-    );
-
-    let data_buffer = dns_query_packet.write_dns_packet().unwrap();
-    let data = data_buffer.get_data();
+            ..query.header().clone()
+        };
+        let response = DNSPacket::new(
+            header,
+            Some(query.questions().to_vec()),
+            Some(vec![aaaa_record()]),
+            None,
+            None,
+        );
+        let data = response.write_dns_packet().unwrap();
+        socket.send_to(data.get_data(), source).unwrap();
+        query
+    });
 
-    socket.send_to(data, google_dns_server).unwrap();
+    let mut resolver = Resolver::new(vec![nameserver]);
+    resolver.set_timeout(Duration::from_millis(200));
+    let recv_packet = resolver
+        .query(&"splitkb.com".parse().unwrap(), QueryType::AAAA)
+        .unwrap();
+    let query = handle.join().unwrap();
 
-    let mut recv_data = [0; PACKET_SIZE];
-
-    socket.recv_from(&mut recv_data).unwrap();
-    println!("{:02X?}", recv_data);
-
-    let recv_packet = DNSPacket::parse_dns_packet(&mut DNSPacketBuffer::new(&recv_data));
-
-    println!("{:#?}", recv_packet)
+    assert_eq!(
+        query.questions()[0].domain,
+        "splitkb.com".parse::<Domain>().unwrap()
+    );
+    assert_eq!(query.questions()[0].record_type, QueryType::AAAA);
+    assert_eq!(recv_packet.response_code(), Ok(ResponseCode::NoError));
+    assert_eq!(recv_packet.answers(), [aaaa_record()]);
 }